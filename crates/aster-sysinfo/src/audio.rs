@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Audio output sensors of the default sink.
+//!
+//! The volume and mute state are queried with `wpctl` of WirePlumber (PipeWire), or with `pactl`
+//! of PulseAudio and `pipewire-pulse` if `wpctl` is not installed. Both tools connect to the sound
+//! server of the current user session.
+
+use crate::add_sensor;
+use log::debug;
+use std::collections::HashMap;
+use std::process::Command;
+
+#[derive(Debug, PartialEq)]
+struct VolumeInfo {
+    /// Volume in percent, can exceed 100 with software amplification.
+    volume: u32,
+    muted: bool,
+}
+
+/// Add volume and mute sensors of the default audio output.
+///
+/// Sensor keys:
+/// - `audio_volume`: volume of the default sink in percent
+/// - `audio_muted`: 1 if the default sink is muted, 0 otherwise
+///
+/// No sensors are added if no sound server is available.
+pub fn update_audio_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(info) = get_wpctl_volume().or_else(get_pactl_volume) else {
+        debug!("No audio sink available");
+        return Ok(());
+    };
+
+    add_sensor(sensors, "audio_volume", info.volume);
+    add_sensor(sensors, "audio_volume#unit", "%");
+    add_sensor(sensors, "audio_muted", u8::from(info.muted));
+
+    Ok(())
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .inspect_err(|e| debug!("Unable to query audio volume with {program}: {e}"))
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn get_wpctl_volume() -> Option<VolumeInfo> {
+    parse_wpctl_volume(&run("wpctl", &["get-volume", "@DEFAULT_AUDIO_SINK@"])?)
+}
+
+fn get_pactl_volume() -> Option<VolumeInfo> {
+    let volume = run("pactl", &["get-sink-volume", "@DEFAULT_SINK@"])?;
+    let mute = run("pactl", &["get-sink-mute", "@DEFAULT_SINK@"])?;
+    parse_pactl_volume(&volume, &mute)
+}
+
+/// Parse the output of `wpctl get-volume`, e.g. `Volume: 0.45 [MUTED]`.
+fn parse_wpctl_volume(output: &str) -> Option<VolumeInfo> {
+    let value = output.trim().strip_prefix("Volume:")?;
+    let volume = value.split_whitespace().next()?.parse::<f32>().ok()?;
+    Some(VolumeInfo {
+        volume: (volume * 100.0).round() as u32,
+        muted: value.contains("[MUTED]"),
+    })
+}
+
+/// Parse the output of `pactl get-sink-volume` and `pactl get-sink-mute`.
+///
+/// The volume of the first channel is used.
+fn parse_pactl_volume(volume: &str, mute: &str) -> Option<VolumeInfo> {
+    let percent = volume
+        .split('/')
+        .map(str::trim)
+        .find_map(|part| part.strip_suffix('%')?.trim().parse::<u32>().ok())?;
+    Some(VolumeInfo {
+        volume: percent,
+        muted: mute.trim() == "Mute: yes",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wpctl_output() {
+        assert_eq!(
+            Some(VolumeInfo {
+                volume: 45,
+                muted: false
+            }),
+            parse_wpctl_volume("Volume: 0.45\n")
+        );
+        assert_eq!(
+            Some(VolumeInfo {
+                volume: 120,
+                muted: true
+            }),
+            parse_wpctl_volume("Volume: 1.20 [MUTED]\n")
+        );
+        assert_eq!(None, parse_wpctl_volume("Object not found\n"));
+    }
+
+    #[test]
+    fn parse_pactl_output() {
+        let volume =
+            "Volume: front-left: 29491 /  45% / -20.81 dB,   front-right: 29491 /  45% / -20.81 dB
+        balance 0.00
+";
+        assert_eq!(
+            Some(VolumeInfo {
+                volume: 45,
+                muted: true
+            }),
+            parse_pactl_volume(volume, "Mute: yes\n")
+        );
+        assert_eq!(None, parse_pactl_volume("", "Mute: no\n"));
+    }
+}
@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Battery sensors of laptops and handhelds.
+//!
+//! Reads all batteries in `/sys/class/power_supply`.
+
+use crate::add_sensor;
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+
+/// Read battery sensors from all batteries.
+///
+/// Sensor keys:
+/// - `battery_{name}_level`: charge level in percent
+/// - `battery_{name}_status`: charging state reported by the driver: `Charging`, `Discharging`,
+///   `Not charging`, `Full` or `Unknown`
+/// - `battery_{name}_charging`: 1 if charging, 0 otherwise
+/// - `battery_{name}_power`: power draw in W
+/// - `battery_{name}_time_to_empty`: remaining runtime in minutes while discharging, 0 otherwise
+///
+/// The battery name is the power supply device name in lowercase, e.g. `bat0`.
+pub fn update_battery_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    read_battery_sensors(Path::new(POWER_SUPPLY_PATH), sensors)
+}
+
+fn read_battery_sensors(
+    power_supply_path: &Path,
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !power_supply_path.exists() {
+        debug!("No power supply devices found");
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(power_supply_path)?.flatten() {
+        let path = entry.path();
+        if read_string(&path, "type").as_deref() != Some("Battery") {
+            continue;
+        }
+        // skip peripherals like wireless mice reporting their battery over HID
+        if read_string(&path, "scope").as_deref() == Some("Device") {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        let prefix = format!("battery_{name}");
+
+        let voltage = read_value(&path, "voltage_now");
+        // power draw in µW, either reported directly or calculated from current and voltage
+        let power = read_value(&path, "power_now")
+            .or_else(|| Some(read_value(&path, "current_now")? * voltage? / 1_000_000.0));
+        // remaining energy in µWh
+        let energy = read_value(&path, "energy_now")
+            .or_else(|| Some(read_value(&path, "charge_now")? * voltage? / 1_000_000.0));
+
+        let status = read_string(&path, "status").unwrap_or_else(|| "Unknown".to_string());
+        let level = read_value(&path, "capacity").or_else(|| {
+            let full = read_value(&path, "energy_full")
+                .or_else(|| Some(read_value(&path, "charge_full")? * voltage? / 1_000_000.0))
+                .filter(|full| *full > 0.0)?;
+            Some(energy? * 100.0 / full)
+        });
+
+        let time_to_empty = if status == "Discharging" {
+            read_value(&path, "time_to_empty_now")
+                .map(|secs| secs / 60.0)
+                .or_else(|| {
+                    let power = power.filter(|p| *p > 0.0)?;
+                    Some(energy? / power * 60.0)
+                })
+        } else {
+            None
+        };
+
+        if let Some(level) = level {
+            add_sensor(sensors, format!("{prefix}_level"), format!("{level:.0}"));
+            add_sensor(sensors, format!("{prefix}_level#unit"), "%");
+        }
+        add_sensor(
+            sensors,
+            format!("{prefix}_charging"),
+            u8::from(status == "Charging"),
+        );
+        add_sensor(sensors, format!("{prefix}_status"), status);
+        add_sensor(
+            sensors,
+            format!("{prefix}_power"),
+            format!("{:.2}", power.unwrap_or_default().abs() / 1_000_000.0),
+        );
+        add_sensor(sensors, format!("{prefix}_power#unit"), "W");
+        add_sensor(
+            sensors,
+            format!("{prefix}_time_to_empty"),
+            format!("{:.0}", time_to_empty.unwrap_or_default()),
+        );
+        add_sensor(sensors, format!("{prefix}_time_to_empty#unit"), "min");
+    }
+
+    Ok(())
+}
+
+/// Check if the host runs on battery power.
+///
+/// The host is on battery if it has mains adapters and none of them is online. Without a mains
+/// adapter, a discharging system battery is used as indicator.
+pub fn on_battery() -> bool {
+    read_on_battery(Path::new(POWER_SUPPLY_PATH))
+}
+
+fn read_on_battery(power_supply_path: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(power_supply_path) else {
+        return false;
+    };
+
+    let mut mains = None;
+    let mut discharging = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match read_string(&path, "type").as_deref() {
+            Some("Mains") => {
+                let online = read_string(&path, "online").as_deref() == Some("1");
+                mains = Some(mains.unwrap_or_default() || online);
+            }
+            Some("Battery") if read_string(&path, "scope").as_deref() != Some("Device") => {
+                discharging |= read_string(&path, "status").as_deref() == Some("Discharging");
+            }
+            _ => {}
+        }
+    }
+
+    match mains {
+        Some(online) => !online,
+        None => discharging,
+    }
+}
+
+fn read_string(path: &Path, attribute: &str) -> Option<String> {
+    fs::read_to_string(path.join(attribute))
+        .ok()
+        .map(|v| v.trim().to_string())
+}
+
+fn read_value(path: &Path, attribute: &str) -> Option<f64> {
+    read_string(path, attribute)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn discharging_battery_with_energy_values() {
+        let root = tempfile::tempdir().unwrap();
+        let bat = root.path().join("BAT0");
+        write(&bat.join("type"), "Battery\n");
+        write(&bat.join("status"), "Discharging\n");
+        write(&bat.join("capacity"), "81\n");
+        write(&bat.join("power_now"), "12500000\n");
+        write(&bat.join("energy_now"), "25000000\n");
+        write(&root.path().join("AC/type"), "Mains\n");
+        write(&root.path().join("AC/online"), "0\n");
+
+        let mut sensors = HashMap::new();
+        read_battery_sensors(root.path(), &mut sensors).unwrap();
+
+        assert_eq!(Some(&"81".to_string()), sensors.get("battery_bat0_level"));
+        assert_eq!(
+            Some(&"Discharging".to_string()),
+            sensors.get("battery_bat0_status")
+        );
+        assert_eq!(Some(&"0".to_string()), sensors.get("battery_bat0_charging"));
+        assert_eq!(
+            Some(&"12.50".to_string()),
+            sensors.get("battery_bat0_power")
+        );
+        assert_eq!(
+            Some(&"120".to_string()),
+            sensors.get("battery_bat0_time_to_empty")
+        );
+        assert_eq!(8, sensors.len());
+        assert!(read_on_battery(root.path()));
+
+        write(&root.path().join("AC/online"), "1\n");
+        assert!(!read_on_battery(root.path()));
+    }
+
+    #[test]
+    fn charging_battery_with_charge_values() {
+        let root = tempfile::tempdir().unwrap();
+        let bat = root.path().join("BAT1");
+        write(&bat.join("type"), "Battery\n");
+        write(&bat.join("status"), "Charging\n");
+        write(&bat.join("voltage_now"), "8000000\n");
+        write(&bat.join("current_now"), "1500000\n");
+        write(&bat.join("charge_now"), "2000000\n");
+        write(&bat.join("charge_full"), "4000000\n");
+        let mouse = root.path().join("hidpp_battery_0");
+        write(&mouse.join("type"), "Battery\n");
+        write(&mouse.join("scope"), "Device\n");
+
+        let mut sensors = HashMap::new();
+        read_battery_sensors(root.path(), &mut sensors).unwrap();
+
+        assert_eq!(Some(&"50".to_string()), sensors.get("battery_bat1_level"));
+        assert_eq!(Some(&"1".to_string()), sensors.get("battery_bat1_charging"));
+        assert_eq!(
+            Some(&"12.00".to_string()),
+            sensors.get("battery_bat1_power")
+        );
+        assert_eq!(
+            Some(&"0".to_string()),
+            sensors.get("battery_bat1_time_to_empty")
+        );
+        assert!(!sensors.keys().any(|k| k.contains("hidpp")));
+        // no mains adapter: a charging battery isn't on battery power
+        assert!(!read_on_battery(root.path()));
+    }
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Bluetooth sensors.
+//!
+//! Reads the Bluetooth adapters and their connections from `/sys/class/bluetooth`. The kernel
+//! Bluetooth stack of BlueZ adds a `hciX:handle` device for every connection of adapter `hciX`.
+
+use crate::add_sensor;
+use log::debug;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const BLUETOOTH_PATH: &str = "/sys/class/bluetooth";
+
+/// Add Bluetooth sensors of all adapters.
+///
+/// Sensor keys:
+/// - `bluetooth_{adapter}_enabled`: 1 if the adapter is not blocked by rfkill, 0 otherwise
+/// - `bluetooth_{adapter}_connected_devices`: number of connected devices
+/// - `bluetooth_connected_devices`: number of connected devices of all adapters
+///
+/// No sensors are added if there is no Bluetooth adapter.
+pub fn update_bluetooth_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    read_bluetooth_sensors(Path::new(BLUETOOTH_PATH), sensors)
+}
+
+fn read_bluetooth_sensors(
+    bluetooth_path: &Path,
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Ok(entries) = fs::read_dir(bluetooth_path) else {
+        debug!("No Bluetooth adapters found");
+        return Ok(());
+    };
+
+    // adapter name -> connection count
+    let mut adapters = BTreeMap::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        match name.split_once(':') {
+            Some((adapter, _)) => *adapters.entry(adapter.to_string()).or_insert(0) += 1,
+            None => {
+                adapters.entry(name).or_insert(0);
+            }
+        }
+    }
+    if adapters.is_empty() {
+        return Ok(());
+    }
+
+    for (adapter, connections) in &adapters {
+        let prefix = format!("bluetooth_{adapter}");
+        add_sensor(
+            sensors,
+            format!("{prefix}_enabled"),
+            u8::from(!is_blocked(&bluetooth_path.join(adapter))),
+        );
+        add_sensor(sensors, format!("{prefix}_connected_devices"), connections);
+    }
+    add_sensor(
+        sensors,
+        "bluetooth_connected_devices",
+        adapters.values().sum::<u32>(),
+    );
+
+    Ok(())
+}
+
+/// Check if the rfkill switch of an adapter blocks it, either by software or hardware.
+fn is_blocked(adapter_path: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(adapter_path) else {
+        return false;
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("rfkill"))
+        .any(|entry| {
+            ["soft", "hard"].iter().any(|state| {
+                fs::read_to_string(entry.path().join(state)).is_ok_and(|v| v.trim() == "1")
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn count_connections_of_adapters() {
+        let root = tempfile::tempdir().unwrap();
+        write(&root.path().join("hci0/rfkill1/soft"), "0\n");
+        write(&root.path().join("hci0/rfkill1/hard"), "0\n");
+        fs::create_dir_all(root.path().join("hci0:256")).unwrap();
+        fs::create_dir_all(root.path().join("hci0:512")).unwrap();
+        write(&root.path().join("hci1/rfkill2/soft"), "1\n");
+
+        let mut sensors = HashMap::new();
+        read_bluetooth_sensors(root.path(), &mut sensors).unwrap();
+
+        assert_eq!(
+            Some(&"2".to_string()),
+            sensors.get("bluetooth_hci0_connected_devices")
+        );
+        assert_eq!(
+            Some(&"1".to_string()),
+            sensors.get("bluetooth_hci0_enabled")
+        );
+        assert_eq!(
+            Some(&"0".to_string()),
+            sensors.get("bluetooth_hci1_connected_devices")
+        );
+        assert_eq!(
+            Some(&"0".to_string()),
+            sensors.get("bluetooth_hci1_enabled")
+        );
+        assert_eq!(
+            Some(&"2".to_string()),
+            sensors.get("bluetooth_connected_devices")
+        );
+    }
+}
@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! FreeBSD sensors, e.g. for TrueNAS CORE.
+//!
+//! Temperatures are read with `sysctl` from the `coretemp` / `amdtemp` CPU sensors and the ACPI
+//! thermal zones, storage devices from `kern.disks` and their file system usage with `df`.
+
+use crate::{DiskUsage, add_sensor, format_bytes, get_smartctl_disk_temperature};
+use log::debug;
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Read the CPU and ACPI thermal zone temperatures.
+///
+/// `temperature_cpu` is the highest core temperature of `dev.cpu.{n}.temperature`, or the
+/// temperature of the first thermal zone if no CPU temperature driver is loaded. Thermal zones are
+/// reported as `temperature_acpi_{zone}`.
+pub fn update_freebsd_temperature_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new("sysctl")
+        .args(["-e", "dev.cpu", "hw.acpi.thermal"])
+        .output()?;
+    let temperatures = parse_temperatures(&String::from_utf8_lossy(&output.stdout));
+
+    let cpu = temperatures
+        .iter()
+        .filter(|(name, _)| name.starts_with("dev.cpu."))
+        .map(|(_, temperature)| *temperature)
+        .reduce(f32::max);
+    let mut zones: Vec<(&str, f32)> = temperatures
+        .iter()
+        .filter_map(|(name, temperature)| {
+            let zone = name.strip_prefix("hw.acpi.thermal.")?;
+            Some((zone.strip_suffix(".temperature")?, *temperature))
+        })
+        .collect();
+    zones.sort_by(|a, b| a.0.cmp(b.0));
+
+    if let Some(temperature) = cpu.or(zones.first().map(|(_, t)| *t)) {
+        add_sensor(sensors, "temperature_cpu#unit", "°C");
+        add_sensor(sensors, "temperature_cpu", format!("{temperature:.1}"));
+    }
+    for (zone, temperature) in zones {
+        add_sensor(sensors, format!("temperature_acpi_{zone}#unit"), "°C");
+        add_sensor(
+            sensors,
+            format!("temperature_acpi_{zone}"),
+            format!("{temperature:.1}"),
+        );
+    }
+
+    Ok(())
+}
+
+/// Read the storage devices with the same sensor keys as
+/// [update_linux_storage_sensors](crate::update_linux_storage_sensors).
+///
+/// NVMe devices (`nvd`, `nda`) are reported as `storage_ssd[{idx}]`, ATA and SCSI devices (`ada`,
+/// `da`) as `storage_hdd[{idx}]`. The usage includes UFS file systems on the device only: disks of
+/// ZFS pools are reported with zero usage, see [update_zfs_sensors](crate::update_zfs_sensors).
+pub fn update_freebsd_storage_sensors(
+    sensors: &mut HashMap<String, String>,
+    use_smartctl: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new("sysctl").args(["-n", "kern.disks"]).output()?;
+    let (hdd_devices, nvme_devices) = classify_disks(&String::from_utf8_lossy(&output.stdout));
+    debug!("HDD devices: {hdd_devices:?}, NVMe devices: {nvme_devices:?}");
+
+    let output = Command::new("df").arg("-k").output()?;
+    let df = String::from_utf8_lossy(&output.stdout);
+    for (kind, devices) in [("hdd", hdd_devices), ("ssd", nvme_devices)] {
+        for (idx, device) in devices.iter().enumerate() {
+            let usage = disk_usage(&df, device);
+            let prefix = format!("storage_{kind}[{idx}]");
+            add_sensor(
+                sensors,
+                format!("{prefix}_total_size_bytes"),
+                usage.total_size,
+            );
+            add_sensor(
+                sensors,
+                format!("{prefix}_total_size"),
+                format_bytes(usage.total_size),
+            );
+            add_sensor(
+                sensors,
+                format!("{prefix}_total_used_bytes"),
+                usage.total_used,
+            );
+            add_sensor(
+                sensors,
+                format!("{prefix}_total_used"),
+                format_bytes(usage.total_used),
+            );
+            add_sensor(
+                sensors,
+                format!("{prefix}_usage_percent"),
+                usage.usage_percent,
+            );
+
+            if use_smartctl && let Some(temperature) = get_smartctl_disk_temperature(device)? {
+                add_sensor(sensors, format!("{prefix}_temperature"), temperature);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse `name=45.0C` lines of temperature OIDs into the OID name and the temperature in °C.
+fn parse_temperatures(output: &str) -> Vec<(String, f32)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once('=').or_else(|| line.split_once(": "))?;
+            if !name.ends_with(".temperature") {
+                return None;
+            }
+            let temperature = value.trim().strip_suffix('C')?.parse().ok()?;
+            Some((name.trim().to_string(), temperature))
+        })
+        .collect()
+}
+
+/// Split the `kern.disks` list into sorted ATA/SCSI and NVMe devices. Other devices, e.g. optical
+/// drives and memory disks, are ignored.
+fn classify_disks(disks: &str) -> (Vec<String>, Vec<String>) {
+    let mut hdd_devices = Vec::new();
+    let mut nvme_devices = Vec::new();
+    for disk in disks.split_whitespace() {
+        let driver = disk.trim_end_matches(|c: char| c.is_ascii_digit());
+        match driver {
+            "ada" | "da" => hdd_devices.push(disk.to_string()),
+            "nvd" | "nda" => nvme_devices.push(disk.to_string()),
+            _ => {}
+        }
+    }
+    hdd_devices.sort();
+    nvme_devices.sort();
+    (hdd_devices, nvme_devices)
+}
+
+/// Sum the file systems on the partitions of a device in the `df -k` output.
+fn disk_usage(df: &str, device: &str) -> DiskUsage {
+    let dev = format!("/dev/{device}");
+    let mut usage = DiskUsage {
+        usage_percent: 0.0,
+        total_used: 0,
+        total_size: 0,
+    };
+    for line in df.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [source, size, used, ..] = parts[..] else {
+            continue;
+        };
+        // partitions: ada0p2 (GPT) or ada0s1a (MBR slice)
+        let Some(partition) = source.strip_prefix(&dev) else {
+            continue;
+        };
+        if !partition.is_empty() && !partition.starts_with(['p', 's']) {
+            continue;
+        }
+        if let (Ok(size), Ok(used)) = (size.parse::<u64>(), used.parse::<u64>()) {
+            usage.total_size += size * 1024;
+            usage.total_used += used * 1024;
+        }
+    }
+    if usage.total_size != 0 {
+        usage.usage_percent =
+            ((usage.total_used as f64 / usage.total_size as f64) * 100.0 * 100.0).round() / 100.0;
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sysctl_temperatures() {
+        let output = "dev.cpu.0.temperature=45.0C\ndev.cpu.0.freq=3600\n\
+            dev.cpu.1.temperature=47.5C\nhw.acpi.thermal.tz0.temperature=27.9C\n";
+        assert_eq!(
+            vec![
+                ("dev.cpu.0.temperature".to_string(), 45.0),
+                ("dev.cpu.1.temperature".to_string(), 47.5),
+                ("hw.acpi.thermal.tz0.temperature".to_string(), 27.9),
+            ],
+            parse_temperatures(output)
+        );
+    }
+
+    #[test]
+    fn storage_devices_and_usage() {
+        let (hdd, nvme) = classify_disks("nda0 ada1 ada0 cd0 da0 md0\n");
+        assert_eq!(vec!["ada0", "ada1", "da0"], hdd);
+        assert_eq!(vec!["nda0"], nvme);
+
+        let df = "Filesystem  1024-blocks  Used  Avail Capacity  Mounted on
+/dev/ada0p2     1000000  250000  670000    27%    /
+/dev/ada0p3     1000000  250000  670000    27%    /var
+/dev/ada10p2    5000000  100000 4500000     2%    /data
+devfs                 1       1       0   100%    /dev
+";
+        let usage = disk_usage(df, "ada0");
+        assert_eq!(2_048_000_000, usage.total_size);
+        assert_eq!(512_000_000, usage.total_used);
+        assert_eq!(25.0, usage.usage_percent);
+        assert_eq!(0, disk_usage(df, "ada1").total_size);
+    }
+}
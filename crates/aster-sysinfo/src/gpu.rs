@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! GPU video engine sensors: encoder and decoder utilization for transcoding workloads.
+//!
+//! AMD (VCN) and Intel (Quick Sync) engines are measured with the DRM client usage statistics,
+//! which the kernel exposes as `drm-engine-{engine}: {busy} ns` lines in `/proc/{pid}/fdinfo` of
+//! every open GPU device. The utilization is the busy time of all clients between two refreshes.
+//! NVIDIA (NVENC/NVDEC) engines are queried with `nvidia-smi`.
+
+use crate::{SysinfoSource, add_sensor};
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+
+/// Video engine sensors of the DRM drivers, reported as idle without any clients.
+const DRIVER_ENGINES: [(&str, &[&str]); 2] =
+    [("amdgpu", &["decoder", "encoder"]), ("i915", &["video"])];
+
+/// DRM engine names of the video engines and their sensor name.
+const VIDEO_ENGINES: [(&str, &str); 4] = [
+    // amdgpu: VCN decode and encode rings. VCN 4+ uses the encode ring for decoding as well.
+    ("dec", "decoder"),
+    ("enc", "encoder"),
+    ("enc_1", "encoder"),
+    // i915: video command streamer used for decoding and encoding
+    ("video", "video"),
+];
+
+/// Busy time of a DRM client engine: (PCI device, client ID, sensor name) -> busy ns
+type EngineBusyTimes = HashMap<(String, String, &'static str), u64>;
+
+/// DRM client usage of the previous refresh.
+#[derive(Debug, Default)]
+pub(crate) struct GpuEngineSample {
+    time: Option<Instant>,
+    busy: EngineBusyTimes,
+}
+
+impl SysinfoSource {
+    /// Add video engine utilization sensors of all GPUs.
+    ///
+    /// Sensor keys, with `{card}` the DRM card name, e.g. `card0`:
+    /// - `gpu_{card}_encoder_usage`: encoder utilization in percent (AMD, NVIDIA)
+    /// - `gpu_{card}_decoder_usage`: decoder utilization in percent (AMD, NVIDIA)
+    /// - `gpu_{card}_video_usage`: utilization of the shared video engine in percent (Intel)
+    ///
+    /// The DRM client sensors are available from the second call on, and require access to the
+    /// processes using the GPU, i.e. running as root.
+    pub fn update_gpu_engine_sensors(
+        &mut self,
+        sensors: &mut HashMap<String, String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let cards = read_card_names(Path::new("/sys/class/drm"));
+
+        let now = Instant::now();
+        let busy = read_drm_client_usage(Path::new("/proc"));
+        if let Some(time) = self.gpu_engine_sample.time {
+            let elapsed = now.duration_since(time).as_nanos() as f64;
+            let mut usage = engine_usage(&self.gpu_engine_sample.busy, &busy, elapsed);
+            for (pdev, (_, driver)) in &cards {
+                let engines = DRIVER_ENGINES.iter().filter(|(d, _)| d == driver);
+                for engine in engines.flat_map(|(_, engines)| engines.iter()) {
+                    usage.entry((pdev.clone(), *engine)).or_insert(0.0);
+                }
+            }
+            for ((pdev, engine), usage) in usage {
+                let card = cards
+                    .get(&pdev)
+                    .map(|(card, _)| card.clone())
+                    .unwrap_or(pdev);
+                add_sensor(
+                    sensors,
+                    format!("gpu_{card}_{engine}_usage"),
+                    format!("{usage:.0}"),
+                );
+                add_sensor(sensors, format!("gpu_{card}_{engine}_usage#unit"), "%");
+            }
+        }
+        self.gpu_engine_sample = GpuEngineSample {
+            time: Some(now),
+            busy,
+        };
+
+        if Path::new("/proc/driver/nvidia").exists() {
+            for (index, (bus_id, encoder, decoder)) in get_nvidia_usage().into_iter().enumerate() {
+                let card = cards
+                    .get(&bus_id)
+                    .map(|(card, _)| card.clone())
+                    .unwrap_or_else(|| format!("nvidia{index}"));
+                for (engine, usage) in [("encoder", encoder), ("decoder", decoder)] {
+                    add_sensor(sensors, format!("gpu_{card}_{engine}_usage"), usage);
+                    add_sensor(sensors, format!("gpu_{card}_{engine}_usage#unit"), "%");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Map the PCI addresses of the GPUs to their DRM card name and driver, e.g. `0000:03:00.0` ->
+/// (`card1`, `amdgpu`).
+fn read_card_names(drm_path: &Path) -> HashMap<String, (String, String)> {
+    let Ok(entries) = fs::read_dir(drm_path) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // skip connectors like card0-DP-1
+            if !name.starts_with("card") || name.contains('-') {
+                return None;
+            }
+            let device = fs::canonicalize(entry.path().join("device")).ok()?;
+            let pdev = device.file_name()?.to_string_lossy().to_string();
+            let driver = fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|driver| Some(driver.file_name()?.to_string_lossy().to_string()))
+                .unwrap_or_default();
+            Some((pdev, (name, driver)))
+        })
+        .collect()
+}
+
+/// Read the video engine busy times of all DRM clients.
+///
+/// A client can be shared between processes and file descriptors, it is counted once.
+fn read_drm_client_usage(proc_path: &Path) -> EngineBusyTimes {
+    let mut busy = EngineBusyTimes::new();
+    let Ok(processes) = fs::read_dir(proc_path) else {
+        return busy;
+    };
+    for process in processes.flatten() {
+        if !process
+            .file_name()
+            .to_string_lossy()
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+        {
+            continue;
+        }
+        let Ok(fds) = fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if !fs::read_link(fd.path()).is_ok_and(|target| target.starts_with("/dev/dri")) {
+                continue;
+            }
+            let fdinfo = process.path().join("fdinfo").join(fd.file_name());
+            if let Ok(content) = fs::read_to_string(fdinfo) {
+                busy.extend(parse_fdinfo(&content));
+            }
+        }
+    }
+    busy
+}
+
+/// Parse the video engine busy times of a DRM file descriptor.
+///
+/// Example:
+/// ```text
+/// drm-driver:     amdgpu
+/// drm-client-id:  42
+/// drm-pdev:       0000:c5:00.0
+/// drm-engine-gfx: 84533000 ns
+/// drm-engine-dec: 1200000 ns
+/// ```
+fn parse_fdinfo(content: &str) -> EngineBusyTimes {
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let (k, v) = line.split_once(':')?;
+            (k.trim() == key).then(|| v.trim().to_string())
+        })
+    };
+    let (Some(pdev), Some(client_id)) = (value("drm-pdev"), value("drm-client-id")) else {
+        return EngineBusyTimes::new();
+    };
+
+    let mut busy = EngineBusyTimes::new();
+    for line in content.lines() {
+        let Some((engine, ns)) = line
+            .strip_prefix("drm-engine-")
+            .and_then(|line| line.split_once(':'))
+        else {
+            continue;
+        };
+        let Some((_, name)) = VIDEO_ENGINES.iter().find(|(e, _)| *e == engine.trim()) else {
+            continue;
+        };
+        let Some(ns) = ns.trim().trim_end_matches("ns").trim().parse::<u64>().ok() else {
+            continue;
+        };
+        *busy
+            .entry((pdev.clone(), client_id.clone(), *name))
+            .or_default() += ns;
+    }
+    busy
+}
+
+/// Calculate the engine utilization in percent per GPU from two samples.
+///
+/// Only clients of both samples are considered.
+fn engine_usage(
+    previous: &EngineBusyTimes,
+    current: &EngineBusyTimes,
+    elapsed_ns: f64,
+) -> HashMap<(String, &'static str), f64> {
+    let mut usage = HashMap::new();
+    for ((pdev, client_id, engine), busy) in current {
+        let entry = usage.entry((pdev.clone(), *engine)).or_insert(0.0);
+        if elapsed_ns > 0.0
+            && let Some(previous) = previous.get(&(pdev.clone(), client_id.clone(), *engine))
+        {
+            *entry += busy.saturating_sub(*previous) as f64 * 100.0 / elapsed_ns;
+        }
+    }
+    usage.values_mut().for_each(|u| *u = u.min(100.0));
+    usage
+}
+
+/// Query the encoder and decoder utilization of all NVIDIA GPUs.
+fn get_nvidia_usage() -> Vec<(String, u32, u32)> {
+    Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=pci.bus_id,utilization.encoder,utilization.decoder",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+        .inspect_err(|e| debug!("Unable to query NVIDIA GPUs with nvidia-smi: {e}"))
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parse the `nvidia-smi` CSV output, e.g. `00000000:01:00.0, 12, 3`.
+///
+/// The 32-bit PCI domain of the bus ID is converted to the 16-bit sysfs notation.
+fn parse_nvidia_smi(output: &str) -> Vec<(String, u32, u32)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(',').map(str::trim);
+            let bus_id = fields.next()?.to_lowercase();
+            let bus_id = match bus_id.split_once(':') {
+                Some((domain, rest)) if domain.len() > 4 => {
+                    format!("{}:{rest}", &domain[domain.len() - 4..])
+                }
+                _ => bus_id,
+            };
+            Some((
+                bus_id,
+                fields.next()?.parse().ok()?,
+                fields.next()?.parse().ok()?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FDINFO: &str = "pos:\t0
+flags:\t02100002
+drm-driver:\tamdgpu
+drm-client-id:\t42
+drm-pdev:\t0000:c5:00.0
+drm-memory-vram:\t1024 KiB
+drm-engine-gfx:\t84533000 ns
+drm-engine-dec:\t1200000 ns
+drm-engine-enc:\t500 ns
+drm-engine-enc_1:\t500 ns
+";
+
+    #[test]
+    fn parse_drm_fdinfo() {
+        let busy = parse_fdinfo(FDINFO);
+        let key = |engine| ("0000:c5:00.0".to_string(), "42".to_string(), engine);
+        assert_eq!(2, busy.len());
+        assert_eq!(Some(&1_200_000), busy.get(&key("decoder")));
+        assert_eq!(Some(&1000), busy.get(&key("encoder")));
+
+        assert!(parse_fdinfo("pos:\t0\nflags:\t02\n").is_empty());
+    }
+
+    #[test]
+    fn engine_usage_of_clients() {
+        let key = |client: &str| ("pdev".to_string(), client.to_string(), "decoder");
+        let previous = EngineBusyTimes::from([(key("1"), 1_000), (key("2"), 0)]);
+        let current = EngineBusyTimes::from([
+            (key("1"), 251_000),
+            (key("2"), 250_000),
+            // new client, counted with the next sample
+            (key("3"), 900_000),
+        ]);
+
+        let usage = engine_usage(&previous, &current, 1_000_000.0);
+        assert_eq!(Some(&50.0), usage.get(&("pdev".to_string(), "decoder")));
+    }
+
+    #[test]
+    fn parse_nvidia_smi_output() {
+        assert_eq!(
+            vec![("0000:01:00.0".to_string(), 12, 3)],
+            parse_nvidia_smi("00000000:01:00.0, 12, 3\n[N/A], [N/A], [N/A]\n")
+        );
+    }
+}
@@ -0,0 +1,226 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Linux hwmon sensor enumeration, similar to the `sensors` tool of lm-sensors.
+//!
+//! Reads fan speeds, voltages and power readings of all chips in `/sys/class/hwmon`.
+
+use crate::add_sensor;
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const HWMON_PATH: &str = "/sys/class/hwmon";
+
+/// hwmon sensor channel types exposed as sensors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Channel {
+    /// `fan*_input` in RPM
+    Fan,
+    /// `in*_input` in millivolts
+    Voltage,
+    /// `power*_input` or `power*_average` in microwatts
+    Power,
+}
+
+impl Channel {
+    /// Parse a hwmon attribute file name, e.g. `fan2_input`, into the channel type and id `fan2`.
+    fn from_file_name(name: &str) -> Option<(Self, &str)> {
+        let (id, average) = match name.strip_suffix("_input") {
+            Some(id) => (id, false),
+            None => (name.strip_suffix("_average")?, true),
+        };
+        let (channel, number) = if let Some(number) = id.strip_prefix("fan") {
+            (Channel::Fan, number)
+        } else if let Some(number) = id.strip_prefix("power") {
+            (Channel::Power, number)
+        } else if let Some(number) = id.strip_prefix("in") {
+            (Channel::Voltage, number)
+        } else {
+            return None;
+        };
+        if average && channel != Channel::Power
+            || number.is_empty()
+            || !number.chars().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        Some((channel, id))
+    }
+
+    fn key_prefix(&self) -> &'static str {
+        match self {
+            Channel::Fan => "fan",
+            Channel::Voltage => "voltage",
+            Channel::Power => "power",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Channel::Fan => "RPM",
+            Channel::Voltage => "V",
+            Channel::Power => "W",
+        }
+    }
+
+    fn format(&self, raw: i64) -> String {
+        match self {
+            Channel::Fan => raw.to_string(),
+            Channel::Voltage => format!("{:.3}", raw as f64 / 1000.0),
+            Channel::Power => format!("{:.2}", raw as f64 / 1_000_000.0),
+        }
+    }
+}
+
+/// Read fan, voltage and power sensors from all hwmon chips.
+///
+/// Sensor keys are qualified with the chip name: `{fan|voltage|power}_{chip}_{label}`.
+/// The label is taken from the channel's `*_label` file, or the channel name (e.g. `fan2`) if not
+/// available. Spaces are replaced with underscores. A `#unit` key is added for every sensor.
+///
+/// # Arguments
+///
+/// * `sensors`: sensor map to add the hwmon sensors to.
+/// * `labels`: optional rename map from a chip-qualified sensor key to a friendly sensor key.
+///   Example: `fan_nct6798_fan2` ⇒ `fan_cpu`
+///
+/// returns: Result<(), Box<dyn Error>>
+pub fn update_hwmon_sensors(
+    sensors: &mut HashMap<String, String>,
+    labels: &HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    read_hwmon_sensors(Path::new(HWMON_PATH), sensors, labels)
+}
+
+fn read_hwmon_sensors(
+    hwmon_path: &Path,
+    sensors: &mut HashMap<String, String>,
+    labels: &HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !hwmon_path.exists() {
+        debug!("No hwmon devices found");
+        return Ok(());
+    }
+
+    let mut chips: Vec<_> = fs::read_dir(hwmon_path)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    // hwmon0, hwmon1, ... for a stable chip order if the same chip name is used multiple times
+    chips.sort();
+
+    let mut chip_names: HashMap<String, u32> = HashMap::new();
+    for chip in chips {
+        let Ok(name) = fs::read_to_string(chip.join("name")) else {
+            continue;
+        };
+        let name = name.trim().replace(' ', "_");
+        let count = chip_names.entry(name.clone()).or_default();
+        let chip_name = if *count == 0 {
+            name
+        } else {
+            format!("{name}_{count}")
+        };
+        *count += 1;
+
+        let entries = match fs::read_dir(&chip) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("Unable to read hwmon chip {chip:?}: {e}");
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            let Some((channel, id)) = Channel::from_file_name(&file_name) else {
+                continue;
+            };
+            if file_name.ends_with("_average") && chip.join(format!("{id}_input")).exists() {
+                // prefer the instantaneous value if both are provided
+                continue;
+            }
+            let Some(raw) = fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+            else {
+                // some channels return an error (e.g. ENODATA) if not connected
+                continue;
+            };
+
+            let label = fs::read_to_string(chip.join(format!("{id}_label")))
+                .map(|l| l.trim().replace(' ', "_"))
+                .ok()
+                .filter(|l| !l.is_empty())
+                .unwrap_or_else(|| id.to_string());
+
+            let key = format!("{}_{chip_name}_{label}", channel.key_prefix());
+            let key = labels.get(&key).cloned().unwrap_or(key);
+            add_sensor(sensors, format!("{key}#unit"), channel.unit());
+            add_sensor(sensors, key, channel.format(raw));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn read_hwmon_sensors_with_labels_and_rename_map() {
+        let root = tempfile::tempdir().unwrap();
+        let chip = root.path().join("hwmon0");
+        write(&chip.join("name"), "nct6798\n");
+        write(&chip.join("fan1_input"), "1234\n");
+        write(&chip.join("fan2_input"), "800\n");
+        write(&chip.join("fan2_label"), "CPU FAN\n");
+        write(&chip.join("in0_input"), "1104\n");
+        write(&chip.join("fan1_min"), "100\n");
+        let gpu = root.path().join("hwmon1");
+        write(&gpu.join("name"), "amdgpu\n");
+        write(&gpu.join("power1_average"), "15250000\n");
+        write(&gpu.join("power1_label"), "PPT\n");
+
+        let labels = HashMap::from([("fan_nct6798_fan1".to_string(), "fan_case".to_string())]);
+        let mut sensors = HashMap::new();
+        read_hwmon_sensors(root.path(), &mut sensors, &labels).unwrap();
+
+        assert_eq!(Some(&"1234".to_string()), sensors.get("fan_case"));
+        assert_eq!(Some(&"RPM".to_string()), sensors.get("fan_case#unit"));
+        assert_eq!(Some(&"800".to_string()), sensors.get("fan_nct6798_CPU_FAN"));
+        assert_eq!(
+            Some(&"1.104".to_string()),
+            sensors.get("voltage_nct6798_in0")
+        );
+        assert_eq!(Some(&"15.25".to_string()), sensors.get("power_amdgpu_PPT"));
+        assert_eq!(Some(&"W".to_string()), sensors.get("power_amdgpu_PPT#unit"));
+        assert!(!sensors.contains_key("fan_nct6798_fan1"));
+        assert_eq!(8, sensors.len());
+    }
+
+    #[test]
+    fn duplicate_chip_names_are_numbered() {
+        let root = tempfile::tempdir().unwrap();
+        write(&root.path().join("hwmon2/name"), "nvme\n");
+        write(&root.path().join("hwmon2/in0_input"), "1000\n");
+        write(&root.path().join("hwmon3/name"), "nvme\n");
+        write(&root.path().join("hwmon3/in0_input"), "2000\n");
+
+        let mut sensors = HashMap::new();
+        read_hwmon_sensors(root.path(), &mut sensors, &HashMap::new()).unwrap();
+
+        assert_eq!(Some(&"1.000".to_string()), sensors.get("voltage_nvme_in0"));
+        assert_eq!(
+            Some(&"2.000".to_string()),
+            sensors.get("voltage_nvme_1_in0")
+        );
+    }
+}
@@ -9,16 +9,51 @@
 #![forbid(non_ascii_idents)]
 #![deny(unsafe_code)]
 
+mod audio;
+mod battery;
+mod bluetooth;
+mod freebsd;
+mod gpu;
+mod hwmon;
+mod maintenance;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+mod smc;
+mod thermal;
+mod wireless;
+mod workload;
+mod zfs;
+
+pub use audio::update_audio_sensors;
+pub use battery::{on_battery, update_battery_sensors};
+pub use bluetooth::update_bluetooth_sensors;
+pub use freebsd::{update_freebsd_storage_sensors, update_freebsd_temperature_sensors};
+pub use hwmon::update_hwmon_sensors;
+pub use maintenance::update_maintenance_sensors;
+pub use smc::SmcSource;
+pub use thermal::update_thermal_zone_sensors;
+pub use wireless::update_wireless_sensors;
+pub use zfs::update_zfs_sensors;
+
 use log::{debug, error, info};
 use regex::Regex;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::path::Path;
 use std::process::Command;
 use std::time::{Duration, Instant};
 use sysinfo::{Components, DiskKind, Disks, Networks, System};
 
+/// Default preference order of the chips reporting the CPU temperature, see
+/// [SysinfoSource::set_cpu_temperature_sources].
+pub const CPU_TEMPERATURE_SOURCES: [&str; 5] =
+    ["zenpower", "k10temp", "coretemp", "cpu_thermal", "acpitz"];
+
+/// Temperature labels of a chip which report the CPU package or control temperature.
+const CPU_TEMPERATURE_LABELS: [&str; 3] = ["Tctl", "Tdie", "Package id"];
+
 pub struct SysinfoSource {
     sys: System,
     disks: Disks,
@@ -26,6 +61,11 @@ pub struct SysinfoSource {
     networks: Networks,
     last_refresh: Option<Instant>,
     refresh_duration: Option<Duration>,
+    workload_interfaces: workload::WorkloadInterfaces,
+    gpu_engine_sample: gpu::GpuEngineSample,
+    cpu_temperature_sources: Vec<String>,
+    #[cfg(target_os = "macos")]
+    smc: Option<SmcSource>,
 }
 
 impl Default for SysinfoSource {
@@ -43,9 +83,25 @@ impl SysinfoSource {
             networks: Networks::new_with_refreshed_list(),
             last_refresh: None,
             refresh_duration: None,
+            workload_interfaces: Default::default(),
+            gpu_engine_sample: Default::default(),
+            cpu_temperature_sources: CPU_TEMPERATURE_SOURCES.map(String::from).into(),
+            #[cfg(target_os = "macos")]
+            smc: SmcSource::open()
+                .map_err(|e| info!("No SMC sensors: {e}"))
+                .ok(),
         }
     }
 
+    /// Set the preference order of the chips reporting the CPU temperature, e.g. `k10temp` or
+    /// `acpitz`. Defaults to [CPU_TEMPERATURE_SOURCES].
+    ///
+    /// The first available chip of the list provides the `temperature_cpu` sensor, the
+    /// temperatures of the other chips keep their chip-qualified sensor keys.
+    pub fn set_cpu_temperature_sources(&mut self, sources: Vec<String>) {
+        self.cpu_temperature_sources = sources;
+    }
+
     pub fn refresh(&mut self) {
         self.sys.refresh_all();
         debug!("Refreshing disks, components, networks");
@@ -60,6 +116,29 @@ impl SysinfoSource {
         self.last_refresh = Some(Instant::now());
     }
 
+    /// Add static system identity sensors, which don't change while running.
+    ///
+    /// These are only read once and not part of [update_sensors](Self::update_sensors).
+    pub fn update_static_sensors(&self, sensors: &mut HashMap<String, String>) {
+        if let Some(cpu) = self.sys.cpus().first() {
+            add_sensor(sensors, "cpu_model", cpu.brand().trim());
+            add_sensor(sensors, "cpu_vendor", cpu.vendor_id());
+        }
+        if let Some(cores) = System::physical_core_count() {
+            add_sensor(sensors, "cpu_physical_cores", cores);
+        }
+        add_sensor(sensors, "system_arch", System::cpu_arch());
+        // e.g. "Debian GNU/Linux 12" instead of "Linux (Debian GNU/Linux 12)"
+        let distro = match (System::name(), System::os_version()) {
+            (Some(name), Some(version)) => Some(format!("{name} {version}")),
+            _ => System::long_os_version(),
+        };
+        if let Some(distro) = distro {
+            add_sensor(sensors, "system_distro", distro);
+        }
+        add_sensor(sensors, "system_distro_id", System::distribution_id());
+    }
+
     pub fn update_sensors(
         &self,
         sensors: &mut HashMap<String, String>,
@@ -217,27 +296,46 @@ impl SysinfoSource {
         }
 
         // Components temperature:
-        for component in &self.components {
-            if let Some(temperature) = component.temperature() {
-                let label;
-                if component.label().contains("spd5118") {
-                    label = "temperature_memory".to_string();
-                } else if component.label().contains("amdgpu") {
-                    label = "temperature_gpu".to_string();
-                } else if component.label().contains("Tctl") {
-                    label = "temperature_cpu".to_string();
-                } else if component.label().contains("Composite")
-                    && !component.label().contains("nvme")
-                {
-                    // just a guess...
-                    label = "temperature_motherboard".to_string();
-                } else {
-                    label = format!("temperature_{}", component.label().replace(' ', "_"));
-                }
-
-                add_sensor(sensors, format!("{label}#unit"), "°C");
-                add_sensor(sensors, label, format!("{temperature:.1}"));
+        let temperatures: Vec<(&str, f32)> = self
+            .components
+            .iter()
+            .filter_map(|c| Some((c.label(), c.temperature()?)))
+            .collect();
+        let labels: Vec<&str> = temperatures.iter().map(|(label, _)| *label).collect();
+        let cpu_label = cpu_temperature_label(&labels, &self.cpu_temperature_sources);
+        for (component_label, temperature) in temperatures {
+            let label;
+            if Some(component_label) == cpu_label {
+                label = "temperature_cpu".to_string();
+            } else if component_label.contains("spd5118") {
+                label = "temperature_memory".to_string();
+            } else if component_label.contains("amdgpu") {
+                label = "temperature_gpu".to_string();
+            } else if component_label.contains("Composite") && !component_label.contains("nvme") {
+                // just a guess...
+                label = "temperature_motherboard".to_string();
+            } else {
+                label = format!("temperature_{}", component_label.replace(' ', "_"));
             }
+
+            add_sensor(sensors, format!("{label}#unit"), "°C");
+            add_sensor(sensors, label, format!("{temperature:.1}"));
+        }
+        // single-board computers may only provide a CPU thermal zone
+        if cpu_label.is_none()
+            && let Some(temperature) =
+                thermal::cpu_zone_temperature(Path::new("/sys/class/thermal"))
+        {
+            add_sensor(sensors, "temperature_cpu#unit", "°C");
+            add_sensor(sensors, "temperature_cpu", format!("{temperature:.1}"));
+        }
+        // FreeBSD CPU components are labelled per core, e.g. `CPU 0`
+        #[cfg(target_os = "freebsd")]
+        update_freebsd_temperature_sensors(sensors)?;
+        // macOS components don't include the fans and a CPU temperature
+        #[cfg(target_os = "macos")]
+        if let Some(smc) = &self.smc {
+            smc.update_sensors(sensors);
         }
 
         // Network interfaces name, total data received and total data transmitted:
@@ -266,18 +364,33 @@ impl SysinfoSource {
                 );
             }
 
+            // Primary addresses, empty if the interface lost its address
+            let (ipv4, ipv6) = primary_addresses(data.ip_networks().iter().map(|net| net.addr));
+            add_sensor(
+                sensors,
+                format!("network_{interface_name}_ipv4"),
+                ipv4.map(|addr| addr.to_string()).unwrap_or_default(),
+            );
+            add_sensor(
+                sensors,
+                format!("network_{interface_name}_ipv6"),
+                ipv6.map(|addr| addr.to_string()).unwrap_or_default(),
+            );
+
             if let Some(refresh) = self.refresh_duration {
                 let interval = refresh.as_millis() as u64;
-                if interval > 0 {
+                if let Some(received) = (1000 * data.received()).checked_div(interval) {
                     add_sensor(
                         sensors,
                         format!("network_{interface_name}_download_speed"),
-                        format!("{}/s", format_bytes(1000 * data.received() / interval)),
+                        format!("{}/s", format_bytes(received)),
                     );
+                }
+                if let Some(transmitted) = (1000 * data.transmitted()).checked_div(interval) {
                     add_sensor(
                         sensors,
                         format!("network_{interface_name}_upload_speed"),
-                        format!("{}/s", format_bytes(1000 * data.transmitted() / interval)),
+                        format!("{}/s", format_bytes(transmitted)),
                     );
                 }
             }
@@ -308,6 +421,65 @@ impl SysinfoSource {
     }
 }
 
+/// Select the primary IPv4 and IPv6 address of an interface.
+///
+/// Global addresses are preferred over unique local (`fc00::/7`) and link-local addresses
+/// (`169.254.0.0/16`, `fe80::/10`). Among equally ranked addresses, the lowest address wins to get
+/// a stable result between refreshes.
+fn primary_addresses(addrs: impl Iterator<Item = IpAddr>) -> (Option<Ipv4Addr>, Option<Ipv6Addr>) {
+    let mut ipv4: Option<Ipv4Addr> = None;
+    let mut ipv6: Option<Ipv6Addr> = None;
+    for addr in addrs {
+        match addr {
+            IpAddr::V4(addr) if !addr.is_loopback() && !addr.is_unspecified() => {
+                let rank = |a: &Ipv4Addr| (a.is_link_local(), *a);
+                if ipv4.is_none_or(|current| rank(&addr) < rank(&current)) {
+                    ipv4 = Some(addr);
+                }
+            }
+            IpAddr::V6(addr) if !addr.is_loopback() && !addr.is_unspecified() => {
+                let rank = |a: &Ipv6Addr| {
+                    let scope = if a.is_unicast_link_local() {
+                        2
+                    } else if a.is_unique_local() {
+                        1
+                    } else {
+                        0
+                    };
+                    (scope, *a)
+                };
+                if ipv6.is_none_or(|current| rank(&addr) < rank(&current)) {
+                    ipv6 = Some(addr);
+                }
+            }
+            _ => {}
+        }
+    }
+    (ipv4, ipv6)
+}
+
+/// Elect the component label of the CPU temperature.
+///
+/// The first chip of `sources` with a temperature component wins, its CPU package or control
+/// temperature is preferred over other temperatures of the chip. Component labels start with the
+/// chip name, e.g. `k10temp Tctl` or `acpitz temp1`. Without a preferred chip, the first `Tctl`
+/// temperature is used.
+fn cpu_temperature_label<'a>(labels: &[&'a str], sources: &[String]) -> Option<&'a str> {
+    let is_cpu_label = |label: &str| CPU_TEMPERATURE_LABELS.iter().any(|l| label.contains(l));
+    for source in sources {
+        let chip: Vec<&str> = labels
+            .iter()
+            .copied()
+            .filter(|label| label.split(' ').next() == Some(source.as_str()))
+            .collect();
+        let cpu_label = chip.iter().find(|label| is_cpu_label(label));
+        if let Some(label) = cpu_label.or(chip.first()) {
+            return Some(label);
+        }
+    }
+    labels.iter().copied().find(|label| label.contains("Tctl"))
+}
+
 pub fn add_sensor(
     sensors: &mut HashMap<String, String>,
     label: impl Into<String>,
@@ -316,6 +488,21 @@ pub fn add_sensor(
     sensors.insert(label.into(), value.to_string());
 }
 
+/// Read the storage devices of the operating system and the ZFS pools.
+///
+/// The storage devices are read with [update_freebsd_storage_sensors] on FreeBSD and with
+/// [update_linux_storage_sensors] on other systems.
+pub fn update_storage_sensors(
+    sensors: &mut HashMap<String, String>,
+    use_smartctl: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(target_os = "freebsd")]
+    update_freebsd_storage_sensors(sensors, use_smartctl)?;
+    #[cfg(not(target_os = "freebsd"))]
+    update_linux_storage_sensors(sensors, use_smartctl)?;
+    update_zfs_sensors(sensors)
+}
+
 pub fn update_linux_storage_sensors(
     sensors: &mut HashMap<String, String>,
     use_smartctl: bool,
@@ -658,4 +845,59 @@ mod tests {
         assert_eq!(format_bytes(1048576), "1.00 MB");
         assert_eq!(format_bytes(1073741824), "1.00 GB");
     }
+
+    #[test]
+    fn elect_cpu_temperature() {
+        let labels = [
+            "acpitz temp1",
+            "k10temp Tccd1",
+            "k10temp Tctl",
+            "zenpower Tdie",
+            "nvme Composite",
+        ];
+        let sources = CPU_TEMPERATURE_SOURCES.map(String::from);
+        assert_eq!(
+            Some("zenpower Tdie"),
+            cpu_temperature_label(&labels, &sources)
+        );
+        let sources = ["k10temp".to_string()];
+        assert_eq!(
+            Some("k10temp Tctl"),
+            cpu_temperature_label(&labels, &sources)
+        );
+        let sources = ["coretemp".to_string(), "acpitz".to_string()];
+        assert_eq!(
+            Some("acpitz temp1"),
+            cpu_temperature_label(&labels, &sources)
+        );
+        assert_eq!(Some("k10temp Tctl"), cpu_temperature_label(&labels, &[]));
+        assert_eq!(None, cpu_temperature_label(&labels[3..], &[]));
+    }
+
+    #[test]
+    fn primary_addresses_prefer_global_addresses() {
+        let addrs = [
+            "fe80::1",
+            "169.254.3.4",
+            "fd00::2",
+            "192.168.1.20",
+            "2001:db8::5",
+            "10.0.0.7",
+            "2001:db8::3",
+        ]
+        .map(|a| a.parse::<IpAddr>().unwrap());
+        assert_eq!(
+            (
+                Some("10.0.0.7".parse().unwrap()),
+                Some("2001:db8::3".parse().unwrap())
+            ),
+            primary_addresses(addrs.into_iter())
+        );
+
+        let addrs = ["fe80::1", "fd00::2"].map(|a| a.parse::<IpAddr>().unwrap());
+        assert_eq!(
+            (None, Some("fd00::2".parse().unwrap())),
+            primary_addresses(addrs.into_iter())
+        );
+    }
 }
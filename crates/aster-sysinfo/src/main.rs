@@ -5,13 +5,20 @@
 #![forbid(non_ascii_idents)]
 #![deny(unsafe_code)]
 
-use aster_sysinfo::{SysinfoSource, update_linux_storage_sensors};
-use clap::Parser;
+#[cfg(feature = "mqtt")]
+use aster_sysinfo::mqtt::{self, MqttConfig, MqttPublisher};
+use aster_sysinfo::{
+    SysinfoSource, update_audio_sensors, update_battery_sensors, update_bluetooth_sensors,
+    update_hwmon_sensors, update_maintenance_sensors, update_storage_sensors,
+    update_thermal_zone_sensors, update_wireless_sensors,
+};
+use chrono::{DateTime, Local, SecondsFormat};
+use clap::{Parser, ValueEnum};
 use env_logger::Env;
 use itertools::Itertools;
 use log::{debug, info};
 use std::collections::HashMap;
-use std::fs;
+use std::fs::{self, OpenOptions};
 use std::io::{BufWriter, Write};
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
@@ -28,6 +35,10 @@ struct Args {
     #[arg(short, long)]
     out: Option<PathBuf>,
 
+    /// Format of the output sensor file.
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Kv)]
+    format: OutputFormat,
+
     /// Temporary directory for preparing the output sensor file.
     ///
     /// The system temp directory is used if not specified.
@@ -35,6 +46,12 @@ struct Args {
     #[arg(short, long)]
     temp_dir: Option<PathBuf>,
 
+    /// Append timestamped sensor snapshots to a JSON lines file, e.g. for `asterctl --replay`.
+    ///
+    /// The first snapshot contains all sensor values, the following ones only the changed values.
+    #[arg(long)]
+    record: Option<PathBuf>,
+
     /// Print values in console
     #[arg(long)]
     console: bool,
@@ -50,18 +67,51 @@ struct Args {
     /// Retrieve drive temperature if `disk-update` option is enabled.
     ///
     /// Requires smartctl and password-less sudo!
-    #[cfg(target_os = "linux")]
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     #[arg(long)]
     smartctl: bool,
+
+    /// Publish sensor values to an MQTT broker: `mqtt://[user[:password]@]host[:port]`.
+    ///
+    /// The password can also be set with the `MQTT_PASSWORD` environment variable.
+    #[cfg(feature = "mqtt")]
+    #[arg(long)]
+    mqtt: Option<String>,
+
+    /// MQTT topic prefix of the sensor values. Default: `aster-sysinfo/{hostname}`
+    #[cfg(feature = "mqtt")]
+    #[arg(long)]
+    mqtt_topic: Option<String>,
+
+    /// Home Assistant MQTT discovery prefix.
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value = mqtt::DEFAULT_DISCOVERY_PREFIX)]
+    mqtt_discovery_prefix: String,
+
+    /// Don't publish Home Assistant MQTT discovery messages.
+    #[cfg(feature = "mqtt")]
+    #[arg(long)]
+    no_mqtt_discovery: bool,
+}
+
+/// Output sensor file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// `key: value` lines, as read by the asterctl text file data source.
+    Kv,
+    /// JSON object with a `{"value": ..., "unit": ...}` object per sensor.
+    Json,
+    /// CSV with `key,value,unit` columns.
+    Csv,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
     let args = Args::parse();
-    #[cfg(target_os = "linux")]
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     let use_smartctl = args.smartctl;
-    #[cfg(not(target_os = "linux"))]
+    #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
     let use_smartctl = false;
 
     if let Some(out_file) = &args.out
@@ -71,15 +121,47 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     let mut sensors = HashMap::with_capacity(64);
     let mut sysinfo_source = SysinfoSource::new();
+    sysinfo_source.update_static_sensors(&mut sensors);
 
     let refresh = Duration::from_secs(args.refresh.unwrap_or_default() as u64);
 
     let disk_refresh = Duration::from_secs(args.disk_refresh.unwrap_or_default() as u64);
     let mut disk_refresh_time = Instant::now();
     if !disk_refresh.is_zero() {
-        update_linux_storage_sensors(&mut sensors, use_smartctl)?;
+        update_storage_sensors(&mut sensors, use_smartctl)?;
     }
 
+    #[cfg(feature = "mqtt")]
+    let mut mqtt_publisher = match &args.mqtt {
+        Some(_) if refresh.is_zero() => {
+            log::error!("MQTT publisher requires a refresh interval");
+            exit(1);
+        }
+        Some(url) => {
+            let host_name = sysinfo::System::host_name().unwrap_or_else(|| "localhost".into());
+            let mut cfg = MqttConfig::from_url(url, &host_name)?;
+            if cfg.password.is_none() {
+                cfg.password = std::env::var("MQTT_PASSWORD").ok();
+            }
+            if let Some(topic) = &args.mqtt_topic {
+                cfg.topic_prefix = topic.trim_end_matches('/').to_string();
+            }
+            cfg.discovery_prefix =
+                (!args.no_mqtt_discovery).then(|| args.mqtt_discovery_prefix.clone());
+            Some(MqttPublisher::connect(cfg)?)
+        }
+        None => None,
+    };
+
+    let mut recording = match &args.record {
+        Some(path) => {
+            info!("Recording sensor snapshots to {}", path.display());
+            Some(OpenOptions::new().create(true).append(true).open(path)?)
+        }
+        None => None,
+    };
+    let mut recorded = HashMap::new();
+
     if !refresh.is_zero() {
         info!(
             "Starting aster-sysinfo with refresh={}ms",
@@ -92,15 +174,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         sysinfo_source.refresh();
         sysinfo_source.update_sensors(&mut sensors)?;
+        sysinfo_source.update_workload_sensors(&mut sensors)?;
+        sysinfo_source.update_gpu_engine_sensors(&mut sensors)?;
+        update_hwmon_sensors(&mut sensors, &HashMap::new())?;
+        update_thermal_zone_sensors(&mut sensors)?;
+        update_wireless_sensors(&mut sensors)?;
+        update_bluetooth_sensors(&mut sensors)?;
+        update_battery_sensors(&mut sensors)?;
+        update_audio_sensors(&mut sensors)?;
+        update_maintenance_sensors(&mut sensors)?;
 
         if !disk_refresh.is_zero() && disk_refresh_time.elapsed() > disk_refresh {
             debug!("Refreshing individual disks");
-            update_linux_storage_sensors(&mut sensors, use_smartctl)?;
+            update_storage_sensors(&mut sensors, use_smartctl)?;
             disk_refresh_time = Instant::now();
         }
 
         if let Some(out_file) = &args.out {
-            write_sensor_file(out_file, args.temp_dir.as_deref(), &sensors)?;
+            write_sensor_file(out_file, args.temp_dir.as_deref(), args.format, &sensors)?;
+        }
+
+        if let Some(recording) = &mut recording {
+            let mut stream = BufWriter::new(recording);
+            write_snapshot(&mut stream, Local::now(), &sensors, &mut recorded)?;
+            stream.flush()?;
+        }
+
+        #[cfg(feature = "mqtt")]
+        if let Some(publisher) = &mut mqtt_publisher {
+            publisher.publish(&sensors);
         }
 
         if args.console {
@@ -127,6 +229,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 fn write_sensor_file(
     out_file: &Path,
     temp_dir: Option<&Path>,
+    format: OutputFormat,
     sensors: &HashMap<String, String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if out_file.is_dir() {
@@ -151,9 +254,7 @@ fn write_sensor_file(
     debug!("Writing sensor temp file...");
     let mut stream = BufWriter::new(&tmp_file);
 
-    for (label, value) in sensors.iter() {
-        writeln!(stream, "{label}: {value}")?;
-    }
+    write_sensors(&mut stream, format, sensors)?;
 
     stream.flush()?;
     drop(stream);
@@ -162,3 +263,173 @@ fn write_sensor_file(
 
     Ok(())
 }
+
+fn write_sensors(
+    out: &mut impl Write,
+    format: OutputFormat,
+    sensors: &HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // unit metadata is folded into the sensor entries of structured formats
+    let unit = |label: &str| sensors.get(&format!("{label}#unit"));
+    let values = sensors
+        .iter()
+        .filter(|(label, _)| format == OutputFormat::Kv || !label.ends_with("#unit"))
+        .sorted();
+
+    match format {
+        OutputFormat::Kv => {
+            for (label, value) in values {
+                writeln!(out, "{label}: {value}")?;
+            }
+        }
+        OutputFormat::Json => {
+            let json: serde_json::Map<String, serde_json::Value> = values
+                .map(|(label, value)| {
+                    let mut entry = serde_json::json!({ "value": value });
+                    if let Some(unit) = unit(label) {
+                        entry["unit"] = unit.as_str().into();
+                    }
+                    (label.clone(), entry)
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut *out, &json)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "key,value,unit")?;
+            for (label, value) in values {
+                let unit = unit(label).map(String::as_str).unwrap_or_default();
+                writeln!(
+                    out,
+                    "{},{},{}",
+                    csv_field(label),
+                    csv_field(value),
+                    csv_field(unit)
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a timestamped sensor snapshot as a JSON line of a sensor recording.
+///
+/// Only the values changed since the `recorded` values are written, and the `recorded` values are
+/// updated. Snapshots without changes are skipped.
+fn write_snapshot(
+    out: &mut impl Write,
+    time: DateTime<Local>,
+    sensors: &HashMap<String, String>,
+    recorded: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let values: serde_json::Map<String, serde_json::Value> = sensors
+        .iter()
+        .filter(|(label, value)| recorded.get(*label) != Some(*value))
+        .map(|(label, value)| (label.clone(), value.as_str().into()))
+        .collect();
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    let snapshot = serde_json::json!({
+        "time": time.to_rfc3339_opts(SecondsFormat::Millis, false),
+        "values": values,
+    });
+    serde_json::to_writer(&mut *out, &snapshot)?;
+    writeln!(out)?;
+    recorded.clone_from(sensors);
+
+    Ok(())
+}
+
+/// Quote a CSV field if required, see RFC 4180.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensors() -> HashMap<String, String> {
+        HashMap::from([
+            ("temperature_cpu".to_string(), "45.0".to_string()),
+            ("temperature_cpu#unit".to_string(), "°C".to_string()),
+            ("wifi_wlan0_ssid".to_string(), "Home, \"5G\"".to_string()),
+        ])
+    }
+
+    fn write(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_sensors(&mut out, format, &sensors()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn write_sensors_as_kv() {
+        assert_eq!(
+            "temperature_cpu: 45.0\ntemperature_cpu#unit: °C\nwifi_wlan0_ssid: Home, \"5G\"\n",
+            write(OutputFormat::Kv)
+        );
+    }
+
+    #[test]
+    fn write_sensors_as_json_with_units() {
+        let json: serde_json::Value = serde_json::from_str(&write(OutputFormat::Json)).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "temperature_cpu": { "value": "45.0", "unit": "°C" },
+                "wifi_wlan0_ssid": { "value": "Home, \"5G\"" },
+            }),
+            json
+        );
+    }
+
+    #[test]
+    fn write_sensors_as_csv() {
+        assert_eq!(
+            "key,value,unit\ntemperature_cpu,45.0,°C\nwifi_wlan0_ssid,\"Home, \"\"5G\"\"\",\n",
+            write(OutputFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn write_changed_values_in_snapshots() {
+        let time = DateTime::parse_from_rfc3339("2026-10-17T12:00:00+02:00")
+            .unwrap()
+            .with_timezone(&Local);
+        let mut sensors = sensors();
+        let mut recorded = HashMap::new();
+        let mut out = Vec::new();
+
+        write_snapshot(&mut out, time, &sensors, &mut recorded).unwrap();
+        write_snapshot(&mut out, time, &sensors, &mut recorded).unwrap();
+        sensors.insert("temperature_cpu".to_string(), "47.0".to_string());
+        write_snapshot(&mut out, time, &sensors, &mut recorded).unwrap();
+
+        let snapshots: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(2, snapshots.len());
+        assert_eq!(3, snapshots[0]["values"].as_object().unwrap().len());
+        assert_eq!(
+            serde_json::json!({ "temperature_cpu": "47.0" }),
+            snapshots[1]["values"]
+        );
+        let time = snapshots[1]["time"].as_str().unwrap();
+        assert_eq!(
+            "2026-10-17T10:00:00Z",
+            DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .to_utc()
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Maintenance sensors for server dashboards: last reboot, last package upgrade and pending reboot.
+//!
+//! The last upgrade time is the modification time of the package database of dpkg, rpm, pacman or
+//! apk. A pending reboot is detected with the `/run/reboot-required` flag file of Debian and
+//! Ubuntu, or by missing kernel modules of the running kernel after a kernel upgrade.
+
+use crate::add_sensor;
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::System;
+
+/// Package databases, modified on every package installation or upgrade.
+const PACKAGE_DATABASES: [&str; 6] = [
+    "var/lib/dpkg/status",
+    "var/lib/rpm/rpmdb.sqlite",
+    "var/lib/rpm/Packages",
+    "usr/lib/sysimage/rpm/rpmdb.sqlite",
+    "var/lib/pacman/local",
+    "lib/apk/db/installed",
+];
+
+/// Date/time format of the timestamp sensors.
+const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Read maintenance sensors.
+///
+/// Sensor keys:
+/// - `system_boot_time`: local date and time of the last boot, e.g. `2026-10-16 08:15`
+/// - `system_boot_timestamp`: last boot as Unix timestamp
+/// - `system_last_upgrade`: local date and time of the last package installation or upgrade
+/// - `system_last_upgrade_days`: days since the last package installation or upgrade
+/// - `system_reboot_required`: 1 if a reboot is required to apply upgrades, 0 otherwise
+pub fn update_maintenance_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let boot_time = UNIX_EPOCH + Duration::from_secs(System::boot_time());
+    read_maintenance_sensors(
+        Path::new("/"),
+        System::kernel_version().as_deref(),
+        boot_time,
+        SystemTime::now(),
+        sensors,
+    );
+    Ok(())
+}
+
+fn read_maintenance_sensors(
+    root: &Path,
+    kernel_release: Option<&str>,
+    boot_time: SystemTime,
+    now: SystemTime,
+    sensors: &mut HashMap<String, String>,
+) {
+    add_sensor(sensors, "system_boot_time", format_time(boot_time));
+    add_sensor(
+        sensors,
+        "system_boot_timestamp",
+        boot_time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    );
+
+    let last_upgrade = PACKAGE_DATABASES
+        .iter()
+        .filter_map(|db| fs::metadata(root.join(db)).and_then(|m| m.modified()).ok())
+        .max();
+    if let Some(last_upgrade) = last_upgrade {
+        let age = now.duration_since(last_upgrade).unwrap_or_default();
+        add_sensor(sensors, "system_last_upgrade", format_time(last_upgrade));
+        add_sensor(sensors, "system_last_upgrade_days", age.as_secs() / 86400);
+        add_sensor(sensors, "system_last_upgrade_days#unit", "d");
+    }
+
+    add_sensor(
+        sensors,
+        "system_reboot_required",
+        u8::from(reboot_required(root, kernel_release)),
+    );
+}
+
+fn reboot_required(root: &Path, kernel_release: Option<&str>) -> bool {
+    if root.join("run/reboot-required").exists() || root.join("var/run/reboot-required").exists() {
+        return true;
+    }
+    // Arch & co. remove the modules of the running kernel on upgrade
+    let modules = root.join("lib/modules");
+    if let Some(release) = kernel_release
+        && modules.is_dir()
+        && fs::read_dir(&modules).is_ok_and(|mut entries| entries.next().is_some())
+    {
+        return !modules.join(release).exists();
+    }
+    false
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time)
+        .format(DATE_TIME_FORMAT)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn maintenance_sensors_from_package_database_and_kernel_modules() {
+        let root = tempfile::tempdir().unwrap();
+        write(&root.path().join("var/lib/dpkg/status"), "Package: foo\n");
+        fs::create_dir_all(root.path().join("lib/modules/6.1.0-21-amd64")).unwrap();
+
+        let modified = fs::metadata(root.path().join("var/lib/dpkg/status"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        let now = modified + Duration::from_secs(3 * 86400 + 100);
+        let boot_time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut sensors = HashMap::new();
+        read_maintenance_sensors(
+            root.path(),
+            Some("6.1.0-21-amd64"),
+            boot_time,
+            now,
+            &mut sensors,
+        );
+        assert_eq!(
+            Some(&"1700000000".to_string()),
+            sensors.get("system_boot_timestamp")
+        );
+        assert_eq!(
+            Some(&"3".to_string()),
+            sensors.get("system_last_upgrade_days")
+        );
+        assert_eq!(
+            Some(&"0".to_string()),
+            sensors.get("system_reboot_required")
+        );
+
+        // kernel upgrade removed the modules of the running kernel
+        read_maintenance_sensors(
+            root.path(),
+            Some("6.1.0-18-amd64"),
+            boot_time,
+            now,
+            &mut sensors,
+        );
+        assert_eq!(
+            Some(&"1".to_string()),
+            sensors.get("system_reboot_required")
+        );
+
+        write(&root.path().join("run/reboot-required"), "");
+        assert!(reboot_required(root.path(), None));
+    }
+}
@@ -0,0 +1,303 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! MQTT publisher with Home Assistant MQTT discovery.
+//!
+//! Publishes every sensor as retained message to `{topic_prefix}/{sensor}` and announces it to Home
+//! Assistant with a discovery message in `{discovery_prefix}/sensor/{node_id}/{sensor}/config`.
+//! The availability topic `{topic_prefix}/status` is set to `offline` by the broker when the
+//! connection is lost.
+
+use log::{debug, info, warn};
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Default MQTT broker port.
+pub const DEFAULT_PORT: u16 = 1883;
+/// Default Home Assistant discovery prefix.
+pub const DEFAULT_DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// MQTT publisher configuration.
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Unique node ID, used as MQTT client ID and Home Assistant device identifier.
+    pub node_id: String,
+    /// Device name shown in Home Assistant.
+    pub device_name: String,
+    /// Topic prefix of the sensor values.
+    pub topic_prefix: String,
+    /// Home Assistant discovery prefix, or `None` to disable discovery.
+    pub discovery_prefix: Option<String>,
+}
+
+impl MqttConfig {
+    /// Create a configuration from a broker URL: `mqtt://[user[:password]@]host[:port]`.
+    ///
+    /// The node ID and default topic prefix `aster-sysinfo/{node_id}` are derived from the host
+    /// name.
+    pub fn from_url(url: &str, host_name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let address = url.strip_prefix("mqtt://").unwrap_or(url);
+        let (credentials, address) = match address.rsplit_once('@') {
+            Some((credentials, address)) => (Some(credentials), address),
+            None => (None, address),
+        };
+        let (username, password) = match credentials.map(|c| c.split_once(':')) {
+            Some(Some((user, password))) => (Some(user.to_string()), Some(password.to_string())),
+            Some(None) => (credentials.map(str::to_string), None),
+            None => (None, None),
+        };
+        let address = address.trim_end_matches('/');
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse()?),
+            None => (address, DEFAULT_PORT),
+        };
+        if host.is_empty() {
+            return Err(format!("Missing MQTT broker host in {url}").into());
+        }
+
+        let node_id = topic_level(host_name);
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            username,
+            password,
+            topic_prefix: format!("aster-sysinfo/{node_id}"),
+            node_id,
+            device_name: host_name.to_string(),
+            discovery_prefix: Some(DEFAULT_DISCOVERY_PREFIX.to_string()),
+        })
+    }
+}
+
+/// Publishes sensor values to an MQTT broker.
+pub struct MqttPublisher {
+    cfg: MqttConfig,
+    client: Client,
+    /// Connection to the broker is established.
+    connected: Arc<AtomicBool>,
+    /// Set after (re)connects and Home Assistant restarts to publish everything again.
+    announce: Arc<AtomicBool>,
+    /// Last published sensor values.
+    published: HashMap<String, String>,
+}
+
+impl MqttPublisher {
+    /// Connect to the MQTT broker.
+    ///
+    /// The connection is established and maintained in a background thread.
+    pub fn connect(cfg: MqttConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let status_topic = format!("{}/status", cfg.topic_prefix);
+        let mut options = MqttOptions::new(
+            format!("aster-sysinfo-{}", cfg.node_id),
+            &cfg.host,
+            cfg.port,
+        );
+        options
+            .set_keep_alive(Duration::from_secs(30))
+            .set_last_will(LastWill::new(
+                &status_topic,
+                "offline",
+                QoS::AtLeastOnce,
+                true,
+            ));
+        if let Some(username) = &cfg.username {
+            options.set_credentials(username, cfg.password.clone().unwrap_or_default());
+        }
+
+        let (client, mut connection) = Client::new(options, 256);
+        let connected = Arc::new(AtomicBool::new(false));
+        let announce = Arc::new(AtomicBool::new(false));
+        let ha_status_topic = cfg
+            .discovery_prefix
+            .as_ref()
+            .map(|prefix| format!("{prefix}/status"));
+
+        info!("Connecting to MQTT broker {}:{}", cfg.host, cfg.port);
+        let thread_client = client.clone();
+        let thread_connected = connected.clone();
+        let thread_announce = announce.clone();
+        std::thread::Builder::new()
+            .name("mqtt".into())
+            .spawn(move || {
+                for event in connection.iter() {
+                    match event {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            info!("Connected to MQTT broker");
+                            let _ = thread_client.try_publish(
+                                &status_topic,
+                                QoS::AtLeastOnce,
+                                true,
+                                "online",
+                            );
+                            if let Some(topic) = &ha_status_topic {
+                                let _ = thread_client.try_subscribe(topic, QoS::AtMostOnce);
+                            }
+                            thread_announce.store(true, Ordering::Relaxed);
+                            thread_connected.store(true, Ordering::Relaxed);
+                        }
+                        Ok(Event::Incoming(Packet::Publish(publish)))
+                            if Some(&publish.topic) == ha_status_topic.as_ref()
+                                && publish.payload.as_ref() == b"online" =>
+                        {
+                            debug!("Home Assistant started, announcing sensors again");
+                            thread_announce.store(true, Ordering::Relaxed);
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            warn!("MQTT connection error: {e}");
+                            thread_connected.store(false, Ordering::Relaxed);
+                            std::thread::sleep(Duration::from_secs(5));
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            cfg,
+            client,
+            connected,
+            announce,
+            published: HashMap::new(),
+        })
+    }
+
+    /// Publish changed sensor values, and discovery messages for new sensors.
+    ///
+    /// Unit keys (`{sensor}#unit`) are not published, but used as unit of measurement in the
+    /// discovery message. Nothing is published while disconnected, all values are published again
+    /// after reconnecting.
+    pub fn publish(&mut self, sensors: &HashMap<String, String>) {
+        if !self.connected.load(Ordering::Relaxed) {
+            return;
+        }
+        if self.announce.swap(false, Ordering::Relaxed) {
+            self.published.clear();
+        }
+
+        for (key, value) in sensors {
+            if key.contains('#') {
+                continue;
+            }
+            let is_new = match self.published.get(key) {
+                Some(published) if published == value => continue,
+                Some(_) => false,
+                None => true,
+            };
+
+            let object_id = topic_level(key);
+            let state_topic = format!("{}/{object_id}", self.cfg.topic_prefix);
+            if is_new && let Some(discovery_prefix) = &self.cfg.discovery_prefix {
+                let unit = sensors.get(&format!("{key}#unit"));
+                let config = self.discovery_config(key, &object_id, &state_topic, unit, value);
+                let topic = format!(
+                    "{discovery_prefix}/sensor/{}/{object_id}/config",
+                    self.cfg.node_id
+                );
+                if let Err(e) =
+                    self.client
+                        .try_publish(topic, QoS::AtLeastOnce, true, config.to_string())
+                {
+                    warn!("Failed to publish MQTT discovery message for {key}: {e}");
+                    continue;
+                }
+            }
+
+            match self
+                .client
+                .try_publish(state_topic, QoS::AtMostOnce, true, value.as_str())
+            {
+                Ok(()) => {
+                    self.published.insert(key.clone(), value.clone());
+                }
+                Err(e) => debug!("Failed to publish MQTT sensor value {key}: {e}"),
+            }
+        }
+    }
+
+    fn discovery_config(
+        &self,
+        key: &str,
+        object_id: &str,
+        state_topic: &str,
+        unit: Option<&String>,
+        value: &str,
+    ) -> serde_json::Value {
+        let mut config = json!({
+            "name": key,
+            "object_id": format!("{}_{object_id}", self.cfg.node_id),
+            "unique_id": format!("aster_sysinfo_{}_{object_id}", self.cfg.node_id),
+            "state_topic": state_topic,
+            "availability_topic": format!("{}/status", self.cfg.topic_prefix),
+            "device": {
+                "identifiers": [format!("aster_sysinfo_{}", self.cfg.node_id)],
+                "name": self.cfg.device_name,
+                "model": "aster-sysinfo",
+                "sw_version": env!("CARGO_PKG_VERSION"),
+            },
+        });
+        if let Some(unit) = unit {
+            config["unit_of_measurement"] = json!(unit);
+            if value.parse::<f64>().is_ok() {
+                config["state_class"] = json!("measurement");
+            }
+        }
+        config
+    }
+}
+
+/// Convert a sensor key or host name to a valid MQTT topic level and Home Assistant object ID.
+fn topic_level(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mqtt_config_from_url() {
+        let cfg = MqttConfig::from_url("mqtt://user:p@ss@broker.local:1884", "My NAS").unwrap();
+        assert_eq!("broker.local", cfg.host);
+        assert_eq!(1884, cfg.port);
+        assert_eq!(Some("user"), cfg.username.as_deref());
+        assert_eq!(Some("p@ss"), cfg.password.as_deref());
+        assert_eq!("my_nas", cfg.node_id);
+        assert_eq!("aster-sysinfo/my_nas", cfg.topic_prefix);
+
+        let cfg = MqttConfig::from_url("192.168.1.2", "nas").unwrap();
+        assert_eq!(("192.168.1.2", DEFAULT_PORT), (cfg.host.as_str(), cfg.port));
+        assert_eq!(None, cfg.username);
+
+        assert!(MqttConfig::from_url("mqtt://:1883", "nas").is_err());
+    }
+
+    #[test]
+    fn topic_level_of_sensor_keys() {
+        assert_eq!(
+            "disk__dev_nvme0n1_free",
+            topic_level("disk_/dev/nvme0n1_free")
+        );
+        assert_eq!(
+            "storage_hdd_0__usage_percent",
+            topic_level("storage_hdd[0]_usage_percent")
+        );
+    }
+}
@@ -0,0 +1,423 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! macOS System Management Controller (SMC) sensors.
+//!
+//! The SMC is accessed through the `AppleSMC` IOKit service. Its four-character keys are
+//! enumerated once: all temperature keys (`T...`) with a plausible value and the fan speeds
+//! (`F{n}Ac`) are read with every refresh. Intel Macs use fixed-point values, Apple silicon Macs
+//! floats.
+
+use crate::add_sensor;
+use apple_smc::AppleSmc;
+use log::debug;
+use std::collections::HashMap;
+
+/// Preferred keys of the CPU temperature: CPU die and proximity temperatures of Intel Macs,
+/// performance core temperatures of Apple silicon Macs.
+const CPU_TEMPERATURE_KEYS: [&str; 8] = [
+    "TC0F", "TC0E", "TC0D", "TC0P", "Tp09", "Tp0T", "Tp01", "Tp05",
+];
+/// Preferred keys of the GPU temperature.
+const GPU_TEMPERATURE_KEYS: [&str; 4] = ["TG0D", "TG0P", "Tg05", "Tg0D"];
+
+/// Raw SMC key value with its data type, e.g. `sp78` or `flt `.
+#[derive(Debug, Clone)]
+struct SmcValue {
+    data_type: String,
+    bytes: Vec<u8>,
+}
+
+impl SmcValue {
+    /// Decode a numeric value. Returns `None` for unsupported data types.
+    fn decode(&self) -> Option<f32> {
+        let b = &self.bytes;
+        let value = match self.data_type.as_str() {
+            "sp78" => f32::from(i16::from_be_bytes(b.get(..2)?.try_into().ok()?)) / 256.0,
+            "fpe2" => f32::from(u16::from_be_bytes(b.get(..2)?.try_into().ok()?)) / 4.0,
+            "flt " => f32::from_le_bytes(b.get(..4)?.try_into().ok()?),
+            "ui8 " => f32::from(*b.first()?),
+            "ui16" => f32::from(u16::from_be_bytes(b.get(..2)?.try_into().ok()?)),
+            "ui32" => u32::from_be_bytes(b.get(..4)?.try_into().ok()?) as f32,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Key access of an SMC.
+trait SmcKeys {
+    /// Key name at the given index, `0..#KEY`.
+    fn key_at(&self, index: u32) -> Option<String>;
+    /// Read the value of a key.
+    fn read_key(&self, key: &str) -> Option<SmcValue>;
+}
+
+/// SMC sensor source with the discovered temperature keys and fan count.
+pub struct SmcSource {
+    smc: AppleSmc,
+    temperature_keys: Vec<String>,
+    fans: u8,
+}
+
+impl SmcSource {
+    /// Open the SMC and discover the available temperature keys and fans.
+    pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+        let smc = AppleSmc::open()?;
+        let temperature_keys = discover_temperature_keys(&smc);
+        let fans = smc
+            .read_key("FNum")
+            .and_then(|value| value.decode())
+            .unwrap_or_default() as u8;
+        debug!("SMC temperature keys: {temperature_keys:?}, fans: {fans}");
+        Ok(Self {
+            smc,
+            temperature_keys,
+            fans,
+        })
+    }
+
+    /// Read the SMC temperatures and fan speeds.
+    ///
+    /// Sensor keys:
+    /// - `temperature_smc_{key}`: temperature of an SMC key, e.g. `temperature_smc_TC0P`
+    /// - `temperature_cpu`, `temperature_gpu`: first available key of a preference list
+    /// - `fan_smc_fan{n}`: speed of fan `n` in RPM, starting with 1
+    pub fn update_sensors(&self, sensors: &mut HashMap<String, String>) {
+        read_sensors(&self.smc, &self.temperature_keys, self.fans, sensors);
+    }
+}
+
+/// Enumerate all temperature keys with a plausible temperature value.
+fn discover_temperature_keys(smc: &impl SmcKeys) -> Vec<String> {
+    let count = smc
+        .read_key("#KEY")
+        .and_then(|value| value.decode())
+        .unwrap_or_default() as u32;
+    (0..count)
+        .filter_map(|index| smc.key_at(index))
+        .filter(|key| key.starts_with('T'))
+        .filter(|key| {
+            let value = smc.read_key(key);
+            let temperature = value.filter(|v| ["sp78", "flt "].contains(&v.data_type.as_str()));
+            temperature
+                .and_then(|v| v.decode())
+                .is_some_and(|t| t > 0.0 && t < 150.0)
+        })
+        .collect()
+}
+
+fn read_sensors(
+    smc: &impl SmcKeys,
+    temperature_keys: &[String],
+    fans: u8,
+    sensors: &mut HashMap<String, String>,
+) {
+    let mut temperatures = HashMap::with_capacity(temperature_keys.len());
+    for key in temperature_keys {
+        if let Some(temperature) = smc.read_key(key).and_then(|value| value.decode()) {
+            add_sensor(sensors, format!("temperature_smc_{key}#unit"), "°C");
+            add_sensor(
+                sensors,
+                format!("temperature_smc_{key}"),
+                format!("{temperature:.1}"),
+            );
+            temperatures.insert(key.as_str(), temperature);
+        }
+    }
+    for (label, keys) in [
+        ("temperature_cpu", CPU_TEMPERATURE_KEYS.as_slice()),
+        ("temperature_gpu", GPU_TEMPERATURE_KEYS.as_slice()),
+    ] {
+        if let Some(temperature) = keys.iter().find_map(|key| temperatures.get(key)) {
+            add_sensor(sensors, format!("{label}#unit"), "°C");
+            add_sensor(sensors, label, format!("{temperature:.1}"));
+        }
+    }
+
+    for fan in 0..fans {
+        let key = format!("F{fan}Ac");
+        if let Some(rpm) = smc.read_key(&key).and_then(|value| value.decode()) {
+            let label = format!("fan_smc_fan{}", fan + 1);
+            add_sensor(sensors, format!("{label}#unit"), "RPM");
+            add_sensor(sensors, label, rpm.round());
+        }
+    }
+}
+
+/// IOKit access of the `AppleSMC` service.
+#[cfg(target_os = "macos")]
+#[allow(unsafe_code)]
+mod apple_smc {
+    use super::{SmcKeys, SmcValue};
+    use std::ffi::{c_char, c_void};
+
+    /// `IOConnectCallStructMethod` selector of the SMC user client.
+    const KERNEL_INDEX_SMC: u32 = 2;
+    const SMC_CMD_READ_BYTES: u8 = 5;
+    const SMC_CMD_READ_INDEX: u8 = 8;
+    const SMC_CMD_READ_KEYINFO: u8 = 9;
+
+    // the C structs of the SMC user client include fields which are never read
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Debug, Default, Clone, Copy)]
+    struct KeyDataVersion {
+        major: u8,
+        minor: u8,
+        build: u8,
+        reserved: u8,
+        release: u16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Debug, Default, Clone, Copy)]
+    struct KeyDataLimit {
+        version: u16,
+        length: u16,
+        cpu_limit: u32,
+        gpu_limit: u32,
+        mem_limit: u32,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Debug, Default, Clone, Copy)]
+    struct KeyInfo {
+        data_size: u32,
+        data_type: u32,
+        data_attributes: u8,
+    }
+
+    /// Input and output struct of the SMC user client.
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Debug, Default, Clone, Copy)]
+    struct KeyData {
+        key: u32,
+        version: KeyDataVersion,
+        limit: KeyDataLimit,
+        key_info: KeyInfo,
+        result: u8,
+        status: u8,
+        data8: u8,
+        data32: u32,
+        bytes: [u8; 32],
+    }
+
+    const _: () = assert!(size_of::<KeyData>() == 80);
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOServiceMatching(name: *const c_char) -> *mut c_void;
+        fn IOServiceGetMatchingService(main_port: u32, matching: *mut c_void) -> u32;
+        fn IOServiceOpen(service: u32, owning_task: u32, kind: u32, connect: *mut u32) -> i32;
+        fn IOServiceClose(connect: u32) -> i32;
+        fn IOObjectRelease(object: u32) -> i32;
+        fn IOConnectCallStructMethod(
+            connection: u32,
+            selector: u32,
+            input: *const c_void,
+            input_size: usize,
+            output: *mut c_void,
+            output_size: *mut usize,
+        ) -> i32;
+    }
+
+    unsafe extern "C" {
+        static mach_task_self_: u32;
+    }
+
+    /// Open connection to the `AppleSMC` service.
+    pub struct AppleSmc {
+        connection: u32,
+    }
+
+    impl AppleSmc {
+        pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+            // SAFETY: the matching dictionary is consumed by IOServiceGetMatchingService, the
+            // service object is released after opening the connection.
+            unsafe {
+                let matching = IOServiceMatching(c"AppleSMC".as_ptr());
+                let service = IOServiceGetMatchingService(0, matching);
+                if service == 0 {
+                    return Err("AppleSMC service not found".into());
+                }
+                let mut connection = 0;
+                let result = IOServiceOpen(service, mach_task_self_, 0, &mut connection);
+                IOObjectRelease(service);
+                if result != 0 {
+                    return Err(format!("Failed to open AppleSMC service: {result:#x}").into());
+                }
+                Ok(Self { connection })
+            }
+        }
+
+        fn call(&self, input: &KeyData) -> Option<KeyData> {
+            let mut output = KeyData::default();
+            let mut output_size = size_of::<KeyData>();
+            // SAFETY: input and output point to SMC structs of the size passed to the call
+            let result = unsafe {
+                IOConnectCallStructMethod(
+                    self.connection,
+                    KERNEL_INDEX_SMC,
+                    (input as *const KeyData).cast(),
+                    size_of::<KeyData>(),
+                    (&mut output as *mut KeyData).cast(),
+                    &mut output_size,
+                )
+            };
+            (result == 0 && output.result == 0).then_some(output)
+        }
+    }
+
+    impl SmcKeys for AppleSmc {
+        fn key_at(&self, index: u32) -> Option<String> {
+            let output = self.call(&KeyData {
+                data8: SMC_CMD_READ_INDEX,
+                data32: index,
+                ..Default::default()
+            })?;
+            Some(String::from_utf8_lossy(&output.key.to_be_bytes()).into_owned())
+        }
+
+        fn read_key(&self, key: &str) -> Option<SmcValue> {
+            let key = u32::from_be_bytes(key.as_bytes().try_into().ok()?);
+            let key_info = self
+                .call(&KeyData {
+                    key,
+                    data8: SMC_CMD_READ_KEYINFO,
+                    ..Default::default()
+                })?
+                .key_info;
+            let output = self.call(&KeyData {
+                key,
+                key_info,
+                data8: SMC_CMD_READ_BYTES,
+                ..Default::default()
+            })?;
+            let size = (key_info.data_size as usize).min(output.bytes.len());
+            Some(SmcValue {
+                data_type: String::from_utf8_lossy(&key_info.data_type.to_be_bytes()).into_owned(),
+                bytes: output.bytes[..size].to_vec(),
+            })
+        }
+    }
+
+    impl Drop for AppleSmc {
+        fn drop(&mut self) {
+            // SAFETY: the connection was opened by IOServiceOpen and is closed only once
+            unsafe {
+                IOServiceClose(self.connection);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::mem::offset_of;
+
+        /// Layout of `SMCKeyData_t` of the SMC user client.
+        #[test]
+        fn key_data_layout() {
+            assert_eq!(6, size_of::<KeyDataVersion>());
+            assert_eq!(16, size_of::<KeyDataLimit>());
+            assert_eq!(12, size_of::<KeyInfo>());
+            assert_eq!(80, size_of::<KeyData>());
+            assert_eq!(0, offset_of!(KeyData, key));
+            assert_eq!(4, offset_of!(KeyData, version));
+            assert_eq!(12, offset_of!(KeyData, limit));
+            assert_eq!(28, offset_of!(KeyData, key_info));
+            assert_eq!(40, offset_of!(KeyData, result));
+            assert_eq!(41, offset_of!(KeyData, status));
+            assert_eq!(42, offset_of!(KeyData, data8));
+            assert_eq!(44, offset_of!(KeyData, data32));
+            assert_eq!(48, offset_of!(KeyData, bytes));
+        }
+
+        /// The SMC is not available in every environment, e.g. in virtual machines.
+        #[test]
+        fn read_key_count() {
+            let Ok(smc) = AppleSmc::open() else {
+                return;
+            };
+            if let Some(value) = smc.read_key("#KEY") {
+                assert_eq!("ui32", value.data_type);
+                assert_eq!(4, value.bytes.len());
+            }
+        }
+    }
+}
+
+/// The SMC is only available on macOS.
+#[cfg(not(target_os = "macos"))]
+mod apple_smc {
+    use super::{SmcKeys, SmcValue};
+
+    pub struct AppleSmc;
+
+    impl AppleSmc {
+        pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+            Err("The SMC is only available on macOS".into())
+        }
+    }
+
+    impl SmcKeys for AppleSmc {
+        fn key_at(&self, _index: u32) -> Option<String> {
+            None
+        }
+
+        fn read_key(&self, _key: &str) -> Option<SmcValue> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SMC with fixed key values.
+    struct FakeSmc(Vec<(&'static str, &'static str, Vec<u8>)>);
+
+    impl SmcKeys for FakeSmc {
+        fn key_at(&self, index: u32) -> Option<String> {
+            self.0
+                .get(index as usize)
+                .map(|(key, _, _)| key.to_string())
+        }
+
+        fn read_key(&self, key: &str) -> Option<SmcValue> {
+            let (_, data_type, bytes) = self.0.iter().find(|(k, _, _)| *k == key)?;
+            Some(SmcValue {
+                data_type: data_type.to_string(),
+                bytes: bytes.clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn read_smc_temperatures_and_fans() {
+        let smc = FakeSmc(vec![
+            ("#KEY", "ui32", 6u32.to_be_bytes().to_vec()),
+            ("FNum", "ui8 ", vec![1]),
+            ("F0Ac", "fpe2", vec![0x1f, 0x40]),
+            ("TC0P", "sp78", vec![0x2d, 0x80]),
+            ("Tp09", "flt ", 52.25f32.to_le_bytes().to_vec()),
+            ("TZ0P", "sp78", vec![0xff, 0x00]),
+        ]);
+        let keys = discover_temperature_keys(&smc);
+        assert_eq!(vec!["TC0P", "Tp09"], keys);
+
+        let mut sensors = HashMap::new();
+        read_sensors(&smc, &keys, 1, &mut sensors);
+        assert_eq!("45.5", sensors["temperature_smc_TC0P"]);
+        assert_eq!("52.2", sensors["temperature_smc_Tp09"]);
+        assert_eq!("45.5", sensors["temperature_cpu"]);
+        assert!(!sensors.contains_key("temperature_gpu"));
+        assert_eq!("2000", sensors["fan_smc_fan1"]);
+        assert_eq!("RPM", sensors["fan_smc_fan1#unit"]);
+    }
+}
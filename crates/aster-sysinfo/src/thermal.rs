@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Linux thermal zones and Raspberry Pi firmware sensors.
+//!
+//! Single-board computers often report their SoC temperatures only as thermal zones in
+//! `/sys/class/thermal`, without a hwmon chip. The throttling state of a Raspberry Pi, as shown by
+//! `vcgencmd get_throttled`, is read from the `get_throttled` attribute of the firmware driver.
+
+use crate::add_sensor;
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Throttling flags of `get_throttled`: sensor key suffix and bit of the current state. The bit
+/// of the "occurred since boot" state is 16 bits higher.
+const THROTTLED_FLAGS: [(&str, u32); 4] = [
+    ("under_voltage", 0),
+    ("frequency_capped", 1),
+    ("throttled", 2),
+    ("soft_temperature_limit", 3),
+];
+
+/// Read the thermal zone temperatures and the Raspberry Pi throttling state.
+///
+/// Sensor keys:
+/// - `temperature_zone_{type}`: temperature of a thermal zone, e.g.
+///   `temperature_zone_cpu_thermal`. If multiple zones use the same type, a counter is appended:
+///   `acpitz`, `acpitz_1`, ...
+/// - `rpi_{flag}`: 1 if the Raspberry Pi is currently under-voltage, frequency capped, throttled
+///   or at the soft temperature limit, 0 otherwise.
+/// - `rpi_{flag}_occurred`: 1 if the condition occurred since boot.
+pub fn update_thermal_zone_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    read_thermal_zone_sensors(Path::new("/"), sensors)
+}
+
+fn read_thermal_zone_sensors(
+    root: &Path,
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let thermal_path = root.join("sys/class/thermal");
+    if thermal_path.exists() {
+        for (name, temperature) in read_thermal_zones(&thermal_path)? {
+            add_sensor(sensors, format!("temperature_zone_{name}#unit"), "°C");
+            add_sensor(
+                sensors,
+                format!("temperature_zone_{name}"),
+                format!("{temperature:.1}"),
+            );
+        }
+    } else {
+        debug!("No thermal zones found");
+    }
+
+    if let Some(throttled) = read_throttled(root) {
+        for (flag, bit) in THROTTLED_FLAGS {
+            let now = throttled & (1 << bit) != 0;
+            let occurred = throttled & (1 << (bit + 16)) != 0;
+            add_sensor(sensors, format!("rpi_{flag}"), u8::from(now));
+            add_sensor(sensors, format!("rpi_{flag}_occurred"), u8::from(occurred));
+        }
+    }
+
+    Ok(())
+}
+
+/// Temperature of the first CPU thermal zone, e.g. `cpu-thermal`, for systems without a hwmon chip
+/// reporting the CPU temperature.
+pub(crate) fn cpu_zone_temperature(thermal_path: &Path) -> Option<f64> {
+    let zones = read_thermal_zones(thermal_path).ok()?;
+    zones
+        .into_iter()
+        .find_map(|(name, temperature)| name.starts_with("cpu").then_some(temperature))
+}
+
+/// Read the thermal zone names and temperatures in °C, ordered by the zone number.
+fn read_thermal_zones(thermal_path: &Path) -> Result<Vec<(String, f64)>, std::io::Error> {
+    let mut zones: Vec<(u32, String)> = fs::read_dir(thermal_path)?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().to_string_lossy().into_owned();
+            let number = name.strip_prefix("thermal_zone")?.parse().ok()?;
+            Some((number, name))
+        })
+        .collect();
+    zones.sort();
+
+    let mut zone_types: HashMap<String, u32> = HashMap::new();
+    let mut temperatures = Vec::with_capacity(zones.len());
+    for (_, zone) in zones {
+        let zone = thermal_path.join(zone);
+        let (Ok(zone_type), Ok(temp)) = (
+            fs::read_to_string(zone.join("type")),
+            fs::read_to_string(zone.join("temp")),
+        ) else {
+            // disabled zones can't be read
+            continue;
+        };
+        let Ok(millidegrees) = temp.trim().parse::<i64>() else {
+            continue;
+        };
+        let temperature = millidegrees as f64 / 1000.0;
+
+        let zone_type = zone_type.trim().replace([' ', '-'], "_");
+        let count = zone_types.entry(zone_type.clone()).or_default();
+        let name = if *count == 0 {
+            zone_type
+        } else {
+            format!("{zone_type}_{count}")
+        };
+        *count += 1;
+        temperatures.push((name, temperature));
+    }
+
+    Ok(temperatures)
+}
+
+/// Read the `get_throttled` bit mask of the Raspberry Pi firmware driver, e.g. in
+/// `/sys/devices/platform/soc/soc:firmware`.
+fn read_throttled(root: &Path) -> Option<u32> {
+    let platform = fs::read_dir(root.join("sys/devices/platform")).ok()?;
+    for device in platform.flatten() {
+        let Ok(children) = fs::read_dir(device.path()) else {
+            continue;
+        };
+        for child in children.flatten() {
+            if !child.file_name().to_string_lossy().ends_with("firmware") {
+                continue;
+            }
+            if let Ok(value) = fs::read_to_string(child.path().join("get_throttled")) {
+                return u32::from_str_radix(value.trim().trim_start_matches("0x"), 16).ok();
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn read_thermal_zones_and_throttling() {
+        let root = tempfile::tempdir().unwrap();
+        let thermal = root.path().join("sys/class/thermal");
+        write(&thermal.join("thermal_zone0/type"), "cpu-thermal\n");
+        write(&thermal.join("thermal_zone0/temp"), "48312\n");
+        write(&thermal.join("thermal_zone1/type"), "gpu-thermal\n");
+        write(&thermal.join("thermal_zone1/temp"), "45100\n");
+        write(&thermal.join("thermal_zone10/type"), "gpu-thermal\n");
+        write(&thermal.join("thermal_zone10/temp"), "44000\n");
+        write(&thermal.join("cooling_device0/type"), "Processor\n");
+        write(
+            &root
+                .path()
+                .join("sys/devices/platform/soc/soc:firmware/get_throttled"),
+            "50005\n",
+        );
+
+        let mut sensors = HashMap::new();
+        read_thermal_zone_sensors(root.path(), &mut sensors).unwrap();
+        assert_eq!("48.3", sensors["temperature_zone_cpu_thermal"]);
+        assert_eq!("45.1", sensors["temperature_zone_gpu_thermal"]);
+        assert_eq!("44.0", sensors["temperature_zone_gpu_thermal_1"]);
+        assert_eq!("1", sensors["rpi_under_voltage"]);
+        assert_eq!("0", sensors["rpi_frequency_capped"]);
+        assert_eq!("1", sensors["rpi_throttled"]);
+        assert_eq!("1", sensors["rpi_under_voltage_occurred"]);
+        assert_eq!("0", sensors["rpi_frequency_capped_occurred"]);
+        assert_eq!("1", sensors["rpi_throttled_occurred"]);
+        assert_eq!(Some(48.312), cpu_zone_temperature(&thermal));
+    }
+}
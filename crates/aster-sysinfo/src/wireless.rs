@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Wireless link sensors.
+//!
+//! Link quality and signal level are read from `/proc/net/wireless`. The SSID and bitrates are
+//! queried over nl80211 with the `iw` tool, if installed.
+
+use crate::add_sensor;
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+
+const PROC_NET_WIRELESS: &str = "/proc/net/wireless";
+
+/// Maximum link quality value reported by most drivers in `/proc/net/wireless`.
+const MAX_LINK_QUALITY: f32 = 70.0;
+
+#[derive(Debug, Default, PartialEq)]
+struct WirelessStatus {
+    interface: String,
+    link_quality: f32,
+    signal_dbm: f32,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct LinkInfo {
+    ssid: Option<String>,
+    signal_dbm: Option<f32>,
+    rx_bitrate: Option<f32>,
+    tx_bitrate: Option<f32>,
+}
+
+/// Add wireless sensors of all wireless interfaces.
+///
+/// Sensor keys:
+/// - `wifi_{interface}_connected`: 1 if associated with an access point, 0 otherwise
+/// - `wifi_{interface}_link_quality`: link quality in percent
+/// - `wifi_{interface}_signal`: signal level in dBm
+/// - `wifi_{interface}_ssid`: network name, requires `iw`
+/// - `wifi_{interface}_rx_bitrate` & `wifi_{interface}_tx_bitrate`: bitrate in MBit/s, requires `iw`
+pub fn update_wireless_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Ok(content) = fs::read_to_string(PROC_NET_WIRELESS) else {
+        debug!("No wireless extensions available");
+        return Ok(());
+    };
+
+    for status in parse_proc_net_wireless(&content) {
+        let prefix = format!("wifi_{}", status.interface);
+        let link = get_link_info(&status.interface).unwrap_or_default();
+
+        let connected = link.ssid.is_some() || status.link_quality > 0.0;
+        add_sensor(sensors, format!("{prefix}_connected"), u8::from(connected));
+        add_sensor(
+            sensors,
+            format!("{prefix}_link_quality"),
+            format!(
+                "{:.0}",
+                (status.link_quality * 100.0 / MAX_LINK_QUALITY).clamp(0.0, 100.0)
+            ),
+        );
+        add_sensor(sensors, format!("{prefix}_link_quality#unit"), "%");
+        add_sensor(
+            sensors,
+            format!("{prefix}_signal"),
+            link.signal_dbm.unwrap_or(status.signal_dbm),
+        );
+        add_sensor(sensors, format!("{prefix}_signal#unit"), "dBm");
+        add_sensor(
+            sensors,
+            format!("{prefix}_ssid"),
+            link.ssid.unwrap_or_default(),
+        );
+        for (name, bitrate) in [("rx", link.rx_bitrate), ("tx", link.tx_bitrate)] {
+            add_sensor(
+                sensors,
+                format!("{prefix}_{name}_bitrate"),
+                format!("{:.1}", bitrate.unwrap_or_default()),
+            );
+            add_sensor(sensors, format!("{prefix}_{name}_bitrate#unit"), "MBit/s");
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse the interface table of `/proc/net/wireless`.
+///
+/// Example:
+/// ```text
+/// Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+///  face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+/// wlp2s0: 0000   54.  -56.  -256        0      0      0      0     23        0
+/// ```
+fn parse_proc_net_wireless(content: &str) -> Vec<WirelessStatus> {
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (interface, values) = line.split_once(':')?;
+            let mut values = values.split_whitespace().skip(1);
+            let mut next_value = || values.next()?.trim_end_matches('.').parse::<f32>().ok();
+            Some(WirelessStatus {
+                interface: interface.trim().to_string(),
+                link_quality: next_value()?,
+                signal_dbm: next_value()?,
+            })
+        })
+        .collect()
+}
+
+fn get_link_info(interface: &str) -> Option<LinkInfo> {
+    let output = Command::new("iw")
+        .args(["dev", interface, "link"])
+        .output()
+        .inspect_err(|e| debug!("Unable to query wireless link with iw: {e}"))
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_iw_link(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the output of `iw dev <interface> link`.
+fn parse_iw_link(output: &str) -> LinkInfo {
+    let mut info = LinkInfo::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        // first token of values like `-56 dBm` or `866.7 MBit/s VHT-MCS 9 80MHz short GI`
+        let number = || value.split_whitespace().next()?.parse::<f32>().ok();
+        match key {
+            "SSID" => info.ssid = Some(value.to_string()),
+            "signal" => info.signal_dbm = number(),
+            "rx bitrate" => info.rx_bitrate = number(),
+            "tx bitrate" => info.tx_bitrate = number(),
+            _ => {}
+        }
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proc_net_wireless_interfaces() {
+        let content =
+            "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+wlp2s0: 0000   54.  -56.  -256        0      0      0      0     23        0
+ wlan1: 0000    0     0     0        0      0      0      0      0        0
+";
+        let status = parse_proc_net_wireless(content);
+        assert_eq!(
+            vec![
+                WirelessStatus {
+                    interface: "wlp2s0".into(),
+                    link_quality: 54.0,
+                    signal_dbm: -56.0
+                },
+                WirelessStatus {
+                    interface: "wlan1".into(),
+                    link_quality: 0.0,
+                    signal_dbm: 0.0
+                }
+            ],
+            status
+        );
+    }
+
+    #[test]
+    fn parse_iw_link_connected() {
+        let output = "Connected to 11:22:33:44:55:66 (on wlp2s0)
+	SSID: My Network
+	freq: 5180
+	RX: 1234567 bytes (8910 packets)
+	TX: 123456 bytes (789 packets)
+	signal: -57 dBm
+	rx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2
+	tx bitrate: 780.0 MBit/s VHT-MCS 8 80MHz short GI VHT-NSS 2
+";
+        assert_eq!(
+            LinkInfo {
+                ssid: Some("My Network".into()),
+                signal_dbm: Some(-57.0),
+                rx_bitrate: Some(866.7),
+                tx_bitrate: Some(780.0),
+            },
+            parse_iw_link(output)
+        );
+    }
+
+    #[test]
+    fn parse_iw_link_not_connected() {
+        assert_eq!(LinkInfo::default(), parse_iw_link("Not connected.\n"));
+    }
+}
@@ -0,0 +1,384 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Network throughput of containers and virtual machines.
+//!
+//! The traffic of a workload is measured on its host-side virtual interfaces:
+//! - Docker containers: the `iflink` of a container interface, read from the container's sysfs in
+//!   `/proc/{pid}/root/sys/class/net`, is the interface index of the host-side veth peer.
+//! - libvirt domains: the tap devices of the runtime domain XML in `/run/libvirt/qemu`.
+//! - Proxmox VE: the `veth{vmid}i{n}` interfaces of containers and `tap{vmid}i{n}` of VMs, named
+//!   after the guest configuration in `/etc/pve`.
+//!
+//! The mapping is only rebuilt when the virtual interfaces change, i.e. a workload is started or
+//! stopped.
+
+use crate::{SysinfoSource, add_sensor, format_bytes};
+use log::debug;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Host-side interface name prefixes of virtual machines and containers.
+const VIRTUAL_INTERFACE_PREFIXES: [&str; 3] = ["veth", "tap", "vnet"];
+
+/// A container or virtual machine.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Workload {
+    /// Sensor key prefix: `container` or `vm`.
+    kind: &'static str,
+    name: String,
+}
+
+impl Workload {
+    fn container(name: &str) -> Self {
+        Self {
+            kind: "container",
+            name: name.trim().replace(' ', "_"),
+        }
+    }
+
+    fn vm(name: &str) -> Self {
+        Self {
+            kind: "vm",
+            name: name.trim().replace(' ', "_"),
+        }
+    }
+}
+
+/// Cached mapping of host-side virtual interfaces to workloads.
+#[derive(Debug, Default)]
+pub(crate) struct WorkloadInterfaces {
+    /// Virtual interfaces of the last mapping.
+    interfaces: Vec<String>,
+    /// Host interface name -> workload
+    workloads: HashMap<String, Workload>,
+}
+
+impl SysinfoSource {
+    /// Add network throughput sensors of Docker containers, libvirt domains and Proxmox VE guests.
+    ///
+    /// The values are from the point of view of the workload, e.g. the download speed of a
+    /// container is the data transmitted by the host to the container.
+    ///
+    /// Sensor keys, with `{kind}` either `container` or `vm`:
+    /// - `{kind}_{name}_download_speed` & `{kind}_{name}_upload_speed`: rate, e.g. `1.20 MB/s`
+    /// - `{kind}_{name}_total_received` & `{kind}_{name}_total_transmitted`: data since the
+    ///   interfaces were created
+    pub fn update_workload_sensors(
+        &mut self,
+        sensors: &mut HashMap<String, String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut interfaces = self
+            .networks
+            .keys()
+            .filter(|name| is_virtual_interface(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if interfaces.is_empty() {
+            return Ok(());
+        }
+        interfaces.sort();
+        if interfaces != self.workload_interfaces.interfaces {
+            debug!("Virtual interfaces changed, mapping workloads");
+            let containers = if interfaces.iter().any(|name| name.starts_with("veth")) {
+                get_docker_containers()
+            } else {
+                Vec::new()
+            };
+            self.workload_interfaces = WorkloadInterfaces {
+                workloads: read_workload_interfaces(Path::new("/"), &containers),
+                interfaces,
+            };
+        }
+
+        // workload -> (received, transmitted, total received, total transmitted) of the workload
+        let mut traffic = BTreeMap::<&Workload, (u64, u64, u64, u64)>::new();
+        for (interface, workload) in &self.workload_interfaces.workloads {
+            let Some(data) = self.networks.get(interface) else {
+                continue;
+            };
+            let entry = traffic.entry(workload).or_default();
+            // host transmit is workload receive
+            entry.0 += data.transmitted();
+            entry.1 += data.received();
+            entry.2 += data.total_transmitted();
+            entry.3 += data.total_received();
+        }
+
+        for (workload, (received, transmitted, total_received, total_transmitted)) in traffic {
+            let prefix = format!("{}_{}", workload.kind, workload.name);
+            if let Some(refresh) = self.refresh_duration {
+                let interval = refresh.as_millis() as u64;
+                if let Some(received) = (1000 * received).checked_div(interval) {
+                    add_sensor(
+                        sensors,
+                        format!("{prefix}_download_speed"),
+                        format!("{}/s", format_bytes(received)),
+                    );
+                }
+                if let Some(transmitted) = (1000 * transmitted).checked_div(interval) {
+                    add_sensor(
+                        sensors,
+                        format!("{prefix}_upload_speed"),
+                        format!("{}/s", format_bytes(transmitted)),
+                    );
+                }
+            }
+            add_sensor(
+                sensors,
+                format!("{prefix}_total_received"),
+                format_bytes(total_received),
+            );
+            add_sensor(
+                sensors,
+                format!("{prefix}_total_transmitted"),
+                format_bytes(total_transmitted),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn is_virtual_interface(name: &str) -> bool {
+    VIRTUAL_INTERFACE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// Get the name and init process ID of all running Docker containers.
+fn get_docker_containers() -> Vec<(String, u32)> {
+    let ids = Command::new("docker")
+        .args(["ps", "--quiet"])
+        .output()
+        .inspect_err(|e| debug!("Unable to list Docker containers: {e}"))
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default();
+    let ids = ids.split_whitespace().collect::<Vec<_>>();
+    if ids.is_empty() {
+        return Vec::new();
+    }
+
+    Command::new("docker")
+        .args(["inspect", "--format", "{{.Name}} {{.State.Pid}}"])
+        .args(ids)
+        .output()
+        .inspect_err(|e| debug!("Unable to inspect Docker containers: {e}"))
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_docker_inspect(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parse the `{{.Name}} {{.State.Pid}}` lines of `docker inspect`, e.g. `/nextcloud 4242`.
+fn parse_docker_inspect(output: &str) -> Vec<(String, u32)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, pid) = line.trim().rsplit_once(' ')?;
+            let pid = pid.parse().ok().filter(|pid| *pid > 0)?;
+            Some((name.trim_start_matches('/').to_string(), pid))
+        })
+        .collect()
+}
+
+/// Map host interfaces to workloads.
+///
+/// # Arguments
+///
+/// * `root`: file system root.
+/// * `containers`: name and init process ID of the Docker containers.
+fn read_workload_interfaces(
+    root: &Path,
+    containers: &[(String, u32)],
+) -> HashMap<String, Workload> {
+    let mut workloads = HashMap::new();
+
+    // host interface index -> name
+    let host_interfaces = read_interface_indexes(&root.join("sys/class/net"))
+        .into_iter()
+        .map(|(name, index, _)| (index, name))
+        .collect::<HashMap<_, _>>();
+
+    // veth pairs: the container interface links to the host interface index
+    for (name, pid) in containers {
+        let net_path = root.join(format!("proc/{pid}/root/sys/class/net"));
+        for (_, index, iflink) in read_interface_indexes(&net_path) {
+            if iflink != index
+                && let Some(interface) = host_interfaces.get(&iflink)
+            {
+                workloads.insert(interface.clone(), Workload::container(name));
+            }
+        }
+    }
+
+    // libvirt runtime domain XML, e.g. <target dev='vnet0'/>
+    if let Ok(entries) = fs::read_dir(root.join("run/libvirt/qemu")) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|ext| ext != "xml") {
+                continue;
+            }
+            let (Some(domain), Ok(xml)) = (path.file_stem(), fs::read_to_string(&path)) else {
+                continue;
+            };
+            for target in xml.split("<target dev=").skip(1) {
+                let device = target
+                    .trim_start_matches(['\'', '"'])
+                    .split(['\'', '"'])
+                    .next()
+                    .unwrap_or_default();
+                // disk targets like `vda` aren't network interfaces
+                if host_interfaces.values().any(|name| name == device) {
+                    workloads.insert(device.to_string(), Workload::vm(&domain.to_string_lossy()));
+                }
+            }
+        }
+    }
+
+    // Proxmox VE guests
+    for interface in host_interfaces.values() {
+        if workloads.contains_key(interface) {
+            continue;
+        }
+        let Some((kind, vmid)) = parse_proxmox_interface(interface) else {
+            continue;
+        };
+        let workload = if kind == "veth" {
+            let name = read_pve_config_value(root, &format!("lxc/{vmid}.conf"), "hostname");
+            Workload::container(name.as_deref().unwrap_or(vmid))
+        } else {
+            let name = read_pve_config_value(root, &format!("qemu-server/{vmid}.conf"), "name");
+            Workload::vm(name.as_deref().unwrap_or(vmid))
+        };
+        workloads.insert(interface.clone(), workload);
+    }
+
+    workloads
+}
+
+/// Read the name, `ifindex` and `iflink` of all interfaces in a `/sys/class/net` directory.
+fn read_interface_indexes(net_path: &Path) -> Vec<(String, u32, u32)> {
+    let Ok(entries) = fs::read_dir(net_path) else {
+        return Vec::new();
+    };
+    let read_index = |path: &Path, name: &str| {
+        fs::read_to_string(path.join(name))
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            Some((
+                entry.file_name().to_string_lossy().to_string(),
+                read_index(&path, "ifindex")?,
+                read_index(&path, "iflink")?,
+            ))
+        })
+        .collect()
+}
+
+/// Parse a Proxmox VE guest interface name: `veth{vmid}i{n}` or `tap{vmid}i{n}`.
+fn parse_proxmox_interface(interface: &str) -> Option<(&'static str, &str)> {
+    let (kind, rest) = if let Some(rest) = interface.strip_prefix("veth") {
+        ("veth", rest)
+    } else {
+        ("tap", interface.strip_prefix("tap")?)
+    };
+    let (vmid, index) = rest.split_once('i')?;
+    let is_number = |v: &str| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit());
+    (is_number(vmid) && is_number(index)).then_some((kind, vmid))
+}
+
+fn read_pve_config_value(root: &Path, config: &str, key: &str) -> Option<String> {
+    fs::read_to_string(root.join("etc/pve").join(config))
+        .ok()?
+        .lines()
+        // snapshot sections follow the current configuration
+        .take_while(|line| !line.starts_with('['))
+        .find_map(|line| {
+            Some(
+                line.strip_prefix(key)?
+                    .strip_prefix(':')?
+                    .trim()
+                    .to_string(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn add_interface(net_path: &Path, name: &str, ifindex: u32, iflink: u32) {
+        write(
+            &net_path.join(name).join("ifindex"),
+            &format!("{ifindex}\n"),
+        );
+        write(&net_path.join(name).join("iflink"), &format!("{iflink}\n"));
+    }
+
+    #[test]
+    fn parse_docker_inspect_output() {
+        assert_eq!(
+            vec![("nextcloud".to_string(), 4242), ("db".to_string(), 17)],
+            parse_docker_inspect("/nextcloud 4242\n/db 17\n/stopped 0\n")
+        );
+    }
+
+    #[test]
+    fn map_interfaces_to_workloads() {
+        let root = tempfile::tempdir().unwrap();
+        let host_net = root.path().join("sys/class/net");
+        add_interface(&host_net, "eth0", 2, 2);
+        add_interface(&host_net, "veth1a2b3c", 12, 11);
+        add_interface(&host_net, "vnet0", 13, 13);
+        add_interface(&host_net, "veth101i0", 14, 3);
+        add_interface(&host_net, "tap102i0", 15, 15);
+
+        // container eth0 is the peer of host interface 12
+        let container_net = root.path().join("proc/4242/root/sys/class/net");
+        add_interface(&container_net, "lo", 1, 1);
+        add_interface(&container_net, "eth0", 11, 12);
+
+        write(
+            &root.path().join("run/libvirt/qemu/Home Assistant.xml"),
+            "<domstatus><domain><devices>
+            <disk><target dev='vda' bus='virtio'/></disk>
+            <interface type='bridge'><target dev='vnet0'/></interface>
+            </devices></domain></domstatus>",
+        );
+        write(
+            &root.path().join("etc/pve/lxc/101.conf"),
+            "arch: amd64\nhostname: pihole\n\n[snapshot]\nhostname: old\n",
+        );
+
+        let workloads = read_workload_interfaces(root.path(), &[("nextcloud".to_string(), 4242)]);
+
+        assert_eq!(4, workloads.len());
+        assert_eq!(
+            Some(&Workload::container("nextcloud")),
+            workloads.get("veth1a2b3c")
+        );
+        assert_eq!(
+            Some(&Workload::vm("Home_Assistant")),
+            workloads.get("vnet0")
+        );
+        assert_eq!(
+            Some(&Workload::container("pihole")),
+            workloads.get("veth101i0")
+        );
+        // no guest configuration, falls back to the VM ID
+        assert_eq!(Some(&Workload::vm("102")), workloads.get("tap102i0"));
+    }
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! ZFS pool sensors of TrueNAS, FreeBSD and Linux systems with OpenZFS.
+
+use crate::{add_sensor, format_bytes};
+use log::debug;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The `zpool` command is installed. Probed with the first update, most systems don't have ZFS.
+static ZPOOL_INSTALLED: AtomicBool = AtomicBool::new(true);
+
+/// Read the capacity and health of the imported ZFS pools with `zpool list`.
+///
+/// Sensor keys of pool `{pool}`:
+/// - `zfs_{pool}_size_bytes`, `zfs_{pool}_size`: pool size
+/// - `zfs_{pool}_used_bytes`, `zfs_{pool}_used`: allocated space
+/// - `zfs_{pool}_free_bytes`, `zfs_{pool}_free`: free space
+/// - `zfs_{pool}_usage_percent`: allocated space in percent of the pool size
+/// - `zfs_{pool}_health`: pool health, e.g. `ONLINE` or `DEGRADED`
+///
+/// Systems without ZFS don't have any ZFS sensors. If `zpool` isn't installed, it isn't run again
+/// with the next updates.
+pub fn update_zfs_sensors(
+    sensors: &mut HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !ZPOOL_INSTALLED.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let output = match Command::new("zpool")
+        .args(["list", "-Hp", "-o", "name,size,alloc,free,health"])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            if e.kind() == ErrorKind::NotFound {
+                ZPOOL_INSTALLED.store(false, Ordering::Relaxed);
+            }
+            debug!("No ZFS pools: {e}");
+            return Ok(());
+        }
+    };
+
+    for pool in parse_pools(&String::from_utf8_lossy(&output.stdout)) {
+        let prefix = format!("zfs_{}", pool.name);
+        add_sensor(sensors, format!("{prefix}_size_bytes"), pool.size);
+        add_sensor(sensors, format!("{prefix}_size"), format_bytes(pool.size));
+        add_sensor(sensors, format!("{prefix}_used_bytes"), pool.used);
+        add_sensor(sensors, format!("{prefix}_used"), format_bytes(pool.used));
+        add_sensor(sensors, format!("{prefix}_free_bytes"), pool.free);
+        add_sensor(sensors, format!("{prefix}_free"), format_bytes(pool.free));
+        add_sensor(
+            sensors,
+            format!("{prefix}_usage_percent"),
+            pool.usage_percent(),
+        );
+        add_sensor(sensors, format!("{prefix}_health"), pool.health);
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct ZfsPool {
+    name: String,
+    size: u64,
+    used: u64,
+    free: u64,
+    health: String,
+}
+
+impl ZfsPool {
+    fn usage_percent(&self) -> f64 {
+        if self.size == 0 {
+            return 0.0;
+        }
+        ((self.used as f64 / self.size as f64) * 100.0 * 100.0).round() / 100.0
+    }
+}
+
+/// Parse the tab separated `zpool list -Hp` output with exact byte values. Unavailable pools don't
+/// have a size: `-` is reported as zero.
+fn parse_pools(output: &str) -> Vec<ZfsPool> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let [name, size, used, free, health] = line.split('\t').collect::<Vec<_>>()[..] else {
+                return None;
+            };
+            Some(ZfsPool {
+                name: name.to_string(),
+                size: size.parse().unwrap_or_default(),
+                used: used.parse().unwrap_or_default(),
+                free: free.parse().unwrap_or_default(),
+                health: health.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_zpool_list() {
+        let output = "boot-pool\t31138512896\t3221225472\t27917287424\tONLINE\n\
+            tank\t7999999999999\t-\t-\tFAULTED\n\
+            data\t4000000000000\t1000000000000\t3000000000000\tDEGRADED\n";
+        let pools = parse_pools(output);
+        assert_eq!(3, pools.len());
+        assert_eq!("boot-pool", pools[0].name);
+        assert_eq!(10.34, pools[0].usage_percent());
+        assert_eq!("FAULTED", pools[1].health);
+        assert_eq!(0.0, pools[1].usage_percent());
+        assert_eq!(
+            ZfsPool {
+                name: "data".into(),
+                size: 4_000_000_000_000,
+                used: 1_000_000_000_000,
+                free: 3_000_000_000_000,
+                health: "DEGRADED".into(),
+            },
+            pools[2]
+        );
+        assert_eq!(25.0, pools[2].usage_percent());
+    }
+}
@@ -3,40 +3,95 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
 
 use crate::FakeSerialPort;
-use crate::ToRgb565;
+use crate::frame_cache::{FRAME_CACHE_SIZE, FrameCache};
+use crate::model::{
+    DisplayModel, ModelDescriptor, Orientation, Rotation, WTR_MAX, transform_pixels,
+};
+use crate::rle::encode_blocks;
+use crate::trace::{RecordingPort, TraceAction, TraceEvent, TraceRecorder};
+use crate::{PixelFormat, ToRgb565};
 
-use anyhow::{Context, anyhow};
+use anyhow::{Context, anyhow, bail};
 use bytes::{BufMut, BytesMut};
 use log::{debug, error, info, warn};
-use serialport::{SerialPort, SerialPortType};
+use serialport::{ClearBuffer, SerialPort, SerialPortType};
+use std::fmt;
 use std::io::{Read, Write};
+use std::sync::Arc;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
-pub const DISPLAY_SIZE: (u32, u32) = (960, 376);
+/// Resolution of the [WTR_MAX] display. Use [AooScreen::size] for the size of an opened display.
+pub const DISPLAY_SIZE: (u32, u32) = WTR_MAX.size;
 
 const SERIAL_RETRY: u8 = 3;
+/// Default number of consecutive failed image transfers before the display is re-initialized.
+const REINIT_THRESHOLD: u32 = 3;
+/// Maximum number of re-initializations after failed transfers until an image is sent again.
+const MAX_REINITS: u32 = 3;
 const UART_BAUDRATE: u32 = 1_500_000;
+/// Delay before the second reconnect attempt after a disconnect, doubled after each failure.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Default maximum delay between two reconnect attempts.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
 
 const USB_UART_VID: u16 = 0x416;
 const USB_UART_PID: u16 = 0x90A1;
 
-const IMG_CHUNK_SIZE: usize = 47;
+/// Known USB UART devices of AOOSTAR displays, in the probing order of
+/// [AooScreenBuilder::open_default].
+pub const KNOWN_DEVICES: &[DisplayDevice] = &[DisplayDevice {
+    vid: USB_UART_VID,
+    pid: USB_UART_PID,
+    name: "AOOSTAR WTR MAX / GEM12+ PRO",
+    model: &WTR_MAX,
+}];
+
+/// USB UART device of an AOOSTAR display model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayDevice {
+    pub vid: u16,
+    pub pid: u16,
+    /// Display or device models using this USB UART.
+    pub name: &'static str,
+    pub model: &'static ModelDescriptor,
+}
+
+impl fmt::Display for DisplayDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}:{:x} ({})", self.vid, self.pid, self.name)
+    }
+}
 
 static DISPLAY_OFF: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x0A, 0x00, 0x00, 0x00];
-static DISPLAY_ON: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x0B, 0x00, 0x00, 0x00];
+pub(crate) static DISPLAY_ON: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x0B, 0x00, 0x00, 0x00];
 
 static HEADER_START: [u8; 16] = [
     0xAA, 0x55, 0xAA, 0x55, 0x05, 0x00, 0x00, 0x00, 0x04, 0x00, 0x0F, 0x2F, 0x00, 0x04, 0x0B, 0x00,
 ];
-static HEADER_END: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x06, 0x00, 0x00, 0x00];
-static HEADER: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x08, 0x00, 0x00, 0x00];
+pub(crate) static HEADER_END: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x06, 0x00, 0x00, 0x00];
+pub(crate) static HEADER: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x08, 0x00, 0x00, 0x00];
+/// Experimental run-length encoded data chunk, see [AooScreenBuilder::compression].
+///
+/// Not from a firmware reference: `0x09` is assumed as the command following the `0x08` data
+/// chunk, to probe displays for a compressed mode. See `docs/lcd_protocol.md`.
+pub(crate) static HEADER_RLE: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x09, 0x00, 0x00, 0x00];
 
 #[derive(Default)]
 pub struct AooScreenBuilder {
+    model: Option<Arc<dyn DisplayModel>>,
+    recorder: Option<TraceRecorder>,
     timeout: Option<Duration>,
     enable_cache: Option<bool>,
     no_init_check: Option<bool>,
+    reinit_threshold: Option<u32>,
+    reinit_after_idle: Option<Duration>,
+    pixel_format: Option<PixelFormat>,
+    compression: Option<bool>,
+    frame_cache: Option<usize>,
+    max_reconnect_delay: Option<Duration>,
+    rotation: Option<Rotation>,
+    mirror: Option<bool>,
 }
 
 #[allow(dead_code)]
@@ -62,19 +117,115 @@ impl AooScreenBuilder {
         self
     }
 
-    /// Open the default AOOSTAR LCD USB UART device 416:90A1.
-    pub fn open_default(self) -> anyhow::Result<AooScreen> {
-        self.open_usb(USB_UART_VID, USB_UART_PID)
+    /// Re-initialize the display after the given number of consecutive failed image transfers.
+    ///
+    /// The serial port is reopened and the display init sequence is sent again before the failed
+    /// image is retried. After three re-initializations without a successful transfer, failed
+    /// images are returned as errors without re-initializing the display again. Set to 0 to
+    /// disable. Defaults to 3.
+    pub fn reinit_threshold(&mut self, failures: u32) -> &mut Self {
+        self.reinit_threshold = Some(failures);
+        self
+    }
+
+    /// Re-initialize the display before sending an image if no image has been sent for the given
+    /// duration. Some units don't respond anymore after long idle periods. Disabled by default.
+    pub fn reinit_after_idle(&mut self, idle: Duration) -> &mut Self {
+        self.reinit_after_idle = Some(idle);
+        self
+    }
+
+    /// Set the pixel format of the image data sent to the display. Defaults to RGB 565 little endian.
+    ///
+    /// Use [ColorOrder::Bgr](crate::ColorOrder::Bgr) for firmware revisions with swapped red and
+    /// blue channels.
+    pub fn pixel_format(&mut self, format: PixelFormat) -> &mut Self {
+        self.pixel_format = Some(format);
+        self
+    }
+
+    /// Send run-length encoded image data. Experimental, disabled by default.
+    ///
+    /// The original app doesn't use a compressed transfer mode, support in the display firmware
+    /// is unconfirmed: the chunk command is an assumption without firmware reference, see
+    /// `docs/lcd_protocol.md`. Only used with [simulated](Self::simulate) displays and models
+    /// which [support compression](DisplayModel::supports_compression), none of the known models.
+    /// The first compressed image is checked for the display acknowledgement, and compression is
+    /// disabled if it is missing. Images which don't get smaller are sent
+    /// uncompressed. Requires the response check, i.e. doesn't work with
+    /// [no_init_check](Self::no_init_check).
+    pub fn compression(&mut self, enable: bool) -> &mut Self {
+        self.compression = Some(enable);
+        self
+    }
+
+    /// Keep the encoded image data of the given number of recently sent images. Defaults to 4.
+    ///
+    /// Sending an identical image again, e.g. a paused page, skips the conversion into the pixel
+    /// format of the display. Set to 0 to disable.
+    pub fn frame_cache(&mut self, frames: usize) -> &mut Self {
+        self.frame_cache = Some(frames);
+        self
+    }
+
+    /// Set the maximum delay between two reconnect attempts after the display has been
+    /// disconnected. Defaults to 60 seconds.
+    ///
+    /// The first attempt is made immediately, the delay is doubled after each failed attempt.
+    pub fn max_reconnect_delay(&mut self, delay: Duration) -> &mut Self {
+        self.max_reconnect_delay = Some(delay);
+        self
+    }
+
+    /// Set the display model. Defaults to the model of a known USB UART device, or [WTR_MAX].
+    pub fn model(&mut self, model: impl DisplayModel + 'static) -> &mut Self {
+        self.model = Some(Arc::new(model));
+        self
+    }
+
+    /// Rotate the images clockwise before they are sent to the display, e.g. for a vertically
+    /// mounted chassis. Defaults to no rotation.
+    ///
+    /// The [size](AooScreen::size) of the images to send is rotated accordingly.
+    pub fn rotation(&mut self, rotation: Rotation) -> &mut Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Mirror the images horizontally before they are rotated and sent to the display. Defaults
+    /// to false.
+    pub fn mirror(&mut self, mirror: bool) -> &mut Self {
+        self.mirror = Some(mirror);
+        self
+    }
+
+    /// Record the serial communication with the display into a protocol trace, which can be
+    /// replayed with [check_conformance](crate::check_conformance).
+    pub fn record_trace(&mut self, recorder: TraceRecorder) -> &mut Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Open the first present AOOSTAR display of the [KNOWN_DEVICES], e.g. USB UART 416:90A1.
+    pub fn open_default(mut self) -> anyhow::Result<AooScreen> {
+        let devices = find_display_devices()?;
+        let Some((port, device)) = devices.first() else {
+            let known: Vec<String> = KNOWN_DEVICES.iter().map(|d| d.to_string()).collect();
+            bail!(
+                "No AOOSTAR display found. Known USB devices: {}",
+                known.join(", ")
+            );
+        };
+        info!("Found display {device} on {port}");
+        self.model.get_or_insert_with(|| Arc::new(*device.model));
+        let mut screen = self.open_device(port)?;
+        screen.usb_id = Some((device.vid, device.pid));
+        Ok(screen)
     }
 
     /// Simulate the LCD device. No real device or serial port is required.
     pub fn simulate(self) -> anyhow::Result<AooScreen> {
-        Ok(AooScreen {
-            port: Some(Box::new(FakeSerialPort::new())),
-            enable_cache: self.enable_cache.unwrap_or(true),
-            prev_frame: None,
-            no_init_check: self.no_init_check.unwrap_or(false),
-        })
+        Ok(self.build(Box::new(FakeSerialPort::new()), None))
     }
 
     /// Open the specified USB UART device id. Format: vid:pid
@@ -86,48 +237,128 @@ impl AooScreenBuilder {
     }
 
     /// Open the specified USB UART
-    pub fn open_usb(self, vid: u16, pid: u16) -> anyhow::Result<AooScreen> {
+    pub fn open_usb(mut self, vid: u16, pid: u16) -> anyhow::Result<AooScreen> {
         let serial_dev = find_usb_serial_port(vid, pid)?;
-        self.open_device(&serial_dev)
+        if let Some(device) = known_device(vid, pid) {
+            self.model.get_or_insert_with(|| Arc::new(*device.model));
+        }
+        let mut screen = self.open_device(&serial_dev)?;
+        screen.usb_id = Some((vid, pid));
+        Ok(screen)
     }
 
     /// Open the specified serial device
     pub fn open_device(self, device: &str) -> anyhow::Result<AooScreen> {
-        let port = serialport::new(device, UART_BAUDRATE)
-            .timeout(self.timeout.unwrap_or(Duration::from_millis(1000)))
-            .open()
-            .with_context(|| format!("Error opening serial port: {device}"))?;
-
-        info!(
-            "Opened serial port {device}: baud={}, {}:{}:{}",
-            port.baud_rate()?,
-            port.data_bits()?,
-            port.parity()?,
-            port.stop_bits()?
-        );
+        let port = open_serial_port(device, self.port_timeout())?;
+        Ok(self.build(port, Some(device.to_string())))
+    }
 
-        Ok(AooScreen {
-            port: Some(port),
+    fn port_timeout(&self) -> Duration {
+        self.timeout.unwrap_or(Duration::from_millis(1000))
+    }
+
+    pub(crate) fn build(self, port: Box<dyn SerialPort>, device: Option<String>) -> AooScreen {
+        let timeout = self.port_timeout();
+        let model = self.model.unwrap_or_else(|| Arc::new(WTR_MAX));
+        // the compressed chunk command is unconfirmed, don't send it to real displays
+        let compression = self.compression.unwrap_or_default()
+            && (device.is_none() || model.supports_compression());
+        if self.compression == Some(true) && !compression {
+            warn!(
+                "Compression is not supported by display model {}, using uncompressed transfers",
+                model.name()
+            );
+        }
+        AooScreen {
+            timeout,
+            port: Some(record_port(port, &self.recorder)),
+            recorder: self.recorder,
+            model,
+            device,
+            usb_id: None,
             enable_cache: self.enable_cache.unwrap_or(true),
             prev_frame: None,
+            frame_cache: FrameCache::new(self.frame_cache.unwrap_or(FRAME_CACHE_SIZE)),
             no_init_check: self.no_init_check.unwrap_or(false),
-        })
+            reinit_threshold: self.reinit_threshold.unwrap_or(REINIT_THRESHOLD),
+            reinit_after_idle: self.reinit_after_idle,
+            pixel_format: self.pixel_format.unwrap_or_default(),
+            compression,
+            compression_supported: None,
+            rotation: self.rotation.unwrap_or_default(),
+            mirror: self.mirror.unwrap_or_default(),
+            failed_sends: 0,
+            reinits: 0,
+            last_send: None,
+            reconnect: Backoff::new(self.max_reconnect_delay.unwrap_or(MAX_RECONNECT_DELAY)),
+        }
+    }
+}
+
+fn open_serial_port(device: &str, timeout: Duration) -> anyhow::Result<Box<dyn SerialPort>> {
+    let port = serialport::new(device, UART_BAUDRATE)
+        .timeout(timeout)
+        .open()
+        .with_context(|| format!("Error opening serial port: {device}"))?;
+
+    info!(
+        "Opened serial port {device}: baud={}, {}:{}:{}",
+        port.baud_rate()?,
+        port.data_bits()?,
+        port.parity()?,
+        port.stop_bits()?
+    );
+
+    Ok(port)
+}
+
+fn record_port(port: Box<dyn SerialPort>, recorder: &Option<TraceRecorder>) -> Box<dyn SerialPort> {
+    match recorder {
+        Some(recorder) => Box::new(RecordingPort::new(port, recorder.clone())),
+        None => port,
     }
 }
 
 pub struct AooScreen {
     port: Option<Box<dyn SerialPort>>,
+    /// Protocol trace recorder of the display operations.
+    recorder: Option<TraceRecorder>,
+    model: Arc<dyn DisplayModel>,
+    /// Serial device path for reopening the port. `None` if simulated.
+    device: Option<String>,
+    /// USB VID:PID to find the serial device again after a disconnect.
+    usb_id: Option<(u16, u16)>,
+    timeout: Duration,
     enable_cache: bool,
     prev_frame: Option<BytesMut>,
+    /// Encoded image data of recently sent images.
+    frame_cache: FrameCache,
     no_init_check: bool,
+    reinit_threshold: u32,
+    reinit_after_idle: Option<Duration>,
+    pixel_format: PixelFormat,
+    compression: bool,
+    /// Result of the compressed transfer check, `None` until the first compressed transfer.
+    compression_supported: Option<bool>,
+    rotation: Rotation,
+    mirror: bool,
+    /// Number of consecutive failed image transfers.
+    failed_sends: u32,
+    /// Number of re-initializations after failed transfers since the last sent image.
+    reinits: u32,
+    last_send: Option<Instant>,
+    /// Reconnect attempts while the display is disconnected.
+    reconnect: Backoff,
 }
 
 #[allow(dead_code)]
 impl AooScreen {
     pub fn init(&mut self) -> anyhow::Result<()> {
+        self.record(TraceAction::Init);
         let port = self.port.as_mut().ok_or(anyhow!("LCD port not open"))?;
 
-        port.write(&DISPLAY_ON)
+        port.write_all(self.model.init_sequence())
+            .and_then(|_| port.flush())
             .with_context(|| "Error sending display on command")?;
 
         if self.no_init_check {
@@ -160,6 +391,65 @@ impl AooScreen {
         Ok(())
     }
 
+    /// Re-initialize the display: reopen the serial port and send the display init sequence.
+    ///
+    /// The previous frame cache is cleared, since the display content is unknown afterward.
+    ///
+    /// A USB display is looked up again by its VID:PID, since the serial device path can change
+    /// when the device is re-enumerated.
+    pub fn reinit(&mut self) -> anyhow::Result<()> {
+        info!("Re-initializing display");
+        self.clear_cache();
+        if self.device.is_some() {
+            // close the old port first, some drivers don't allow opening a device twice
+            self.port = None;
+            if let Some((vid, pid)) = self.usb_id {
+                self.device = Some(find_usb_serial_port(vid, pid)?);
+            }
+            let device = self.device.as_deref().unwrap_or_default();
+            let port = open_serial_port(device, self.timeout)?;
+            self.port = Some(record_port(port, &self.recorder));
+        }
+        self.init()
+    }
+
+    /// The display is connected, i.e. no reconnect is pending after a disconnect.
+    pub fn is_connected(&self) -> bool {
+        !self.reconnect.is_pending()
+    }
+
+    /// Re-initialize the display after a disconnect or failed transfers.
+    ///
+    /// Failed attempts are repeated with exponential backoff: while the next attempt isn't due,
+    /// an error is returned without accessing the serial port.
+    fn reconnect(&mut self) -> anyhow::Result<()> {
+        let now = Instant::now();
+        if let Some(remaining) = self.reconnect.remaining(now) {
+            return Err(anyhow!(
+                "Display disconnected, next reconnect attempt in {}s",
+                remaining.as_secs_f32().ceil()
+            ));
+        }
+        let disconnected = self.reconnect.is_pending();
+        match self.reinit() {
+            Ok(()) => {
+                if disconnected {
+                    info!("Display reconnected");
+                }
+                self.reconnect.reset();
+                Ok(())
+            }
+            Err(e) => {
+                let delay = self.reconnect.failed(now);
+                warn!(
+                    "Failed to reconnect display, retrying in {}s: {e:#}",
+                    delay.as_secs()
+                );
+                Err(e)
+            }
+        }
+    }
+
     pub fn close(&mut self) {
         if self.port.is_some() {
             if let Err(e) = self.off() {
@@ -170,17 +460,239 @@ impl AooScreen {
     }
 
     pub fn on(&mut self) -> anyhow::Result<()> {
-        self.send(&DISPLAY_ON)
+        self.record(TraceAction::On);
+        self.send_command(&DISPLAY_ON)
             .with_context(|| "Failed to send display on")
     }
 
     pub fn off(&mut self) -> anyhow::Result<()> {
-        self.send(&DISPLAY_OFF)
+        self.record(TraceAction::Off);
+        self.send_command(&DISPLAY_OFF)
             .with_context(|| "Failed to send display off")
     }
 
+    /// Send a display command, reconnecting a disconnected display first.
+    fn send_command(&mut self, command: &[u8]) -> anyhow::Result<()> {
+        if !self.is_connected() {
+            self.reconnect()?;
+        }
+        let result = self.send(command);
+        if let Err(e) = &result
+            && self.is_disconnect(e)
+        {
+            warn!("Display disconnected: {e:#}");
+            self.reconnect()?;
+            return self.send(command);
+        }
+        result
+    }
+
+    /// Send an image to the display.
+    ///
+    /// If the configured number of consecutive transfers failed, the display is re-initialized
+    /// and the transfer retried once before returning an error. The number of re-initializations
+    /// is limited until an image is sent successfully again: a display which is initialized, but
+    /// doesn't accept images, returns the transfer errors.
+    ///
+    /// If the serial device disappeared, e.g. after suspend/resume or a loose cable, the display
+    /// is reconnected immediately. Failed reconnects are retried with exponential backoff with
+    /// the next images, see [AooScreenBuilder::max_reconnect_delay]. Until then, images are
+    /// rejected with an error without accessing the serial port.
     pub fn send_image(&mut self, image: impl ToRgb565) -> anyhow::Result<()> {
-        let img_rgb565 = image.to_rgb565_le();
+        self.send_image_with_progress(image, |_, _| {})
+    }
+
+    /// Send an image to the display and report the transfer progress.
+    ///
+    /// The `progress` callback is called after each image data chunk with the number of processed
+    /// image bytes and the total image size in bytes. Chunks that are unchanged from the previous
+    /// frame are counted as processed without being sent. The callback is called again from the
+    /// start if the transfer is retried after a re-initialization.
+    ///
+    /// The time between two callbacks can be used to detect stalled transfers, e.g. on flaky USB
+    /// hubs.
+    pub fn send_image_with_progress(
+        &mut self,
+        image: impl ToRgb565,
+        mut progress: impl FnMut(usize, usize),
+    ) -> anyhow::Result<()> {
+        self.record(TraceAction::Image);
+        let frame_key = image.frame_key();
+        let img_rgb565 = match frame_key.and_then(|key| self.frame_cache.get(key)) {
+            Some(frame) => {
+                debug!("Using cached encoded image");
+                frame
+            }
+            None => {
+                let frame = self.encode_image(&image)?;
+                if let Some(key) = frame_key {
+                    self.frame_cache.insert(key, frame.clone());
+                }
+                frame
+            }
+        };
+
+        if !self.is_connected() {
+            self.reconnect()?;
+        } else if let Some(idle) = self.reinit_after_idle
+            && let Some(last_send) = self.last_send
+            && last_send.elapsed() > idle
+        {
+            info!("Display idle for {}s", last_send.elapsed().as_secs());
+            self.reconnect()
+                .with_context(|| "Failed to re-initialize idle display")?;
+        }
+
+        let result = match self.write_image(&img_rgb565, &mut progress) {
+            Err(e) if self.is_disconnect(&e) => {
+                warn!("Display disconnected: {e:#}");
+                self.reconnect()
+                    .and_then(|_| self.write_image(&img_rgb565, &mut progress))
+            }
+            Err(e)
+                if self.reinit_threshold > 0 && self.failed_sends + 1 >= self.reinit_threshold =>
+            {
+                warn!(
+                    "{} consecutive image transfers failed: {e:#}",
+                    self.failed_sends + 1
+                );
+                if self.reinits >= MAX_REINITS {
+                    Err(e.context(format!(
+                        "Display not responding after {MAX_REINITS} re-initializations"
+                    )))
+                } else {
+                    self.reinits += 1;
+                    self.reconnect()
+                        .with_context(|| "Failed to re-initialize display")
+                        .and_then(|_| self.write_image(&img_rgb565, &mut progress))
+                }
+            }
+            result => result,
+        };
+
+        match result {
+            Ok(()) => {
+                self.failed_sends = 0;
+                self.reinits = 0;
+                self.last_send = Some(Instant::now());
+                if self.enable_cache {
+                    self.prev_frame.replace(img_rgb565);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                self.failed_sends += 1;
+                // the display state is unknown after a failed transfer
+                self.clear_cache();
+                Err(e)
+            }
+        }
+    }
+
+    /// Convert an image into the pixel format, rotation and mirroring of the display.
+    fn encode_image(&self, image: &impl ToRgb565) -> anyhow::Result<BytesMut> {
+        let img_rgb565 = image.to_rgb565(self.pixel_format);
+        let rotation = match self.model.orientation() {
+            Orientation::Landscape => self.rotation,
+            Orientation::Portrait => self.rotation.then(Rotation::Rotate90),
+        };
+        if rotation == Rotation::Rotate0 && !self.mirror {
+            return Ok(img_rgb565);
+        }
+        let (width, height) = self.size();
+        if img_rgb565.len() != (width * height * 2) as usize {
+            bail!("Image size doesn't match the display size {width}x{height}");
+        }
+        Ok(transform_pixels(
+            &img_rgb565,
+            (width, height),
+            rotation,
+            self.mirror,
+        ))
+    }
+
+    fn record(&self, action: TraceAction) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&TraceEvent::Action(action));
+        }
+    }
+
+    /// Display model of the screen.
+    pub fn model(&self) -> &dyn DisplayModel {
+        self.model.as_ref()
+    }
+
+    /// Width and height of the images sent to the display, in pixels.
+    ///
+    /// The display size of the model, swapped if the images are rotated by 90° or 270°.
+    pub fn size(&self) -> (u32, u32) {
+        self.rotation.rotate_size(self.model.size())
+    }
+
+    /// Clockwise rotation of the images sent to the display.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Change the rotation of the images sent to the display, see
+    /// [AooScreenBuilder::rotation].
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+        self.frame_cache.clear();
+    }
+
+    /// Images are mirrored horizontally before they are sent to the display.
+    pub fn is_mirrored(&self) -> bool {
+        self.mirror
+    }
+
+    /// Change the horizontal mirroring of the images sent to the display.
+    pub fn set_mirror(&mut self, mirror: bool) {
+        self.mirror = mirror;
+        self.frame_cache.clear();
+    }
+
+    /// Pixel format of the image data sent to the display.
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    /// Number of consecutive failed image transfers.
+    pub fn failed_sends(&self) -> u32 {
+        self.failed_sends
+    }
+
+    /// Check if the error is caused by a disconnected serial device. Always false if simulated.
+    fn is_disconnect(&self, error: &anyhow::Error) -> bool {
+        self.device.is_some() && is_disconnect_error(error)
+    }
+
+    /// Compressed image transfers are enabled and haven't failed the acknowledgement check.
+    pub fn is_compression_active(&self) -> bool {
+        self.compression && !self.no_init_check && self.compression_supported != Some(false)
+    }
+
+    fn write_image(
+        &mut self,
+        img_rgb565: &BytesMut,
+        progress: &mut impl FnMut(usize, usize),
+    ) -> anyhow::Result<()> {
+        if self.is_compression_active() {
+            let checked = self.compression_supported.is_some();
+            match self.write_compressed_image(img_rgb565, progress) {
+                Ok(true) => return Ok(()),
+                // not smaller than the uncompressed image
+                Ok(false) => {}
+                Err(e) if checked => return Err(e),
+                Err(e) => {
+                    warn!("Compressed image transfer failed, using uncompressed transfers: {e:#}");
+                    self.compression_supported = Some(false);
+                    // the display content is unknown
+                    self.clear_cache();
+                }
+            }
+        }
+
         debug!(
             "Start sending image (size {}) {} cache... ",
             img_rgb565.len(),
@@ -195,19 +707,22 @@ impl AooScreen {
         self.send(&HEADER_START)
             .with_context(|| "Failed to send header start")?;
 
-        let mut buf = BytesMut::with_capacity(HEADER.len() + 4 + IMG_CHUNK_SIZE);
+        let chunk_size = self.model.chunk_size();
+        let mut buf = BytesMut::with_capacity(HEADER.len() + 4 + chunk_size);
+        let total = img_rgb565.len();
         let mut sent_chunks = 0;
-        for (idx, chunk) in img_rgb565.chunks(IMG_CHUNK_SIZE).enumerate() {
-            let offset = idx * IMG_CHUNK_SIZE;
+        for (idx, chunk) in img_rgb565.chunks(chunk_size).enumerate() {
+            let offset = idx * chunk_size;
 
             if self.enable_cache
                 && let Some(cache) = self.prev_frame.as_mut()
             {
-                let offset = idx * IMG_CHUNK_SIZE;
-                if offset + IMG_CHUNK_SIZE <= cache.len()
-                    && cache[offset..offset + IMG_CHUNK_SIZE].eq(chunk)
+                let offset = idx * chunk_size;
+                if offset + chunk_size <= cache.len()
+                    && cache[offset..offset + chunk_size].eq(chunk)
                 {
                     // Block is unchanged from the previous frame; skip sending
+                    progress(offset + chunk.len(), total);
                     continue;
                 }
             }
@@ -220,20 +735,124 @@ impl AooScreen {
             self.send(&buf)
                 .with_context(|| format!("Failed to send image data chunk {idx}"))?;
             sent_chunks += 1;
+            progress(offset + chunk.len(), total);
         }
 
         self.send(&HEADER_END)
             .with_context(|| "Failed to send header end")?;
 
-        if self.enable_cache {
-            self.prev_frame.replace(img_rgb565);
+        debug!(
+            "Image sent: {}ms, {sent_chunks} chunks",
+            start_time.elapsed().as_millis()
+        );
+
+        Ok(())
+    }
+
+    /// Changed ranges of the image compared to the previous frame, in whole chunks.
+    fn changed_ranges(&self, img_rgb565: &BytesMut) -> Vec<(usize, usize)> {
+        let chunk_size = self.model.chunk_size();
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for (idx, chunk) in img_rgb565.chunks(chunk_size).enumerate() {
+            let offset = idx * chunk_size;
+            if self.enable_cache
+                && let Some(cache) = &self.prev_frame
+                && offset + chunk.len() <= cache.len()
+                && cache[offset..offset + chunk.len()].eq(chunk)
+            {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some((_, end)) if *end == offset => *end += chunk.len(),
+                _ => ranges.push((offset, offset + chunk.len())),
+            }
+        }
+        ranges
+    }
+
+    /// Send the changed ranges of the image run-length encoded.
+    ///
+    /// Returns false without sending anything if the encoded data isn't smaller than the changed
+    /// chunks. The first transfer is checked for the display acknowledgement.
+    fn write_compressed_image(
+        &mut self,
+        img_rgb565: &BytesMut,
+        progress: &mut impl FnMut(usize, usize),
+    ) -> anyhow::Result<bool> {
+        let total = img_rgb565.len();
+        let chunk_size = self.model.chunk_size();
+        let mut changed = 0;
+        let mut blocks = Vec::new();
+        for (start, end) in self.changed_ranges(img_rgb565) {
+            // RLE packets contain whole pixels
+            let start = start & !1;
+            let pixels = &img_rgb565[start..end];
+            changed += pixels.len();
+            blocks.extend(encode_blocks(pixels, start, chunk_size));
+        }
+        let encoded: usize = blocks.iter().map(|(_, data)| data.len()).sum();
+        let chunks = changed.div_ceil(chunk_size);
+        let packet_header = HEADER_RLE.len() + 4;
+        if encoded + blocks.len() * packet_header >= changed + chunks * packet_header {
+            debug!("Image doesn't compress: {encoded} bytes for {changed} changed bytes");
+            return Ok(false);
         }
 
         debug!(
-            "Image sent: {}ms, {sent_chunks} chunks",
+            "Start sending compressed image: {} blocks instead of {chunks} chunks",
+            blocks.len()
+        );
+        let start_time = Instant::now();
+        let check = self.compression_supported.is_none();
+        if check && let Some(port) = &self.port {
+            port.clear(ClearBuffer::Input)?;
+        }
+        self.send(&HEADER_START)
+            .with_context(|| "Failed to send header start")?;
+
+        let mut buf = BytesMut::with_capacity(HEADER_RLE.len() + 4 + chunk_size);
+        for (idx, (offset, data)) in blocks.iter().enumerate() {
+            buf.clear();
+            buf.extend(&HEADER_RLE);
+            buf.put_u32_le(*offset as u32);
+            buf.extend(data);
+            self.send(&buf)
+                .with_context(|| format!("Failed to send compressed image block {idx}"))?;
+            let processed = blocks.get(idx + 1).map_or(total, |(next, _)| *next);
+            progress(processed, total);
+        }
+
+        self.send(&HEADER_END)
+            .with_context(|| "Failed to send header end")?;
+
+        if check {
+            self.read_acknowledgement()
+                .with_context(|| "Compressed image transfer not acknowledged")?;
+            info!("Display accepts compressed image transfers");
+            self.compression_supported = Some(true);
+        }
+
+        debug!(
+            "Compressed image sent: {}ms, {encoded} bytes",
             start_time.elapsed().as_millis()
         );
 
+        Ok(true)
+    }
+
+    /// Wait for the `A` response of the display.
+    fn read_acknowledgement(&mut self) -> anyhow::Result<()> {
+        let port = self.port.as_mut().ok_or(anyhow!("LCD port not open"))?;
+        let mut serial_buf = [0; 64];
+        let read = port
+            .read(&mut serial_buf)
+            .with_context(|| "Failed to read from serial port")?;
+        if !serial_buf[..read].contains(&b'A') {
+            return Err(anyhow!(
+                "received: {}",
+                String::from_utf8_lossy(&serial_buf[..read])
+            ));
+        }
         Ok(())
     }
 
@@ -283,6 +902,67 @@ impl AooScreen {
     }
 }
 
+/// Exponential backoff of reconnect attempts.
+#[derive(Debug)]
+struct Backoff {
+    max_delay: Duration,
+    /// Delay after the next failed attempt.
+    delay: Duration,
+    /// Time of the next attempt, `None` if no attempt failed.
+    next_attempt: Option<Instant>,
+}
+
+impl Backoff {
+    fn new(max_delay: Duration) -> Self {
+        Self {
+            max_delay,
+            delay: RECONNECT_DELAY.min(max_delay),
+            next_attempt: None,
+        }
+    }
+
+    /// An attempt failed and another one is pending.
+    fn is_pending(&self) -> bool {
+        self.next_attempt.is_some()
+    }
+
+    /// Remaining time until the next attempt, `None` if an attempt is due.
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.next_attempt
+            .and_then(|next| next.checked_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Schedule the next attempt after a failed attempt and return its delay.
+    fn failed(&mut self, now: Instant) -> Duration {
+        let delay = self.delay;
+        self.next_attempt = Some(now + delay);
+        self.delay = (delay * 2).min(self.max_delay);
+        delay
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.max_delay);
+    }
+}
+
+/// Check if the error is caused by a disconnected serial device, e.g. an unplugged USB cable.
+fn is_disconnect_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<serialport::Error>() {
+            return e.kind() == serialport::ErrorKind::NoDevice;
+        }
+        if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+            // ENXIO, ENODEV and EIO of an unplugged USB serial device
+            return matches!(
+                e.kind(),
+                std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::NotConnected
+            ) || (cfg!(unix) && matches!(e.raw_os_error(), Some(5 | 6 | 19)));
+        }
+        false
+    })
+}
+
 pub fn find_usb_serial_port(vid: u16, pid: u16) -> serialport::Result<String> {
     info!("Looking for USB serial port {vid:x}:{pid:x}");
     let ports = serialport::available_ports()?;
@@ -301,3 +981,236 @@ pub fn find_usb_serial_port(vid: u16, pid: u16) -> serialport::Result<String> {
         format!("USB serial port {vid:x}:{pid:x} not found"),
     ))
 }
+
+/// Find the serial ports of all present AOOSTAR displays, in the order of the [KNOWN_DEVICES].
+pub fn find_display_devices() -> serialport::Result<Vec<(String, DisplayDevice)>> {
+    let ports = serialport::available_ports()?;
+    let mut devices: Vec<(String, DisplayDevice)> = ports
+        .into_iter()
+        .filter_map(|p| {
+            debug!("Found serial port: {}", p.port_name);
+            match p.port_type {
+                SerialPortType::UsbPort(info) => {
+                    known_device(info.vid, info.pid).map(|device| (p.port_name, *device))
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    devices.sort_by_key(|(_, device)| KNOWN_DEVICES.iter().position(|d| d == device));
+    Ok(devices)
+}
+
+fn known_device(vid: u16, pid: u16) -> Option<&'static DisplayDevice> {
+    KNOWN_DEVICES.iter().find(|d| d.vid == vid && d.pid == pid)
+}
+
+/// Describe all available serial ports, including the VID:PID of USB serial ports and the
+/// display model of known AOOSTAR displays.
+pub fn list_serial_ports() -> serialport::Result<Vec<String>> {
+    let ports = serialport::available_ports()?;
+    Ok(ports
+        .into_iter()
+        .map(|p| match p.port_type {
+            SerialPortType::UsbPort(info) => {
+                let description = format!(
+                    "{} (USB {:x}:{:x}, {})",
+                    p.port_name,
+                    info.vid,
+                    info.pid,
+                    info.product.as_deref().unwrap_or("unknown product")
+                );
+                match known_device(info.vid, info.pid) {
+                    Some(device) => format!("{description}: {}", device.name),
+                    None => description,
+                }
+            }
+            port_type => format!("{} ({port_type:?})", p.port_name),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn known_display_devices() {
+        let device = known_device(0x0416, 0x90a1).unwrap();
+        assert_eq!(
+            "416:90a1 (AOOSTAR WTR MAX / GEM12+ PRO)",
+            device.to_string()
+        );
+        assert_eq!(None, known_device(0x0403, 0x6001));
+    }
+
+    #[test]
+    fn send_image_reports_progress() {
+        let mut screen = AooScreenBuilder::new().simulate().unwrap();
+        let image = RgbImage::from_pixel(DISPLAY_SIZE.0, DISPLAY_SIZE.1, Rgb([0, 0, 255]));
+        let total = (DISPLAY_SIZE.0 * DISPLAY_SIZE.1 * 2) as usize;
+
+        let mut reports = Vec::new();
+        screen
+            .send_image_with_progress(&image, |sent, size| reports.push((sent, size)))
+            .unwrap();
+        assert_eq!(total.div_ceil(WTR_MAX.chunk_size), reports.len());
+        assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(Some(&(total, total)), reports.last());
+
+        // unchanged chunks of a cached frame are reported as well
+        let mut last = None;
+        screen
+            .send_image_with_progress(&image, |sent, size| last = Some((sent, size)))
+            .unwrap();
+        assert_eq!(Some((total, total)), last);
+    }
+
+    #[test]
+    fn send_compressed_image() {
+        let mut builder = AooScreenBuilder::new();
+        builder.compression(true);
+        let mut screen = builder.simulate().unwrap();
+        let image = RgbImage::from_pixel(DISPLAY_SIZE.0, DISPLAY_SIZE.1, Rgb([0, 0, 255]));
+        let total = (DISPLAY_SIZE.0 * DISPLAY_SIZE.1 * 2) as usize;
+
+        let mut reports = Vec::new();
+        screen
+            .send_image_with_progress(&image, |sent, size| reports.push((sent, size)))
+            .unwrap();
+        // a single color compresses into runs of 128 pixels, 15 runs per block
+        assert_eq!(total.div_ceil(128 * 2 * 15), reports.len());
+        assert_eq!(Some(&(total, total)), reports.last());
+        assert!(screen.is_compression_active());
+    }
+
+    #[test]
+    fn compression_requires_model_support() {
+        #[derive(Debug)]
+        struct RleModel;
+
+        impl DisplayModel for RleModel {
+            fn name(&self) -> &str {
+                "RLE test display"
+            }
+            fn size(&self) -> (u32, u32) {
+                WTR_MAX.size
+            }
+            fn init_sequence(&self) -> &[u8] {
+                WTR_MAX.init_sequence
+            }
+            fn chunk_size(&self) -> usize {
+                WTR_MAX.chunk_size
+            }
+            fn supports_compression(&self) -> bool {
+                true
+            }
+        }
+
+        let mut builder = AooScreenBuilder::new();
+        builder.compression(true);
+        let screen = builder.build(Box::new(fast_port()), Some("/dev/ttyACM0".into()));
+        assert!(!screen.is_compression_active());
+
+        let mut builder = AooScreenBuilder::new();
+        builder.compression(true).model(RleModel);
+        let screen = builder.build(Box::new(fast_port()), Some("/dev/ttyACM0".into()));
+        assert!(screen.is_compression_active());
+    }
+
+    #[test]
+    fn repeated_images_use_encoded_frames() {
+        let mut screen = AooScreenBuilder::new().build(Box::new(fast_port()), None);
+        let blue = RgbImage::from_pixel(DISPLAY_SIZE.0, DISPLAY_SIZE.1, Rgb([0, 0, 255]));
+        let red = RgbImage::from_pixel(DISPLAY_SIZE.0, DISPLAY_SIZE.1, Rgb([255, 0, 0]));
+
+        screen.send_image(&blue).unwrap();
+        screen.send_image(&red).unwrap();
+        screen.send_image(&blue).unwrap();
+        assert_eq!(2, screen.frame_cache.len());
+        let cached = screen
+            .frame_cache
+            .get((&blue).frame_key().unwrap())
+            .unwrap();
+        assert_eq!((&blue).to_rgb565(screen.pixel_format()), cached);
+
+        // the encoded frames depend on the rotation
+        screen.set_rotation(Rotation::Rotate180);
+        assert_eq!(0, screen.frame_cache.len());
+    }
+
+    /// Fake serial port without the transfer time of the display baud rate.
+    fn fast_port() -> FakeSerialPort {
+        let mut port = FakeSerialPort::new();
+        port.set_baud_rate(u32::MAX).unwrap();
+        port
+    }
+
+    #[test]
+    fn reinit_after_failed_transfers() {
+        let mut builder = AooScreenBuilder::new();
+        builder.reinit_threshold(1).no_init_check(true);
+        // the image header fails until the display is re-initialized
+        let port = fast_port().fail_writes(DISPLAY_ON.len(), SERIAL_RETRY as u32 + 1);
+        let mut screen = builder.build(Box::new(port), None);
+        let image = RgbImage::from_pixel(DISPLAY_SIZE.0, DISPLAY_SIZE.1, Rgb([0, 0, 255]));
+
+        screen.send_image(&image).unwrap();
+        assert_eq!(0, screen.failed_sends());
+        assert_eq!(0, screen.reinits);
+    }
+
+    #[test]
+    fn limit_reinits_of_unresponsive_display() {
+        let mut builder = AooScreenBuilder::new();
+        builder.reinit_threshold(1).no_init_check(true);
+        let port = fast_port().fail_writes(DISPLAY_ON.len(), u32::MAX);
+        let mut screen = builder.build(Box::new(port), None);
+        let image = RgbImage::from_pixel(DISPLAY_SIZE.0, DISPLAY_SIZE.1, Rgb([0, 0, 255]));
+
+        for reinits in 1..=MAX_REINITS {
+            let error = screen.send_image(&image).unwrap_err();
+            assert_eq!("Failed to send header start", error.to_string());
+            assert_eq!(reinits, screen.reinits);
+        }
+        let error = screen.send_image(&image).unwrap_err();
+        assert_eq!(
+            "Display not responding after 3 re-initializations",
+            error.to_string()
+        );
+        assert_eq!(MAX_REINITS, screen.reinits);
+        assert_eq!(MAX_REINITS + 1, screen.failed_sends());
+    }
+
+    #[test]
+    fn reconnect_backoff() {
+        let mut backoff = Backoff::new(Duration::from_secs(5));
+        let now = Instant::now();
+        assert!(!backoff.is_pending());
+        assert_eq!(None, backoff.remaining(now));
+
+        let delays: Vec<_> = (0..5).map(|_| backoff.failed(now).as_secs()).collect();
+        assert_eq!(vec![1, 2, 4, 5, 5], delays);
+        assert!(backoff.is_pending());
+        assert_eq!(Some(Duration::from_secs(5)), backoff.remaining(now));
+        assert_eq!(None, backoff.remaining(now + Duration::from_secs(5)));
+
+        backoff.reset();
+        assert!(!backoff.is_pending());
+        assert_eq!(Duration::from_secs(1), backoff.failed(now));
+    }
+
+    #[test]
+    fn detect_disconnect_errors() {
+        let unplugged = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe))
+            .context("Failed to send header start");
+        assert!(is_disconnect_error(&unplugged));
+
+        let no_device = serialport::Error::new(serialport::ErrorKind::NoDevice, "not found");
+        assert!(is_disconnect_error(&no_device.into()));
+
+        let timeout = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert!(!is_disconnect_error(&timeout));
+    }
+}
@@ -13,6 +13,8 @@ pub struct FakeSerialPort {
     parity: Parity,
     stop_bits: StopBits,
     timeout: Duration,
+    /// Minimum size and number of failing writes.
+    failing_writes: Option<(usize, u32)>,
 }
 
 impl Default for FakeSerialPort {
@@ -30,8 +32,18 @@ impl FakeSerialPort {
             parity: Parity::None,
             stop_bits: StopBits::One,
             timeout: Default::default(),
+            failing_writes: None,
         }
     }
+
+    /// Let the given number of writes with more than `size` bytes fail with a timeout error.
+    ///
+    /// Shorter writes like display commands still succeed, e.g. to simulate a display which
+    /// doesn't accept image data anymore.
+    pub fn fail_writes(mut self, size: usize, count: u32) -> Self {
+        self.failing_writes = Some((size, count));
+        self
+    }
 }
 
 impl std::io::Read for FakeSerialPort {
@@ -43,6 +55,13 @@ impl std::io::Read for FakeSerialPort {
 
 impl std::io::Write for FakeSerialPort {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some((size, count)) = &mut self.failing_writes
+            && buf.len() > *size
+            && *count > 0
+        {
+            *count -= 1;
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
         // just some approximation, additional overhead like flushing etc is not considered
         let byte_rate =
             self.baud_rate / (1 + u8::from(self.data_bits) + u8::from(self.stop_bits)) as u32;
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Cache of encoded display frames.
+//!
+//! Converting an image into the pixel format of the display, including rotation and mirroring,
+//! takes longer than the diff against the previous frame. Frames which are shown repeatedly, e.g.
+//! a paused page or a static alert, are looked up by the hash of the source image instead of
+//! being encoded again.
+
+use bytes::BytesMut;
+use std::collections::VecDeque;
+
+/// Default number of cached encoded frames.
+pub(crate) const FRAME_CACHE_SIZE: usize = 4;
+
+/// Least recently used cache of encoded frames by image hash.
+pub(crate) struct FrameCache {
+    capacity: usize,
+    /// Most recently used frame first.
+    frames: VecDeque<(u64, BytesMut)>,
+}
+
+impl FrameCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            frames: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Get a copy of the encoded frame of an image hash.
+    pub fn get(&mut self, key: u64) -> Option<BytesMut> {
+        let idx = self.frames.iter().position(|(k, _)| *k == key)?;
+        let entry = self.frames.remove(idx)?;
+        let frame = entry.1.clone();
+        self.frames.push_front(entry);
+        Some(frame)
+    }
+
+    /// Add an encoded frame, the least recently used frame is dropped if the cache is full.
+    pub fn insert(&mut self, key: u64, frame: BytesMut) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.frames.retain(|(k, _)| *k != key);
+        self.frames.truncate(self.capacity - 1);
+        self.frames.push_front((key, frame));
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Remove all frames, e.g. after the rotation changed.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_frame_is_dropped() {
+        let mut cache = FrameCache::new(2);
+        cache.insert(1, BytesMut::from(&b"one"[..]));
+        cache.insert(2, BytesMut::from(&b"two"[..]));
+        assert_eq!(Some(&b"one"[..]), cache.get(1).as_deref());
+
+        cache.insert(3, BytesMut::from(&b"three"[..]));
+        assert_eq!(2, cache.len());
+        assert!(cache.get(2).is_none());
+        assert_eq!(Some(&b"one"[..]), cache.get(1).as_deref());
+        assert_eq!(Some(&b"three"[..]), cache.get(3).as_deref());
+
+        let mut disabled = FrameCache::new(0);
+        disabled.insert(1, BytesMut::from(&b"one"[..]));
+        assert!(disabled.get(1).is_none());
+    }
+}
@@ -7,48 +7,215 @@
 
 use bytes::{BufMut, BytesMut};
 use image::{RgbImage, RgbaImage};
+use std::fmt;
+use std::hash::{DefaultHasher, Hasher};
+use std::str::FromStr;
 
 mod aoo_screen;
 mod fake_serialport;
+mod frame_cache;
+mod model;
+mod rle;
+mod trace;
 
-pub use aoo_screen::{AooScreen, AooScreenBuilder, DISPLAY_SIZE};
+pub use aoo_screen::{
+    AooScreen, AooScreenBuilder, DISPLAY_SIZE, DisplayDevice, KNOWN_DEVICES, find_display_devices,
+    list_serial_ports,
+};
 pub use fake_serialport::FakeSerialPort;
+pub use model::{DisplayModel, ModelDescriptor, Orientation, Rotation, WTR_MAX};
+pub use trace::{Trace, TraceAction, TraceEvent, TraceRecorder, check_conformance};
+
+/// Order of the color components in a packed pixel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorOrder {
+    /// Red in the most significant bits.
+    #[default]
+    Rgb,
+    /// Blue in the most significant bits, i.e. swapped red and blue channels.
+    Bgr,
+}
+
+/// Byte order of a 16 bit pixel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteOrder {
+    #[default]
+    LittleEndian,
+    BigEndian,
+}
+
+/// Pixel format of the image data sent to the display.
+///
+/// The display uses 16 bit RGB 565 pixels in little endian format. Some firmware revisions are
+/// reported to swap the red and blue channels, which can be worked around with [ColorOrder::Bgr].
+///
+/// The format can be parsed from a string like `rgb565le` or `bgr565be`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PixelFormat {
+    pub color_order: ColorOrder,
+    pub byte_order: ByteOrder,
+}
+
+impl PixelFormat {
+    /// Pack a single RGB 888 pixel into a 16 bit 565 pixel with the configured color order.
+    pub fn pack(&self, r: u8, g: u8, b: u8) -> u16 {
+        let (high, low) = match self.color_order {
+            ColorOrder::Rgb => (r, b),
+            ColorOrder::Bgr => (b, r),
+        };
+        ((high & 248) as u16) << 8 | ((g & 252) as u16) << 3 | ((low as u16) >> 3)
+    }
+
+    /// Unpack a 16 bit 565 pixel with the configured color order into RGB 888 components.
+    ///
+    /// The discarded low bits are zero, packing the result again gives the same pixel.
+    pub fn unpack(&self, pixel: u16) -> [u8; 3] {
+        let high = (pixel >> 8) as u8 & 248;
+        let g = (pixel >> 3) as u8 & 252;
+        let low = (pixel << 3) as u8;
+        match self.color_order {
+            ColorOrder::Rgb => [high, g, low],
+            ColorOrder::Bgr => [low, g, high],
+        }
+    }
+
+    /// Append a packed pixel with the configured byte order.
+    pub fn put_pixel(&self, buf: &mut BytesMut, r: u8, g: u8, b: u8) {
+        let pixel = self.pack(r, g, b);
+        match self.byte_order {
+            ByteOrder::LittleEndian => buf.put_u16_le(pixel),
+            ByteOrder::BigEndian => buf.put_u16(pixel),
+        }
+    }
+}
+
+impl FromStr for PixelFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (color_order, byte_order) = match s.to_ascii_lowercase().as_str() {
+            "rgb565le" | "rgb565" => (ColorOrder::Rgb, ByteOrder::LittleEndian),
+            "rgb565be" => (ColorOrder::Rgb, ByteOrder::BigEndian),
+            "bgr565le" | "bgr565" => (ColorOrder::Bgr, ByteOrder::LittleEndian),
+            "bgr565be" => (ColorOrder::Bgr, ByteOrder::BigEndian),
+            _ => {
+                return Err(format!(
+                    "Invalid pixel format '{s}', expected rgb565le, rgb565be, bgr565le or bgr565be"
+                ));
+            }
+        };
+        Ok(PixelFormat {
+            color_order,
+            byte_order,
+        })
+    }
+}
+
+impl fmt::Display for PixelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let color_order = match self.color_order {
+            ColorOrder::Rgb => "rgb",
+            ColorOrder::Bgr => "bgr",
+        };
+        let byte_order = match self.byte_order {
+            ByteOrder::LittleEndian => "le",
+            ByteOrder::BigEndian => "be",
+        };
+        write!(f, "{color_order}565{byte_order}")
+    }
+}
 
 /// Trait definition to get a RGB 565 representation from a source image.
 pub trait ToRgb565 {
     /// Get an RGB 565 representation of the image in little endian format.
-    fn to_rgb565_le(&self) -> BytesMut;
+    fn to_rgb565_le(&self) -> BytesMut {
+        self.to_rgb565(PixelFormat::default())
+    }
+
+    /// Get a 16 bit 565 representation of the image in the given pixel format.
+    fn to_rgb565(&self, format: PixelFormat) -> BytesMut;
 
     /// Convert a single RGB 888 pixel to 16 bit RGB 565 format.
     fn convert_rgb(&self, r: u8, g: u8, b: u8) -> u16 {
-        ((r & 248) as u16) << 8 | ((g & 252) as u16) << 3 | ((b as u16) >> 3)
+        PixelFormat::default().pack(r, g, b)
+    }
+
+    /// Hash of the image content to look up the already encoded image in the frame cache of
+    /// [AooScreen]. `None` if the image isn't cached.
+    fn frame_key(&self) -> Option<u64> {
+        None
     }
 }
 
+/// Hash of the image size and raw pixel data.
+fn image_hash(width: u32, height: u32, pixels: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u32(width);
+    hasher.write_u32(height);
+    hasher.write(pixels);
+    hasher.finish()
+}
+
 // TODO quick & dirty approach for converting RgbImage & RgbaImage to RGB 565.
 //      There should be a more generic way, maybe with PixelEnumerator...
 impl ToRgb565 for &RgbImage {
-    fn to_rgb565_le(&self) -> BytesMut {
+    fn to_rgb565(&self, format: PixelFormat) -> BytesMut {
         let mut img_rgb565 =
             BytesMut::with_capacity(self.width() as usize * self.height() as usize * 2);
 
         for (_x, _y, pixel) in self.enumerate_pixels() {
-            img_rgb565.put_u16_le(self.convert_rgb(pixel.0[0], pixel.0[1], pixel.0[2]));
+            format.put_pixel(&mut img_rgb565, pixel.0[0], pixel.0[1], pixel.0[2]);
         }
 
         img_rgb565
     }
+
+    fn frame_key(&self) -> Option<u64> {
+        Some(image_hash(self.width(), self.height(), self.as_raw()))
+    }
 }
 
 impl ToRgb565 for &RgbaImage {
-    fn to_rgb565_le(&self) -> BytesMut {
+    fn to_rgb565(&self, format: PixelFormat) -> BytesMut {
         let mut img_rgb565 =
             BytesMut::with_capacity(self.width() as usize * self.height() as usize * 2);
 
         for (_x, _y, pixel) in self.enumerate_pixels() {
-            img_rgb565.put_u16_le(self.convert_rgb(pixel.0[0], pixel.0[1], pixel.0[2]));
+            format.put_pixel(&mut img_rgb565, pixel.0[0], pixel.0[1], pixel.0[2]);
         }
 
         img_rgb565
     }
+
+    fn frame_key(&self) -> Option<u64> {
+        Some(image_hash(self.width(), self.height(), self.as_raw()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_formats() {
+        let image = RgbImage::from_pixel(1, 1, image::Rgb([0xff, 0x80, 0x00]));
+        let image = &image;
+        assert_eq!(&[0x00, 0xfc][..], &image.to_rgb565_le()[..]);
+        for (format, expected) in [
+            ("rgb565le", [0x00, 0xfc]),
+            ("rgb565be", [0xfc, 0x00]),
+            ("bgr565le", [0x1f, 0x04]),
+            ("BGR565BE", [0x04, 0x1f]),
+        ] {
+            let format = format.parse::<PixelFormat>().unwrap();
+            assert_eq!(&expected[..], &image.to_rgb565(format)[..], "{format}");
+        }
+        assert!("rgb888".parse::<PixelFormat>().is_err());
+
+        let format = "bgr565le".parse::<PixelFormat>().unwrap();
+        assert_eq!(
+            [0xf8, 0x80, 0x00],
+            format.unpack(format.pack(0xff, 0x80, 0x00))
+        );
+    }
 }
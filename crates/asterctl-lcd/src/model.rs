@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Display models: resolution and transfer parameters of the displays using the AOOSTAR UART
+//! protocol.
+//!
+//! Another display can be supported by adding a [ModelDescriptor], and its USB UART to the
+//! [KNOWN_DEVICES](crate::KNOWN_DEVICES). Protocol variations can implement [DisplayModel].
+
+use crate::aoo_screen::DISPLAY_ON;
+use bytes::{BufMut, BytesMut};
+use std::fmt;
+use std::str::FromStr;
+
+/// AOOSTAR WTR MAX and GEM12+ PRO display with 960 × 376 pixels.
+pub const WTR_MAX: ModelDescriptor = ModelDescriptor {
+    name: "AOOSTAR WTR MAX / GEM12+ PRO",
+    size: (960, 376),
+    orientation: Orientation::Landscape,
+    init_sequence: &DISPLAY_ON,
+    chunk_size: 47,
+};
+
+/// Orientation of the display memory, relative to the images sent to the display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    /// The image rows are sent as they are.
+    #[default]
+    Landscape,
+    /// The display memory is in portrait orientation: images are rotated 90° clockwise before
+    /// they are sent.
+    Portrait,
+}
+
+/// Resolution and transfer parameters of a display model.
+pub trait DisplayModel: fmt::Debug + Send + Sync {
+    /// Model name for log messages.
+    fn name(&self) -> &str;
+
+    /// Width and height of the images sent to the display, in pixels.
+    fn size(&self) -> (u32, u32);
+
+    /// Orientation of the display memory. Defaults to [Orientation::Landscape].
+    fn orientation(&self) -> Orientation {
+        Orientation::Landscape
+    }
+
+    /// Command sequence sent to initialize the display, which is acknowledged with `A`.
+    fn init_sequence(&self) -> &[u8];
+
+    /// Number of image data bytes of a transfer chunk.
+    fn chunk_size(&self) -> usize;
+
+    /// Run-length encoded image transfers are confirmed by a protocol trace of the display.
+    /// Defaults to false: [compression](crate::AooScreenBuilder::compression) is only used with
+    /// simulated displays.
+    fn supports_compression(&self) -> bool {
+        false
+    }
+}
+
+/// Display model defined by its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelDescriptor {
+    pub name: &'static str,
+    pub size: (u32, u32),
+    pub orientation: Orientation,
+    pub init_sequence: &'static [u8],
+    pub chunk_size: usize,
+}
+
+impl DisplayModel for ModelDescriptor {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    fn init_sequence(&self) -> &[u8] {
+        self.init_sequence
+    }
+
+    fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+}
+
+/// Clockwise rotation of the images shown on the display, e.g. for a vertically mounted chassis.
+///
+/// The rotation can be parsed from the angle in degrees: `0`, `90`, `180` or `270`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Rotate0,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+impl Rotation {
+    /// Get the rotation of the given angle in degrees, `None` if it isn't a multiple of 90°.
+    pub fn from_degrees(degrees: u32) -> Option<Self> {
+        match degrees % 360 {
+            0 => Some(Rotation::Rotate0),
+            90 => Some(Rotation::Rotate90),
+            180 => Some(Rotation::Rotate180),
+            270 => Some(Rotation::Rotate270),
+            _ => None,
+        }
+    }
+
+    /// Rotation angle in degrees.
+    pub fn degrees(self) -> u32 {
+        match self {
+            Rotation::Rotate0 => 0,
+            Rotation::Rotate90 => 90,
+            Rotation::Rotate180 => 180,
+            Rotation::Rotate270 => 270,
+        }
+    }
+
+    /// Size of an image with the given size after the rotation.
+    pub fn rotate_size(self, (width, height): (u32, u32)) -> (u32, u32) {
+        match self {
+            Rotation::Rotate0 | Rotation::Rotate180 => (width, height),
+            Rotation::Rotate90 | Rotation::Rotate270 => (height, width),
+        }
+    }
+
+    /// Rotation of this rotation followed by the given rotation.
+    pub(crate) fn then(self, other: Rotation) -> Rotation {
+        Rotation::from_degrees(self.degrees() + other.degrees()).unwrap_or_default()
+    }
+}
+
+impl FromStr for Rotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim_end_matches('°')
+            .parse()
+            .ok()
+            .and_then(Rotation::from_degrees)
+            .ok_or_else(|| format!("Invalid rotation '{s}', expected 0, 90, 180 or 270"))
+    }
+}
+
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}°", self.degrees())
+    }
+}
+
+/// Mirror the 16 bit pixels of an image with the given size horizontally if `mirror` is set, and
+/// rotate them clockwise.
+pub(crate) fn transform_pixels(
+    pixels: &[u8],
+    (width, height): (u32, u32),
+    rotation: Rotation,
+    mirror: bool,
+) -> BytesMut {
+    let (out_width, out_height) = rotation.rotate_size((width, height));
+    let (width, height) = (width as usize, height as usize);
+    let mut transformed = BytesMut::with_capacity(pixels.len());
+    for y in 0..out_height as usize {
+        for x in 0..out_width as usize {
+            // position in the mirrored image
+            let (src_x, src_y) = match rotation {
+                Rotation::Rotate0 => (x, y),
+                Rotation::Rotate90 => (y, height - 1 - x),
+                Rotation::Rotate180 => (width - 1 - x, height - 1 - y),
+                Rotation::Rotate270 => (width - 1 - y, x),
+            };
+            let src_x = if mirror { width - 1 - src_x } else { src_x };
+            let idx = (src_y * width + src_x) * 2;
+            transformed.put_slice(&pixels[idx..idx + 2]);
+        }
+    }
+    transformed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Numbers of the transformed pixels of a 3 × 2 image with pixels numbered by rows.
+    fn transform(rotation: Rotation, mirror: bool) -> Vec<u8> {
+        let pixels: Vec<u8> = (1..=6).flat_map(|p| [p, 0]).collect();
+        let transformed = transform_pixels(&pixels, (3, 2), rotation, mirror);
+        transformed.chunks(2).map(|p| p[0]).collect()
+    }
+
+    #[test]
+    fn rotate_clockwise() {
+        for (rotation, expected) in [
+            (Rotation::Rotate0, [1, 2, 3, 4, 5, 6]),
+            (Rotation::Rotate90, [4, 1, 5, 2, 6, 3]),
+            (Rotation::Rotate180, [6, 5, 4, 3, 2, 1]),
+            (Rotation::Rotate270, [3, 6, 2, 5, 1, 4]),
+        ] {
+            assert_eq!(expected[..], transform(rotation, false), "{rotation}");
+        }
+        assert_eq!(
+            Rotation::Rotate90,
+            Rotation::Rotate270.then(Rotation::Rotate180)
+        );
+        assert_eq!(Ok(Rotation::Rotate270), "270".parse());
+        assert!("45".parse::<Rotation>().is_err());
+    }
+
+    #[test]
+    fn mirror_horizontally() {
+        assert_eq!(vec![3, 2, 1, 6, 5, 4], transform(Rotation::Rotate0, true));
+        // mirrored before the rotation
+        assert_eq!(vec![6, 3, 5, 2, 4, 1], transform(Rotation::Rotate90, true));
+    }
+}
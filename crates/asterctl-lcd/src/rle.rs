@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Run-length encoding of 16 bit pixel data for compressed image transfers.
+//!
+//! The data is encoded in packets of whole pixels, starting with a control byte:
+//! - `0x80 | (n - 1)`: the following pixel is repeated `n` times, 1 ≤ n ≤ 128.
+//! - `n - 1`: `n` literal pixels follow, 1 ≤ n ≤ 128.
+//!
+//! Pixels are copied as they are, independent of the pixel format. The format is the common
+//! PackBits-style encoding of whole pixels, not a confirmed format of the display firmware.
+
+use bytes::{BufMut, BytesMut};
+
+/// Maximum number of pixels of a packet.
+const MAX_PACKET_PIXELS: usize = 128;
+/// Bytes per pixel.
+const PIXEL_SIZE: usize = 2;
+
+/// Encode the pixel data into blocks of at most `max_block_size` bytes, which fit into a transfer
+/// chunk.
+///
+/// Returns the offset of the first decoded byte and the encoded data of each block. `offset` is
+/// the position of `pixels` in the image, it is added to the offset of each block.
+/// A trailing odd byte is encoded as a literal pixel padded with zero.
+///
+/// # Panics
+///
+/// Panics if `max_block_size` can't hold a single packet of one pixel.
+pub(crate) fn encode_blocks(
+    pixels: &[u8],
+    offset: usize,
+    max_block_size: usize,
+) -> Vec<(usize, BytesMut)> {
+    assert!(max_block_size > PIXEL_SIZE, "RLE block size too small");
+    // longest literal packet fitting into a block
+    let max_literal = ((max_block_size - 1) / PIXEL_SIZE).min(MAX_PACKET_PIXELS);
+    let pixel = |idx: usize| {
+        let start = idx * PIXEL_SIZE;
+        [
+            pixels[start],
+            pixels.get(start + 1).copied().unwrap_or_default(),
+        ]
+    };
+    let count = pixels.len().div_ceil(PIXEL_SIZE);
+
+    let mut blocks = Vec::new();
+    let mut block = BytesMut::with_capacity(max_block_size);
+    let mut block_start = 0;
+    let mut idx = 0;
+    while idx < count {
+        let value = pixel(idx);
+        let run = (idx..count.min(idx + MAX_PACKET_PIXELS))
+            .take_while(|&i| pixel(i) == value)
+            .count();
+        let packet_len = if run > 1 {
+            1 + PIXEL_SIZE
+        } else {
+            // literal pixels up to the next run of at least two identical pixels
+            let mut end = idx + 1;
+            while end < count
+                && end - idx < max_literal
+                && (end + 1 >= count || pixel(end) != pixel(end + 1))
+            {
+                end += 1;
+            }
+            1 + (end - idx) * PIXEL_SIZE
+        };
+
+        if block.len() + packet_len > max_block_size {
+            blocks.push((offset + block_start * PIXEL_SIZE, block.split()));
+            block_start = idx;
+        }
+
+        if run > 1 {
+            block.put_u8(0x80 | (run - 1) as u8);
+            block.put_slice(&value);
+            idx += run;
+        } else {
+            let literals = (packet_len - 1) / PIXEL_SIZE;
+            block.put_u8((literals - 1) as u8);
+            for i in idx..idx + literals {
+                block.put_slice(&pixel(i));
+            }
+            idx += literals;
+        }
+    }
+    if !block.is_empty() {
+        blocks.push((offset + block_start * PIXEL_SIZE, block));
+    }
+
+    blocks
+}
+
+/// Decode the data of a block. Returns `None` if the data ends within a packet.
+pub(crate) fn decode_block(data: &[u8]) -> Option<Vec<u8>> {
+    let mut pixels = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let count = (data[pos] & 0x7f) as usize + 1;
+        if data[pos] & 0x80 != 0 {
+            let pixel = data.get(pos + 1..pos + 1 + PIXEL_SIZE)?;
+            for _ in 0..count {
+                pixels.extend_from_slice(pixel);
+            }
+            pos += 1 + PIXEL_SIZE;
+        } else {
+            pixels.extend_from_slice(data.get(pos + 1..pos + 1 + count * PIXEL_SIZE)?);
+            pos += 1 + count * PIXEL_SIZE;
+        }
+    }
+    Some(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_runs_and_literals() {
+        let mut pixels = [0x1f, 0x00].repeat(300);
+        pixels.extend([1, 2, 3, 4, 5, 6, 5, 6, 7, 8]);
+        let blocks = encode_blocks(&pixels, 100, 47);
+
+        // 300 pixels in runs of 128 + 128 + 44, 2 literals, a run of 2 and a literal
+        assert_eq!(1, blocks.len());
+        assert_eq!(100, blocks[0].0);
+        assert_eq!(3 * 3 + 5 + 3 + 3, blocks[0].1.len());
+        assert_eq!(pixels, decode_block(&blocks[0].1).unwrap());
+    }
+
+    #[test]
+    fn split_into_blocks() {
+        let pixels: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let mut decoded = Vec::new();
+        for (offset, block) in &encode_blocks(&pixels, 0, 47) {
+            assert!(block.len() <= 47);
+            assert_eq!(decoded.len(), *offset);
+            decoded.extend(decode_block(block).unwrap());
+        }
+        assert_eq!(pixels, decoded);
+        assert_eq!(None, decode_block(&[0x81, 0x1f]));
+    }
+}
@@ -0,0 +1,821 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Protocol traces for conformance tests.
+//!
+//! A trace records the serial communication with a display: the [AooScreen](crate::AooScreen)
+//! operations, the data written to the display and the responses read from it. Traces captured
+//! from real devices with [AooScreenBuilder::record_trace] are replayed against the
+//! implementation with [check_conformance], so protocol changes, e.g. for new firmware revisions,
+//! can be validated without the hardware.
+//!
+//! Trace files are line based:
+//! - `@ init`, `@ on`, `@ off`, `@ image`: a display operation.
+//! - `> aa55aa550b000000`: data written to the display in hex notation, one line per command.
+//! - `< 41`: a response read from the display.
+//! - Empty lines and lines starting with `#` are ignored.
+//!
+//! Images aren't stored in the trace. The image of a replayed `@ image` operation is
+//! reconstructed from the recorded image data chunks.
+
+use crate::FakeSerialPort;
+use crate::aoo_screen::{AooScreenBuilder, HEADER, HEADER_RLE};
+use crate::model::{DisplayModel, Orientation};
+use crate::rle::decode_block;
+use crate::{ByteOrder, PixelFormat};
+
+use anyhow::{Context, anyhow, bail};
+use image::{Rgb, RgbImage, imageops};
+use log::warn;
+use serialport::{ClearBuffer, DataBits, FlowControl, Parity, SerialPort, StopBits};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// Maximum number of bytes shown in a write mismatch.
+const MAX_DIFF_BYTES: usize = 24;
+
+/// Display operation of a trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceAction {
+    /// [AooScreen::init](crate::AooScreen::init)
+    Init,
+    /// [AooScreen::on](crate::AooScreen::on)
+    On,
+    /// [AooScreen::off](crate::AooScreen::off)
+    Off,
+    /// [AooScreen::send_image](crate::AooScreen::send_image)
+    Image,
+}
+
+impl fmt::Display for TraceAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TraceAction::Init => "init",
+            TraceAction::On => "on",
+            TraceAction::Off => "off",
+            TraceAction::Image => "image",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for TraceAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "init" => Ok(TraceAction::Init),
+            "on" => Ok(TraceAction::On),
+            "off" => Ok(TraceAction::Off),
+            "image" => Ok(TraceAction::Image),
+            _ => bail!("Unknown operation '{s}', expected init, on, off or image"),
+        }
+    }
+}
+
+/// Recorded event of a trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// Display operation.
+    Action(TraceAction),
+    /// Data written to the display.
+    Write(Vec<u8>),
+    /// Response read from the display.
+    Read(Vec<u8>),
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::Action(action) => write!(f, "@ {action}"),
+            TraceEvent::Write(data) => write!(f, "> {}", to_hex(data)),
+            TraceEvent::Read(data) => write!(f, "< {}", to_hex(data)),
+        }
+    }
+}
+
+/// Recorded serial communication with a display.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+    /// Line number of each event in the trace file.
+    lines: Vec<usize>,
+}
+
+impl Trace {
+    /// Load a trace file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read trace {}", path.display()))?
+            .parse()
+            .with_context(|| format!("Invalid trace {}", path.display()))
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+}
+
+impl FromStr for Trace {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut trace = Trace::default();
+        for (idx, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let event = parse_event(line).with_context(|| format!("line {}: {line}", idx + 1))?;
+            trace.events.push(event);
+            trace.lines.push(idx + 1);
+        }
+        Ok(trace)
+    }
+}
+
+fn parse_event(line: &str) -> anyhow::Result<TraceEvent> {
+    let mut chars = line.chars();
+    let kind = chars.next();
+    let value = chars.as_str().trim();
+    match kind {
+        Some('@') => Ok(TraceEvent::Action(value.parse()?)),
+        Some('>') => Ok(TraceEvent::Write(parse_hex(value)?)),
+        Some('<') => Ok(TraceEvent::Read(parse_hex(value)?)),
+        _ => bail!("Expected an operation `@`, written data `>` or a response `<`"),
+    }
+}
+
+fn parse_hex(value: &str) -> anyhow::Result<Vec<u8>> {
+    let digits: Vec<u32> = value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .ok_or_else(|| anyhow!("Invalid hex digit '{c}'"))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        bail!("Expected an even number of hex digits");
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| (pair[0] << 4 | pair[1]) as u8)
+        .collect())
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Records the serial communication of an [AooScreen](crate::AooScreen) into a trace file.
+///
+/// All clones of a recorder write into the same trace.
+#[derive(Clone)]
+pub struct TraceRecorder {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl TraceRecorder {
+    /// Create a trace file.
+    pub fn create(path: &Path) -> anyhow::Result<Self> {
+        let mut file = BufWriter::new(
+            File::create(path)
+                .with_context(|| format!("Failed to create trace {}", path.display()))?,
+        );
+        writeln!(
+            file,
+            "# asterctl-lcd {} protocol trace",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        Ok(Self::new(file))
+    }
+
+    /// Record into the given writer.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+        }
+    }
+
+    pub(crate) fn record(&self, event: &TraceEvent) {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = writeln!(writer, "{event}").and_then(|_| writer.flush()) {
+            warn!("Failed to record protocol trace: {e}");
+        }
+    }
+}
+
+/// Serial port recording all written and read data.
+pub(crate) struct RecordingPort {
+    port: Box<dyn SerialPort>,
+    recorder: TraceRecorder,
+    /// Data written since the last flush, recorded as one command.
+    written: Vec<u8>,
+}
+
+impl RecordingPort {
+    pub(crate) fn new(port: Box<dyn SerialPort>, recorder: TraceRecorder) -> Self {
+        Self {
+            port,
+            recorder,
+            written: Vec::new(),
+        }
+    }
+}
+
+impl Read for RecordingPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.port.read(buf)?;
+        if read > 0 {
+            self.recorder
+                .record(&TraceEvent::Read(buf[..read].to_vec()));
+        }
+        Ok(read)
+    }
+}
+
+impl Write for RecordingPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.port.write(buf)?;
+        self.written.extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.port.flush()?;
+        if !self.written.is_empty() {
+            let data = std::mem::take(&mut self.written);
+            self.recorder.record(&TraceEvent::Write(data));
+        }
+        Ok(())
+    }
+}
+
+impl SerialPort for RecordingPort {
+    fn name(&self) -> Option<String> {
+        self.port.name()
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        self.port.baud_rate()
+    }
+
+    fn data_bits(&self) -> serialport::Result<DataBits> {
+        self.port.data_bits()
+    }
+
+    fn flow_control(&self) -> serialport::Result<FlowControl> {
+        self.port.flow_control()
+    }
+
+    fn parity(&self) -> serialport::Result<Parity> {
+        self.port.parity()
+    }
+
+    fn stop_bits(&self) -> serialport::Result<StopBits> {
+        self.port.stop_bits()
+    }
+
+    fn timeout(&self) -> Duration {
+        self.port.timeout()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> serialport::Result<()> {
+        self.port.set_baud_rate(baud_rate)
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> serialport::Result<()> {
+        self.port.set_data_bits(data_bits)
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> serialport::Result<()> {
+        self.port.set_flow_control(flow_control)
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> serialport::Result<()> {
+        self.port.set_parity(parity)
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> serialport::Result<()> {
+        self.port.set_stop_bits(stop_bits)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.port.set_timeout(timeout)
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()> {
+        self.port.write_request_to_send(level)
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> {
+        self.port.write_data_terminal_ready(level)
+    }
+
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        self.port.read_clear_to_send()
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        self.port.read_data_set_ready()
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        self.port.read_ring_indicator()
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        self.port.read_carrier_detect()
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        self.port.bytes_to_read()
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        self.port.bytes_to_write()
+    }
+
+    fn clear(&self, buffer_to_clear: ClearBuffer) -> serialport::Result<()> {
+        self.port.clear(buffer_to_clear)
+    }
+
+    fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> {
+        Err(serialport::Error::new(
+            serialport::ErrorKind::Unknown,
+            "A recording serial port can't be cloned",
+        ))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        self.port.set_break()
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        self.port.clear_break()
+    }
+}
+
+/// Replay state of a trace, shared between the [ReplayPort] and [check_conformance].
+struct Replay {
+    trace: Trace,
+    /// Index of the next event.
+    pos: usize,
+    /// Data written since the last flush.
+    written: Vec<u8>,
+    /// First difference between the trace and the written data.
+    mismatch: Option<String>,
+}
+
+impl Replay {
+    /// Skip recorded responses which haven't been read, e.g. image transfer acknowledgements.
+    fn skip_responses(&mut self) {
+        while matches!(self.trace.events.get(self.pos), Some(TraceEvent::Read(_))) {
+            self.pos += 1;
+        }
+    }
+
+    fn line(&self) -> usize {
+        self.trace.lines.get(self.pos).copied().unwrap_or_default()
+    }
+
+    fn check_written(&mut self) -> Result<(), String> {
+        let data = std::mem::take(&mut self.written);
+        self.skip_responses();
+        let mismatch = match self.trace.events.get(self.pos) {
+            Some(TraceEvent::Write(expected)) if *expected == data => {
+                self.pos += 1;
+                return Ok(());
+            }
+            Some(TraceEvent::Write(expected)) => format!(
+                "line {}: written data differs\n  expected: {}\n  written:  {}",
+                self.line(),
+                diff_hex(expected),
+                diff_hex(&data)
+            ),
+            Some(event) => format!(
+                "line {}: unexpected write before `{event}`: {}",
+                self.line(),
+                diff_hex(&data)
+            ),
+            None => format!(
+                "unexpected write after the end of the trace: {}",
+                diff_hex(&data)
+            ),
+        };
+        self.mismatch.get_or_insert(mismatch.clone());
+        Err(mismatch)
+    }
+}
+
+fn diff_hex(data: &[u8]) -> String {
+    if data.len() > MAX_DIFF_BYTES {
+        format!(
+            "{}... ({} bytes)",
+            to_hex(&data[..MAX_DIFF_BYTES]),
+            data.len()
+        )
+    } else {
+        to_hex(data)
+    }
+}
+
+/// Serial port comparing the written data with a trace and returning the recorded responses.
+struct ReplayPort {
+    replay: Arc<Mutex<Replay>>,
+    /// Serial port settings.
+    settings: FakeSerialPort,
+}
+
+impl ReplayPort {
+    fn replay(&self) -> MutexGuard<'_, Replay> {
+        self.replay.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Read for ReplayPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut replay = self.replay();
+        let pos = replay.pos;
+        match replay.trace.events.get_mut(pos) {
+            Some(TraceEvent::Read(data)) => {
+                let read = buf.len().min(data.len());
+                buf[..read].copy_from_slice(&data[..read]);
+                data.drain(..read);
+                if data.is_empty() {
+                    replay.pos += 1;
+                }
+                Ok(read)
+            }
+            _ => Err(io::ErrorKind::TimedOut.into()),
+        }
+    }
+}
+
+impl Write for ReplayPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.replay().written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut replay = self.replay();
+        if replay.written.is_empty() {
+            return Ok(());
+        }
+        replay.check_written().map_err(io::Error::other)
+    }
+}
+
+impl SerialPort for ReplayPort {
+    fn name(&self) -> Option<String> {
+        Some("Trace replay".into())
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        self.settings.baud_rate()
+    }
+
+    fn data_bits(&self) -> serialport::Result<DataBits> {
+        self.settings.data_bits()
+    }
+
+    fn flow_control(&self) -> serialport::Result<FlowControl> {
+        self.settings.flow_control()
+    }
+
+    fn parity(&self) -> serialport::Result<Parity> {
+        self.settings.parity()
+    }
+
+    fn stop_bits(&self) -> serialport::Result<StopBits> {
+        self.settings.stop_bits()
+    }
+
+    fn timeout(&self) -> Duration {
+        self.settings.timeout()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> serialport::Result<()> {
+        self.settings.set_baud_rate(baud_rate)
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> serialport::Result<()> {
+        self.settings.set_data_bits(data_bits)
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> serialport::Result<()> {
+        self.settings.set_flow_control(flow_control)
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> serialport::Result<()> {
+        self.settings.set_parity(parity)
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> serialport::Result<()> {
+        self.settings.set_stop_bits(stop_bits)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.settings.set_timeout(timeout)
+    }
+
+    fn write_request_to_send(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn write_data_terminal_ready(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        Ok(false)
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        Ok(false)
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        let replay = self.replay();
+        Ok(match replay.trace.events.get(replay.pos) {
+            Some(TraceEvent::Read(data)) => data.len() as u32,
+            _ => 0,
+        })
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        Ok(0)
+    }
+
+    fn clear(&self, _buffer_to_clear: ClearBuffer) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> {
+        Err(serialport::Error::new(
+            serialport::ErrorKind::Unknown,
+            "A replay serial port can't be cloned",
+        ))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+}
+
+/// Replay a trace against the [AooScreen](crate::AooScreen) implementation.
+///
+/// The operations of the trace are executed on a screen created with the given builder, which
+/// must write the same data as recorded. Recorded responses are returned when the screen reads
+/// from the serial port, responses which aren't read are skipped.
+///
+/// Returns an error with the trace line of the first difference.
+pub fn check_conformance(trace: &Trace, builder: AooScreenBuilder) -> anyhow::Result<()> {
+    let replay = Arc::new(Mutex::new(Replay {
+        trace: trace.clone(),
+        pos: 0,
+        written: Vec::new(),
+        mismatch: None,
+    }));
+    let port = ReplayPort {
+        replay: replay.clone(),
+        settings: FakeSerialPort::new(),
+    };
+    let mut screen = builder.build(Box::new(port), None);
+    let lock = || replay.lock().unwrap_or_else(PoisonError::into_inner);
+
+    // display memory reconstructed from the image data chunks
+    let (width, height) = screen.size();
+    let mut frame = vec![0; (width * height * 2) as usize];
+    for (idx, event) in trace.events.iter().enumerate() {
+        let TraceEvent::Action(action) = event else {
+            continue;
+        };
+        let line = trace.lines[idx];
+        {
+            let mut replay = lock();
+            replay.skip_responses();
+            if replay.pos < idx {
+                bail!(
+                    "line {}: recorded data not written: {}",
+                    replay.line(),
+                    trace.events[replay.pos]
+                );
+            }
+            replay.pos = idx + 1;
+        }
+
+        let result = match action {
+            TraceAction::Init => screen.init(),
+            TraceAction::On => screen.on(),
+            TraceAction::Off => screen.off(),
+            TraceAction::Image => {
+                apply_image_data(&mut frame, &trace.events[idx + 1..])
+                    .with_context(|| format!("line {line}: invalid image data"))?;
+                screen.send_image(&frame_image(&frame, screen.model(), screen.pixel_format()))
+            }
+        };
+        if let Some(mismatch) = lock().mismatch.take() {
+            bail!("`@ {action}` at line {line}: {mismatch}");
+        }
+        result.with_context(|| format!("`@ {action}` at line {line} failed"))?;
+    }
+
+    let mut replay = lock();
+    replay.skip_responses();
+    if let Some(event) = trace.events.get(replay.pos) {
+        bail!("line {}: recorded data not written: {event}", replay.line());
+    }
+    Ok(())
+}
+
+/// Apply the image data chunks written for an image operation to the display memory.
+fn apply_image_data(frame: &mut [u8], events: &[TraceEvent]) -> anyhow::Result<()> {
+    let writes = events
+        .iter()
+        .take_while(|event| !matches!(event, TraceEvent::Action(_)))
+        .filter_map(|event| match event {
+            TraceEvent::Write(data) => Some(data),
+            _ => None,
+        });
+    for data in writes {
+        let compressed = data.starts_with(&HEADER_RLE);
+        if !compressed && !data.starts_with(&HEADER) {
+            continue;
+        }
+        let offset = data
+            .get(HEADER.len()..HEADER.len() + 4)
+            .context("Missing chunk offset")?;
+        let offset = u32::from_le_bytes(offset.try_into()?) as usize;
+        let chunk = &data[HEADER.len() + 4..];
+        let pixels = if compressed {
+            decode_block(chunk).context("Invalid run-length encoded chunk")?
+        } else {
+            chunk.to_vec()
+        };
+        frame
+            .get_mut(offset..offset + pixels.len())
+            .context("Image data outside of the display")?
+            .copy_from_slice(&pixels);
+    }
+    Ok(())
+}
+
+/// Convert the display memory into an image, which gives the same data when sent again.
+fn frame_image(frame: &[u8], model: &dyn DisplayModel, format: PixelFormat) -> RgbImage {
+    let (width, height) = match model.orientation() {
+        Orientation::Landscape => model.size(),
+        Orientation::Portrait => (model.size().1, model.size().0),
+    };
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        let idx = ((y * width + x) * 2) as usize;
+        let bytes = [frame[idx], frame[idx + 1]];
+        let pixel = match format.byte_order {
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+        };
+        Rgb(format.unpack(pixel))
+    });
+    match model.orientation() {
+        Orientation::Landscape => image,
+        // images are rotated clockwise before they are sent
+        Orientation::Portrait => imageops::rotate270(&image),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DISPLAY_SIZE, ModelDescriptor};
+
+    #[test]
+    fn parse_trace() {
+        let trace: Trace = "# display on\n\n@ on\n> aa55 aa55 0b00 0000\n< 41\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            &[
+                TraceEvent::Action(TraceAction::On),
+                TraceEvent::Write(vec![0xaa, 0x55, 0xaa, 0x55, 0x0b, 0, 0, 0]),
+                TraceEvent::Read(vec![b'A']),
+            ],
+            trace.events()
+        );
+        assert_eq!("> aa55aa550b000000", trace.events()[1].to_string());
+
+        let error = "@ on\n> aa5\n".parse::<Trace>().unwrap_err();
+        assert_eq!("line 2: > aa5", error.to_string());
+        assert!("@ reset".parse::<Trace>().is_err());
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let path = std::env::temp_dir().join(format!("asterctl-lcd-{}.trace", std::process::id()));
+        let mut port = FakeSerialPort::new();
+        port.set_baud_rate(u32::MAX).unwrap();
+        let mut builder = AooScreenBuilder::new();
+        builder.record_trace(TraceRecorder::create(&path).unwrap());
+        let mut screen = builder.build(Box::new(port), None);
+
+        let mut image = RgbImage::from_pixel(DISPLAY_SIZE.0, DISPLAY_SIZE.1, Rgb([0, 0, 255]));
+        screen.init().unwrap();
+        screen.send_image(&image).unwrap();
+        image.put_pixel(100, 200, Rgb([255, 128, 0]));
+        screen.send_image(&image).unwrap();
+        screen.off().unwrap();
+        drop(screen);
+
+        let trace = Trace::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let actions: Vec<_> = trace
+            .events()
+            .iter()
+            .filter(|event| matches!(event, TraceEvent::Action(_)))
+            .collect();
+        assert_eq!(4, actions.len());
+        assert_eq!(Some(&TraceEvent::Read(vec![b'A'])), trace.events().get(2));
+
+        check_conformance(&trace, AooScreenBuilder::new()).unwrap();
+
+        // without the frame cache, all chunks of the second image are sent
+        let mut builder = AooScreenBuilder::new();
+        builder.enable_cache(false);
+        let error = check_conformance(&trace, builder).unwrap_err();
+        assert!(
+            error.to_string().contains("written data differs"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn replay_portrait_model() {
+        let model = ModelDescriptor {
+            name: "portrait strip",
+            size: (4, 3),
+            orientation: Orientation::Portrait,
+            init_sequence: &[0xaa],
+            chunk_size: 6,
+        };
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let mut builder = AooScreenBuilder::new();
+        builder
+            .model(model)
+            .record_trace(TraceRecorder::new(SharedBuffer(recorded.clone())));
+        let mut screen = builder.build(Box::new(FakeSerialPort::new()), None);
+        let image = RgbImage::from_fn(4, 3, |x, y| Rgb([x as u8 * 64, y as u8 * 64, 0]));
+        screen.send_image(&image).unwrap();
+
+        let trace: Trace = String::from_utf8(recorded.lock().unwrap().clone())
+            .unwrap()
+            .parse()
+            .unwrap();
+        // image, start, 4 chunks of 3 pixels, end
+        assert_eq!(7, trace.events().len());
+        // first column from bottom to top
+        assert_eq!(
+            TraceEvent::Write(
+                [
+                    &HEADER[..],
+                    &[0, 0, 0, 0],
+                    &[0x00, 0x04, 0x00, 0x02, 0x00, 0x00]
+                ]
+                .concat()
+            ),
+            trace.events()[2]
+        );
+        let mut builder = AooScreenBuilder::new();
+        builder.model(model);
+        check_conformance(&trace, builder).unwrap();
+    }
+
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Replay the protocol traces in `tests/traces` against the implementation.
+//!
+//! Record a trace with `asterctl --record-trace <file>` and add it to the directory to validate
+//! the protocol of a new device or firmware revision.
+
+use asterctl_lcd::{AooScreenBuilder, Trace, check_conformance};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn replay_traces() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/traces");
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "trace"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "No traces in {}", dir.display());
+
+    for path in paths {
+        let trace = Trace::load(&path).unwrap();
+        if let Err(e) = check_conformance(&trace, AooScreenBuilder::new()) {
+            panic!("{}: {e:#}", path.display());
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Alert engine: interrupts the page rotation with an alert page while a sensor condition is met.
+//!
+//! An alert becomes active after its condition is met for the configured time, and is cleared as
+//! soon as the condition is no longer met. An active alert can be acknowledged with a control
+//! command, which hides its alert page until the alert is cleared and triggered again.
+
+use crate::cfg::AlertRule;
+use crate::sensors::SensorStore;
+use log::info;
+use std::time::{Duration, Instant};
+
+struct AlertState {
+    rule: AlertRule,
+    /// Condition met since.
+    since: Option<Instant>,
+    active: bool,
+    acknowledged: bool,
+}
+
+/// State of all configured alert rules.
+pub struct Alerts {
+    states: Vec<AlertState>,
+}
+
+impl Alerts {
+    pub fn new(rules: &[AlertRule]) -> Self {
+        Self {
+            states: rules
+                .iter()
+                .map(|rule| AlertState {
+                    rule: rule.clone(),
+                    since: None,
+                    active: false,
+                    acknowledged: false,
+                })
+                .collect(),
+        }
+    }
+
+    /// Evaluate the alert conditions with the current sensor values.
+    ///
+    /// Missing or non-numeric sensor values don't meet any condition.
+    pub fn update(&mut self, values: &SensorStore, now: Instant) {
+        for state in self.states.iter_mut() {
+            let triggered = values
+                .value(&state.rule.sensor)
+                .and_then(|value| value.parse::<f32>().ok())
+                .is_some_and(|value| state.rule.is_triggered(value));
+
+            if !triggered {
+                if state.active {
+                    info!("Alert cleared: {}", state.rule.name());
+                }
+                state.since = None;
+                state.active = false;
+                state.acknowledged = false;
+                continue;
+            }
+
+            let since = *state.since.get_or_insert(now);
+            let delay = Duration::from_secs_f32(state.rule.for_secs.max(0.0));
+            if !state.active && now.duration_since(since) >= delay {
+                info!("Alert triggered: {}", state.rule.name());
+                state.active = true;
+            }
+        }
+    }
+
+    /// The alert to show: the active, unacknowledged alert with the highest priority. The first
+    /// configured rule wins between equal priorities.
+    pub fn current(&self) -> Option<&AlertRule> {
+        self.states
+            .iter()
+            .filter(|state| state.active && !state.acknowledged)
+            .map(|state| &state.rule)
+            .rev()
+            .max_by_key(|rule| rule.priority)
+    }
+
+    /// Acknowledge all active alerts.
+    ///
+    /// Returns false if there is no active, unacknowledged alert.
+    pub fn acknowledge(&mut self) -> bool {
+        let mut acknowledged = false;
+        for state in self.states.iter_mut() {
+            if state.active && !state.acknowledged {
+                info!("Alert acknowledged: {}", state.rule.name());
+                state.acknowledged = true;
+                acknowledged = true;
+            }
+        }
+        acknowledged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Vec<AlertRule> {
+        serde_json::from_str(
+            r#"[
+              { "name": "CPU hot", "sensor": "temperature_cpu", "above": 85, "for": 10 },
+              { "sensor": "battery_level", "below": 10, "priority": 1 }
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    fn values(temperature: &str, battery: &str) -> SensorStore {
+        SensorStore::from_iter([
+            ("temperature_cpu".to_string(), temperature.to_string()),
+            ("battery_level".to_string(), battery.to_string()),
+        ])
+    }
+
+    fn current(alerts: &Alerts) -> Option<&str> {
+        alerts.current().map(AlertRule::name)
+    }
+
+    #[test]
+    fn alert_is_shown_after_delay_until_cleared() {
+        let mut alerts = Alerts::new(&rules());
+        let start = Instant::now();
+
+        alerts.update(&values("90", "50"), start);
+        assert_eq!(None, current(&alerts));
+        alerts.update(&values("90", "50"), start + Duration::from_secs(5));
+        assert_eq!(None, current(&alerts));
+        alerts.update(&values("91", "50"), start + Duration::from_secs(10));
+        assert_eq!(Some("CPU hot"), current(&alerts));
+
+        // higher priority alert without delay
+        alerts.update(&values("91", "5"), start + Duration::from_secs(11));
+        assert_eq!(Some("battery_level"), current(&alerts));
+
+        alerts.update(&values("80", "N/A"), start + Duration::from_secs(12));
+        assert_eq!(None, current(&alerts));
+    }
+
+    #[test]
+    fn acknowledged_alert_is_hidden_until_triggered_again() {
+        let mut alerts = Alerts::new(&rules());
+        let start = Instant::now();
+        assert!(!alerts.acknowledge());
+
+        alerts.update(&values("40", "5"), start);
+        assert_eq!(Some("battery_level"), current(&alerts));
+        assert!(alerts.acknowledge());
+        alerts.update(&values("40", "4"), start + Duration::from_secs(1));
+        assert_eq!(None, current(&alerts));
+
+        alerts.update(&values("40", "20"), start + Duration::from_secs(2));
+        alerts.update(&values("40", "5"), start + Duration::from_secs(3));
+        assert_eq!(Some("battery_level"), current(&alerts));
+    }
+}
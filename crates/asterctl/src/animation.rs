@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Startup and shutdown animations.
+//!
+//! An animation is an animated GIF or a directory of PNG frames. All frames are loaded and scaled
+//! to the display size before playing, so that slow image decoding doesn't stall the animation.
+
+use crate::cfg::AnimationConfig;
+use crate::img::{ColorLut, Size, load_image};
+use anyhow::{Context, anyhow};
+use asterctl_lcd::AooScreen;
+use image::codecs::gif::GifDecoder;
+use image::imageops::FilterType;
+use image::{AnimationDecoder, DynamicImage, RgbImage};
+use log::{debug, error, warn};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Default frame rate of PNG frames.
+const DEFAULT_FPS: f32 = 10.0;
+/// Frame delay of GIF frames without a delay, as used by web browsers.
+const DEFAULT_GIF_DELAY: Duration = Duration::from_millis(100);
+/// Maximum animation duration, longer animations are cut off.
+const MAX_DURATION: Duration = Duration::from_secs(10);
+
+/// Display frames with their display duration.
+pub struct Animation {
+    frames: Vec<(RgbImage, Duration)>,
+}
+
+impl Animation {
+    /// Load the animation frames and scale them to the display size.
+    ///
+    /// Relative paths are resolved against the configuration directory. The color calibration of
+    /// the display is applied to all frames.
+    pub fn load(
+        cfg: &AnimationConfig,
+        config_dir: &Path,
+        size: Size,
+        color_lut: Option<&ColorLut>,
+    ) -> anyhow::Result<Self> {
+        let path = config_dir.join(&cfg.path);
+        let mut frames = if path.is_dir() {
+            let fps = cfg.fps.unwrap_or(DEFAULT_FPS).max(0.1);
+            let delay = Duration::from_millis((1000.0 / fps).round() as u64);
+            load_png_frames(&path, size, delay)?
+        } else {
+            load_gif_frames(&path, size)?
+        };
+        if frames.is_empty() {
+            return Err(anyhow!("No animation frames in {path:?}"));
+        }
+
+        let mut duration = Duration::ZERO;
+        let frame_count = frames.len();
+        frames.retain(|(_, delay)| {
+            duration += *delay;
+            duration <= MAX_DURATION
+        });
+        if frames.len() < frame_count {
+            warn!(
+                "Animation {path:?} is cut off after {}s: {} of {frame_count} frames",
+                MAX_DURATION.as_secs(),
+                frames.len()
+            );
+        }
+        if let Some(lut) = color_lut {
+            for (frame, _) in &mut frames {
+                lut.apply(frame);
+            }
+        }
+        debug!("Loaded {} animation frames from {path:?}", frames.len());
+
+        Ok(Self { frames })
+    }
+
+    /// Total duration of the animation.
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|(_, delay)| *delay).sum()
+    }
+
+    /// Send the frames to the display with their frame delays. Playing stops at the first error.
+    ///
+    /// Frames are skipped if sending takes longer than the frame delay, the last frame is always
+    /// shown.
+    pub fn play(&self, screen: &mut AooScreen) {
+        let start = Instant::now();
+        let mut frame_start = start;
+        for (idx, (frame, delay)) in self.frames.iter().enumerate() {
+            let now = Instant::now();
+            let last = idx + 1 == self.frames.len();
+            if now > frame_start + *delay && !last {
+                frame_start += *delay;
+                continue;
+            }
+            sleep(frame_start.saturating_duration_since(now));
+            if let Err(e) = screen.send_image(frame) {
+                error!("Error sending animation frame to display: {e:#}");
+                return;
+            }
+            frame_start += *delay;
+        }
+        sleep(frame_start.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// Load the PNG files of a directory in file name order.
+fn load_png_frames(
+    dir: &Path,
+    size: Size,
+    delay: Duration,
+) -> anyhow::Result<Vec<(RgbImage, Duration)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {dir:?}"))? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    files
+        .iter()
+        .map(|file| {
+            let image = load_image(file, Some(size))
+                .with_context(|| format!("Failed to load animation frame {file:?}"))?;
+            Ok((image.to_rgb8(), delay))
+        })
+        .collect()
+}
+
+/// Load the frames of an animated GIF with the frame delays of the file.
+fn load_gif_frames(path: &Path, size: Size) -> anyhow::Result<Vec<(RgbImage, Duration)>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {path:?}"))?;
+    let decoder = GifDecoder::new(BufReader::new(file))
+        .with_context(|| format!("Failed to decode animated GIF {path:?}"))?;
+
+    let mut frames = Vec::new();
+    for frame in decoder.into_frames() {
+        let frame = frame.with_context(|| format!("Invalid GIF frame in {path:?}"))?;
+        let delay = Duration::from(frame.delay());
+        let delay = if delay.is_zero() {
+            DEFAULT_GIF_DELAY
+        } else {
+            delay
+        };
+        let mut image = DynamicImage::ImageRgba8(frame.into_buffer());
+        if (image.width(), image.height()) != size {
+            image = image.resize_exact(size.0, size.1, FilterType::Triangle);
+        }
+        frames.push((image.to_rgb8(), delay));
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Frame, Rgba, RgbaImage};
+
+    #[test]
+    fn load_gif_and_png_frames() {
+        let dir = std::env::temp_dir().join(format!("asterctl-animation-{}", std::process::id()));
+        let png_dir = dir.join("frames");
+        fs::create_dir_all(&png_dir).unwrap();
+        for (idx, color) in [[255, 0, 0], [0, 255, 0], [0, 0, 255]].iter().enumerate() {
+            RgbImage::from_pixel(4, 2, image::Rgb(*color))
+                .save(png_dir.join(format!("{idx:02}.png")))
+                .unwrap();
+        }
+        fs::write(png_dir.join("readme.txt"), "not a frame").unwrap();
+
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            for delay in [50, 0] {
+                let image = RgbaImage::from_pixel(2, 1, Rgba([255, 255, 255, 255]));
+                let delay = Delay::from_numer_denom_ms(delay, 1);
+                encoder
+                    .encode_frame(Frame::from_parts(image, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+        fs::write(dir.join("boot.gif"), gif).unwrap();
+
+        let cfg = AnimationConfig {
+            path: "frames".into(),
+            fps: Some(20.0),
+        };
+        let animation = Animation::load(&cfg, &dir, (8, 4), None).unwrap();
+        assert_eq!(3, animation.frames.len());
+        assert_eq!((8, 4), animation.frames[0].0.dimensions());
+        assert_eq!([0, 0, 255], animation.frames[2].0.get_pixel(0, 0).0);
+        assert_eq!(Duration::from_millis(150), animation.duration());
+
+        let cfg = AnimationConfig {
+            path: "boot.gif".into(),
+            fps: None,
+        };
+        let animation = Animation::load(&cfg, &dir, (8, 4), None).unwrap();
+        assert_eq!(2, animation.frames.len());
+        assert_eq!((8, 4), animation.frames[1].0.dimensions());
+        assert_eq!(Duration::from_millis(150), animation.duration());
+
+        let cfg = AnimationConfig {
+            path: "missing.gif".into(),
+            fps: None,
+        };
+        assert!(Animation::load(&cfg, &dir, (8, 4), None).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -5,6 +5,7 @@
 use asterctl::cfg;
 use asterctl::font::FontHandler;
 use asterctl::render::PanelRenderer;
+use asterctl::sensors::SensorStore;
 use asterctl_lcd::{AooScreen, AooScreenBuilder, DISPLAY_SIZE};
 
 use ab_glyph::PxScale;
@@ -14,7 +15,6 @@ use image::imageops::FilterType;
 use image::{ImageReader, Rgb, RgbImage};
 use imageproc::drawing::{draw_line_segment_mut, draw_text_mut};
 use log::{error, info};
-use std::collections::HashMap;
 use std::fs;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
@@ -29,7 +29,7 @@ struct Args {
     #[arg(short, long)]
     device: Option<String>,
 
-    /// USB serial UART "vid:pid" in hex notation (lsusb output). Default: 416:90A1
+    /// USB serial UART "vid:pid" in hex notation (lsusb output). Default: the first known AOOSTAR display
     #[arg(short, long)]
     usb: Option<String>,
 
@@ -130,13 +130,16 @@ fn run_demo(
             info!("Displaying demo panel...");
 
             // get sensor values from panel configuration
-            let mut demo_values = HashMap::new();
-            for sensor in &panel.sensor {
-                demo_values.insert(
-                    sensor.label.clone(),
-                    sensor.value.clone().unwrap_or_default(),
-                );
-            }
+            let demo_values: SensorStore = panel
+                .sensor
+                .iter()
+                .map(|sensor| {
+                    (
+                        sensor.label.clone(),
+                        sensor.value.clone().unwrap_or_default(),
+                    )
+                })
+                .collect();
 
             let mut renderer = PanelRenderer::new(DISPLAY_SIZE, &font_dir, &config_dir);
             renderer.set_save_render_img(save_images);
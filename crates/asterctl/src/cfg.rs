@@ -6,26 +6,67 @@
 //!
 //! Derived from the available Monitor3.json file in AOOSTAR-X v1.3.4.
 //! Likely not fully compatible with files created with the original editor.
+//! The same configuration can also be written in the native TOML format, see [ConfigFormat].
 
+mod components;
+mod condition;
+mod format;
+mod include;
+mod migrate;
+mod overrides;
+
+pub use condition::Condition;
+pub use format::{ConfigFormat, parse_config, write_config};
+pub use migrate::{CONFIG_VERSION, migrate_config};
+pub use overrides::{SETUP_ENV_PREFIX, SetupOverride, apply_overrides};
+
+use crate::ValueFormat;
+use crate::font::font_names;
+use crate::img::{ColorCalibration, ImageAdjust};
+use crate::secrets::{resolve_optional_secret, resolve_secret};
+use crate::sensors::SensorFilters;
 use anyhow::Context;
+use asterctl_lcd::Rotation;
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, NaiveTime, Weekday};
 use image::{Rgb, Rgba};
 use imageproc::definitions::HasWhite;
 use log::{info, warn};
-use regex::Regex;
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::HashMap;
 use std::io::BufReader;
 use std::num::ParseIntError;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::{fmt, fs};
 
+/// Load a monitor configuration in the JSON or TOML format, detected by the file extension.
+///
+/// Configurations of older schema versions are migrated to the current version, and the
+/// configurations listed in `include` are merged. The `ASTERCTL_SETUP__` environment variables
+/// override `setup` values, see [SetupOverride].
 pub fn load_cfg<P: AsRef<Path>>(path: P) -> anyhow::Result<MonitorConfig> {
+    load_cfg_with_overrides(path, &[])
+}
+
+/// Load a monitor configuration like [load_cfg], with additional `setup` overrides applied after
+/// the environment variables, e.g. the `--set` command line switches.
+pub fn load_cfg_with_overrides<P: AsRef<Path>>(
+    path: P,
+    overrides: &[SetupOverride],
+) -> anyhow::Result<MonitorConfig> {
     let path = path.as_ref();
-    let file = fs::File::open(path).with_context(|| format!("Failed to load config {path:?}"))?;
-    let reader = BufReader::new(file);
-    let config: MonitorConfig = serde_json::from_reader(reader)?;
+    let mut value = include::load_config_value(path)?;
+    apply_overrides(&mut value, &SetupOverride::from_env()?);
+    apply_overrides(&mut value, overrides);
+    components::expand_config_components(&mut value)
+        .with_context(|| format!("Invalid component in config {path:?}"))?;
+    let mut config: MonitorConfig =
+        serde_json::from_value(value).with_context(|| format!("Invalid config {path:?}"))?;
+    for panel in config.panels.iter_mut() {
+        panel.apply_font_fallback();
+    }
 
     for active in config.active_panels.clone() {
         if active == 0 || active > config.panels.len() as u32 {
@@ -77,7 +118,11 @@ pub fn load_custom_panel<P: AsRef<Path>>(path: P) -> anyhow::Result<Panel> {
     let file = fs::File::open(&panel_file)
         .with_context(|| format!("Failed to load custom panel {panel_file:?}"))?;
     let reader = BufReader::new(file);
-    let mut panel: Panel = serde_json::from_reader(reader)?;
+    let mut value: serde_json::Value = serde_json::from_reader(reader)?;
+    components::expand_panel_components(&mut value, &serde_json::Map::new())
+        .with_context(|| format!("Invalid component in custom panel {panel_file:?}"))?;
+    let mut panel: Panel = serde_json::from_value(value)?;
+    panel.dir = Some(fs::canonicalize(path)?);
 
     // adjust font and image file paths
     let img_path = fs::canonicalize(path.join("img"))?;
@@ -93,22 +138,47 @@ pub fn load_custom_panel<P: AsRef<Path>>(path: P) -> anyhow::Result<Panel> {
         {
             sensor.pic = Some(img_path.join(pic).display().to_string());
         }
+        for icon in sensor.icons.iter_mut() {
+            if !Path::new(&icon.pic).is_absolute() {
+                icon.pic = img_path.join(&icon.pic).display().to_string();
+            }
+        }
         if let Some(font_family) = &sensor.font_family
             && !font_family.is_empty()
-            && !Path::new(&font_family).is_absolute()
         {
-            sensor.font_family = Some(font_path.join(font_family).display().to_string());
+            sensor.font_family = Some(font_paths(&font_path, font_family));
         }
     }
+    if let Some(font_fallback) = &panel.font_fallback {
+        panel.font_fallback = Some(font_paths(&font_path, font_fallback));
+    }
+    panel.apply_font_fallback();
 
     Ok(panel)
 }
 
+/// Prefix the relative font names of a comma-separated font list with the font directory.
+fn font_paths(font_dir: &Path, font_family: &str) -> String {
+    let fonts: Vec<String> = font_names(font_family)
+        .map(|name| {
+            if Path::new(name).is_absolute() {
+                name.to_string()
+            } else {
+                font_dir.join(name).display().to_string()
+            }
+        })
+        .collect();
+    fonts.join(",")
+}
+
 /// AOOSTAR-X monitor json configuration file
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MonitorConfig {
     // _Not used_
     // pub credentials: Option<Credentials>,
+    /// Configuration schema version, see [CONFIG_VERSION]. 0 for AOOSTAR-X files.
+    #[serde(default)]
+    pub version: u32,
     /// Configuration settings.
     pub setup: Setup,
     /// Panels: 1-based index into `panels`
@@ -120,13 +190,95 @@ pub struct MonitorConfig {
     /// Internal index of the currently active panel. 1-based!
     #[serde(skip)]
     active_panel_idx: Option<usize>,
-    /// Sensor filter patterns (regex strings) to exclude matching sensor keys.
-    /// Can be specified inline in the JSON or loaded from an external filter file.
-    #[serde(default, rename = "sensorFilter")]
-    sensor_filter_patterns: Option<Vec<String>>,
-    /// Compiled sensor filter regexes (built from sensor_filter_patterns or external file).
+    /// Sensor filter: a list of regex patterns to exclude matching sensor keys, or a
+    /// [SensorFilterConfig] with allowlists and per-source filters.
+    #[serde(
+        default,
+        rename = "sensorFilter",
+        deserialize_with = "sensor_filter_config"
+    )]
+    sensor_filter_config: Option<SensorFilterConfig>,
+    /// Compiled sensor filters (built from sensor_filter_config).
     #[serde(skip)]
-    pub sensor_filter: Option<Vec<Regex>>,
+    pub sensor_filter: SensorFilters,
+    /// Rename map for hwmon fan, voltage and power sensors.
+    /// Maps a chip-qualified sensor key to a stable, friendly sensor key.
+    /// Example: `"fan_nct6798_fan2": "fan_cpu"`
+    #[serde(default, rename = "hwmonLabels")]
+    pub hwmon_labels: HashMap<String, String>,
+    /// Rename map for the sensor keys of all sensor sources.
+    /// Maps a raw sensor key, which varies between kernels and hardware, to a stable sensor key
+    /// for portable panels. Example: `"temperature_k10temp_Tctl": "temperature_cpu_package"`
+    #[serde(default, rename = "sensorAliases")]
+    pub sensor_aliases: HashMap<String, String>,
+    /// Preference order of the chips reporting the CPU temperature, the first available chip
+    /// provides the `temperature_cpu` sensor. Default: zenpower, k10temp, coretemp, cpu_thermal,
+    /// acpitz
+    #[serde(default, rename = "cpuTemperatureSources")]
+    pub cpu_temperature_sources: Option<Vec<String>>,
+    /// Poll intervals of the system sensor groups. Default: `setup.refresh`, individual disks
+    /// every 5 minutes.
+    #[serde(default, rename = "sensorIntervals")]
+    pub sensor_intervals: SensorIntervals,
+    /// Host names or IP addresses to ping at the sensor refresh interval.
+    #[serde(default, rename = "pingHosts")]
+    pub ping_hosts: Vec<String>,
+    /// Ping interval in seconds, overriding `setup.refresh`. Minimum: 1
+    #[serde(default, rename = "pingInterval")]
+    pub ping_interval: Option<f32>,
+    /// Optional panel slots to show multiple independent page rotations side by side.
+    /// If not set, all active panels are shown in a single full-screen rotation.
+    #[serde(default)]
+    pub slots: Vec<PanelSlot>,
+    /// Generic HTTP/JSON sensor sources.
+    #[serde(default, rename = "httpSources")]
+    pub http_sources: Vec<HttpSource>,
+    /// External command sensor sources.
+    #[serde(default, rename = "execSources")]
+    pub exec_sources: Vec<ExecSource>,
+    /// Home Assistant entity state source.
+    #[serde(default, rename = "homeAssistant")]
+    pub home_assistant: Option<HomeAssistantConfig>,
+    /// Open-Meteo weather source.
+    #[serde(default)]
+    pub weather: Option<WeatherConfig>,
+    /// systemd journal error and warning counter.
+    #[serde(default)]
+    pub journal: Option<JournalConfig>,
+    /// Unix domain socket path for pushing sensor values from external programs.
+    #[serde(default, rename = "pushSocket")]
+    pub push_socket: Option<PathBuf>,
+    /// HTTP control API of the running sensor panel.
+    #[serde(default, rename = "httpApi")]
+    pub http_api: Option<HttpApiConfig>,
+    /// D-Bus interface for desktop control: `session` or `system` bus.
+    #[serde(default)]
+    pub dbus: Option<DbusBus>,
+    /// Additional outputs of the rendered frames besides the LCD.
+    #[serde(default)]
+    pub outputs: Vec<OutputConfig>,
+    /// Alert rules interrupting the page rotation.
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    /// Scheduled message pages in the full-screen page rotation, if no slots are configured.
+    #[serde(default)]
+    pub messages: Vec<MessagePage>,
+    /// Derived sensors computed from other sensor values: sensor key -> expression or
+    /// [DerivedSensor] definition.
+    #[serde(default, deserialize_with = "derived_sensors")]
+    pub derived: HashMap<String, DerivedSensor>,
+    /// Session min/max/average tracking of sensor values.
+    #[serde(default)]
+    pub statistics: Option<StatisticsConfig>,
+    /// Energy consumption and electricity cost sensors.
+    #[serde(default)]
+    pub energy: Option<EnergyConfig>,
+    /// Summary page of the previous day.
+    #[serde(default, rename = "dailySummary")]
+    pub daily_summary: Option<DailySummaryConfig>,
+    /// Playback of recorded sensor values instead of the system sensors, for development.
+    #[serde(default)]
+    pub replay: Option<ReplayConfig>,
 }
 
 impl MonitorConfig {
@@ -163,27 +315,112 @@ impl MonitorConfig {
         self.active_panels.push(self.panels.len() as u32);
     }
 
+    /// Adds a custom panel or replaces the custom panel loaded from the same directory.
+    pub fn replace_custom_panel(&mut self, panel: Panel) {
+        match self
+            .panels
+            .iter_mut()
+            .find(|p| p.dir.is_some() && p.dir == panel.dir)
+        {
+            Some(existing) => *existing = panel,
+            None => self.include_custom_panel(panel),
+        }
+    }
+
+    /// Removes the custom panel loaded from the given directory.
+    ///
+    /// The panel is removed from the active panels and all slots, the indexes of the following
+    /// panels are adjusted. Returns false if no panel was loaded from the directory.
+    pub fn remove_custom_panel(&mut self, dir: &Path) -> bool {
+        let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let Some(idx) = self
+            .panels
+            .iter()
+            .position(|panel| panel.dir.as_deref() == Some(dir.as_path()))
+        else {
+            return false;
+        };
+        self.panels.remove(idx);
+
+        // 1-based panel index
+        let removed = idx as u32 + 1;
+        let renumber = |panels: &mut Vec<u32>| {
+            panels.retain(|&panel| panel != removed);
+            for panel in panels.iter_mut().filter(|panel| **panel > removed) {
+                *panel -= 1;
+            }
+        };
+        renumber(&mut self.active_panels);
+        for slot in self.slots.iter_mut() {
+            renumber(&mut slot.panels);
+        }
+        self.active_panel_idx = None;
+        true
+    }
+
+    /// Get the Home Assistant source configuration.
+    ///
+    /// The Home Assistant URL and access token default to `setup.ha_url` and `setup.ha_token`.
+    /// Returns `None` if no entities are configured or the URL or token are missing.
+    pub fn home_assistant_config(&self) -> Option<HomeAssistantConfig> {
+        let ha = self.home_assistant.as_ref()?;
+        if ha.entities.is_empty() {
+            return None;
+        }
+        let url = ha.url.clone().or_else(|| self.setup.ha_url.clone());
+        let token = ha.token.clone().or_else(|| self.setup.ha_token.clone());
+        if url.is_none() || token.is_none() {
+            warn!("Ignoring Home Assistant entities: URL or access token not configured");
+            return None;
+        }
+        Some(HomeAssistantConfig {
+            url,
+            token,
+            ..ha.clone()
+        })
+    }
+
     /// Compile inline sensor filter patterns into regexes.
     ///
     /// Returns true if inline patterns were present and compiled successfully.
     pub fn compile_sensor_filters(&mut self) -> bool {
-        if let Some(patterns) = &self.sensor_filter_patterns {
-            let filters: Vec<Regex> = patterns
-                .iter()
-                .filter_map(|p| match Regex::new(p) {
-                    Ok(r) => Some(r),
-                    Err(e) => {
-                        warn!("Invalid sensor filter regex '{p}': {e}");
-                        None
-                    }
-                })
-                .collect();
-            if !filters.is_empty() {
-                self.sensor_filter = Some(filters);
-                return true;
+        if let Some(filter_cfg) = &self.sensor_filter_config {
+            self.sensor_filter = SensorFilters::new(filter_cfg);
+        }
+        !self.sensor_filter.is_empty()
+    }
+
+    /// Add the message page of the daily summary to the page rotation, or to the first slot if
+    /// panel slots are configured.
+    pub fn add_summary_page(&mut self) {
+        let Some(summary) = &self.daily_summary else {
+            return;
+        };
+        let page = summary.message_page();
+        match self.slots.first_mut() {
+            Some(slot) => slot.messages.push(page),
+            None => self.messages.push(page),
+        }
+    }
+
+    /// Resolve secret references of access tokens and HTTP request headers.
+    ///
+    /// See [crate::secrets] for the supported secret references.
+    pub fn resolve_secrets(&mut self) -> anyhow::Result<()> {
+        resolve_optional_secret(&mut self.setup.ha_token).context("Invalid setup.ha_token")?;
+        if let Some(ha) = &mut self.home_assistant {
+            resolve_optional_secret(&mut ha.token).context("Invalid homeAssistant.token")?;
+        }
+        if let Some(http_api) = &mut self.http_api {
+            resolve_optional_secret(&mut http_api.token).context("Invalid httpApi.token")?;
+        }
+        for source in &mut self.http_sources {
+            for (name, value) in &mut source.headers {
+                *value = resolve_secret(value)
+                    .with_context(|| format!("Invalid header {name} of {}", source.url))?;
             }
         }
-        false
+        Ok(())
     }
 }
 
@@ -199,7 +436,7 @@ pub struct Credentials {
 /// Configuration settings.
 ///
 /// Note: Trimmed down object to include only required fields for `asterctl`.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Setup {
     /// Panel redraw interval in seconds. Default: 1
@@ -215,6 +452,8 @@ pub struct Setup {
     pub time_page: Option<String>,
     /// Font size for the time page. Default: 64
     pub time_page_font_size: Option<f32>,
+    /// Digital or analog clock of the time page. Default: digital clock
+    pub time_page_style: Option<TimePageStyle>,
     /// Hour (0–23) when the display should turn on. Used with `display_off_hour` for scheduling.
     pub display_on_hour: Option<u32>,
     /// Hour (0–23) when the display should turn off. Used with `display_on_hour` for scheduling.
@@ -222,6 +461,44 @@ pub struct Setup {
     /// Configuration for the sensor name label shown on each sensor page.
     /// If not set, defaults are used.
     pub sensor_page_label: Option<SensorPageLabel>,
+    /// Optional ticker region at the bottom of the display, shown on top of all pages.
+    pub ticker: Option<TickerConfig>,
+    /// Unit conversion and decimal separator of all sensor values.
+    pub value_format: Option<ValueFormat>,
+    /// Display of sensor values which weren't updated for a while. Disabled if not set.
+    pub stale_sensors: Option<StaleSensors>,
+    /// Warning page shown instead of the panels if no sensor value was updated for a while.
+    /// Disabled if not set.
+    pub sensor_watchdog: Option<SensorWatchdog>,
+    /// Power saving policy if the host runs on battery power. Disabled if not set.
+    pub low_power: Option<LowPowerConfig>,
+    /// Animated transition between the pages of the page rotation. Disabled if not set.
+    pub page_transition: Option<PageTransition>,
+    /// Gamma and white point correction of all display images. Disabled if not set.
+    pub color_calibration: Option<ColorCalibration>,
+    /// Clockwise rotation of the display images in degrees: 0, 90, 180 or 270, e.g. for a
+    /// vertically mounted chassis. Default: 0
+    #[serde(
+        default,
+        deserialize_with = "option_rotation_degrees",
+        serialize_with = "serialize_rotation_degrees"
+    )]
+    pub rotation: Option<Rotation>,
+    /// Mirror the display images horizontally before they are rotated. Default: false
+    pub mirror: Option<bool>,
+    /// Animation played when the sensor panel starts. Disabled if not set.
+    pub startup_animation: Option<AnimationConfig>,
+    /// Animation played before the sensor panel exits on `SIGTERM` or `SIGINT`. Disabled if not
+    /// set.
+    pub shutdown_animation: Option<AnimationConfig>,
+    /// Home Assistant URL
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    #[serde(rename = "ha_url")]
+    pub ha_url: Option<String>, // "" in JSON ⇒ Option<String>
+    /// Home Assistant long-lived access token
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    #[serde(rename = "ha_token")]
+    pub ha_token: Option<String>, // "" in JSON ⇒ Option<String>
     /*
     // The following fields of the AOOSTAR-X json configuration file are NOT used in `asterctl`
     /// Default: true
@@ -243,14 +520,6 @@ pub struct Setup {
     pub operation_type: Option<i16>,
     /// Default: 300
     pub disk_update: i32,
-    /// Home Assistant URL
-    #[serde(deserialize_with = "empty_string_as_none")]
-    #[serde(rename = "ha_url")]
-    pub ha_url: Option<String>, // "" in JSON ⇒ Option<String>
-    /// Home Assistant long-lived access token
-    #[serde(deserialize_with = "empty_string_as_none")]
-    #[serde(rename = "ha_token")]
-    pub ha_token: Option<String>, // "" in JSON ⇒ Option<String>
     */
 }
 
@@ -292,7 +561,7 @@ pub enum SensorDirection {
 }
 
 /// Configuration for the sensor name label displayed on sensor pages.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SensorPageLabel {
     /// Font family name. Default: system default font
@@ -309,6 +578,673 @@ pub struct SensorPageLabel {
     pub y: Option<i32>,
 }
 
+/// Configuration for the ticker region at the bottom of the display.
+///
+/// The ticker is independent of the page rotation and cycles through its own items.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerConfig {
+    /// Ticker items: static messages or sensor values. Sensor keys in curly braces are replaced
+    /// with the current sensor value and unit. Example: `"CPU {temperature_cpu}"`
+    #[serde(default)]
+    pub items: Vec<String>,
+    /// Time in seconds to display each item. Default: 5
+    pub item_time: Option<f32>,
+    /// Height of the ticker region in pixels. Default: 40
+    pub height: Option<u32>,
+    /// Font family name. Default: system default font
+    pub font_family: Option<String>,
+    /// Font size in points. Default: 24
+    pub font_size: Option<f32>,
+    /// Font color in `#RRGGBB` notation or -1 for default. Default: white
+    pub font_color: Option<FontColor>,
+    /// Background color of the ticker region in `#RRGGBB` notation. Default: black
+    pub background: Option<FontColor>,
+    /// Horizontal text alignment. Default: center
+    pub text_align: Option<TextAlign>,
+}
+
+/// Power saving policy while the host runs on battery power.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LowPowerConfig {
+    /// Panel redraw interval in seconds on battery power, at least 1 second. Default: 10
+    pub refresh: Option<f32>,
+    /// Keep animations like scrolling text and flashing alert pages on battery power.
+    /// Default: false
+    #[serde(default)]
+    pub animations: bool,
+}
+
+/// Default color of greyed out stale sensor values.
+const DEFAULT_STALE_COLOR: FontColor = FontColor(Rgb([0x80, 0x80, 0x80]));
+
+/// Display policy of sensor values which weren't updated by their source for a while, e.g. after
+/// a sensor provider stopped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleSensors {
+    /// Time in seconds without an update after which a value is stale. `0` disables the check.
+    pub after: f32,
+    /// Display of a stale value. Default: grey
+    #[serde(default)]
+    pub action: StaleAction,
+    /// Font color of greyed out values. Default: `#808080`
+    pub color: Option<FontColor>,
+}
+
+impl StaleSensors {
+    /// Check if a value last updated at `updated_at` is stale at time `now`.
+    pub fn is_stale(&self, updated_at: DateTime<Local>, now: DateTime<Local>) -> bool {
+        self.after > 0.0 && (now - updated_at).as_seconds_f32() > self.after
+    }
+
+    /// Font color of greyed out values.
+    pub fn grey_color(&self) -> FontColor {
+        self.color.unwrap_or(DEFAULT_STALE_COLOR)
+    }
+}
+
+/// Watchdog of the sensor sources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorWatchdog {
+    /// Time in seconds without any sensor update after which the warning page is shown.
+    pub timeout: f32,
+}
+
+/// Poll intervals in seconds of the system sensor groups.
+///
+/// The system sensor source is polled at the shortest interval, each group is only read again
+/// once its own interval elapsed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorIntervals {
+    /// CPU, memory, network, disk total and workload sensors. Default: `setup.refresh`
+    pub system: Option<f32>,
+    /// GPU engine usage sensors. Default: `system` interval
+    pub gpu: Option<f32>,
+    /// hwmon temperature, fan, voltage and power sensors. Default: `system` interval
+    pub hwmon: Option<f32>,
+    /// Thermal zone sensors. Default: `system` interval
+    pub thermal: Option<f32>,
+    /// Wireless link sensors. Default: `system` interval
+    pub wireless: Option<f32>,
+    /// Bluetooth device sensors. Default: `system` interval
+    pub bluetooth: Option<f32>,
+    /// Battery sensors. Default: `system` interval
+    pub battery: Option<f32>,
+    /// Audio sensors. Default: `system` interval
+    pub audio: Option<f32>,
+    /// Individual disk usage, SMART temperature and maintenance sensors. Default: 300
+    pub storage: Option<f32>,
+}
+
+/// Display of a stale sensor value.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StaleAction {
+    /// Show the last value in grey, without color rules.
+    #[default]
+    Grey,
+    /// Show `--` instead of the value.
+    Dash,
+    /// Don't show the element.
+    Hide,
+}
+
+/// Smoothing of a numeric sensor value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Smoothing {
+    /// Smoothing method. Default: ema
+    #[serde(default)]
+    pub method: SmoothingMethod,
+    /// Time window in seconds: the time constant of the exponential smoothing, or the length of
+    /// the moving average. Default: 5
+    pub window: Option<f32>,
+    /// Minimum time in seconds between two changes of the shown value. Default: 0
+    pub hold: Option<f32>,
+}
+
+/// Smoothing method of a numeric sensor value.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmoothingMethod {
+    /// Exponential moving average, recent values have a higher weight.
+    #[default]
+    Ema,
+    /// Average of the values within the time window.
+    Average,
+}
+
+/// Animated transition between two pages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageTransition {
+    pub effect: TransitionEffect,
+    /// Duration of the transition in seconds. Default: 0.5
+    pub duration: Option<f32>,
+    /// Maximum frame rate of the transition. Default: 10
+    pub fps: Option<f32>,
+}
+
+/// Startup or shutdown animation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationConfig {
+    /// Animated GIF file, or directory with PNG frames played in file name order. Relative paths
+    /// are resolved against the configuration directory.
+    pub path: String,
+    /// Frame rate of PNG frames. GIF files use the frame delays of the file. Default: 10
+    pub fps: Option<f32>,
+}
+
+/// Page transition effect.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransitionEffect {
+    /// The new page pushes the previous page out to the left.
+    SlideLeft,
+    /// The new page pushes the previous page out to the right.
+    SlideRight,
+    /// Cross-fade from the previous to the new page.
+    Fade,
+    /// The new page is revealed from left to right.
+    Wipe,
+}
+
+/// Clock style of the time page.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimePageStyle {
+    /// Clock type. Default: digital
+    #[serde(default)]
+    pub clock: ClockType,
+    /// Face image of the analog clock, scaled to the clock size. Default: dial with hour marks
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub face: Option<String>,
+    /// Hour hand color of the analog clock. Default: white
+    pub hour_color: Option<FontColor>,
+    /// Minute hand color of the analog clock. Default: white
+    pub minute_color: Option<FontColor>,
+    /// Second hand color of the analog clock. Default: `#ff3d00`
+    pub second_color: Option<FontColor>,
+    /// Show the second hand of the analog clock. Default: true
+    pub seconds: Option<bool>,
+    /// Move the second hand continuously instead of in one-second steps. Default: false
+    #[serde(default)]
+    pub smooth_seconds: bool,
+    /// Draw the digital clock with built-in seven-segment or dot-matrix digits. Default: built-in
+    /// font
+    pub digit_style: Option<DigitStyle>,
+}
+
+/// Clock type of the time page.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockType {
+    /// Date/time text of the `timePage` label.
+    #[default]
+    Digital,
+    /// Clock face with hour, minute and second hands.
+    Analog,
+}
+
+/// Sensor key filter with global and per-source rules.
+///
+/// A sensor key is dropped if it matches an exclude pattern, or if include patterns are set and
+/// the key matches none of them. The rules of a source apply in addition to the global rules.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SensorFilterConfig {
+    /// Global rules for all sensor sources.
+    #[serde(flatten)]
+    pub rules: SensorFilterRules,
+    /// Rules of individual sensor sources: source name -> rules.
+    #[serde(default)]
+    pub sources: HashMap<String, SensorFilterRules>,
+}
+
+/// Regex patterns of a [SensorFilterConfig].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SensorFilterRules {
+    /// Drop sensor keys matching any pattern.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Allowlist: if set, only sensor keys matching any pattern are kept.
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+/// A generic HTTP/JSON sensor source, polling a URL and extracting sensor values from the response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpSource {
+    /// Source name for health reporting. Default: `http_{host}` of the URL.
+    pub name: Option<String>,
+    /// URL returning a JSON document.
+    pub url: String,
+    /// Poll interval in seconds. Default: 60, minimum: 5
+    pub interval: Option<f32>,
+    /// Request timeout in seconds. Default: 10
+    pub timeout: Option<f32>,
+    /// Maximum random delay in seconds added to the poll interval. Default: 10% of the interval
+    pub jitter: Option<f32>,
+    /// Additional request headers, e.g. for authorization. Values can be secret references.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Sensor keys and the path expression to extract the sensor value, e.g. `$.main.temp`.
+    pub values: HashMap<String, String>,
+    /// Optional units of the sensor keys.
+    #[serde(default)]
+    pub units: HashMap<String, String>,
+}
+
+/// An external command sensor source, running a command and parsing its output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecSource {
+    /// Source name used in log messages. Default: the program name.
+    pub name: Option<String>,
+    /// Program and arguments, e.g. `["python3", "/opt/sensors/ups.py"]`.
+    pub command: Vec<String>,
+    /// Run interval in seconds. Default: `setup.refresh`
+    pub interval: Option<f32>,
+    /// Timeout in seconds, the command is killed afterward. Default: 10
+    pub timeout: Option<f32>,
+    /// Output format. Default: auto-detect
+    #[serde(default)]
+    pub format: ExecOutputFormat,
+    /// Number of consecutive failures after which the sensor values of the source are removed.
+    /// Default: 3
+    pub max_failures: Option<u32>,
+}
+
+/// Output format of an external command sensor source.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecOutputFormat {
+    /// JSON if the output starts with `{`, otherwise lines.
+    #[default]
+    Auto,
+    /// `key: value` lines.
+    Lines,
+    /// JSON object, nested objects and arrays are flattened with `_`.
+    Json,
+}
+
+/// Home Assistant entity state source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeAssistantConfig {
+    /// Home Assistant URL, e.g. `http://homeassistant.local:8123`. Defaults to `setup.ha_url`.
+    pub url: Option<String>,
+    /// Long-lived access token or secret reference. Defaults to `setup.ha_token`.
+    pub token: Option<String>,
+    /// Poll interval in seconds. Default: 10, minimum: 2
+    pub interval: Option<f32>,
+    /// Request timeout in seconds. Default: 10
+    pub timeout: Option<f32>,
+    /// Maximum random delay in seconds added to the poll interval. Default: 10% of the interval
+    pub jitter: Option<f32>,
+    /// Entities to map to sensor keys.
+    #[serde(default)]
+    pub entities: Vec<HomeAssistantEntity>,
+}
+
+/// A Home Assistant entity mapped to sensor keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeAssistantEntity {
+    /// Entity id, e.g. `sensor.outdoor_temperature`.
+    pub entity: String,
+    /// Sensor key of the entity state. Default: `ha_` + entity id with `.` replaced by `_`.
+    pub key: Option<String>,
+    /// Entity attributes to expose as `{key}_{attribute}` sensor keys.
+    #[serde(default)]
+    pub attributes: Vec<String>,
+}
+
+/// Open-Meteo weather source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherConfig {
+    /// Latitude of the location in degrees.
+    pub latitude: f64,
+    /// Longitude of the location in degrees.
+    pub longitude: f64,
+    /// Poll interval in seconds. Default: 900, minimum: 60
+    pub interval: Option<f32>,
+    /// Request timeout in seconds. Default: 15
+    pub timeout: Option<f32>,
+    /// Maximum random delay in seconds added to the poll interval. Default: 10% of the interval
+    pub jitter: Option<f32>,
+    /// Number of forecast days including today, 1 to 16. Default: 3
+    pub forecast_days: Option<u8>,
+    /// Temperature unit: `celsius` or `fahrenheit`. Default: `celsius`
+    pub temperature_unit: Option<String>,
+    /// Open-Meteo API URL. Default: `https://api.open-meteo.com/v1/forecast`
+    pub url: Option<String>,
+}
+
+/// systemd journal source counting the recent error and warning messages.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalConfig {
+    /// Time window of the counted messages in minutes. Default: 15
+    pub minutes: Option<u32>,
+    /// Only count the messages of these systemd units, e.g. `nginx.service`. Default: all units
+    #[serde(default)]
+    pub units: Vec<String>,
+    /// Poll interval in seconds. Default: 60, minimum: 10
+    pub interval: Option<f32>,
+}
+
+/// HTTP control API configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiConfig {
+    /// Listen address. Default: `127.0.0.1:8686`
+    pub listen: Option<String>,
+    /// Optional access token or secret reference, required as `Authorization: Bearer` header.
+    pub token: Option<String>,
+}
+
+/// Additional output of the rendered frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum OutputConfig {
+    /// The latest frame as `latest.png` in a directory.
+    Png {
+        /// Output directory, created if it doesn't exist.
+        dir: PathBuf,
+        /// Number of previous frames to keep as `frame-{n}.png`. Default: 0
+        keep: Option<usize>,
+    },
+    /// MJPEG HTTP stream.
+    Mjpeg {
+        /// Listen address. Default: `127.0.0.1:8687`
+        listen: Option<String>,
+        /// JPEG quality 1..100. Default: 80
+        quality: Option<u8>,
+    },
+    /// Linux framebuffer device.
+    Framebuffer {
+        /// Device path, e.g. `/dev/fb0`.
+        device: PathBuf,
+    },
+    /// Desktop window, requires the `preview-window` feature.
+    Window,
+}
+
+/// Alert rule: an alert page is shown instead of the page rotation while the sensor value is
+/// above or below a level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    /// Alert name shown on the alert page. Default: sensor key
+    pub name: Option<String>,
+    /// Sensor key to watch.
+    pub sensor: String,
+    /// Trigger if the value is above this level.
+    pub above: Option<f32>,
+    /// Trigger if the value is below this level.
+    pub below: Option<f32>,
+    /// Time in seconds the condition must be met before the alert is shown. Default: 0
+    #[serde(default, rename = "for")]
+    pub for_secs: f32,
+    /// The active alert with the highest priority is shown. Default: 0
+    #[serde(default)]
+    pub priority: i32,
+    /// Optional unit text after the value. Default: unit of the sensor
+    pub unit: Option<String>,
+    /// Number of decimal places for the value. Default: value as is
+    pub decimal_digits: Option<i32>,
+    /// Background color. Default: `#d50000`
+    pub background: Option<FontColor>,
+    /// Font color. Default: `#ffffff`
+    pub font_color: Option<FontColor>,
+    /// Flash the background on every refresh. Default: true
+    #[serde(default = "default_true")]
+    pub flash: bool,
+}
+
+impl AlertRule {
+    /// Alert name, or the sensor key if not set.
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.sensor)
+    }
+
+    /// Check if the alert condition is met for a sensor value.
+    pub fn is_triggered(&self, value: f32) -> bool {
+        self.above.is_some_and(|level| value > level)
+            || self.below.is_some_and(|level| value < level)
+    }
+}
+
+/// A derived sensor, computed from other sensor values with an expression.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedSensor {
+    /// Arithmetic expression, e.g. `swap_free_bytes / swap_total_bytes * 100`. See [crate::expr].
+    pub expr: String,
+    /// Optional unit of the sensor value.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub unit: Option<String>,
+    /// Optional description of the sensor, e.g. for the HTTP API.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub description: Option<String>,
+    /// Number of integer places for the sensor value.
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
+    pub integer_digits: Option<i32>,
+    /// Number of decimal places for the sensor value. Default: value as is
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
+    pub decimal_digits: Option<i32>,
+}
+
+/// Tracking of the minimum, maximum and average values of sensors.
+///
+/// The tracked values are available as `{key}#min`, `{key}#max` and `{key}#avg` sensors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticsConfig {
+    /// Regex patterns of the sensor keys to track.
+    pub sensors: Vec<String>,
+    /// Averaging time window in seconds. Default: average of the whole session
+    pub window: Option<f32>,
+}
+
+/// Energy consumption tracking with daily and monthly energy and cost sensors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyConfig {
+    /// Power sensor in watts to accumulate, e.g. of a smart plug. Default: RAPL energy counters
+    /// of the CPU packages
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub sensor: Option<String>,
+    /// Electricity price per kWh. Without a rate, no cost sensors are provided.
+    pub rate: Option<f64>,
+    /// Currency unit of the cost sensors. Default: `€`
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub currency: Option<String>,
+}
+
+/// Playback of a sensor recording.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayConfig {
+    /// JSON lines file with timestamped sensor snapshots.
+    pub file: PathBuf,
+    /// Playback speed factor, e.g. 10 for ten times faster. Default: 1
+    pub speed: Option<f32>,
+}
+
+/// Daily summary of sensor values, shown as a "yesterday" page in the page rotation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySummaryConfig {
+    /// Page title. Default: `Yesterday`
+    pub title: Option<String>,
+    /// Summary lines of the page.
+    pub items: Vec<SummaryItem>,
+    /// Time in seconds to display the page. Defaults to the sensor page time.
+    pub page_time: Option<f32>,
+    /// Font size of the summary lines. Default: 36
+    pub font_size: Option<f32>,
+}
+
+impl DailySummaryConfig {
+    /// Message page showing the summary values of the previous day.
+    pub fn message_page(&self) -> MessagePage {
+        let text = self
+            .items
+            .iter()
+            .map(|item| format!("{}: {{{}}}", item.label, item.key()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        MessagePage {
+            name: Some("yesterday".to_string()),
+            title: Some(self.title.as_deref().unwrap_or("Yesterday").to_string()),
+            text,
+            page_time: self.page_time,
+            font_size: Some(self.font_size.unwrap_or(36.0)),
+            ..Default::default()
+        }
+    }
+}
+
+/// A line of the daily summary page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryItem {
+    /// Label in front of the value, e.g. `CPU max`.
+    pub label: String,
+    /// Sensor key of the summarized sensor.
+    pub sensor: String,
+    /// Daily aggregate of the sensor values.
+    pub stat: SummaryStat,
+}
+
+impl SummaryItem {
+    /// Sensor key of the summary value of the previous day, e.g. `temperature_cpu#yesterday_max`.
+    pub fn key(&self) -> String {
+        format!("{}#yesterday_{}", self.sensor, self.stat.name())
+    }
+}
+
+/// Daily aggregate of a summarized sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryStat {
+    Avg,
+    Min,
+    Max,
+    /// Increase of a counter like the total received bytes of a network interface.
+    Increase,
+}
+
+impl SummaryStat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SummaryStat::Avg => "avg",
+            SummaryStat::Min => "min",
+            SummaryStat::Max => "max",
+            SummaryStat::Increase => "increase",
+        }
+    }
+}
+
+/// Message bus of the D-Bus interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DbusBus {
+    /// Per-user session bus of the desktop.
+    Session,
+    /// System bus, requires a D-Bus policy for the `org.aoostar.asterctl` service.
+    System,
+}
+
+/// A display region with its own page rotation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanelSlot {
+    /// Optional slot name used for logging.
+    pub name: Option<String>,
+    /// Horizontal offset of the slot on the display.
+    pub x: u32,
+    /// Width of the slot. Sensor template positions are relative to the slot.
+    pub width: u32,
+    /// Panels of this slot: 1-based index into `diy`.
+    pub panels: Vec<u32>,
+    /// Time in seconds to display each sensor page of this slot. Defaults to `setup.sensorPageTime`.
+    pub sensor_page_time: Option<f32>,
+    /// Date/time label for a time page in this slot's rotation. If not set, no time page is shown.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub time_page: Option<String>,
+    /// Time in seconds to display the time page. Defaults to `setup.timePageTime`.
+    pub time_page_time: Option<f32>,
+    /// Scheduled message pages in this slot's rotation.
+    #[serde(default)]
+    pub messages: Vec<MessagePage>,
+}
+
+/// A static message page, shown in the page rotation during its schedule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessagePage {
+    /// Page name used for logging and page selectors. Default: the title
+    pub name: Option<String>,
+    /// Optional title above the message text.
+    pub title: Option<String>,
+    /// Message text. `{sensor_key}` placeholders are replaced with sensor values, `\n` starts a new
+    /// line.
+    pub text: String,
+    /// Start time of the daily schedule, e.g. `02:00`. Default: start of the day
+    pub from: Option<NaiveTime>,
+    /// End time of the daily schedule, exclusive. Supports wrap-around, e.g. from `22:00` to
+    /// `02:00`. Default: end of the day
+    pub to: Option<NaiveTime>,
+    /// Weekdays of the schedule, e.g. `["sat", "sun"]`. A wrap-around schedule belongs to the day it
+    /// starts. Default: every day
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Time in seconds to display the page. Defaults to the sensor page time.
+    pub page_time: Option<f32>,
+    /// Font name matching font filename without file extension. Default: built-in font
+    pub font_family: Option<String>,
+    /// Font size of the message text. Default: 48
+    pub font_size: Option<f32>,
+    /// Font color. Default: `#ffffff`
+    pub font_color: Option<FontColor>,
+    /// Background color. Default: `#000000`
+    pub background: Option<FontColor>,
+}
+
+impl MessagePage {
+    /// Page name: the configured name, the title or `message`.
+    pub fn name(&self) -> &str {
+        self.name
+            .as_deref()
+            .or(self.title.as_deref())
+            .unwrap_or("message")
+    }
+
+    /// Check if the message is scheduled at the given local time.
+    pub fn is_scheduled(&self, now: NaiveDateTime) -> bool {
+        let time = now.time();
+        let (in_window, day) = match (self.from, self.to) {
+            (Some(from), Some(to)) if from > to && time < to => (true, now.date().pred_opt()),
+            (Some(from), Some(to)) if from > to => (time >= from, Some(now.date())),
+            (from, to) => (
+                from.is_none_or(|from| time >= from) && to.is_none_or(|to| time < to),
+                Some(now.date()),
+            ),
+        };
+        in_window
+            && (self.days.is_empty() || day.is_some_and(|day| self.days.contains(&day.weekday())))
+    }
+}
+
 /// Custom DIY panel definition
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Panel {
@@ -326,8 +1262,21 @@ pub struct Panel {
      */
     /// Background image filename
     pub img: Option<String>,
+    /// Adjustments of the background image, e.g. to dim a bright wallpaper.
+    #[serde(default, rename = "imgAdjust")]
+    pub img_adjust: Option<ImageAdjust>,
+    /// Only show the panel if the condition is met, see [Condition].
+    #[serde(default, rename = "showIf")]
+    pub show_if: Option<Condition>,
+    /// Comma-separated fallback fonts of the text elements, used for characters missing in the
+    /// font of an element.
+    #[serde(default, rename = "fontFallback")]
+    pub font_fallback: Option<String>,
     /// Sensors
     pub sensor: Vec<Sensor>,
+    /// Directory of a custom panel loaded with [load_custom_panel].
+    #[serde(skip)]
+    pub dir: Option<PathBuf>,
 }
 
 impl Panel {
@@ -348,13 +1297,24 @@ impl Panel {
             .unwrap_or_else(|| "panel".into())
     }
 
+    /// Set the fallback fonts of the panel elements, see [Sensor::font_fallback].
+    fn apply_font_fallback(&mut self) {
+        for sensor in self.sensor.iter_mut() {
+            sensor.font_fallback = self.font_fallback.clone();
+        }
+    }
 }
 
+/// Default color of sensor values above `warnAbove`.
+const DEFAULT_WARN_COLOR: FontColor = FontColor(Rgb([0xff, 0xd6, 0]));
+/// Default color of sensor values above `critAbove`.
+const DEFAULT_CRIT_COLOR: FontColor = FontColor(Rgb([0xff, 0, 0]));
+
 /// One Data Display Unit
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Sensor {
-    /// Sensor mode: text, fan, progress, pointer
+    /// Sensor mode: text, fan, progress, pointer, icon, bar
     pub mode: SensorMode,
     /// Sensor type, _not used_.
     /// - 1 Time / Date Labels
@@ -407,6 +1367,9 @@ pub struct Sensor {
 
     /// Font name matching font filename without file extension.
     pub font_family: Option<String>,
+    /// Fallback fonts of the panel, set when loading the configuration.
+    #[serde(skip)]
+    pub font_fallback: Option<String>,
     /// TODO font size unit: points or pixels?
     pub font_size: Option<i32>,
     /// Font color in `#RRGGBB` notation, or -1 if not set. #ffffff = white, #ff0000 = red
@@ -414,6 +1377,18 @@ pub struct Sensor {
     /// _Not (yet) used_
     pub font_weight: Option<FontWeight>,
     pub text_align: Option<TextAlign>,
+    /// Scroll speed in pixels per second of text values wider than the element `width`.
+    /// If not set, long text values are drawn beyond the element.
+    pub scroll_speed: Option<f32>,
+    /// Wrap text values wider than the element `width` into multiple lines. Default: false
+    pub word_wrap: Option<bool>,
+    /// Line height of multi-line text as a factor of the font size. Default: 1.2
+    pub line_spacing: Option<f32>,
+    /// Vertical text alignment within the element `height`. Default: center
+    pub vertical_align: Option<VerticalAlign>,
+    /// Draw the value with built-in seven-segment or dot-matrix digits instead of a font. The
+    /// `font_size` is the digit height in pixels.
+    pub digit_style: Option<DigitStyle>,
 
     /// Number of integer places for the sensor value.
     // -1 ≈ unset ⇒ Option<i32>
@@ -423,6 +1398,8 @@ pub struct Sensor {
     // -1 ≈ unset ⇒ Option<i32>
     #[serde(default, deserialize_with = "option_none_if_minus_one")]
     pub decimal_digits: Option<i32>,
+    /// Unit conversion and decimal separator, overriding the `setup` options.
+    pub value_format: Option<ValueFormat>,
     /// Color thresholds for dynamic value-based coloring.
     /// Each entry is a `[threshold, "#RRGGBB"]` pair. The color of the highest threshold
     /// that the sensor value meets or exceeds is used. If the value is below all thresholds,
@@ -432,10 +1409,31 @@ pub struct Sensor {
     /// means: green ≥50, yellow ≥65, orange ≥75, red ≥85.
     #[serde(default)]
     pub color_thresholds: Vec<(f32, FontColor)>,
+    /// Warning level: values above use `warn_color`, taking priority over `color_ranges` and
+    /// `color_thresholds`.
+    pub warn_above: Option<f32>,
+    /// Critical level: values above use `crit_color`, taking priority over `warn_above`.
+    pub crit_above: Option<f32>,
+    /// Color of values above `warn_above`. Default: `#ffd600`
+    pub warn_color: Option<FontColor>,
+    /// Color of values above `crit_above`. Default: `#ff0000`
+    pub crit_color: Option<FontColor>,
+    /// Value ranges with their color. The first matching range is used, taking priority over
+    /// `color_thresholds`.
+    ///
+    /// Example: `[{"max": 20, "color": "#ff0000"}, {"min": 20, "max": 50, "color": "#ffd600"}]`
+    #[serde(default)]
+    pub color_ranges: Vec<ColorRange>,
 
     /// Image for progress, fan and pointer indicators
     #[serde(default, deserialize_with = "empty_string_as_none")]
     pub pic: Option<String>,
+    /// Images of icon sensors selected by the sensor value. The first matching entry is used,
+    /// taking priority over `pic`.
+    ///
+    /// Example: `[{"value": "up", "pic": "net_up.png"}, {"min": 80, "pic": "disk_full.png"}]`
+    #[serde(default)]
+    pub icons: Vec<IconMapping>,
 
     /// Used for fan & pointer sensors
     pub min_angle: Option<i32>,
@@ -448,6 +1446,25 @@ pub struct Sensor {
     /// Pivot y
     #[serde(rename = "xz_y")]
     pub xz_y: Option<i32>,
+
+    /// Built-in page layout for sensor templates. The element position is not used.
+    #[serde(default)]
+    pub page: Option<PageLayout>,
+    /// Style of bar graph sensors.
+    #[serde(default)]
+    pub bar: Option<BarStyle>,
+    /// Only show the element if the condition is met, see [Condition].
+    #[serde(default)]
+    pub show_if: Option<Condition>,
+    /// Display of a stale sensor value, overriding `staleSensors` of the setup.
+    #[serde(default)]
+    pub stale: Option<StaleSensors>,
+    /// Smoothing of jittery numeric sensor values, e.g. CPU load or fan speeds. Disabled if not
+    /// set.
+    pub smoothing: Option<Smoothing>,
+    /// Hysteresis of the color rules: the color only changes once the value crossed a rule level
+    /// by more than this amount. Disabled if not set.
+    pub hysteresis: Option<f32>,
     /*
     // The following fields of the AOOSTAR-X json configuration file are NOT used in `asterctl`
     /// _Not (yet) used_
@@ -462,16 +1479,56 @@ pub struct Sensor {
 }
 
 impl Sensor {
-    /// Resolve the font color based on `color_thresholds` and the current sensor value.
-    /// Returns the color of the highest threshold ≤ value, or `font_color` if no threshold matches.
+    /// Resolve the image of an icon sensor for the current sensor value.
+    ///
+    /// Returns the image of the first matching entry of `icons`, or `pic` with the `{value}`
+    /// placeholder replaced by the sanitized sensor value.
+    pub fn resolve_icon(&self, value: &str) -> Option<String> {
+        if let Some(icon) = self.icons.iter().find(|icon| icon.matches(value)) {
+            return Some(icon.pic.clone());
+        }
+
+        let value: String = value
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>()
+            .replace("..", "_");
+        self.pic.as_ref().map(|pic| pic.replace("{value}", &value))
+    }
+
+    /// Resolve the font color based on the color rules and the current sensor value.
+    ///
+    /// The rules are checked in order: `crit_above`, `warn_above`, the first matching range of
+    /// `color_ranges`, and the color of the highest threshold ≤ value of `color_thresholds`.
+    /// Returns `font_color` if no rule matches or the value is not numeric.
     pub fn resolve_color(&self, value_str: &str) -> Rgba<u8> {
         let default_color: Rgba<u8> = self.font_color.unwrap_or_default().into();
-        if self.color_thresholds.is_empty() {
+        if self.color_thresholds.is_empty()
+            && self.color_ranges.is_empty()
+            && self.warn_above.is_none()
+            && self.crit_above.is_none()
+        {
             return default_color;
         }
         let Ok(val) = value_str.parse::<f32>() else {
             return default_color;
         };
+        if self.crit_above.is_some_and(|level| val > level) {
+            return self.crit_color.unwrap_or(DEFAULT_CRIT_COLOR).into();
+        }
+        if self.warn_above.is_some_and(|level| val > level) {
+            return self.warn_color.unwrap_or(DEFAULT_WARN_COLOR).into();
+        }
+        if let Some(range) = self.color_ranges.iter().find(|range| range.contains(val)) {
+            return range.color.into();
+        }
         let mut result = default_color;
         for (threshold, color) in &self.color_thresholds {
             if val >= *threshold {
@@ -480,6 +1537,113 @@ impl Sensor {
         }
         result
     }
+
+    /// Copy of the sensor element with a fixed font color instead of the color rules.
+    pub fn with_color(&self, color: FontColor) -> Sensor {
+        let mut sensor = self.clone();
+        sensor.font_color = Some(color);
+        sensor.color_thresholds.clear();
+        sensor.color_ranges.clear();
+        sensor.warn_above = None;
+        sensor.crit_above = None;
+        sensor
+    }
+
+    /// Copy of the sensor element drawn in a single color, without color rules and gradients.
+    ///
+    /// Used for stale sensor values. Images of fan, progress, pointer and icon elements are not
+    /// changed.
+    pub fn greyed_out(&self, color: FontColor) -> Sensor {
+        let mut sensor = self.with_color(color);
+        if let Some(bar) = &mut sensor.bar {
+            bar.gradient_color = None;
+        }
+        if let Some(digit_style) = &mut sensor.digit_style {
+            digit_style.glow_color = None;
+        }
+        sensor
+    }
+}
+
+/// Image of an icon sensor for a sensor value or a value range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconMapping {
+    /// Sensor value, compared case-insensitively. If set, `min` and `max` are not used.
+    pub value: Option<String>,
+    /// Inclusive lower bound of a numeric value. Default: unbounded
+    pub min: Option<f32>,
+    /// Exclusive upper bound of a numeric value. Default: unbounded
+    pub max: Option<f32>,
+    /// Image file name.
+    pub pic: String,
+}
+
+impl IconMapping {
+    pub fn matches(&self, value: &str) -> bool {
+        let value = value.trim();
+        if let Some(expected) = &self.value {
+            return expected.eq_ignore_ascii_case(value);
+        }
+        value.parse::<f32>().is_ok_and(|value| {
+            self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value < max)
+        })
+    }
+}
+
+/// Color of a sensor value range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorRange {
+    /// Inclusive lower bound. Default: unbounded
+    pub min: Option<f32>,
+    /// Exclusive upper bound. Default: unbounded
+    pub max: Option<f32>,
+    pub color: FontColor,
+}
+
+impl ColorRange {
+    pub fn contains(&self, value: f32) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value < max)
+    }
+}
+
+/// Style of a bar graph sensor.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BarStyle {
+    /// Corner radius in pixels. Default: 0
+    #[serde(default)]
+    pub radius: u32,
+    /// Color of the unfilled part of the bar. Default: transparent
+    pub background_color: Option<FontColor>,
+    /// End color of a gradient along the bar, starting with the fill color. Default: no gradient
+    pub gradient_color: Option<FontColor>,
+}
+
+/// Built-in seven-segment or dot-matrix digits, drawn without a TTF font.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigitStyle {
+    /// Digit type. Default: sevenSegment
+    #[serde(default)]
+    pub font: DigitFont,
+    /// Segment thickness or dot diameter in pixels. Default: 1/8 of the digit height for segments,
+    /// 4/5 of the dot spacing for dots
+    pub thickness: Option<f32>,
+    /// Color of unlit segments and dots. Default: not drawn
+    pub off_color: Option<FontColor>,
+    /// Color of a glow around lit segments and dots. Default: no glow
+    pub glow_color: Option<FontColor>,
+}
+
+/// Digit type of a [DigitStyle].
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DigitFont {
+    /// Seven-segment digits of LED and LCD displays.
+    #[default]
+    SevenSegment,
+    /// 5×7 dot-matrix characters.
+    DotMatrix,
 }
 
 /// Sensor element type. Name is based on AOOSTAR-X web configuration
@@ -494,6 +1658,10 @@ pub enum SensorMode {
     Progress = 3,
     /// Rotating pointer/dial indicator
     Pointer = 4,
+    /// Image selected by the sensor value, e.g. a weather condition icon
+    Icon = 5,
+    /// Horizontal or vertical bar graph drawn without an image
+    Bar = 6,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -548,6 +1716,14 @@ pub enum FontWeight {
     Bold,
 }
 
+/// Built-in sensor page layouts.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageLayout {
+    /// Gauge with the current value, a chart of the sensor history and the min/max values.
+    History,
+}
+
 #[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TextAlign {
@@ -557,6 +1733,15 @@ pub enum TextAlign {
     Right,
 }
 
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerticalAlign {
+    Top,
+    #[default]
+    Center,
+    Bottom,
+}
+
 fn option_none_if_minus_one<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
 where
     D: Deserializer<'de>,
@@ -567,9 +1752,26 @@ where
     }
 }
 
+/// Color names accepted in addition to the `#RRGGBB` notation.
+const NAMED_COLORS: [(&str, [u8; 3]); 11] = [
+    ("black", [0, 0, 0]),
+    ("white", [0xff, 0xff, 0xff]),
+    ("gray", [0x80, 0x80, 0x80]),
+    ("grey", [0x80, 0x80, 0x80]),
+    ("red", [0xff, 0, 0]),
+    ("orange", [0xff, 0x8c, 0]),
+    ("yellow", [0xff, 0xd6, 0]),
+    ("green", [0, 0xc8, 0x53]),
+    ("cyan", [0, 0xe5, 0xff]),
+    ("blue", [0x29, 0x79, 0xff]),
+    ("magenta", [0xff, 0, 0xff]),
+];
+
 /// Special font color type since it is represented either as numeric -1 or as a string :-(
 ///
 /// A good serde programming exercise...
+///
+/// Strings are colors in `#RRGGBB` notation or simple color names like `green`.
 #[derive(Debug, Clone, Copy)]
 pub struct FontColor(Rgb<u8>);
 
@@ -591,7 +1793,12 @@ impl TryFrom<&str> for FontColor {
     type Error = ParseIntError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.len() != 7 || !value.starts_with('#') {
+        if let Some((_, color)) = NAMED_COLORS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        {
+            Ok(FontColor(Rgb(*color)))
+        } else if value.len() != 7 || !value.starts_with('#') {
             warn!("Invalid font color: {value}");
             Ok(FontColor::default())
         } else {
@@ -675,6 +1882,93 @@ impl<'de> Deserialize<'de> for FontColor {
     }
 }
 
+/// Deserialize derived sensor definitions: either an expression string or a [DerivedSensor]
+/// object.
+fn derived_sensors<'de, D>(deserializer: D) -> Result<HashMap<String, DerivedSensor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Definition {
+        Expression(String),
+        Sensor(DerivedSensor),
+    }
+
+    let definitions = HashMap::<String, Definition>::deserialize(deserializer)?;
+    Ok(definitions
+        .into_iter()
+        .map(|(key, definition)| {
+            let sensor = match definition {
+                Definition::Expression(expr) => DerivedSensor {
+                    expr,
+                    unit: None,
+                    description: None,
+                    integer_digits: None,
+                    decimal_digits: None,
+                },
+                Definition::Sensor(sensor) => sensor,
+            };
+            (key, sensor)
+        })
+        .collect())
+}
+
+/// Deserialize the sensor filter: either a list of exclude patterns or a [SensorFilterConfig]
+/// object.
+fn sensor_filter_config<'de, D>(deserializer: D) -> Result<Option<SensorFilterConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Definition {
+        Exclude(Vec<String>),
+        Config(SensorFilterConfig),
+    }
+
+    Ok(match Option::<Definition>::deserialize(deserializer)? {
+        Some(Definition::Exclude(exclude)) => Some(SensorFilterConfig {
+            rules: SensorFilterRules {
+                exclude,
+                include: Vec::new(),
+            },
+            sources: HashMap::new(),
+        }),
+        Some(Definition::Config(config)) => Some(config),
+        None => None,
+    })
+}
+
+/// Deserialize a rotation angle in degrees.
+fn option_rotation_degrees<'de, D>(deserializer: D) -> Result<Option<Rotation>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(degrees) = Option::<u32>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    Rotation::from_degrees(degrees).map(Some).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "invalid rotation {degrees}, expected 0, 90, 180 or 270"
+        ))
+    })
+}
+
+fn serialize_rotation_degrees<S>(
+    rotation: &Option<Rotation>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    rotation.map(Rotation::degrees).serialize(serializer)
+}
+
+fn default_true() -> bool {
+    true
+}
+
 fn empty_string_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -698,3 +1992,118 @@ where
     let rounded = f32::deserialize(deserializer).map(f32::round)?;
     Ok(Some(rounded as i32))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor(json: &str) -> Sensor {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn resolve_color_rules() {
+        let sensor = sensor(
+            r##"{ "mode": 1, "label": "temperature_cpu", "x": 0, "y": 0, "fontColor": "#ffffff",
+              "warnAbove": 70, "critAbove": 85, "critColor": "#ff0000",
+              "colorRanges": [{ "max": 20, "color": "#0000ff" }, { "min": 20, "max": 40, "color": "#00ff00" }],
+              "colorThresholds": [[40, "#888888"]] }"##,
+        );
+
+        assert_eq!(Rgba([0, 0, 255, 255]), sensor.resolve_color("-5"));
+        assert_eq!(Rgba([0, 255, 0, 255]), sensor.resolve_color("20"));
+        assert_eq!(Rgba([0x88, 0x88, 0x88, 255]), sensor.resolve_color("55"));
+        assert_eq!(Rgba([0x88, 0x88, 0x88, 255]), sensor.resolve_color("70"));
+        assert_eq!(Rgba([0xff, 0xd6, 0, 255]), sensor.resolve_color("70.5"));
+        assert_eq!(Rgba([255, 0, 0, 255]), sensor.resolve_color("90"));
+        assert_eq!(Rgba([255, 255, 255, 255]), sensor.resolve_color("n/a"));
+    }
+
+    #[test]
+    fn resolve_icon_mapping() {
+        let sensor = sensor(
+            r#"{ "mode": 5, "label": "net_eth0_up", "x": 0, "y": 0, "pic": "status/{value}.png",
+              "icons": [{ "value": "Up", "pic": "net_up.png" }, { "value": "down", "pic": "net_down.png" },
+                        { "min": 90, "pic": "disk_full.png" }] }"#,
+        );
+
+        assert_eq!(Some("net_up.png".into()), sensor.resolve_icon("up"));
+        assert_eq!(Some("net_down.png".into()), sensor.resolve_icon(" DOWN "));
+        assert_eq!(Some("disk_full.png".into()), sensor.resolve_icon("95.5"));
+        assert_eq!(Some("status/50.png".into()), sensor.resolve_icon("50"));
+        assert_eq!(
+            Some("status/___etc_passwd.png".into()),
+            sensor.resolve_icon("/../etc/passwd")
+        );
+
+        let sensor = self::sensor(r#"{ "mode": 5, "label": "raid", "x": 0, "y": 0 }"#);
+        assert_eq!(None, sensor.resolve_icon("clean"));
+    }
+
+    #[test]
+    fn named_font_colors() {
+        let color = |json: &str| Rgb::from(serde_json::from_str::<FontColor>(json).unwrap());
+        assert_eq!(Rgb([0, 0xc8, 0x53]), color(r#""Green""#));
+        assert_eq!(Rgb([0x12, 0x34, 0x56]), color(r##""#123456""##));
+        assert_eq!(Rgb([0xff, 0xff, 0xff]), color("-1"));
+    }
+
+    #[test]
+    fn message_page_schedule() {
+        let message: MessagePage = serde_json::from_str(
+            r#"{ "text": "Backup window", "from": "22:00", "to": "02:00", "days": ["fri"] }"#,
+        )
+        .unwrap();
+        // 2026-10-16 is a Friday
+        let at = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+
+        assert!(!message.is_scheduled(at("2026-10-16 21:59")));
+        assert!(message.is_scheduled(at("2026-10-16 22:00")));
+        assert!(message.is_scheduled(at("2026-10-17 01:59")));
+        assert!(!message.is_scheduled(at("2026-10-17 02:00")));
+        assert!(!message.is_scheduled(at("2026-10-17 22:30")));
+        assert!(!message.is_scheduled(at("2026-10-16 01:00")));
+
+        let message: MessagePage =
+            serde_json::from_str(r#"{ "text": "Maintenance", "from": "02:00" }"#).unwrap();
+        assert!(!message.is_scheduled(at("2026-10-17 01:59")));
+        assert!(message.is_scheduled(at("2026-10-17 23:59")));
+    }
+
+    #[test]
+    fn remove_custom_panel() {
+        let mut cfg: MonitorConfig = serde_json::from_str(
+            r#"{ "setup": { "refresh": 1 }, "mianban": [1, 2, 3],
+              "slots": [{ "x": 0, "width": 480, "panels": [3, 2] }],
+              "diy": [{ "sensor": [] }, { "sensor": [] }, { "sensor": [] }] }"#,
+        )
+        .unwrap();
+        cfg.panels[1].dir = Some(PathBuf::from("/panels/does-not-exist"));
+
+        assert!(!cfg.remove_custom_panel(Path::new("/panels/other")));
+        assert!(cfg.remove_custom_panel(Path::new("/panels/does-not-exist")));
+        assert_eq!(2, cfg.panels.len());
+        assert_eq!(vec![1, 2], cfg.active_panels);
+        assert_eq!(vec![2], cfg.slots[0].panels);
+    }
+
+    #[test]
+    fn sensor_filter_list_or_object() {
+        let config = |filter: &str| {
+            let mut cfg: MonitorConfig = serde_json::from_str(&format!(
+                r#"{{ "setup": {{ "refresh": 1 }}, "mianban": [], "diy": [], "sensorFilter": {filter} }}"#
+            ))
+            .unwrap();
+            assert!(cfg.compile_sensor_filters());
+            cfg.sensor_filter.source("sysinfo")
+        };
+
+        let filter = config(r#"["^temperature_.*#unit"]"#);
+        assert!(filter.is_filtered("temperature_cpu#unit"));
+        assert!(!filter.is_filtered("cpu_usage_percent"));
+
+        let filter = config(r#"{ "include": ["^cpu_"] }"#);
+        assert!(!filter.is_filtered("cpu_usage_percent"));
+        assert!(filter.is_filtered("mem_usage_percent"));
+    }
+}
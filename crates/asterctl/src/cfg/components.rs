@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Reusable element groups ("components") in panel configurations.
+//!
+//! A component is a named group of sensor elements, defined once in a `components` object of the
+//! monitor configuration or of a panel:
+//!
+//! ```json
+//! "components": {
+//!   "labeledGauge": {
+//!     "params": { "color": "#ffffff" },
+//!     "elements": [
+//!       { "mode": 1, "label": "${name}_label", "x": 0, "y": 0, "fontColor": "${color}" },
+//!       { "mode": 2, "label": "${sensor}", "x": 0, "y": 30, "pic": "gauge.png" }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! and instantiated in the `sensor` array of a panel:
+//!
+//! ```json
+//! { "component": "labeledGauge", "x": 100, "y": 50, "params": { "sensor": "cpu_temp" } }
+//! ```
+//!
+//! The element positions are relative to the instance position. `${param}` placeholders in string
+//! values are replaced with the instance parameters, or the default parameters of the component.
+//! A string consisting of a single placeholder is replaced with the parameter value as is, which
+//! allows numeric parameters. Components may contain other components.
+
+use anyhow::{anyhow, bail};
+use serde_json::{Map, Value};
+
+/// Maximum nesting level of components to detect recursive definitions.
+const MAX_DEPTH: usize = 8;
+
+/// Expand all component instances in the panels of a monitor configuration.
+pub(super) fn expand_config_components(config: &mut Value) -> anyhow::Result<()> {
+    let global = component_definitions(config, &Map::new());
+    if let Some(panels) = config.get_mut("diy").and_then(Value::as_array_mut) {
+        for panel in panels {
+            expand_panel_components(panel, &global)?;
+        }
+    }
+    Ok(())
+}
+
+/// Expand all component instances in a panel.
+///
+/// Components defined in the panel take priority over the given `global` components.
+pub(super) fn expand_panel_components(
+    panel: &mut Value,
+    global: &Map<String, Value>,
+) -> anyhow::Result<()> {
+    let definitions = component_definitions(panel, global);
+    if let Some(elements) = panel.get_mut("sensor").and_then(Value::as_array_mut) {
+        *elements = expand_elements(std::mem::take(elements), &definitions, 0)?;
+    }
+    Ok(())
+}
+
+fn component_definitions(value: &Value, inherited: &Map<String, Value>) -> Map<String, Value> {
+    let mut definitions = inherited.clone();
+    if let Some(components) = value.get("components").and_then(Value::as_object) {
+        definitions.extend(components.clone());
+    }
+    definitions
+}
+
+fn expand_elements(
+    elements: Vec<Value>,
+    definitions: &Map<String, Value>,
+    depth: usize,
+) -> anyhow::Result<Vec<Value>> {
+    let mut expanded = Vec::with_capacity(elements.len());
+
+    for element in elements {
+        let Some(name) = element.get("component").and_then(Value::as_str) else {
+            expanded.push(element);
+            continue;
+        };
+        if depth >= MAX_DEPTH {
+            bail!("Component '{name}' is nested too deep, recursive definition?");
+        }
+        let definition = definitions
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown component '{name}'"))?;
+
+        let mut params = definition
+            .get("params")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(overrides) = element.get("params").and_then(Value::as_object) {
+            params.extend(overrides.clone());
+        }
+        let x = element.get("x").and_then(Value::as_f64).unwrap_or_default();
+        let y = element.get("y").and_then(Value::as_f64).unwrap_or_default();
+
+        let children = definition
+            .get("elements")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Component '{name}' has no elements array"))?
+            .iter()
+            .map(|child| {
+                let mut child = substitute_params(child, &params);
+                offset_position(&mut child, "x", x);
+                offset_position(&mut child, "y", y);
+                child
+            })
+            .collect();
+
+        expanded.extend(expand_elements(children, definitions, depth + 1)?);
+    }
+
+    Ok(expanded)
+}
+
+fn offset_position(element: &mut Value, field: &str, offset: f64) {
+    if let Some(obj) = element.as_object_mut() {
+        let pos = obj.get(field).and_then(Value::as_f64).unwrap_or_default();
+        obj.insert(field.to_string(), Value::from(pos + offset));
+    }
+}
+
+fn substitute_params(value: &Value, params: &Map<String, Value>) -> Value {
+    match value {
+        Value::String(s) => {
+            if let Some(name) = s.strip_prefix("${").and_then(|s| s.strip_suffix('}'))
+                && let Some(param) = params.get(name)
+            {
+                return param.clone();
+            }
+            let mut result = s.clone();
+            for (name, param) in params {
+                let placeholder = format!("${{{name}}}");
+                if result.contains(&placeholder) {
+                    let replacement = match param {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    result = result.replace(&placeholder, &replacement);
+                }
+            }
+            Value::String(result)
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|v| substitute_params(v, params))
+                .collect(),
+        ),
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(k, v)| (k.clone(), substitute_params(v, params)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn expand_component_instances_with_params_and_offsets() {
+        let mut config = json!({
+            "components": {
+                "gauge": {
+                    "params": { "size": 20, "unit": " °C" },
+                    "elements": [
+                        { "mode": 1, "label": "${sensor}", "x": 10, "y": 0, "fontSize": "${size}", "unit": "${unit}" },
+                        { "mode": 2, "label": "${sensor}", "x": 0, "y": 30, "pic": "${sensor}_gauge.png" }
+                    ]
+                }
+            },
+            "diy": [{
+                "sensor": [
+                    { "mode": 1, "label": "DATE_h_m_3", "x": 1, "y": 2 },
+                    { "component": "gauge", "x": 100, "y": 50, "params": { "sensor": "cpu", "size": 32 } }
+                ]
+            }]
+        });
+
+        expand_config_components(&mut config).unwrap();
+
+        assert_eq!(
+            json!([
+                { "mode": 1, "label": "DATE_h_m_3", "x": 1, "y": 2 },
+                { "mode": 1, "label": "cpu", "x": 110.0, "y": 50.0, "fontSize": 32, "unit": " °C" },
+                { "mode": 2, "label": "cpu", "x": 100.0, "y": 80.0, "pic": "cpu_gauge.png" }
+            ]),
+            config["diy"][0]["sensor"]
+        );
+    }
+
+    #[test]
+    fn panel_components_and_nesting() {
+        let global = component_definitions(
+            &json!({ "components": {
+                "value": { "elements": [{ "mode": 1, "label": "${sensor}", "x": 0, "y": 0 }] }
+            }}),
+            &Map::new(),
+        );
+        let mut panel = json!({
+            "components": {
+                "pair": { "elements": [
+                    { "component": "value", "x": 0, "y": 0, "params": { "sensor": "${a}" } },
+                    { "component": "value", "x": 200, "y": 0, "params": { "sensor": "${b}" } }
+                ]}
+            },
+            "sensor": [{ "component": "pair", "x": 5, "y": 5, "params": { "a": "cpu", "b": "gpu" } }]
+        });
+
+        expand_panel_components(&mut panel, &global).unwrap();
+
+        assert_eq!(
+            json!([
+                { "mode": 1, "label": "cpu", "x": 5.0, "y": 5.0 },
+                { "mode": 1, "label": "gpu", "x": 205.0, "y": 5.0 }
+            ]),
+            panel["sensor"]
+        );
+    }
+
+    #[test]
+    fn unknown_and_recursive_components_fail() {
+        let mut panel = json!({ "sensor": [{ "component": "missing" }] });
+        assert!(expand_panel_components(&mut panel, &Map::new()).is_err());
+
+        let mut panel = json!({
+            "components": { "loop": { "elements": [{ "component": "loop" }] } },
+            "sensor": [{ "component": "loop" }]
+        });
+        assert!(expand_panel_components(&mut panel, &Map::new()).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Visibility conditions of panels and sensor elements (`showIf`).
+//!
+//! A condition is evaluated against the current sensor values:
+//!
+//! - `gpu0_temp`: the sensor exists.
+//! - `!gpu0_temp`: the sensor doesn't exist.
+//! - `battery_bat0_level < 20`: compares the sensor value with `==`, `!=`, `<`, `<=`, `>` or `>=`.
+//!   Values are compared as numbers if both sides are numeric, otherwise only `==` and `!=` are
+//!   supported and compare the text. A missing sensor doesn't meet any comparison.
+//!
+//! Terms can be combined with `&&` and `||`, where `&&` binds stronger. Parentheses are not
+//! supported.
+
+use crate::sensors::SensorStore;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed `showIf` condition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Condition {
+    expression: String,
+    /// Alternatives of `||`, each a list of terms combined with `&&`.
+    any: Vec<Vec<Term>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Exists(String),
+    Missing(String),
+    Compare { key: String, op: Op, value: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Operators in matching order: two-character operators first.
+const OPERATORS: [(&str, Op); 6] = [
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    ("<=", Op::Le),
+    (">=", Op::Ge),
+    ("<", Op::Lt),
+    (">", Op::Gt),
+];
+
+impl Condition {
+    /// Check if the condition is met with the given sensor values.
+    pub fn is_met(&self, values: &SensorStore) -> bool {
+        self.any
+            .iter()
+            .any(|terms| terms.iter().all(|term| term.is_met(values)))
+    }
+}
+
+impl Term {
+    fn is_met(&self, values: &SensorStore) -> bool {
+        match self {
+            Term::Exists(key) => values.contains_key(key),
+            Term::Missing(key) => !values.contains_key(key),
+            Term::Compare { key, op, value } => values
+                .value(key)
+                .is_some_and(|current| op.compare(current.trim(), value)),
+        }
+    }
+}
+
+impl Op {
+    fn compare(self, left: &str, right: &str) -> bool {
+        if let (Ok(left), Ok(right)) = (left.parse::<f64>(), right.parse::<f64>()) {
+            return match self {
+                Op::Eq => left == right,
+                Op::Ne => left != right,
+                Op::Lt => left < right,
+                Op::Le => left <= right,
+                Op::Gt => left > right,
+                Op::Ge => left >= right,
+            };
+        }
+        match self {
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+            _ => false,
+        }
+    }
+}
+
+fn parse_key(key: &str) -> Result<String, String> {
+    let key = key.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err(format!("invalid sensor key '{key}'"));
+    }
+    Ok(key.to_string())
+}
+
+fn parse_term(term: &str) -> Result<Term, String> {
+    for (symbol, op) in OPERATORS {
+        if let Some((key, value)) = term.split_once(symbol) {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            return Ok(Term::Compare {
+                key: parse_key(key)?,
+                op,
+                value: value.to_string(),
+            });
+        }
+    }
+    match term.trim().strip_prefix('!') {
+        Some(key) => Ok(Term::Missing(parse_key(key)?)),
+        None => Ok(Term::Exists(parse_key(term)?)),
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let any = expression
+            .split("||")
+            .map(|all| all.split("&&").map(parse_term).collect())
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("invalid condition '{expression}': {e}"))?;
+        Ok(Condition {
+            expression: expression.to_string(),
+            any,
+        })
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Condition> for String {
+    fn from(value: Condition) -> Self {
+        value.expression
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> SensorStore {
+        SensorStore::from_iter([
+            ("gpu0_temp".to_string(), "61".to_string()),
+            ("battery_ac_online".to_string(), "1".to_string()),
+            ("network_state".to_string(), "up".to_string()),
+        ])
+    }
+
+    fn is_met(expression: &str) -> bool {
+        expression.parse::<Condition>().unwrap().is_met(&values())
+    }
+
+    #[test]
+    fn evaluate_conditions() {
+        assert!(is_met("gpu0_temp"));
+        assert!(!is_met("gpu1_temp"));
+        assert!(is_met("!gpu1_temp"));
+        assert!(!is_met("battery_ac_online == 0"));
+        assert!(is_met("battery_ac_online != 0"));
+        assert!(is_met("gpu0_temp >= 61.0"));
+        assert!(!is_met("gpu0_temp < 61"));
+        assert!(is_met("network_state == \"up\""));
+        assert!(!is_met("network_state > 1"));
+        // missing sensor doesn't meet any comparison
+        assert!(!is_met("gpu1_temp != 0"));
+
+        assert!(is_met("gpu0_temp > 60 && battery_ac_online == 1"));
+        assert!(!is_met("gpu0_temp > 60 && gpu1_temp"));
+        assert!(is_met("gpu1_temp || gpu0_temp > 60 && network_state == up"));
+    }
+
+    #[test]
+    fn invalid_conditions() {
+        assert!("".parse::<Condition>().is_err());
+        assert!("gpu0_temp &&".parse::<Condition>().is_err());
+        assert!("cpu temp > 50".parse::<Condition>().is_err());
+        assert!(serde_json::from_str::<Condition>(r#""gpu0_temp || !""#).is_err());
+    }
+}
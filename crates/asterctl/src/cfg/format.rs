@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Configuration file formats: the AOOSTAR-X JSON format and the native TOML format.
+//!
+//! The TOML format has the same structure and field names as the JSON format, except for the
+//! top-level fields named after the AOOSTAR-X editor:
+//!
+//! | JSON      | TOML           |
+//! |-----------|----------------|
+//! | `mianban` | `activePanels` |
+//! | `diy`     | `panels`       |
+//!
+//! Both formats are parsed into the JSON representation, which is then deserialized into a
+//! [MonitorConfig](super::MonitorConfig). Converting between the formats keeps all fields, also
+//! unknown ones and component definitions.
+
+use anyhow::{Context, bail};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Top-level fields with a different name in the TOML format: (JSON name, TOML name).
+const NATIVE_FIELDS: [(&str, &str); 2] = [("mianban", "activePanels"), ("diy", "panels")];
+
+/// Configuration file format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// AOOSTAR-X JSON format.
+    Json,
+    /// Native TOML format.
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Detect the format by the file extension: TOML for `.toml` files, JSON otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Parse a configuration file into its JSON representation.
+pub fn parse_config(text: &str, format: ConfigFormat) -> anyhow::Result<Value> {
+    match format {
+        ConfigFormat::Json => Ok(serde_json::from_str(text)?),
+        ConfigFormat::Toml => {
+            let mut value: Value = toml::from_str(text)?;
+            if let Value::Object(fields) = &mut value {
+                for (json, toml) in NATIVE_FIELDS {
+                    rename_field(fields, toml, json);
+                }
+            }
+            Ok(value)
+        }
+    }
+}
+
+/// Write the JSON representation of a configuration file in the given format.
+///
+/// TOML has no null value: null fields are left out, which is the same as not set.
+pub fn write_config(value: &Value, format: ConfigFormat) -> anyhow::Result<String> {
+    match format {
+        ConfigFormat::Json => Ok(serde_json::to_string_pretty(value)? + "\n"),
+        ConfigFormat::Toml => {
+            let mut value = value.clone();
+            remove_nulls(&mut value);
+            let Value::Object(fields) = &mut value else {
+                bail!("A configuration must be an object");
+            };
+            for (json, toml) in NATIVE_FIELDS {
+                rename_field(fields, json, toml);
+            }
+            toml::to_string(&value).context("Failed to write the TOML configuration")
+        }
+    }
+}
+
+/// Rename a field, keeping its position.
+fn rename_field(fields: &mut Map<String, Value>, from: &str, to: &str) {
+    if fields.contains_key(from) {
+        *fields = std::mem::take(fields)
+            .into_iter()
+            .map(|(key, value)| {
+                let key = if key == from { to.to_string() } else { key };
+                (key, value)
+            })
+            .collect();
+    }
+}
+
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            fields.retain(|_, value| !value.is_null());
+            fields.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(values) => values.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TOML: &str = r##"
+activePanels = [1]
+
+[setup]
+refresh = 1
+timePage = "DATE_h_m_s_1"
+
+[[panels]]
+name = "Sensors"
+
+[[panels.sensor]]
+mode = 1
+match = "^temperature_cpu$"
+x = 480
+y = 200
+colorThresholds = [[0, "#00ff00"], [80, "#ff0000"]]
+"##;
+
+    #[test]
+    fn parse_toml_with_native_fields() {
+        let value = parse_config(TOML, ConfigFormat::Toml).unwrap();
+
+        assert_eq!(json!([1]), value["mianban"]);
+        assert_eq!(json!("Sensors"), value["diy"][0]["name"]);
+        assert_eq!(
+            json!([[0, "#00ff00"], [80, "#ff0000"]]),
+            value["diy"][0]["sensor"][0]["colorThresholds"]
+        );
+        let cfg: crate::cfg::MonitorConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(1, cfg.panels[0].sensor.len());
+    }
+
+    #[test]
+    fn convert_between_json_and_toml() {
+        let json = json!({
+            "setup": { "refresh": 1, "timePage": null },
+            "mianban": [1],
+            "diy": [ { "name": "Sensors", "sensor": [ { "mode": 1, "label": "cpu", "x": 0 } ] } ],
+        });
+
+        let toml = write_config(&json, ConfigFormat::Toml).unwrap();
+        assert!(toml.contains("activePanels = [1]"), "{toml}");
+        assert!(toml.contains("[[panels.sensor]]"), "{toml}");
+        assert!(!toml.contains("timePage"), "{toml}");
+
+        let mut expected = json;
+        expected["setup"]
+            .as_object_mut()
+            .unwrap()
+            .remove("timePage");
+        assert_eq!(expected, parse_config(&toml, ConfigFormat::Toml).unwrap());
+        assert_eq!(
+            ConfigFormat::Toml,
+            ConfigFormat::from_path(Path::new("cfg/monitor.TOML"))
+        );
+        assert_eq!(
+            ConfigFormat::Json,
+            ConfigFormat::from_path(Path::new("monitor.json"))
+        );
+    }
+}
@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Configuration files including other configuration files.
+//!
+//! The `include` array of a configuration lists base configurations, relative to the including
+//! file:
+//!
+//! ```json
+//! { "include": ["shared/panels.json", "shared/sources.toml"], "sensorFilter": ["^fan_.*"] }
+//! ```
+//!
+//! The files are merged in order with later-wins semantics: each included file overrides the
+//! previous ones, and the including file overrides all of them. Objects are merged field by field,
+//! all other values including arrays are replaced. Included files may include other files, each
+//! file is migrated to the current schema version before merging.
+
+use super::format::{ConfigFormat, parse_config};
+use super::migrate::{CONFIG_VERSION, migrate_config};
+use anyhow::{Context, bail};
+use log::info;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Load the JSON representation of a configuration file with all included files merged.
+pub(super) fn load_config_value(path: &Path) -> anyhow::Result<Value> {
+    load(path, &mut Vec::new())
+}
+
+/// Load a configuration file and its includes.
+///
+/// # Arguments
+///
+/// * `path`: configuration file.
+/// * `including`: canonical paths of the files including this file, to detect recursion.
+fn load(path: &Path, including: &mut Vec<PathBuf>) -> anyhow::Result<Value> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to load config {path:?}"))?;
+    let mut value = parse_config(&text, ConfigFormat::from_path(path))
+        .with_context(|| format!("Failed to parse config {path:?}"))?;
+    if let Some(version) =
+        migrate_config(&mut value).with_context(|| format!("Invalid config {path:?}"))?
+    {
+        info!("Migrated config {path:?} from version {version} to {CONFIG_VERSION}");
+    }
+
+    let includes = match value
+        .as_object_mut()
+        .and_then(|cfg| cfg.shift_remove("include"))
+    {
+        None => return Ok(value),
+        Some(Value::Array(includes)) => includes,
+        Some(_) => bail!("The include field of config {path:?} must be an array of files"),
+    };
+    let canonical = fs::canonicalize(path)?;
+    if including.contains(&canonical) {
+        bail!("Recursive include of config {path:?}");
+    }
+    including.push(canonical);
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut merged = Value::Null;
+    for include in includes {
+        let Some(include) = include.as_str() else {
+            bail!("Invalid include {include} in config {path:?}");
+        };
+        info!("Including config {include} in {path:?}");
+        merge(&mut merged, load(&dir.join(include), including)?);
+    }
+    merge(&mut merged, value);
+
+    including.pop();
+    Ok(merged)
+}
+
+/// Merge an overlay into a base value: objects are merged field by field, other values replace
+/// the base value.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(base) => merge(base, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_objects_later_wins() {
+        let mut base = json!({
+            "setup": { "refresh": 1, "sensorPageTime": 5 },
+            "sensorFilter": { "exclude": ["^a"], "include": ["^b"] },
+            "diy": [ { "name": "shared" } ],
+        });
+
+        merge(
+            &mut base,
+            json!({
+                "setup": { "sensorPageTime": 10 },
+                "sensorFilter": { "exclude": ["^c"] },
+                "pingHosts": ["router"],
+            }),
+        );
+
+        assert_eq!(
+            json!({
+                "setup": { "refresh": 1, "sensorPageTime": 10 },
+                "sensorFilter": { "exclude": ["^c"], "include": ["^b"] },
+                "diy": [ { "name": "shared" } ],
+                "pingHosts": ["router"],
+            }),
+            base
+        );
+    }
+
+    #[test]
+    fn load_included_configs() {
+        let dir = std::env::temp_dir().join(format!("asterctl-include-{}", std::process::id()));
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(
+            dir.join("shared/panels.toml"),
+            "activePanels = [1]\n[setup]\nrefresh = 1\nsensorPageTime = 5\n[[panels]]\nname = \"Sensors\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("shared/filter.json"),
+            r#"{ "sensorFilter": ["^fan_.*"], "setup": { "sensorPageTime": 8 } }"#,
+        )
+        .unwrap();
+        let path = dir.join("monitor.json");
+        fs::write(
+            &path,
+            r#"{ "include": ["shared/panels.toml", "shared/filter.json"], "setup": { "refresh": 2 } }"#,
+        )
+        .unwrap();
+
+        let cfg = load_config_value(&path).unwrap();
+
+        assert_eq!(json!({ "refresh": 2, "sensorPageTime": 8 }), cfg["setup"]);
+        assert_eq!(json!({ "exclude": ["^fan_.*"] }), cfg["sensorFilter"]);
+        assert_eq!(json!("Sensors"), cfg["diy"][0]["name"]);
+        assert_eq!(json!(CONFIG_VERSION), cfg["version"]);
+        assert!(cfg.get("include").is_none());
+
+        fs::write(&path, r#"{ "include": ["monitor.json"] }"#).unwrap();
+        let error = load_config_value(&path).unwrap_err();
+        assert!(
+            error.to_string().starts_with("Recursive include"),
+            "{error}"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
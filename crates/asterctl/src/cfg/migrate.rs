@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Configuration schema versions and migrations.
+//!
+//! The `version` field of a configuration is the schema version it was written for. Files without
+//! version, e.g. of AOOSTAR-X, are version 0. On load, older configurations are upgraded to
+//! [CONFIG_VERSION] by applying the migration of each version in order, on the JSON representation
+//! before it is deserialized.
+//!
+//! Versions:
+//! - 0: AOOSTAR-X format without version.
+//! - 1: `sensorFilter` is an object with `exclude` and `include` patterns, the array of exclude
+//!   patterns is converted.
+//!
+//! A change of a configuration field adds a new version with a migration of the previous version.
+
+use anyhow::bail;
+use serde_json::{Map, Value, json};
+
+/// Current configuration schema version.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Upgrade of a configuration object to the next version.
+type Migration = fn(&mut Map<String, Value>);
+
+/// Migration of each version to the next version, indexed by the version to upgrade.
+const MIGRATIONS: [Migration; CONFIG_VERSION as usize] = [migrate_v0];
+
+/// Upgrade the JSON representation of a configuration to the current version.
+///
+/// returns: Result<Option<u32>, Error>, the original version if the configuration was migrated,
+/// or an error if the version is invalid or newer than supported.
+pub fn migrate_config(config: &mut Value) -> anyhow::Result<Option<u32>> {
+    let Value::Object(fields) = config else {
+        bail!("A configuration must be an object");
+    };
+    let version = match fields.get("version") {
+        None => 0,
+        Some(version) => match version.as_u64().and_then(|v| u32::try_from(v).ok()) {
+            Some(version) => version,
+            None => bail!("Invalid configuration version: {version}"),
+        },
+    };
+    if version > CONFIG_VERSION {
+        bail!(
+            "Configuration version {version} is newer than the supported version {CONFIG_VERSION}, please update asterctl"
+        );
+    }
+    if version == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(fields);
+    }
+    // the version is the first field of a migrated configuration
+    fields.shift_remove("version");
+    *fields = [("version".to_string(), json!(CONFIG_VERSION))]
+        .into_iter()
+        .chain(std::mem::take(fields))
+        .collect();
+    Ok(Some(version))
+}
+
+/// Version 0 -> 1: convert the `sensorFilter` array of exclude patterns to an object.
+fn migrate_v0(config: &mut Map<String, Value>) {
+    if let Some(filter) = config.get_mut("sensorFilter")
+        && filter.is_array()
+    {
+        *filter = json!({ "exclude": filter.take() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_unversioned_config() {
+        let mut config = json!({
+            "setup": { "refresh": 1 },
+            "sensorFilter": ["^temperature_.*#unit"],
+            "mianban": [1],
+            "diy": [],
+        });
+
+        assert_eq!(Some(0), migrate_config(&mut config).unwrap());
+
+        assert_eq!(
+            json!({
+                "version": CONFIG_VERSION,
+                "setup": { "refresh": 1 },
+                "sensorFilter": { "exclude": ["^temperature_.*#unit"] },
+                "mianban": [1],
+                "diy": [],
+            }),
+            config
+        );
+        let keys: Vec<_> = config.as_object().unwrap().keys().collect();
+        assert_eq!(
+            vec!["version", "setup", "sensorFilter", "mianban", "diy"],
+            keys
+        );
+        assert_eq!(None, migrate_config(&mut config).unwrap());
+    }
+
+    #[test]
+    fn reject_unsupported_versions() {
+        let error = migrate_config(&mut json!({ "version": CONFIG_VERSION + 1 })).unwrap_err();
+        assert_eq!(
+            "Configuration version 2 is newer than the supported version 1, please update asterctl",
+            error.to_string()
+        );
+        assert!(migrate_config(&mut json!({ "version": "1" })).is_err());
+    }
+}
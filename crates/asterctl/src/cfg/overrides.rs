@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Overrides of `setup` values with environment variables and command line switches.
+//!
+//! For containerized deployments, where editing the configuration file is awkward, any `setup`
+//! value can be set with an environment variable or with `--set`:
+//!
+//! ```shell
+//! ASTERCTL_SETUP__REFRESH=1.5 ASTERCTL_SETUP__SENSOR_PAGE_LABEL__FONT_SIZE=40 asterctl ...
+//! asterctl --set setup.refresh=1.5 --set setup.sensorPageLabel.fontSize=40 ...
+//! ```
+//!
+//! `__` separates the fields of an environment variable. Field names are matched ignoring case
+//! and `_`, new fields are added in camelCase, except the snake_case `ha_url` and `ha_token`.
+//! Values are parsed as JSON, e.g. numbers, booleans or objects, and used as text otherwise. Values
+//! of text fields like `ha_token` are always used as text. The overrides are merged into the loaded
+//! configuration before it is deserialized, the command line switches after the environment
+//! variables.
+
+use anyhow::{Context, bail};
+use log::info;
+use serde_json::{Map, Value};
+use std::env;
+use std::str::FromStr;
+
+/// Environment variable prefix of the `setup` overrides.
+pub const SETUP_ENV_PREFIX: &str = "ASTERCTL_SETUP__";
+
+/// `setup` fields with snake_case names in the AOOSTAR-X format, all others are camelCase.
+const SNAKE_CASE_FIELDS: [&str; 2] = ["ha_url", "ha_token"];
+
+/// `setup` text fields, values like `123456` are not parsed as JSON numbers.
+const TEXT_FIELDS: [&str; 3] = ["timePage", "ha_url", "ha_token"];
+
+/// Override of a `setup` value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupOverride {
+    /// Field names below `setup`.
+    path: Vec<String>,
+    value: Value,
+    /// Unparsed value for text fields.
+    text: String,
+}
+
+impl SetupOverride {
+    /// Overrides of all `ASTERCTL_SETUP__` environment variables, sorted by name.
+    pub fn from_env() -> anyhow::Result<Vec<Self>> {
+        let mut vars: Vec<_> = env::vars()
+            .filter(|(name, _)| name.starts_with(SETUP_ENV_PREFIX))
+            .collect();
+        vars.sort();
+        vars.iter()
+            .map(|(name, value)| Self::from_env_var(name, value))
+            .collect()
+    }
+
+    fn from_env_var(name: &str, value: &str) -> anyhow::Result<Self> {
+        let fields = name.strip_prefix(SETUP_ENV_PREFIX).unwrap_or(name);
+        Self::new(fields.split("__"), value).with_context(|| format!("Invalid variable {name}"))
+    }
+
+    fn new<'a>(path: impl Iterator<Item = &'a str>, value: &str) -> anyhow::Result<Self> {
+        let path: Vec<String> = path.map(str::to_string).collect();
+        if path.iter().any(String::is_empty) {
+            bail!("Empty setup field name");
+        }
+        let text = value.to_string();
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.into()));
+        Ok(Self { path, value, text })
+    }
+
+    /// Set the value in the JSON representation of a configuration.
+    ///
+    /// returns: the path of the set field, e.g. `setup.sensorPageLabel.fontSize`
+    fn apply(&self, config: &mut Value) -> String {
+        let mut value = config;
+        let mut name = "setup".to_string();
+        let mut path = Vec::with_capacity(self.path.len() + 1);
+        for field in &self.path {
+            value = object_field(value, &name);
+            path.push(name);
+            name = field_name(value, field);
+        }
+        path.push(name.clone());
+        let field_value = match self.path.len() == 1 && TEXT_FIELDS.contains(&name.as_str()) {
+            true => Value::String(self.text.clone()),
+            false => self.value.clone(),
+        };
+        if let Value::Object(fields) = value {
+            fields.insert(name, field_value);
+        }
+        path.join(".")
+    }
+}
+
+/// Parse a `setup.field=value` command line switch.
+impl FromStr for SetupOverride {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((path, value)) = s.split_once('=') else {
+            bail!("Expected setup.<field>=<value>");
+        };
+        let Some(fields) = path.trim().strip_prefix("setup.") else {
+            bail!("Only setup values can be overridden: setup.<field>=<value>");
+        };
+        Self::new(fields.split('.'), value)
+    }
+}
+
+/// Apply `setup` overrides to the JSON representation of a configuration, in the given order.
+pub fn apply_overrides(config: &mut Value, overrides: &[SetupOverride]) {
+    for setup in overrides {
+        let path = setup.apply(config);
+        info!("Overriding {path}");
+    }
+}
+
+/// Object field of a value, replacing other values with an empty object.
+fn object_field<'a>(value: &'a mut Value, name: &str) -> &'a mut Value {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    let field = value
+        .as_object_mut()
+        .expect("object value")
+        .entry(name)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !field.is_object() {
+        *field = Value::Object(Map::new());
+    }
+    field
+}
+
+/// Name of an existing field of an object, ignoring case and `_`, or the camelCase name.
+fn field_name(object: &Value, name: &str) -> String {
+    let normalize = |name: &str| name.replace('_', "").to_lowercase();
+    let existing = object
+        .as_object()
+        .and_then(|fields| fields.keys().find(|key| normalize(key) == normalize(name)));
+    if let Some(key) = existing {
+        return key.clone();
+    }
+    if let Some(field) = SNAKE_CASE_FIELDS
+        .iter()
+        .find(|field| normalize(field) == normalize(name))
+    {
+        return field.to_string();
+    }
+    if !name.contains('_') && name.chars().any(|c| c.is_lowercase()) {
+        return name.to_string();
+    }
+    let mut words = name.split('_').filter(|word| !word.is_empty());
+    let mut camel_case = words.next().unwrap_or_default().to_lowercase();
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel_case.extend(first.to_uppercase());
+            camel_case.push_str(&chars.as_str().to_lowercase());
+        }
+    }
+    camel_case
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_overrides() {
+        let set: SetupOverride = "setup.sensorPageLabel.fontSize=40".parse().unwrap();
+        assert_eq!(vec!["sensorPageLabel", "fontSize"], set.path);
+        assert_eq!(json!(40), set.value);
+        let set: SetupOverride = "setup.timePage=DATE_h_m_3".parse().unwrap();
+        assert_eq!(json!("DATE_h_m_3"), set.value);
+        let set = SetupOverride::from_env_var("ASTERCTL_SETUP__LOW_POWER__ENABLED", "true");
+        assert_eq!(vec!["LOW_POWER", "ENABLED"], set.unwrap().path);
+
+        assert!("refresh=1".parse::<SetupOverride>().is_err());
+        assert!("setup.refresh".parse::<SetupOverride>().is_err());
+        assert!(SetupOverride::from_env_var("ASTERCTL_SETUP__A____B", "1").is_err());
+    }
+
+    #[test]
+    fn apply_overrides_to_setup() {
+        let mut config = json!({
+            "setup": { "refresh": 1, "sensorPageTime": 5, "ha_url": "", "rotation": 0 },
+            "mianban": [1],
+        });
+        let overrides = [
+            SetupOverride::from_env_var("ASTERCTL_SETUP__REFRESH", "1.5").unwrap(),
+            SetupOverride::from_env_var("ASTERCTL_SETUP__SENSOR_PAGE_TIME", "8").unwrap(),
+            SetupOverride::from_env_var("ASTERCTL_SETUP__HA_URL", "http://ha:8123").unwrap(),
+            SetupOverride::from_env_var("ASTERCTL_SETUP__HA_TOKEN", "env:HA_TOKEN").unwrap(),
+            SetupOverride::from_env_var("ASTERCTL_SETUP__SENSOR_PAGE_LABEL__FONT_SIZE", "40")
+                .unwrap(),
+            "setup.rotation=90".parse().unwrap(),
+            "setup.sensorPageTime=10".parse().unwrap(),
+            "setup.time_page=1".parse().unwrap(),
+        ];
+
+        apply_overrides(&mut config, &overrides);
+
+        assert_eq!(
+            json!({
+                "setup": {
+                    "refresh": 1.5,
+                    "sensorPageTime": 10,
+                    "ha_url": "http://ha:8123",
+                    "rotation": 90,
+                    "ha_token": "env:HA_TOKEN",
+                    "sensorPageLabel": { "fontSize": 40 },
+                    "timePage": "1",
+                },
+                "mianban": [1],
+            }),
+            config
+        );
+    }
+
+    #[test]
+    fn numeric_text_values() {
+        let mut config = json!({ "setup": { "refresh": 1 } });
+        let overrides = [
+            SetupOverride::from_env_var("ASTERCTL_SETUP__HA_TOKEN", "123456").unwrap(),
+            "setup.ha_url=0123".parse().unwrap(),
+            "setup.refresh=2".parse().unwrap(),
+        ];
+
+        apply_overrides(&mut config, &overrides);
+
+        assert_eq!(
+            json!({ "setup": { "refresh": 2, "ha_token": "123456", "ha_url": "0123" } }),
+            config
+        );
+        let setup: crate::cfg::Setup = serde_json::from_value(config["setup"].clone()).unwrap();
+        assert_eq!(Some("123456"), setup.ha_token.as_deref());
+    }
+}
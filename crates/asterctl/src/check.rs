@@ -0,0 +1,615 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Configuration checks of `asterctl check`.
+//!
+//! The checks report problems which the sensor panel tolerates at runtime, e.g. missing fonts
+//! which are substituted by the next font of the font list or the built-in default font, missing
+//! images, elements outside the display area or sensor templates without matching sensors. Most findings come with
+//! a suggested fix.
+
+use crate::cfg::{MonitorConfig, Panel, Sensor, SummaryItem};
+use crate::font::{FontHandler, font_names};
+use crate::img::Size;
+use crate::sensors::SensorAliases;
+use regex::Regex;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+use std::{fmt, fs};
+
+/// Problem found in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Configuration element, e.g. `panel 'CPU'`.
+    pub location: String,
+    pub message: String,
+    /// Suggested fix of the problem.
+    pub fix: Option<String>,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Check the configuration and its custom panels.
+///
+/// # Arguments
+///
+/// * `cfg`: the configuration with the loaded custom panels.
+/// * `img_dir`: image directory of relative image paths, custom panel images are loaded from the
+///   `img` directory of the panel.
+/// * `font_dir`: font directory, custom panel fonts are loaded from the `fonts` directory of the
+///   panel.
+/// * `size`: display size, the width of the narrowest slot showing a panel is used for its
+///   elements.
+/// * `sensor_keys`: current sensor keys, e.g. of the system sensors. The keys of derived sensors,
+///   HTTP sources, energy sensors, the daily summary and sensor statistics are added from the
+///   configuration.
+pub fn check_config<'a>(
+    cfg: &MonitorConfig,
+    img_dir: &Path,
+    font_dir: &Path,
+    size: Size,
+    sensor_keys: impl IntoIterator<Item = &'a String>,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check_fonts(cfg, font_dir, &mut findings);
+
+    let sensor_keys = known_sensor_keys(cfg, sensor_keys);
+    for (idx, panel) in cfg.panels.iter().enumerate() {
+        let location = format!("panel '{}'", panel.friendly_name());
+        check_unused_fonts(panel, &location, &mut findings);
+        check_images(panel, &location, img_dir, &mut findings);
+        check_positions(panel, &location, panel_size(cfg, idx, size), &mut findings);
+        check_overlaps(panel, &location, &mut findings);
+        check_templates(panel, &location, &sensor_keys, &mut findings);
+    }
+    findings
+}
+
+/// Check a single custom panel, e.g. of a panel package: fonts, images and element positions.
+///
+/// The sensor templates aren't checked, the sensors of the target system are unknown.
+pub fn check_panel(panel: &Panel, font_dir: &Path, size: Size) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let location = format!("panel '{}'", panel.friendly_name());
+    let mut fonts = FontHandler::new(font_dir);
+    for sensor in &panel.sensor {
+        if let Some(font_family) = &sensor.font_family {
+            check_font_family(
+                &mut fonts,
+                &location,
+                FONT_FAMILY,
+                font_family,
+                &mut findings,
+            );
+        }
+    }
+    if let Some(font_fallback) = &panel.font_fallback {
+        check_font_family(
+            &mut fonts,
+            &location,
+            FONT_FALLBACK,
+            font_fallback,
+            &mut findings,
+        );
+    }
+    check_unused_fonts(panel, &location, &mut findings);
+    // image paths of a loaded custom panel are absolute
+    check_images(panel, &location, Path::new(""), &mut findings);
+    check_positions(panel, &location, size, &mut findings);
+    check_overlaps(panel, &location, &mut findings);
+    findings
+}
+
+/// Report fonts which can't be loaded and are substituted.
+fn check_fonts(cfg: &MonitorConfig, font_dir: &Path, findings: &mut Vec<Finding>) {
+    let mut fonts = FontHandler::new(font_dir);
+    for (location, field, font_family) in font_families(cfg) {
+        check_font_family(&mut fonts, &location, field, font_family, findings);
+    }
+}
+
+const FONT_FAMILY: &str = "fontFamily";
+const FONT_FALLBACK: &str = "fontFallback";
+
+/// Report the substituted fonts of a font family of the given configuration field.
+fn check_font_family(
+    fonts: &mut FontHandler,
+    location: &str,
+    field: &str,
+    font_family: &str,
+    findings: &mut Vec<Finding>,
+) {
+    for substitution in fonts.substitutions(font_family) {
+        let finding = Finding {
+            location: location.to_string(),
+            message: format!("Font {substitution}"),
+            fix: Some(format!(
+                "add {}.ttf to the font directory, or remove it from \"{field}\"",
+                substitution.font
+            )),
+        };
+        // same font in multiple elements of a panel
+        if !findings.contains(&finding) {
+            findings.push(finding);
+        }
+    }
+}
+
+/// Font families of all text elements, with their location and configuration field.
+fn font_families(cfg: &MonitorConfig) -> Vec<(String, &'static str, &str)> {
+    let mut families: Vec<(String, &'static str, &Option<String>)> = Vec::new();
+    if let Some(label) = &cfg.setup.sensor_page_label {
+        families.push((
+            "setup.sensorPageLabel".into(),
+            FONT_FAMILY,
+            &label.font_family,
+        ));
+    }
+    if let Some(ticker) = &cfg.setup.ticker {
+        families.push(("setup.ticker".into(), FONT_FAMILY, &ticker.font_family));
+    }
+    let slot_messages = cfg.slots.iter().flat_map(|slot| &slot.messages);
+    for message in cfg.messages.iter().chain(slot_messages) {
+        families.push((
+            format!("message '{}'", message.name()),
+            FONT_FAMILY,
+            &message.font_family,
+        ));
+    }
+    for panel in &cfg.panels {
+        let location = format!("panel '{}'", panel.friendly_name());
+        for sensor in &panel.sensor {
+            families.push((location.clone(), FONT_FAMILY, &sensor.font_family));
+        }
+        families.push((location, FONT_FALLBACK, &panel.font_fallback));
+    }
+
+    families
+        .into_iter()
+        .filter_map(|(location, field, font_family)| {
+            Some((location, field, font_family.as_deref()?))
+        })
+        .collect()
+}
+
+/// Report font files of a custom panel which aren't used by any element of the panel.
+fn check_unused_fonts(panel: &Panel, location: &str, findings: &mut Vec<Finding>) {
+    let Some(dir) = &panel.dir else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir.join("fonts")) else {
+        return;
+    };
+    let used: HashSet<String> = panel
+        .sensor
+        .iter()
+        .filter_map(|sensor| sensor.font_family.as_deref())
+        .chain(panel.font_fallback.as_deref())
+        .flat_map(font_names)
+        .filter_map(|name| Some(Path::new(name).file_name()?.to_string_lossy().to_string()))
+        .collect();
+
+    let mut unused: Vec<String> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ttf"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .filter(|name| !used.contains(name))
+        .collect();
+    unused.sort();
+    for name in unused {
+        findings.push(Finding {
+            location: location.to_string(),
+            message: format!("Font {name}.ttf is not used"),
+            fix: Some(format!("remove fonts/{name}.ttf from the panel directory")),
+        });
+    }
+}
+
+/// Report background and element images which can't be loaded. The element is rendered without
+/// the image, or not at all.
+///
+/// Image names with a `{value}` placeholder depend on the sensor value and aren't checked.
+fn check_images(panel: &Panel, location: &str, img_dir: &Path, findings: &mut Vec<Finding>) {
+    let mut images: Vec<(String, &str)> = Vec::new();
+    if let Some(img) = &panel.img {
+        images.push(("Background image".to_string(), img));
+    }
+    for sensor in &panel.sensor {
+        let pics = sensor
+            .pic
+            .iter()
+            .chain(sensor.icons.iter().map(|icon| &icon.pic));
+        for pic in pics.filter(|pic| !pic.contains("{value}")) {
+            images.push((format!("Image of element {}", element_name(sensor)), pic));
+        }
+    }
+
+    let mut checked = HashSet::new();
+    for (name, image) in images {
+        if !checked.insert(image) {
+            continue;
+        }
+        let path = img_dir.join(image);
+        let (message, fix) = if !path.is_file() {
+            let fix = closest_file(&path).map(|file| {
+                let image = Path::new(image).with_file_name(file);
+                format!("did you mean \"{}\"?", image.display())
+            });
+            (format!("{name} {image} not found"), fix)
+        } else if let Err(e) = image::image_dimensions(&path) {
+            (format!("{name} {image} can't be loaded: {e}"), None)
+        } else {
+            continue;
+        };
+        findings.push(Finding {
+            location: location.to_string(),
+            message,
+            fix,
+        });
+    }
+}
+
+/// File name in the directory of a missing file with the smallest edit distance, if it's similar
+/// enough to be a likely typo.
+fn closest_file(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy();
+    let max_distance = (name.chars().count() / 4).max(2);
+    fs::read_dir(path.parent()?)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .map(|file| (edit_distance(&name, &file), file))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, file)| file)
+}
+
+/// Size of the narrowest slot showing the panel with the given index, or the display size.
+fn panel_size(cfg: &MonitorConfig, idx: usize, size: Size) -> Size {
+    let panel = idx as u32 + 1;
+    let width = cfg
+        .slots
+        .iter()
+        .filter(|slot| slot.panels.contains(&panel))
+        .map(|slot| slot.width.min(size.0.saturating_sub(slot.x)))
+        .min()
+        .unwrap_or(size.0);
+    (width, size.1)
+}
+
+/// Report elements which are partially or completely outside of the display area.
+fn check_positions(panel: &Panel, location: &str, size: Size, findings: &mut Vec<Finding>) {
+    for sensor in panel.sensor.iter().filter(|sensor| sensor.page.is_none()) {
+        let (width, height) = (sensor.width.unwrap_or(0), sensor.height.unwrap_or(0));
+        let right = sensor.x + width.max(1) as i32;
+        let bottom = sensor.y + height.max(1) as i32;
+        if sensor.x >= 0 && sensor.y >= 0 && right <= size.0 as i32 && bottom <= size.1 as i32 {
+            continue;
+        }
+
+        let max_x = (size.0 as i32 - width.max(1) as i32).max(0);
+        let max_y = (size.1 as i32 - height.max(1) as i32).max(0);
+        let (x, y) = (sensor.x.clamp(0, max_x), sensor.y.clamp(0, max_y));
+        let visible =
+            sensor.x < size.0 as i32 && sensor.y < size.1 as i32 && right > 0 && bottom > 0;
+        let outside = if visible {
+            "partially outside"
+        } else {
+            "outside"
+        };
+        findings.push(Finding {
+            location: location.to_string(),
+            message: format!(
+                "Element {} at {},{} is {} the {}x{} display area",
+                element_name(sensor),
+                sensor.x,
+                sensor.y,
+                outside,
+                size.0,
+                size.1
+            ),
+            fix: Some(format!("move the element to \"x\": {x}, \"y\": {y}")),
+        });
+    }
+}
+
+/// Report overlapping elements of the same mode. Both elements need a `width` and `height`.
+///
+/// Elements with a `showIf` condition are ignored, they are usually shown alternatively.
+fn check_overlaps(panel: &Panel, location: &str, findings: &mut Vec<Finding>) {
+    let elements: Vec<(&Sensor, u32, u32)> = panel
+        .sensor
+        .iter()
+        .filter(|sensor| sensor.page.is_none() && sensor.show_if.is_none())
+        .filter_map(|sensor| Some((sensor, sensor.width?, sensor.height?)))
+        .collect();
+
+    for (idx, (a, a_width, a_height)) in elements.iter().enumerate() {
+        for (b, b_width, b_height) in &elements[idx + 1..] {
+            if a.mode != b.mode
+                || a.x >= b.x + *b_width as i32
+                || b.x >= a.x + *a_width as i32
+                || a.y >= b.y + *b_height as i32
+                || b.y >= a.y + *a_height as i32
+            {
+                continue;
+            }
+            findings.push(Finding {
+                location: location.to_string(),
+                message: format!(
+                    "Elements {} and {} overlap",
+                    element_name(a),
+                    element_name(b)
+                ),
+                fix: Some(format!(
+                    "move {} below the other element to \"y\": {}, or show only one of them with \"showIf\"",
+                    element_name(b),
+                    a.y + *a_height as i32
+                )),
+            });
+        }
+    }
+}
+
+/// Report sensor templates whose `match` pattern doesn't match any known sensor key.
+fn check_templates(
+    panel: &Panel,
+    location: &str,
+    sensor_keys: &BTreeSet<String>,
+    findings: &mut Vec<Finding>,
+) {
+    for pattern in panel
+        .sensor
+        .iter()
+        .filter_map(|sensor| sensor.match_pattern.as_deref())
+    {
+        // invalid patterns are reported when the configuration is loaded
+        let Ok(regex) = Regex::new(pattern) else {
+            continue;
+        };
+        if sensor_keys.iter().any(|key| regex.is_match(key)) {
+            continue;
+        }
+        let fix = closest_key(pattern, sensor_keys)
+            .map(|key| format!("did you mean \"match\": \"^{}$\"?", regex::escape(key)));
+        findings.push(Finding {
+            location: location.to_string(),
+            message: format!(
+                "Template \"{pattern}\" matches none of the {} known sensors",
+                sensor_keys.len()
+            ),
+            fix,
+        });
+    }
+}
+
+/// Sensor keys of the sources renamed with their alias, and the statically known keys of the
+/// configuration.
+fn known_sensor_keys<'a>(
+    cfg: &MonitorConfig,
+    sensor_keys: impl IntoIterator<Item = &'a String>,
+) -> BTreeSet<String> {
+    let filter = cfg.sensor_filter.source("sysinfo");
+    let aliases = SensorAliases::new(&cfg.sensor_aliases);
+    let mut keys: BTreeSet<String> = sensor_keys
+        .into_iter()
+        .filter(|key| !key.contains('#'))
+        .map(|key| aliases.resolve(key).into_owned())
+        .filter(|key| !filter.is_filtered(key))
+        .collect();
+    keys.extend(cfg.derived.keys().cloned());
+    for source in &cfg.http_sources {
+        keys.extend(source.values.keys().cloned());
+    }
+    if let Some(energy) = &cfg.energy {
+        keys.extend(["energy_power", "energy_today", "energy_month"].map(str::to_string));
+        if energy.rate.is_some() {
+            keys.extend(["energy_cost_today", "energy_cost_month"].map(str::to_string));
+        }
+    }
+    if let Some(summary) = &cfg.daily_summary {
+        keys.extend(summary.items.iter().map(SummaryItem::key));
+    }
+    if let Some(statistics) = &cfg.statistics {
+        let patterns: Vec<_> = statistics
+            .sensors
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .collect();
+        let tracked: Vec<_> = keys
+            .iter()
+            .filter(|key| patterns.iter().any(|pattern| pattern.is_match(key)))
+            .cloned()
+            .collect();
+        for key in tracked {
+            keys.extend(["min", "max", "avg"].map(|suffix| format!("{key}#{suffix}")));
+        }
+    }
+    keys
+}
+
+/// Sensor key with the smallest edit distance to a literal pattern like `^temperature_cpu$`, if
+/// it's similar enough to be a likely typo.
+fn closest_key<'a>(pattern: &str, sensor_keys: &'a BTreeSet<String>) -> Option<&'a String> {
+    let mut literal = String::new();
+    let mut chars = pattern
+        .trim_start_matches('^')
+        .trim_end_matches('$')
+        .chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => literal.push(chars.next()?),
+            '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' => {
+                return None;
+            }
+            c => literal.push(c),
+        }
+    }
+    let max_distance = (literal.chars().count() / 4).max(2);
+    sensor_keys
+        .iter()
+        .map(|key| (edit_distance(&literal, key), key))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, key)| key)
+}
+
+/// Levenshtein distance of two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Name of an element in findings: its label, name or match pattern.
+fn element_name(sensor: &Sensor) -> String {
+    let name = if !sensor.label.is_empty() {
+        &sensor.label
+    } else {
+        sensor
+            .match_pattern
+            .as_deref()
+            .or(sensor.name.as_deref())
+            .or(sensor.item_name.as_deref())
+            .unwrap_or_default()
+    };
+    format!("'{name}'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::MessagePage;
+
+    const SIZE: Size = (960, 376);
+
+    #[test]
+    fn report_missing_fonts() {
+        let mut cfg: MonitorConfig = serde_json::from_str(
+            r#"{ "setup": { "refresh": 1, "ticker": { "fontFamily": "DejaVuSans" } },
+              "mianban": [], "diy": [] }"#,
+        )
+        .unwrap();
+        cfg.messages.push(MessagePage {
+            name: Some("maintenance".into()),
+            font_family: Some("Missing, DejaVuSans".into()),
+            ..Default::default()
+        });
+        let panel: Panel = serde_json::from_str(
+            r#"{ "name": "CPU", "fontFallback": "NotoSansArabic, HarmonyOS_Sans_SC_Bold", "sensor": [
+                { "label": "cpu", "fontFamily": "Roboto", "mode": 1, "x": 0, "y": 0 },
+                { "label": "gpu", "fontFamily": "Roboto", "mode": 1, "x": 0, "y": 0 }
+            ] }"#,
+        )
+        .unwrap();
+        cfg.panels.push(panel);
+
+        let font_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fonts");
+        let findings: Vec<String> = check_config(&cfg, Path::new("img"), &font_dir, SIZE, &[])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            vec![
+                "message 'maintenance': Font Missing.ttf not found, using DejaVuSans",
+                "panel 'CPU': Font Roboto.ttf not found, using the built-in DejaVu Sans",
+                "panel 'CPU': Font NotoSansArabic.ttf not found, using HarmonyOS_Sans_SC_Bold",
+            ],
+            findings
+        );
+    }
+
+    #[test]
+    fn report_missing_images() {
+        let img_dir = std::env::temp_dir().join(format!("asterctl-check-{}", std::process::id()));
+        fs::create_dir_all(&img_dir).unwrap();
+        image::RgbImage::new(1, 1)
+            .save(img_dir.join("background.png"))
+            .unwrap();
+        fs::write(img_dir.join("broken.png"), "not an image").unwrap();
+        let mut cfg: MonitorConfig =
+            serde_json::from_str(r#"{ "setup": { "refresh": 1 }, "mianban": [], "diy": [] }"#)
+                .unwrap();
+        let panel: Panel = serde_json::from_str(
+            r#"{ "name": "Weather", "img": "backgrond.png", "sensor": [
+                { "label": "fan", "mode": 3, "x": 0, "y": 0, "pic": "broken.png" },
+                { "label": "weather", "mode": 6, "x": 0, "y": 0, "pic": "weather/{value}.png",
+                  "icons": [{ "value": "up", "pic": "background.png" }] }
+            ] }"#,
+        )
+        .unwrap();
+        cfg.panels.push(panel);
+
+        let findings = check_config(&cfg, &img_dir, Path::new("fonts"), SIZE, &[]);
+        fs::remove_dir_all(&img_dir).unwrap();
+        assert_eq!(2, findings.len());
+        assert_eq!(
+            "panel 'Weather': Background image backgrond.png not found",
+            findings[0].to_string()
+        );
+        assert_eq!(
+            Some("did you mean \"background.png\"?"),
+            findings[0].fix.as_deref()
+        );
+        assert!(
+            findings[1]
+                .message
+                .starts_with("Image of element 'fan' broken.png can't be loaded")
+        );
+    }
+
+    #[test]
+    fn lint_panel_elements() {
+        let cfg: MonitorConfig = serde_json::from_str(
+            r#"{ "setup": { "refresh": 1 }, "mianban": [1],
+              "slots": [{ "x": 0, "width": 480, "panels": [1] }],
+              "diy": [{ "name": "CPU", "sensor": [
+                { "label": "cpu_usage", "mode": 6, "x": 400, "y": 10, "width": 100, "height": 20 },
+                { "label": "cpu_freq", "mode": 6, "x": 300, "y": 20, "width": 150, "height": 20 },
+                { "label": "cpu_temp", "mode": 1, "x": 300, "y": 20, "width": 50, "height": 20 },
+                { "label": "gpu_temp", "mode": 1, "x": 20, "y": 400 },
+                { "match": "^temperature_cpuu$", "mode": 1, "x": 0, "y": 0, "page": "history" },
+                { "match": "^fan_", "mode": 1, "x": 0, "y": 0, "page": "history" }
+              ] }]
+            }"#,
+        )
+        .unwrap();
+        let keys = ["temperature_cpu".to_string(), "cpu_usage".to_string()];
+
+        let findings = check_config(&cfg, Path::new("img"), Path::new("fonts"), SIZE, &keys);
+        let messages: Vec<String> = findings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            vec![
+                "panel 'CPU': Element 'cpu_usage' at 400,10 is partially outside the 480x376 display area",
+                "panel 'CPU': Element 'gpu_temp' at 20,400 is outside the 480x376 display area",
+                "panel 'CPU': Elements 'cpu_usage' and 'cpu_freq' overlap",
+                "panel 'CPU': Template \"^temperature_cpuu$\" matches none of the 2 known sensors",
+                "panel 'CPU': Template \"^fan_\" matches none of the 2 known sensors",
+            ],
+            messages
+        );
+        assert_eq!(
+            Some("move the element to \"x\": 380, \"y\": 10"),
+            findings[0].fix.as_deref()
+        );
+        assert_eq!(
+            Some("did you mean \"match\": \"^temperature_cpu$\"?"),
+            findings[3].fix.as_deref()
+        );
+        assert_eq!(None, findings[4].fix);
+    }
+}
@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Remote control of the running sensor panel.
+//!
+//! Control interfaces like the HTTP control API run in their own threads and send commands with
+//! a [ControlHandle] to the display loop, which owns the display and the panel engine. The display
+//! loop handles the commands with the [ControlReceiver] and publishes its current state.
+
+use crate::cfg::MessagePage;
+use anyhow::anyhow;
+use chrono::{DateTime, Local};
+use image::RgbImage;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Maximum time to wait for the display loop to handle a command.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Commands for the display loop.
+#[derive(Debug, Clone)]
+pub enum ControlCommand {
+    /// Switch the display on.
+    On,
+    /// Switch the display off.
+    Off,
+    /// Switch to the next page.
+    NextPage,
+    /// Switch to the page matching the page selector: sensor key or display name of a sensor
+    /// page, or `time`.
+    ShowPage(String),
+    /// Show an image instead of the sensor pages, for the given duration or until the next page
+    /// command. Other image sizes than the display size are scaled.
+    ShowImage {
+        image: RgbImage,
+        duration: Option<Duration>,
+    },
+    /// Show a message page instead of the sensor pages, for the given duration or until the next
+    /// page command.
+    ShowMessage {
+        message: Box<MessagePage>,
+        duration: Option<Duration>,
+    },
+    /// Start a countdown timer page, replacing a running timer.
+    StartTimer {
+        duration: Duration,
+        label: Option<String>,
+    },
+    /// Cancel the running timer.
+    CancelTimer,
+    /// Set the display brightness in percent.
+    SetBrightness(u8),
+    /// Acknowledge the active alerts.
+    AcknowledgeAlert,
+    /// Reset the min/max/average statistics of a sensor, or of all tracked sensors.
+    ResetStatistics(Option<String>),
+    /// Load a custom panel directory, or reload it if it has already been loaded.
+    LoadPanel(PathBuf),
+    /// Unload a custom panel directory loaded at startup or with [ControlCommand::LoadPanel].
+    UnloadPanel(PathBuf),
+}
+
+/// Current state of the display loop.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayStatus {
+    /// Display is switched on.
+    pub on: bool,
+    /// Display brightness in percent.
+    pub brightness: u8,
+    /// Current page names of all panel slots.
+    pub pages: Vec<String>,
+    /// An image or message is shown instead of the sensor pages.
+    pub showing_image: bool,
+    /// Name of the alert shown instead of the sensor pages.
+    pub alert: Option<String>,
+    /// End time of the running countdown timer.
+    pub timer_end: Option<DateTime<Local>>,
+}
+
+struct ControlRequest {
+    command: ControlCommand,
+    reply: Sender<anyhow::Result<()>>,
+}
+
+/// Published display loop state with subscribers for state changes.
+#[derive(Default)]
+struct SharedStatus {
+    status: RwLock<DisplayStatus>,
+    subscribers: Mutex<Vec<Sender<DisplayStatus>>>,
+}
+
+/// Sender side of the control channel, used by control interfaces.
+#[derive(Clone)]
+pub struct ControlHandle {
+    sender: Sender<ControlRequest>,
+    status: Arc<SharedStatus>,
+}
+
+/// Receiver side of the control channel, used by the display loop.
+pub struct ControlReceiver {
+    receiver: Receiver<ControlRequest>,
+    status: Arc<SharedStatus>,
+}
+
+/// Duration of a shown image or message from a number of seconds of a control request.
+///
+/// returns: None for negative, infinite or NaN values and values above `u32::MAX` seconds, which
+/// would overflow the end time of the image.
+pub fn show_duration(seconds: f64) -> Option<Duration> {
+    (seconds.is_finite() && (0.0..=u32::MAX as f64).contains(&seconds))
+        .then(|| Duration::from_secs_f64(seconds))
+}
+
+/// Create a new control channel.
+pub fn control_channel() -> (ControlHandle, ControlReceiver) {
+    let (sender, receiver) = channel();
+    let status = Arc::new(SharedStatus::default());
+    (
+        ControlHandle {
+            sender,
+            status: status.clone(),
+        },
+        ControlReceiver { receiver, status },
+    )
+}
+
+impl ControlHandle {
+    /// Send a command to the display loop and wait until it has been handled.
+    pub fn send(&self, command: ControlCommand) -> anyhow::Result<()> {
+        let (reply, result) = channel();
+        self.sender
+            .send(ControlRequest { command, reply })
+            .map_err(|_| anyhow!("Display loop is not running"))?;
+        result
+            .recv_timeout(REPLY_TIMEOUT)
+            .map_err(|_| anyhow!("Display loop didn't handle the command in time"))?
+    }
+
+    /// Current state of the display loop.
+    pub fn status(&self) -> DisplayStatus {
+        self.status
+            .status
+            .read()
+            .expect("Poisoned status RwLock")
+            .clone()
+    }
+
+    /// Subscribe to state changes of the display loop.
+    ///
+    /// The receiver gets the new state after every change.
+    pub fn subscribe(&self) -> Receiver<DisplayStatus> {
+        let (sender, receiver) = channel();
+        self.status
+            .subscribers
+            .lock()
+            .expect("Poisoned subscriber Mutex")
+            .push(sender);
+        receiver
+    }
+}
+
+impl ControlReceiver {
+    /// Wait up to `timeout` for a command and handle it with the given handler.
+    ///
+    /// Returns true if a command was handled, false after the timeout.
+    pub fn handle_next(
+        &self,
+        timeout: Duration,
+        handler: impl FnOnce(ControlCommand) -> anyhow::Result<()>,
+    ) -> bool {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(request) => {
+                // the sender might have given up waiting
+                let _ = request.reply.send(handler(request.command));
+                true
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => {
+                // no control interfaces, the handle is dropped
+                std::thread::sleep(timeout);
+                false
+            }
+        }
+    }
+
+    /// Publish the current state of the display loop, and notify subscribers if it changed.
+    pub fn set_status(&self, status: DisplayStatus) {
+        {
+            let mut current = self.status.status.write().expect("Poisoned status RwLock");
+            if *current == status {
+                return;
+            }
+            current.clone_from(&status);
+        }
+        self.status
+            .subscribers
+            .lock()
+            .expect("Poisoned subscriber Mutex")
+            .retain(|subscriber| subscriber.send(status.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_command_to_display_loop() {
+        let (handle, receiver) = control_channel();
+
+        let display_loop = std::thread::spawn(move || {
+            let mut commands = Vec::new();
+            while commands.len() < 2 {
+                receiver.handle_next(Duration::from_secs(5), |command| {
+                    commands.push(format!("{command:?}"));
+                    match command {
+                        ControlCommand::ShowPage(_) => Err(anyhow!("unknown page")),
+                        _ => Ok(()),
+                    }
+                });
+            }
+            receiver.set_status(DisplayStatus {
+                on: true,
+                ..Default::default()
+            });
+            commands
+        });
+
+        let changes = handle.subscribe();
+        assert!(handle.send(ControlCommand::NextPage).is_ok());
+        assert!(handle.send(ControlCommand::ShowPage("foo".into())).is_err());
+        assert_eq!(
+            vec!["NextPage", "ShowPage(\"foo\")"],
+            display_loop.join().unwrap()
+        );
+        assert!(handle.status().on);
+        assert!(changes.recv().unwrap().on);
+    }
+
+    #[test]
+    fn validate_show_durations() {
+        assert_eq!(Some(Duration::from_millis(1500)), show_duration(1.5));
+        assert_eq!(Some(Duration::ZERO), show_duration(0.0));
+        assert_eq!(None, show_duration(f64::INFINITY));
+        assert_eq!(None, show_duration(f64::NAN));
+        assert_eq!(None, show_duration(1e19));
+        assert_eq!(None, show_duration(-1.0));
+    }
+}
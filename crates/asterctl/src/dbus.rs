@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! D-Bus interface of the running sensor panel.
+//!
+//! Exposes the `org.aoostar.asterctl` service on the session or system bus, so desktop users can
+//! bind hotkeys and power-management hooks to the display. Only available with the `dbus` feature.
+
+use crate::cfg::{DbusBus, MessagePage};
+use crate::control::{ControlCommand, ControlHandle, show_duration};
+use crate::img;
+use crate::timer::parse_duration;
+use log::{debug, info, warn};
+use zbus::blocking::connection;
+use zbus::fdo;
+use zbus::object_server::SignalEmitter;
+
+/// Well-known D-Bus service name.
+pub const SERVICE_NAME: &str = "org.aoostar.asterctl";
+/// Object path of the control interface.
+pub const OBJECT_PATH: &str = "/org/aoostar/asterctl";
+
+/// D-Bus control interface `org.aoostar.asterctl`.
+struct ControlInterface {
+    control: ControlHandle,
+}
+
+impl ControlInterface {
+    fn send(&self, command: ControlCommand) -> fdo::Result<()> {
+        self.control
+            .send(command)
+            .map_err(|e| fdo::Error::Failed(format!("{e:#}")))
+    }
+}
+
+fn invalid_duration(duration: f64) -> fdo::Error {
+    fdo::Error::InvalidArgs(format!("Invalid duration {duration}"))
+}
+
+#[zbus::interface(name = "org.aoostar.asterctl")]
+impl ControlInterface {
+    /// Switch the display on.
+    fn on(&self) -> fdo::Result<()> {
+        self.send(ControlCommand::On)
+    }
+
+    /// Switch the display off.
+    fn off(&self) -> fdo::Result<()> {
+        self.send(ControlCommand::Off)
+    }
+
+    /// Switch to the next page.
+    fn next_page(&self) -> fdo::Result<()> {
+        self.send(ControlCommand::NextPage)
+    }
+
+    /// Switch to the page matching the sensor key or display name.
+    fn show_page(&self, page: &str) -> fdo::Result<()> {
+        self.send(ControlCommand::ShowPage(page.to_string()))
+    }
+
+    /// Show an image file. The image is shown for `duration` seconds, or until the next page
+    /// command if `duration` is 0.
+    fn show_image(&self, path: &str, duration: f64) -> fdo::Result<()> {
+        let image = img::load_image(path, None)
+            .map_err(|e| fdo::Error::InvalidArgs(format!("Invalid image {path}: {e:#}")))?
+            .to_rgb8();
+        let duration = (duration != 0.0)
+            .then(|| show_duration(duration).ok_or_else(|| invalid_duration(duration)))
+            .transpose()?;
+        self.send(ControlCommand::ShowImage { image, duration })
+    }
+
+    /// Show a message page. The message is shown for `duration` seconds, or until the next page
+    /// command if `duration` is 0.
+    fn show_message(&self, text: &str, duration: f64) -> fdo::Result<()> {
+        let message = MessagePage {
+            text: text.to_string(),
+            ..Default::default()
+        };
+        let duration = (duration != 0.0)
+            .then(|| show_duration(duration).ok_or_else(|| invalid_duration(duration)))
+            .transpose()?;
+        self.send(ControlCommand::ShowMessage {
+            message: Box::new(message),
+            duration,
+        })
+    }
+
+    /// Start a countdown timer page, e.g. `25m` or `1h30m`, with an optional label.
+    fn start_timer(&self, duration: &str, label: &str) -> fdo::Result<()> {
+        let duration = parse_duration(duration).map_err(fdo::Error::InvalidArgs)?;
+        let label = (!label.is_empty()).then(|| label.to_string());
+        self.send(ControlCommand::StartTimer { duration, label })
+    }
+
+    /// Cancel the running timer.
+    fn cancel_timer(&self) -> fdo::Result<()> {
+        self.send(ControlCommand::CancelTimer)
+    }
+
+    /// Set the display brightness in percent.
+    fn set_brightness(&self, brightness: u8) -> fdo::Result<()> {
+        if brightness > 100 {
+            return Err(fdo::Error::InvalidArgs(
+                "Brightness must be 0 to 100".into(),
+            ));
+        }
+        self.send(ControlCommand::SetBrightness(brightness))
+    }
+
+    /// Acknowledge the active alerts, hiding their alert pages until triggered again.
+    fn acknowledge_alert(&self) -> fdo::Result<()> {
+        self.send(ControlCommand::AcknowledgeAlert)
+    }
+
+    /// Reset the min/max/average statistics of a sensor, or of all tracked sensors if empty.
+    fn reset_statistics(&self, sensor: &str) -> fdo::Result<()> {
+        let sensor = (!sensor.is_empty()).then(|| sensor.to_string());
+        self.send(ControlCommand::ResetStatistics(sensor))
+    }
+
+    /// Load or reload a custom panel directory.
+    fn load_panel(&self, path: &str) -> fdo::Result<()> {
+        self.send(ControlCommand::LoadPanel(path.into()))
+    }
+
+    /// Unload a custom panel directory.
+    fn unload_panel(&self, path: &str) -> fdo::Result<()> {
+        self.send(ControlCommand::UnloadPanel(path.into()))
+    }
+
+    /// Display is switched on.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn is_on(&self) -> bool {
+        self.control.status().on
+    }
+
+    /// Display brightness in percent.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn brightness(&self) -> u8 {
+        self.control.status().brightness
+    }
+
+    /// Current page names of all panel slots.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn pages(&self) -> Vec<String> {
+        self.control.status().pages
+    }
+
+    /// Name of the shown alert, empty if no alert is shown.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn alert(&self) -> String {
+        self.control.status().alert.unwrap_or_default()
+    }
+
+    /// The active page of a panel slot changed.
+    #[zbus(signal)]
+    async fn page_changed(emitter: &SignalEmitter<'_>, pages: Vec<String>) -> zbus::Result<()>;
+}
+
+/// Start the D-Bus service.
+///
+/// # Arguments
+///
+/// * `bus`: session or system bus. The system bus requires a D-Bus policy allowing to own the
+///   service name.
+/// * `control`: control channel of the display loop.
+pub fn start_dbus_service(bus: DbusBus, control: ControlHandle) -> anyhow::Result<()> {
+    let builder = match bus {
+        DbusBus::Session => connection::Builder::session()?,
+        DbusBus::System => connection::Builder::system()?,
+    };
+    let changes = control.subscribe();
+    let connection = builder
+        .name(SERVICE_NAME)?
+        .serve_at(OBJECT_PATH, ControlInterface { control })?
+        .build()?;
+
+    info!("Started D-Bus service {SERVICE_NAME} on the {bus:?} bus");
+
+    std::thread::Builder::new()
+        .name("dbus".into())
+        .spawn(move || {
+            let iface = match connection
+                .object_server()
+                .interface::<_, ControlInterface>(OBJECT_PATH)
+            {
+                Ok(iface) => iface,
+                Err(e) => {
+                    warn!("D-Bus interface not available: {e}");
+                    return;
+                }
+            };
+
+            let mut pages = Vec::new();
+            for status in changes {
+                if status.pages == pages {
+                    continue;
+                }
+                pages = status.pages;
+                debug!("D-Bus PageChanged: {pages:?}");
+                let signal = ControlInterface::page_changed(iface.signal_emitter(), pages.clone());
+                if let Err(e) = zbus::block_on(signal) {
+                    warn!("Failed to emit D-Bus PageChanged signal: {e}");
+                }
+            }
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,418 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Built-in seven-segment and dot-matrix digits.
+//!
+//! Big clocks and sensor values can be drawn in the style of LED and LCD displays without hunting
+//! for a suitable TTF font. All characters have a fixed width, except the narrow `.` and `:` of
+//! seven-segment digits, so that changing values don't shift the text.
+
+use crate::cfg::{DigitFont, DigitStyle};
+use image::{Rgba, RgbaImage, imageops};
+use imageproc::drawing::{draw_filled_circle_mut, draw_filled_rect_mut, draw_polygon_mut};
+use imageproc::point::Point;
+use imageproc::rect::Rect;
+
+/// Width of a seven-segment digit relative to its height.
+const SEGMENT_WIDTH: f32 = 0.55;
+/// Space between two characters relative to the digit height.
+const SPACING: f32 = 0.12;
+/// Rows of a dot-matrix character.
+const DOT_ROWS: usize = 7;
+/// Columns of a dot-matrix character.
+const DOT_COLUMNS: usize = 5;
+
+/// Seven-segment bits: `a` top, `b` upper right, `c` lower right, `d` bottom, `e` lower left,
+/// `f` upper left, `g` middle.
+fn segments(c: char) -> Option<u8> {
+    Some(match c {
+        '0' | 'O' => 0x3f,
+        '1' => 0x06,
+        '2' => 0x5b,
+        '3' => 0x4f,
+        '4' => 0x66,
+        '5' | 'S' | 's' => 0x6d,
+        '6' => 0x7d,
+        '7' => 0x07,
+        '8' => 0x7f,
+        '9' => 0x6f,
+        '-' => 0x40,
+        '_' => 0x08,
+        '°' => 0x63,
+        'A' | 'a' => 0x77,
+        'B' | 'b' => 0x7c,
+        'C' => 0x39,
+        'c' => 0x58,
+        'D' | 'd' => 0x5e,
+        'E' | 'e' => 0x79,
+        'F' | 'f' => 0x71,
+        'G' | 'g' => 0x3d,
+        'H' => 0x76,
+        'h' => 0x74,
+        'I' | 'i' => 0x30,
+        'J' | 'j' => 0x1e,
+        'L' | 'l' => 0x38,
+        'N' | 'n' => 0x54,
+        'o' => 0x5c,
+        'P' | 'p' => 0x73,
+        'R' | 'r' => 0x50,
+        'T' | 't' => 0x78,
+        'U' => 0x3e,
+        'u' => 0x1c,
+        'Y' | 'y' => 0x6e,
+        _ => return None,
+    })
+}
+
+/// Rows of a 5×7 dot-matrix character, the highest bit of the 5 columns is the left dot.
+fn dots(c: char) -> Option<[u8; DOT_ROWS]> {
+    Some(match c.to_ascii_uppercase() {
+        '0' => [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        '1' => [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        '2' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        '3' => [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+        '4' => [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        '5' => [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        '6' => [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        '7' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        '9' => [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+        'A' => [0x0e, 0x11, 0x11, 0x11, 0x1f, 0x11, 0x11],
+        'B' => [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+        'C' => [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+        'D' => [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c],
+        'E' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+        'F' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10],
+        'G' => [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f],
+        'H' => [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'I' => [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+        'M' => [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'P' => [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10],
+        'Q' => [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d],
+        'R' => [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+        'S' => [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+        'T' => [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+        'X' => [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04],
+        'Z' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f],
+        ' ' => [0; DOT_ROWS],
+        '-' => [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08],
+        ':' => [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '°' => [0x0c, 0x12, 0x12, 0x0c, 0x00, 0x00, 0x00],
+        _ => return None,
+    })
+}
+
+/// A filled shape of a character.
+enum Shape {
+    Polygon(Vec<Point<i32>>),
+    Rect(Rect),
+    Dot { x: i32, y: i32, radius: i32 },
+}
+
+impl Shape {
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Shape::Rect(Rect::at(x.round() as i32, y.round() as i32).of_size(
+            width.round().max(1.0) as u32,
+            height.round().max(1.0) as u32,
+        ))
+    }
+
+    fn draw(&self, image: &mut RgbaImage, dx: i32, dy: i32, color: Rgba<u8>) {
+        match self {
+            Shape::Polygon(points) => {
+                let points: Vec<_> = points
+                    .iter()
+                    .map(|p| Point::new(p.x + dx, p.y + dy))
+                    .collect();
+                draw_polygon_mut(image, &points, color);
+            }
+            Shape::Rect(rect) => {
+                let moved = Rect::at(rect.left() + dx, rect.top() + dy);
+                draw_filled_rect_mut(image, moved.of_size(rect.width(), rect.height()), color);
+            }
+            Shape::Dot { x, y, radius } => {
+                draw_filled_circle_mut(image, (x + dx, y + dy), *radius, color);
+            }
+        }
+    }
+}
+
+/// Character shapes of a digit style and height.
+struct DigitLayout {
+    font: DigitFont,
+    height: f32,
+    thickness: f32,
+}
+
+impl DigitLayout {
+    fn new(style: &DigitStyle, height: u32) -> Self {
+        let height = height.max(DOT_ROWS as u32) as f32;
+        let default = match style.font {
+            DigitFont::SevenSegment => height / 8.0,
+            DigitFont::DotMatrix => height / DOT_ROWS as f32 * 0.8,
+        };
+        Self {
+            font: style.font,
+            height,
+            thickness: style.thickness.unwrap_or(default).clamp(2.0, height / 3.0),
+        }
+    }
+
+    /// Space between characters.
+    fn spacing(&self) -> f32 {
+        (self.height * SPACING).round()
+    }
+
+    /// Width of a character without spacing.
+    fn char_width(&self, c: char) -> f32 {
+        match self.font {
+            DigitFont::SevenSegment if matches!(c, '.' | ':' | ',') => self.thickness.round(),
+            DigitFont::SevenSegment => (self.height * SEGMENT_WIDTH)
+                .max(self.thickness * 3.0)
+                .round(),
+            DigitFont::DotMatrix => (self.height / DOT_ROWS as f32 * DOT_COLUMNS as f32).round(),
+        }
+    }
+
+    fn text_width(&self, text: &str) -> u32 {
+        let count = text.chars().count();
+        let chars: f32 = text.chars().map(|c| self.char_width(c)).sum();
+        (chars + self.spacing() * count.saturating_sub(1) as f32) as u32
+    }
+
+    /// Lit and unlit shapes of a character, relative to its top left corner.
+    fn shapes(&self, c: char) -> (Vec<Shape>, Vec<Shape>) {
+        match self.font {
+            DigitFont::SevenSegment => self.segment_shapes(c),
+            DigitFont::DotMatrix => self.dot_shapes(c),
+        }
+    }
+
+    fn segment_shapes(&self, c: char) -> (Vec<Shape>, Vec<Shape>) {
+        let (h, t) = (self.height, self.thickness);
+        match c {
+            '.' | ',' => return (vec![Shape::rect(0.0, h - t, t, t)], Vec::new()),
+            ':' => {
+                let dots = [h * 0.3, h * 0.7].map(|y| Shape::rect(0.0, y - t / 2.0, t, t));
+                return (dots.into(), Vec::new());
+            }
+            _ => {}
+        }
+
+        let w = self.char_width(c);
+        let gap = (t / 8.0).max(1.0);
+        let (left, right) = (t / 2.0, w - t / 2.0);
+        let (top, middle, bottom) = (t / 2.0, h / 2.0, h - t / 2.0);
+        // pointed segments, meeting at the corners of the digit
+        let horizontal = |y: f32| segment((left + gap, y), (right - gap, y), t);
+        let vertical = |x: f32, y0: f32, y1: f32| segment((x, y0 + gap), (x, y1 - gap), t);
+        let shapes = [
+            horizontal(top),
+            vertical(right, top, middle),
+            vertical(right, middle, bottom),
+            horizontal(bottom),
+            vertical(left, middle, bottom),
+            vertical(left, top, middle),
+            horizontal(middle),
+        ];
+
+        let bits = segments(c).unwrap_or_default();
+        let (mut lit, mut unlit) = (Vec::new(), Vec::new());
+        for (i, shape) in shapes.into_iter().enumerate() {
+            if bits & (1 << i) != 0 {
+                lit.push(shape);
+            } else {
+                unlit.push(shape);
+            }
+        }
+        (lit, unlit)
+    }
+
+    fn dot_shapes(&self, c: char) -> (Vec<Shape>, Vec<Shape>) {
+        let pitch = self.height / DOT_ROWS as f32;
+        let size = self.thickness.min(pitch);
+        let rows = dots(c).unwrap_or_default();
+        let (mut lit, mut unlit) = (Vec::new(), Vec::new());
+
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..DOT_COLUMNS {
+                let x = (column as f32 + 0.5) * pitch;
+                let y = (row as f32 + 0.5) * pitch;
+                let shape = if size >= 4.0 {
+                    Shape::Dot {
+                        x: x.round() as i32,
+                        y: y.round() as i32,
+                        radius: (size / 2.0).round() as i32,
+                    }
+                } else {
+                    Shape::rect(x - size / 2.0, y - size / 2.0, size, size)
+                };
+                if bits & (1 << (DOT_COLUMNS - 1 - column)) != 0 {
+                    lit.push(shape);
+                } else {
+                    unlit.push(shape);
+                }
+            }
+        }
+        (lit, unlit)
+    }
+}
+
+/// A segment of the given thickness from `start` to `end`, with pointed ends.
+fn segment(start: (f32, f32), end: (f32, f32), thickness: f32) -> Shape {
+    let half = thickness / 2.0;
+    let length = (end.0 - start.0).abs() + (end.1 - start.1).abs();
+    if length <= thickness {
+        let (x, y) = (start.0.min(end.0) - half, start.1.min(end.1) - half);
+        return Shape::rect(
+            x,
+            y,
+            end.0 - start.0 + thickness,
+            end.1 - start.1 + thickness,
+        );
+    }
+
+    // unit vector along and across the horizontal or vertical segment
+    let (ux, uy) = ((end.0 - start.0) / length, (end.1 - start.1) / length);
+    let (nx, ny) = (-uy * half, ux * half);
+    let point = |x: f32, y: f32| Point::new(x.round() as i32, y.round() as i32);
+    Shape::Polygon(vec![
+        point(start.0, start.1),
+        point(start.0 + ux * half + nx, start.1 + uy * half + ny),
+        point(end.0 - ux * half + nx, end.1 - uy * half + ny),
+        point(end.0, end.1),
+        point(end.0 - ux * half - nx, end.1 - uy * half - ny),
+        point(start.0 + ux * half - nx, start.1 + uy * half - ny),
+    ])
+}
+
+/// Width of the text drawn with [draw_digits] in pixels.
+pub fn text_width(style: &DigitStyle, text: &str, height: u32) -> u32 {
+    DigitLayout::new(style, height).text_width(text)
+}
+
+/// Draw the text with built-in digits of the given height, starting at the top left corner `x`,
+/// `y`.
+///
+/// Unsupported characters are drawn as an unlit digit.
+pub fn draw_digits(
+    image: &mut RgbaImage,
+    style: &DigitStyle,
+    text: &str,
+    x: i32,
+    y: i32,
+    height: u32,
+    color: Rgba<u8>,
+) {
+    let layout = DigitLayout::new(style, height);
+    let off_color = style.off_color.map(Rgba::from);
+    let mut chars = Vec::new();
+    let mut offset = 0f32;
+    for c in text.chars() {
+        chars.push((offset.round() as i32, layout.shapes(c)));
+        offset += layout.char_width(c) + layout.spacing();
+    }
+
+    if let Some(glow_color) = style.glow_color.map(Rgba::from) {
+        // blurred copy of the lit shapes, the color is kept in transparent pixels for the blur
+        let margin = (layout.thickness * 1.5).ceil() as i32;
+        let Rgba([r, g, b, _]) = glow_color;
+        let mut glow = RgbaImage::from_pixel(
+            layout.text_width(text) + 2 * margin as u32,
+            layout.height as u32 + 2 * margin as u32,
+            Rgba([r, g, b, 0]),
+        );
+        for (dx, (lit, _)) in &chars {
+            for shape in lit {
+                shape.draw(&mut glow, dx + margin, margin, glow_color);
+            }
+        }
+        let glow = imageops::blur(&glow, margin as f32 / 2.0);
+        imageops::overlay(image, &glow, (x - margin) as i64, (y - margin) as i64);
+    }
+
+    for (dx, (lit, unlit)) in &chars {
+        if let Some(off_color) = off_color {
+            for shape in unlit {
+                shape.draw(image, x + dx, y, off_color);
+            }
+        }
+        for shape in lit {
+            shape.draw(image, x + dx, y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    fn style(font: DigitFont) -> DigitStyle {
+        DigitStyle {
+            font,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn digits_have_fixed_width() {
+        let style = style(DigitFont::SevenSegment);
+        assert_eq!(text_width(&style, "11", 80), text_width(&style, "88", 80));
+        assert!(text_width(&style, "1.1", 80) < text_width(&style, "111", 80));
+
+        let style = self::style(DigitFont::DotMatrix);
+        assert_eq!(text_width(&style, "1:1", 70), text_width(&style, "888", 70));
+    }
+
+    #[test]
+    fn draw_seven_segment_digits() {
+        let mut style = style(DigitFont::SevenSegment);
+        style.off_color = Some("#202020".try_into().unwrap());
+        let mut image = RgbaImage::new(120, 100);
+        draw_digits(&mut image, &style, "-1", 10, 10, 80, WHITE);
+
+        // middle segment of the minus and the right segments of the one
+        let layout = DigitLayout::new(&style, 80);
+        let width = layout.char_width('1') as u32;
+        let next = 10 + width + layout.spacing() as u32;
+        assert_eq!(&WHITE, image.get_pixel(10 + width / 2, 50));
+        assert_eq!(&WHITE, image.get_pixel(next + width - 5, 30));
+        // unlit top segment of the one
+        assert_eq!(
+            &Rgba([32, 32, 32, 255]),
+            image.get_pixel(next + width / 2, 15)
+        );
+        assert_eq!(&Rgba([0, 0, 0, 0]), image.get_pixel(5, 5));
+    }
+
+    #[test]
+    fn draw_dot_matrix_with_glow() {
+        let mut style = style(DigitFont::DotMatrix);
+        style.glow_color = Some("#ff0000".try_into().unwrap());
+        let mut image = RgbaImage::new(60, 90);
+        draw_digits(&mut image, &style, "1", 10, 10, 70, WHITE);
+
+        // center dot of the vertical line of the one
+        assert_eq!(&WHITE, image.get_pixel(10 + 25, 10 + 35));
+        // glow next to the lit dot, outside of the dot matrix
+        let glow = image.get_pixel(10 + 25, 8);
+        assert!(glow[0] == 255 && glow[1] == 0 && glow[3] > 0, "{glow:?}");
+        assert_ne!(&WHITE, image.get_pixel(10 + 5, 10 + 5));
+    }
+}
@@ -0,0 +1,888 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Panel engine: renders the complete display image from a monitor configuration.
+//!
+//! The engine combines the page rotations of all panel slots, the ticker overlay and the sensor
+//! history into a final display image. It is independent of the display device, and can be used
+//! for previews, GUIs and tests as well.
+
+use crate::alerts::Alerts;
+use crate::cfg::{FontColor, MessagePage, MonitorConfig, PageLayout, Setup};
+use crate::history::SensorHistory;
+use crate::img::{ColorLut, Size};
+use crate::pages::{PageKind, PageRotation};
+use crate::render::{ImageProcessingError, PanelRenderer};
+use crate::sensors::{DERIVED_SOURCE, ENERGY_SOURCE, SensorStore};
+use crate::ticker::Ticker;
+use crate::timer::Timer;
+use crate::transition::Transition;
+use chrono::{DateTime, Local};
+use image::{DynamicImage, Rgb, RgbImage, RgbaImage, imageops};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// A display region with its own page rotation and renderer.
+struct Slot {
+    x: u32,
+    width: u32,
+    rotation: PageRotation,
+    renderer: PanelRenderer,
+    /// Name of the last rendered page, to detect page changes for transitions.
+    last_page: Option<String>,
+}
+
+/// Renders the display image of a monitor configuration.
+pub struct PanelEngine {
+    size: Size,
+    setup: Setup,
+    slots: Vec<Slot>,
+    /// Font and configuration directory of the renderers, to recreate the slots.
+    font_dir: PathBuf,
+    config_dir: PathBuf,
+    /// full-size renderer for overlays on top of the panel slots
+    overlay: PanelRenderer,
+    ticker: Option<Ticker>,
+    history: Arc<RwLock<SensorHistory>>,
+    alerts: Alerts,
+    /// Countdown timer shown instead of the panel slots.
+    timer: Option<Timer>,
+    /// Flashing alert pages show the alert color in every other refresh.
+    alert_highlight: bool,
+    /// Time of the last sensor update while the sensor watchdog page is shown.
+    stalled_since: Option<DateTime<Local>>,
+    /// Directory of the saved page images for debugging.
+    img_save_path: Option<PathBuf>,
+    brightness: u8,
+    animations: bool,
+    /// Last rendered image of the panel slots, the start of a page transition.
+    last_image: Option<RgbaImage>,
+    /// Page transition to show before the last rendered image.
+    transition: Option<Transition>,
+    /// Color calibration of the display.
+    color_lut: Option<ColorLut>,
+}
+
+impl PanelEngine {
+    /// Create a new panel engine.
+    ///
+    /// # Arguments
+    ///
+    /// * `cfg`: the monitor configuration.
+    /// * `size`: display size.
+    /// * `font_dir`: font directory to load TTF fonts specified in the configuration.
+    /// * `config_dir`: configuration directory to load background and sensor images from.
+    pub fn new(
+        cfg: &MonitorConfig,
+        size: Size,
+        font_dir: impl AsRef<Path>,
+        config_dir: impl AsRef<Path>,
+    ) -> Self {
+        let font_dir = font_dir.as_ref();
+        let config_dir = config_dir.as_ref();
+
+        let mut engine = Self {
+            size,
+            setup: cfg.setup.clone(),
+            slots: create_slots(cfg, size, font_dir, config_dir),
+            font_dir: font_dir.to_path_buf(),
+            config_dir: config_dir.to_path_buf(),
+            overlay: PanelRenderer::new(size, font_dir, config_dir),
+            ticker: cfg.setup.ticker.as_ref().and_then(Ticker::new),
+            history: Arc::new(RwLock::new(SensorHistory::default())),
+            alerts: Alerts::new(&cfg.alerts),
+            timer: None,
+            alert_highlight: false,
+            stalled_since: None,
+            img_save_path: None,
+            brightness: 100,
+            animations: true,
+            last_image: None,
+            transition: None,
+            color_lut: cfg.setup.color_calibration.as_ref().and_then(ColorLut::new),
+        };
+        if let Some(value_format) = &cfg.setup.value_format {
+            engine.overlay.set_value_format(value_format.clone());
+        }
+        engine.setup_slots();
+        engine
+    }
+
+    /// For debugging: save the rendered page images as .PNG graphics in the given directory.
+    pub fn set_img_save_path(&mut self, img_save_path: impl Into<PathBuf>) {
+        self.img_save_path = Some(img_save_path.into());
+        self.setup_slots();
+    }
+
+    /// Recreate the panel slots and page rotations after the panels of the configuration changed,
+    /// e.g. after loading a custom panel at runtime.
+    ///
+    /// The page lists must be rebuilt with [PanelEngine::rebuild] afterwards. Brightness, timer,
+    /// alerts and the sensor history are kept.
+    pub fn reload_panels(&mut self, cfg: &MonitorConfig) {
+        self.slots = create_slots(cfg, self.size, &self.font_dir, &self.config_dir);
+        self.last_image = None;
+        self.transition = None;
+        self.setup_slots();
+    }
+
+    /// Apply the engine settings to the renderers of all slots.
+    fn setup_slots(&mut self) {
+        for slot in self.slots.iter_mut() {
+            if let Some(value_format) = &self.setup.value_format {
+                slot.renderer.set_value_format(value_format.clone());
+            }
+            if let Some(stale_sensors) = &self.setup.stale_sensors {
+                slot.renderer.set_stale_sensors(stale_sensors.clone());
+            }
+            slot.renderer.set_animations(self.animations);
+            if let Some(img_save_path) = &self.img_save_path {
+                slot.renderer.set_img_save_path(img_save_path);
+                slot.renderer.set_save_render_img(true);
+            }
+        }
+    }
+
+    /// Shared sensor history used for history pages.
+    ///
+    /// The history is not recorded by the engine, see [crate::history::start_history_recorder].
+    pub fn history(&self) -> Arc<RwLock<SensorHistory>> {
+        self.history.clone()
+    }
+
+    /// Number of panel slots.
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Number of compiled sensor templates in all slots.
+    pub fn template_count(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|slot| slot.rotation.template_count())
+            .sum()
+    }
+
+    /// Number of pages in the current rotation cycle of all slots.
+    pub fn page_count(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|slot| slot.rotation.page_count())
+            .sum()
+    }
+
+    /// Number of pages in the current rotation cycle of the slot with the most pages.
+    pub fn max_slot_page_count(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|slot| slot.rotation.page_count())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Names of the current pages of all slots, see [PageKind::name].
+    pub fn current_pages(&self) -> Vec<String> {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.rotation.current())
+            .map(|page| page.name().to_string())
+            .collect()
+    }
+
+    /// Switch all slots to their next page immediately.
+    pub fn next_page(&mut self) {
+        for slot in self.slots.iter_mut() {
+            slot.rotation.next_page();
+        }
+    }
+
+    /// Switch all slots containing a page matching the page selector to this page.
+    ///
+    /// Returns false if no slot contains a matching page, see [PageKind::matches].
+    pub fn show_page(&mut self, selector: &str) -> bool {
+        let mut found = false;
+        for slot in self.slots.iter_mut() {
+            found |= slot.rotation.show_page(selector);
+        }
+        found
+    }
+
+    /// Name of the shown alert, if an alert interrupts the page rotation.
+    pub fn current_alert(&self) -> Option<String> {
+        self.alerts.current().map(|alert| alert.name().to_string())
+    }
+
+    /// Time of the last sensor update if the sensor watchdog page is shown because the sensor
+    /// values are no longer updated.
+    pub fn sensors_stalled_since(&self) -> Option<DateTime<Local>> {
+        self.stalled_since
+    }
+
+    /// Acknowledge all active alerts, hiding their alert pages until they are triggered again.
+    ///
+    /// Returns false if there is no active alert.
+    pub fn acknowledge_alerts(&mut self) -> bool {
+        self.alerts.acknowledge()
+    }
+
+    /// Start a countdown timer page, replacing a running timer.
+    ///
+    /// The timer page is shown instead of the panel slots until the timer is over or cancelled.
+    /// Alert pages take priority over the timer page.
+    pub fn start_timer(&mut self, duration: Duration, label: Option<String>) {
+        self.timer = Some(Timer::new(duration, label));
+    }
+
+    /// Cancel the running timer.
+    ///
+    /// Returns false if there is no running timer.
+    pub fn cancel_timer(&mut self) -> bool {
+        self.timer.take().is_some()
+    }
+
+    /// End time of the running timer.
+    pub fn timer_end(&self) -> Option<DateTime<Local>> {
+        self.timer.as_ref().map(Timer::end_time)
+    }
+
+    /// Display brightness in percent.
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Set the display brightness in percent, limited to 100.
+    ///
+    /// The display has no backlight control, the rendered image is dimmed instead.
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness.min(100);
+    }
+
+    /// Enable or disable animations like scrolling text and flashing alert pages, e.g. to save
+    /// power. Enabled by default.
+    pub fn set_animations(&mut self, enabled: bool) {
+        self.animations = enabled;
+        self.overlay.set_animations(enabled);
+        for slot in self.slots.iter_mut() {
+            slot.renderer.set_animations(enabled);
+        }
+    }
+
+    /// Take the transition from the previous to the last rendered page, if the page of a slot
+    /// changed and a page transition is configured.
+    ///
+    /// The transition frames should be shown before the image returned by [PanelEngine::render].
+    /// No transitions are created while animations are disabled.
+    pub fn take_transition(&mut self) -> Option<Transition> {
+        self.transition.take()
+    }
+
+    /// Rebuild the page lists of all slots from the available sensor keys.
+    pub fn rebuild(&mut self, values: &SensorStore) {
+        for slot in self.slots.iter_mut() {
+            slot.rotation.rebuild(values);
+        }
+    }
+
+    /// Render the current pages of all slots including the ticker overlay, and advance the page
+    /// rotations.
+    ///
+    /// The sensor watchdog page, an active alert or the timer interrupt the page rotations and are
+    /// rendered instead.
+    ///
+    /// Must be called once per display refresh.
+    pub fn render(&mut self, values: &SensorStore) -> Result<RgbaImage, ImageProcessingError> {
+        let now = Instant::now();
+        self.update_watchdog(values, Local::now());
+        self.alerts.update(values, now);
+        if self
+            .timer
+            .as_ref()
+            .is_some_and(|timer| timer.is_expired(now))
+        {
+            self.timer = None;
+        }
+        self.transition = None;
+        let mut image = if let Some(since) = self.stalled_since {
+            self.last_image = None;
+            self.overlay
+                .render_message_page(&watchdog_page(since), values)?
+        } else if let Some(alert) = self.alerts.current() {
+            self.last_image = None;
+            self.alert_highlight = !self.alert_highlight || !alert.flash || !self.animations;
+            self.overlay
+                .render_alert_page(alert, values, self.alert_highlight)?
+        } else if let Some(timer) = &self.timer {
+            self.last_image = None;
+            self.overlay.render_timer_page(
+                timer.label(),
+                timer.remaining(now),
+                timer.progress(now),
+                self.setup.time_page_font_size,
+            )?
+        } else {
+            let (mut image, changed) = self.render_slots(values)?;
+            if let (Some(ticker), Some(ticker_cfg)) = (&self.ticker, &self.setup.ticker) {
+                self.overlay
+                    .render_ticker(&mut image, ticker_cfg, &ticker.current_text(values));
+            }
+            self.adjust_colors(&mut image);
+            self.update_transition(&image, changed);
+            return Ok(image);
+        };
+        self.adjust_colors(&mut image);
+        Ok(image)
+    }
+
+    /// Check if the sensor values were updated within the timeout of the sensor watchdog.
+    ///
+    /// Derived and energy sensors are ignored, they are recomputed from the stalled values.
+    fn update_watchdog(&mut self, values: &SensorStore, now: DateTime<Local>) {
+        let Some(watchdog) = &self.setup.sensor_watchdog else {
+            return;
+        };
+        let last_update = values
+            .iter()
+            .filter(|(_, sensor)| sensor.source != DERIVED_SOURCE && sensor.source != ENERGY_SOURCE)
+            .map(|(_, sensor)| sensor.updated_at)
+            .max();
+        let stalled_since = last_update
+            .filter(|last_update| (now - *last_update).as_seconds_f32() > watchdog.timeout);
+
+        match (self.stalled_since, stalled_since) {
+            (None, Some(since)) => warn!("No sensor update since {since}, showing watchdog page"),
+            (Some(_), None) => info!("Sensor updates resumed"),
+            _ => {}
+        }
+        self.stalled_since = stalled_since;
+    }
+
+    /// Apply the brightness and the color calibration to a rendered image.
+    fn adjust_colors(&self, image: &mut RgbaImage) {
+        if self.brightness < 100 {
+            dim_image(image, self.brightness);
+        }
+        if let Some(lut) = &self.color_lut {
+            lut.apply(image);
+        }
+    }
+
+    /// Apply the color calibration to an image shown instead of the rendered pages.
+    pub fn calibrate_image(&self, image: &mut RgbImage) {
+        if let Some(lut) = &self.color_lut {
+            lut.apply(image);
+        }
+    }
+
+    /// Create the page transition to the rendered panel slots image, if the page of the changed
+    /// region `(x, width)` changed.
+    fn update_transition(&mut self, image: &RgbaImage, changed: Option<(u32, u32)>) {
+        let Some(cfg) = &self.setup.page_transition else {
+            return;
+        };
+        if let (Some(from), Some((x, width))) = (self.last_image.take(), changed)
+            && self.animations
+        {
+            // the ticker isn't part of the page transition
+            let ticker_height = self
+                .setup
+                .ticker
+                .as_ref()
+                .map_or(0, |ticker| ticker.height.unwrap_or(40));
+            let height = self.size.1.saturating_sub(ticker_height);
+            self.transition = Transition::new(cfg, from, image.clone(), x, width, height);
+        }
+        self.last_image = Some(image.clone());
+    }
+
+    /// Draw a line of debug information, e.g. frame statistics, on top of a rendered image.
+    pub fn draw_debug_overlay(&mut self, image: &mut RgbaImage, text: &str) {
+        self.overlay.render_debug_overlay(image, text);
+    }
+
+    /// Render a full-screen message page with the current brightness and color calibration, e.g.
+    /// for an ad-hoc message.
+    pub fn render_message(
+        &mut self,
+        message: &MessagePage,
+        values: &SensorStore,
+    ) -> Result<RgbImage, ImageProcessingError> {
+        let mut image = self.overlay.render_message_page(message, values)?;
+        self.adjust_colors(&mut image);
+        Ok(DynamicImage::ImageRgba8(image).to_rgb8())
+    }
+
+    /// Render the final display image without a display device.
+    ///
+    /// Same as [PanelEngine::render], but returns the composited RGB image as it is sent to the
+    /// display.
+    pub fn render_to_image(
+        &mut self,
+        values: &SensorStore,
+    ) -> Result<RgbImage, ImageProcessingError> {
+        Ok(DynamicImage::ImageRgba8(self.render(values)?).to_rgb8())
+    }
+
+    /// Render the current pages of all slots.
+    ///
+    /// Returns the image and the horizontal region `(x, width)` of the slots with a changed page.
+    fn render_slots(
+        &mut self,
+        values: &SensorStore,
+    ) -> Result<(RgbaImage, Option<(u32, u32)>), ImageProcessingError> {
+        let full_screen =
+            matches!(self.slots.as_slice(), [slot] if slot.x == 0 && slot.width == self.size.0);
+        let mut image = RgbaImage::new(self.size.0, self.size.1);
+        let history = self.history.read().expect("RwLock is poisoned");
+        let mut changed: Option<(u32, u32)> = None;
+
+        for slot in self.slots.iter_mut() {
+            slot.rotation.tick(values);
+            let Some(page) = slot.rotation.current() else {
+                slot.last_page = None;
+                continue;
+            };
+            if slot.last_page.as_deref() != Some(page.name()) {
+                let end = slot.x + slot.width;
+                changed = Some(changed.map_or((slot.x, slot.width), |(x, width)| {
+                    let start = x.min(slot.x);
+                    (start, (x + width).max(end) - start)
+                }));
+                slot.last_page = Some(page.name().to_string());
+            }
+            if self.img_save_path.is_some() {
+                slot.renderer
+                    .set_img_suffix(format!("-{:02}", slot.rotation.refresh_count()));
+            }
+
+            let page_image = match page {
+                PageKind::Sensor(sp) if sp.template.page == Some(PageLayout::History) => {
+                    slot.renderer.render_history_page(
+                        &sp.template,
+                        &sp.sensor_key,
+                        &sp.display_name,
+                        values,
+                        &history,
+                        self.setup.sensor_page_label.as_ref(),
+                    )?
+                }
+                PageKind::Sensor(sp) => slot.renderer.render_sensor_page_from_template(
+                    &sp.template,
+                    &sp.sensor_key,
+                    &sp.display_name,
+                    values,
+                    self.setup.sensor_page_label.as_ref(),
+                )?,
+                PageKind::Message(message) => slot.renderer.render_message_page(message, values)?,
+                PageKind::Time(label) => slot.renderer.render_time_page(
+                    label,
+                    self.setup.time_page_font_size,
+                    self.setup.time_page_style.as_ref(),
+                )?,
+            };
+
+            if full_screen {
+                return Ok((page_image, changed));
+            }
+            imageops::replace(&mut image, &page_image, slot.x as i64, 0);
+        }
+
+        Ok((image, changed))
+    }
+}
+
+/// Built-in warning page of the sensor watchdog.
+fn watchdog_page(since: DateTime<Local>) -> MessagePage {
+    let since = if since.date_naive() == Local::now().date_naive() {
+        since.format("%H:%M")
+    } else {
+        since.format("%Y-%m-%d %H:%M")
+    };
+    MessagePage {
+        title: Some("SENSORS STALLED".to_string()),
+        text: format!("Sensor data stalled\nsince {since}"),
+        font_size: Some(40.0),
+        background: Some(FontColor::from(Rgb([0x80, 0x40, 0]))),
+        ..Default::default()
+    }
+}
+
+/// Dim the image by scaling the color channels with the brightness in percent.
+fn dim_image(image: &mut RgbaImage, brightness: u8) {
+    let brightness = u16::from(brightness);
+    for pixel in image.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel = (u16::from(*channel) * brightness / 100) as u8;
+        }
+    }
+}
+
+/// Create the configured panel slots, or a single full-screen slot with all active panels.
+fn create_slots(cfg: &MonitorConfig, size: Size, font_dir: &Path, config_dir: &Path) -> Vec<Slot> {
+    let sensor_page_time = Duration::from_secs_f32(cfg.setup.sensor_page_time.unwrap_or(10.0));
+    let time_page_time = Duration::from_secs_f32(
+        cfg.setup
+            .time_page_time
+            .unwrap_or(cfg.setup.sensor_page_time.unwrap_or(10.0)),
+    );
+
+    if cfg.slots.is_empty() {
+        return vec![Slot {
+            x: 0,
+            width: size.0,
+            rotation: PageRotation::new(
+                None,
+                cfg,
+                &cfg.active_panels,
+                cfg.setup.time_page.clone(),
+                &cfg.messages,
+                sensor_page_time,
+                time_page_time,
+            ),
+            renderer: PanelRenderer::new(size, font_dir, config_dir),
+            last_page: None,
+        }];
+    }
+
+    cfg.slots
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, slot)| {
+            let x = slot.x.min(size.0);
+            let width = slot.width.min(size.0 - x);
+            if width == 0 {
+                warn!("Ignoring slot {} without width", idx + 1);
+                return None;
+            }
+            let name = slot
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("slot {}", idx + 1));
+            let sensor_page_time = slot
+                .sensor_page_time
+                .map(Duration::from_secs_f32)
+                .unwrap_or(sensor_page_time);
+            let time_page_time = slot
+                .time_page_time
+                .map(Duration::from_secs_f32)
+                .unwrap_or(time_page_time);
+            Some(Slot {
+                x,
+                width,
+                rotation: PageRotation::new(
+                    Some(name),
+                    cfg,
+                    &slot.panels,
+                    slot.time_page.clone(),
+                    &slot.messages,
+                    sensor_page_time,
+                    time_page_time,
+                ),
+                renderer: PanelRenderer::new((width, size.1), font_dir, config_dir),
+                last_page: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::SensorWatchdog;
+    use image::Rgba;
+
+    const SIZE: Size = (960, 376);
+
+    fn config(slots: &str) -> MonitorConfig {
+        serde_json::from_str(&format!(
+            r##"{{
+              "setup": {{ "refresh": 1, "ticker": {{ "items": ["ticker"], "background": "#0000ff" }} }},
+              "mianban": [1],
+              "slots": {slots},
+              "diy": [
+                {{ "sensor": [
+                  {{ "mode": 1, "match": "^temperature_(.+)$", "name": "Temp {{1}}", "x": 240, "y": 200,
+                     "fontSize": 60, "fontColor": "#ff0000", "textAlign": "center" }}
+                ] }},
+                {{ "sensor": [
+                  {{ "mode": 1, "match": "^load$", "x": 240, "y": 200,
+                     "fontSize": 60, "fontColor": "#00ff00", "textAlign": "center" }}
+                ] }}
+              ]
+            }}"##
+        ))
+        .unwrap()
+    }
+
+    fn values() -> SensorStore {
+        SensorStore::from_iter([
+            ("temperature_cpu".to_string(), "42".to_string()),
+            ("load".to_string(), "1.5".to_string()),
+        ])
+    }
+
+    /// Check for text pixels of the given color above the ticker region.
+    /// Glyph edges are anti-aliased, a small deviation is accepted.
+    fn has_color(image: &RgbImage, x: std::ops::Range<u32>, color: Rgb<u8>) -> bool {
+        image.enumerate_pixels().any(|(px, py, p)| {
+            x.contains(&px)
+                && py < image.height() - 40
+                && p.0.iter().zip(color.0).all(|(a, b)| a.abs_diff(b) < 16)
+        })
+    }
+
+    #[test]
+    fn render_to_image_without_device() {
+        let cfg = config("[]");
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values());
+        assert_eq!(1, engine.slot_count());
+        assert_eq!(1, engine.page_count());
+
+        let image = engine.render_to_image(&values()).unwrap();
+
+        assert_eq!(SIZE, image.dimensions());
+        assert!(has_color(&image, 0..240, Rgb([255, 0, 0])));
+        // ticker region at the bottom
+        assert_eq!(&Rgb([0, 0, 255]), image.get_pixel(0, SIZE.1 - 1));
+    }
+
+    #[test]
+    fn render_to_image_composes_slots() {
+        let cfg = config(
+            r#"[{ "x": 0, "width": 480, "panels": [1] }, { "x": 480, "width": 480, "panels": [2] }]"#,
+        );
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values());
+        assert_eq!(2, engine.slot_count());
+
+        let image = engine.render_to_image(&values()).unwrap();
+
+        assert!(has_color(&image, 0..480, Rgb([255, 0, 0])));
+        assert!(!has_color(&image, 480..960, Rgb([255, 0, 0])));
+        assert!(has_color(&image, 480..960, Rgb([0, 255, 0])));
+    }
+
+    #[test]
+    fn brightness_dims_image() {
+        let cfg = config("[]");
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values());
+        engine.set_brightness(50);
+
+        let image = engine.render_to_image(&values()).unwrap();
+
+        assert_eq!(&Rgb([0, 0, 127]), image.get_pixel(0, SIZE.1 - 1));
+        assert!(!has_color(&image, 0..960, Rgb([255, 0, 0])));
+    }
+
+    #[test]
+    fn render_bar_sensor_with_color_thresholds() {
+        let cfg: MonitorConfig = serde_json::from_str(
+            r##"{
+              "setup": { "refresh": 1 },
+              "mianban": [1],
+              "diy": [
+                { "sensor": [
+                  { "mode": 6, "match": "^load$", "x": 100, "y": 200, "width": 200, "height": 20,
+                    "maxValue": 4, "fontColor": "#00ff00", "colorThresholds": [[1, "#ffff00"], [3, "#ff0000"]],
+                    "bar": { "radius": 10, "backgroundColor": "#0000ff" } }
+                ] }
+              ]
+            }"##,
+        )
+        .unwrap();
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values());
+
+        let image = engine.render_to_image(&values()).unwrap();
+
+        // load 1.5 of 4: yellow up to x = 175, blue track after
+        assert_eq!(&Rgb([255, 255, 0]), image.get_pixel(150, 210));
+        assert_eq!(&Rgb([0, 0, 255]), image.get_pixel(250, 210));
+        // rounded corner
+        assert_eq!(&Rgb([0, 0, 0]), image.get_pixel(100, 200));
+    }
+
+    #[test]
+    fn alert_interrupts_page_rotation() {
+        let mut cfg = config("[]");
+        cfg.alerts = serde_json::from_str(
+            r##"[{ "name": "CPU hot", "sensor": "temperature_cpu", "above": 40, "background": "#0000ff" }]"##,
+        )
+        .unwrap();
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values());
+
+        let image = engine.render_to_image(&values()).unwrap();
+        assert_eq!(Some("CPU hot".to_string()), engine.current_alert());
+        assert_eq!(&Rgb([0, 0, 255]), image.get_pixel(0, 0));
+        assert!(!has_color(&image, 0..960, Rgb([255, 0, 0])));
+
+        // flashing
+        let image = engine.render_to_image(&values()).unwrap();
+        assert_eq!(&Rgb([0, 0, 0]), image.get_pixel(0, 0));
+
+        // no flashing without animations
+        engine.set_animations(false);
+        for _ in 0..2 {
+            let image = engine.render_to_image(&values()).unwrap();
+            assert_eq!(&Rgb([0, 0, 255]), image.get_pixel(0, 0));
+        }
+
+        assert!(engine.acknowledge_alerts());
+        let image = engine.render_to_image(&values()).unwrap();
+        assert_eq!(None, engine.current_alert());
+        assert!(has_color(&image, 0..960, Rgb([255, 0, 0])));
+    }
+
+    #[test]
+    fn watchdog_page_replaces_stalled_values() {
+        let mut cfg = config("[]");
+        cfg.setup.sensor_watchdog = Some(SensorWatchdog { timeout: 30.0 });
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        let mut values = values();
+        engine.rebuild(&values);
+
+        engine.render_to_image(&values).unwrap();
+        assert_eq!(None, engine.sensors_stalled_since());
+
+        let updated_at = Local::now() - Duration::from_secs(60);
+        for key in ["temperature_cpu", "load"] {
+            let mut sensor = values.get(key).unwrap().clone();
+            sensor.updated_at = updated_at;
+            values.insert(key, sensor);
+        }
+        values.set("load_pct", "37", DERIVED_SOURCE);
+        let image = engine.render_to_image(&values).unwrap();
+        assert_eq!(Some(updated_at), engine.sensors_stalled_since());
+        assert_eq!(&Rgb([0x80, 0x40, 0]), image.get_pixel(0, 0));
+        assert!(!has_color(&image, 0..960, Rgb([255, 0, 0])));
+
+        values.set("load", "1.2", "sysinfo");
+        engine.render_to_image(&values).unwrap();
+        assert_eq!(None, engine.sensors_stalled_since());
+    }
+
+    #[test]
+    fn timer_replaces_page_rotation() {
+        let cfg = config("[]");
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values());
+
+        engine.start_timer(Duration::from_secs(1500), Some("Focus".into()));
+        assert!(engine.timer_end().is_some());
+        let image = engine.render_to_image(&values()).unwrap();
+        // full green progress ring without the ticker and sensor page
+        assert!(has_color(&image, 0..960, Rgb([0, 0xc8, 0x53])));
+        assert!(!has_color(&image, 0..960, Rgb([255, 0, 0])));
+        assert_eq!(&Rgb([0, 0, 0]), image.get_pixel(0, SIZE.1 - 1));
+
+        assert!(engine.cancel_timer());
+        assert!(!engine.cancel_timer());
+        let image = engine.render_to_image(&values()).unwrap();
+        assert!(has_color(&image, 0..960, Rgb([255, 0, 0])));
+    }
+
+    #[test]
+    fn page_change_creates_transition() {
+        let mut cfg = config(
+            r#"[{ "x": 0, "width": 480, "panels": [1] }, { "x": 480, "width": 480, "panels": [2] }]"#,
+        );
+        cfg.setup.page_transition =
+            serde_json::from_str(r#"{ "effect": "slideLeft", "duration": 0.4, "fps": 10 }"#)
+                .unwrap();
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values());
+
+        // no transition for the first image
+        engine.render(&values()).unwrap();
+        assert!(engine.take_transition().is_none());
+        engine.render(&values()).unwrap();
+        assert!(engine.take_transition().is_none());
+
+        let mut values = values();
+        values.set("temperature_gpu", "50", "");
+        engine.rebuild(&values);
+        engine.next_page();
+        engine.render(&values).unwrap();
+        let transition = engine.take_transition().unwrap();
+        assert_eq!(3, transition.frame_count());
+        // the second slot with a single page and the ticker are unchanged
+        let frame = transition.frame(0.5);
+        assert!(has_color(
+            &DynamicImage::ImageRgba8(frame.clone()).to_rgb8(),
+            480..960,
+            Rgb([0, 255, 0])
+        ));
+        assert_eq!(&Rgba([0, 0, 255, 255]), frame.get_pixel(0, SIZE.1 - 1));
+
+        engine.set_animations(false);
+        engine.next_page();
+        engine.render(&values).unwrap();
+        assert!(engine.take_transition().is_none());
+    }
+
+    #[test]
+    fn scrolling_text_is_clipped_to_element() {
+        let cfg: MonitorConfig = serde_json::from_str(
+            r##"{
+              "setup": { "refresh": 1 },
+              "mianban": [1],
+              "diy": [
+                { "sensor": [
+                  { "mode": 1, "match": "^disk_model$", "x": 100, "y": 200, "width": 200, "height": 40,
+                    "fontSize": 40, "fontColor": "#00ff00", "scrollSpeed": 40 }
+                ] }
+              ]
+            }"##,
+        )
+        .unwrap();
+        let values = SensorStore::from_iter([(
+            "disk_model".to_string(),
+            "Samsung SSD 990 PRO with Heatsink 4TB".to_string(),
+        )]);
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values);
+
+        let image = engine.render_to_image(&values).unwrap();
+
+        assert!(has_color(&image, 100..300, Rgb([0, 255, 0])));
+        assert!(!has_color(&image, 0..100, Rgb([0, 255, 0])));
+        assert!(!has_color(&image, 300..960, Rgb([0, 255, 0])));
+    }
+
+    #[test]
+    fn word_wrapped_text_fits_into_element() {
+        let cfg: MonitorConfig = serde_json::from_str(
+            r##"{
+              "setup": { "refresh": 1 },
+              "mianban": [1],
+              "diy": [
+                { "sensor": [
+                  { "mode": 1, "match": "^status$", "x": 100, "y": 50, "width": 200, "height": 200,
+                    "fontSize": 30, "fontColor": "#00ff00", "wordWrap": true, "verticalAlign": "top" }
+                ] }
+              ]
+            }"##,
+        )
+        .unwrap();
+        let values = SensorStore::from_iter([(
+            "status".to_string(),
+            "Backup running since 03:00, next check in 5 minutes".to_string(),
+        )]);
+        let mut engine = PanelEngine::new(&cfg, SIZE, "fonts", "cfg");
+        engine.rebuild(&values);
+
+        let image = engine.render_to_image(&values).unwrap();
+
+        let green_rows = (0..image.height())
+            .filter(|y| (0..image.width()).any(|x| image.get_pixel(x, *y).0 == [0, 255, 0]))
+            .collect::<Vec<_>>();
+        assert!(green_rows.first().is_some_and(|y| *y >= 50));
+        assert!(green_rows.last().is_some_and(|y| *y > 150 && *y < 250));
+        assert!(!has_color(&image, 0..100, Rgb([0, 255, 0])));
+        assert!(!has_color(&image, 300..960, Rgb([0, 255, 0])));
+    }
+}
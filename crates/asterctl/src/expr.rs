@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Arithmetic expressions with sensor values, used for derived sensors.
+//!
+//! Supported syntax:
+//! - numbers: `100`, `0.5`, `1e3`
+//! - sensor keys: `mem_used_bytes`, or `{key}` for keys with other characters than letters, digits,
+//!   `_` and `.`
+//! - operators `+`, `-`, `*`, `/`, `%` with the usual precedence, unary `-` and parentheses
+//! - functions: `min(a, b, ...)`, `max(a, b, ...)`, `abs(a)`, `round(a)`, `floor(a)`, `ceil(a)`
+//!
+//! Example: `swap_free_bytes / swap_total_bytes * 100`
+
+use crate::sensors::SensorStore;
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed arithmetic expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Sensor(String),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Min,
+    Max,
+    Abs,
+    Round,
+    Floor,
+    Ceil,
+}
+
+/// Expression parse error with the character position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Expr {
+    /// Evaluate the expression with the given sensor values.
+    ///
+    /// Returns `None` if a sensor is missing or not numeric, or if the result is not a finite
+    /// number, e.g. after a division by zero.
+    pub fn eval(&self, values: &SensorStore) -> Option<f64> {
+        let result = match self {
+            Expr::Number(n) => *n,
+            Expr::Sensor(key) => values.value(key)?.trim().parse().ok()?,
+            Expr::Neg(expr) => -expr.eval(values)?,
+            Expr::Binary(left, op, right) => {
+                let (left, right) = (left.eval(values)?, right.eval(values)?);
+                match op {
+                    BinaryOp::Add => left + right,
+                    BinaryOp::Sub => left - right,
+                    BinaryOp::Mul => left * right,
+                    BinaryOp::Div => left / right,
+                    BinaryOp::Rem => left % right,
+                }
+            }
+            Expr::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(values))
+                    .collect::<Option<Vec<_>>>()?;
+                match function {
+                    Function::Min => args.into_iter().reduce(f64::min)?,
+                    Function::Max => args.into_iter().reduce(f64::max)?,
+                    Function::Abs => args[0].abs(),
+                    Function::Round => args[0].round(),
+                    Function::Floor => args[0].floor(),
+                    Function::Ceil => args[0].ceil(),
+                }
+            }
+        };
+        result.is_finite().then_some(result)
+    }
+
+    /// Sensor keys used by the expression, in order of appearance.
+    pub fn sensor_keys(&self) -> Vec<&str> {
+        let mut keys = Vec::new();
+        self.collect_sensor_keys(&mut keys);
+        keys
+    }
+
+    fn collect_sensor_keys<'a>(&'a self, keys: &mut Vec<&'a str>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Sensor(key) => keys.push(key),
+            Expr::Neg(expr) => expr.collect_sensor_keys(keys),
+            Expr::Binary(left, _, right) => {
+                left.collect_sensor_keys(keys);
+                right.collect_sensor_keys(keys);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|arg| arg.collect_sensor_keys(keys)),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s.as_bytes(),
+            pos: 0,
+        };
+        let expr = parser.expr()?;
+        parser.skip_whitespace();
+        if parser.pos < s.len() {
+            return Err(parser.error("unexpected character"));
+        }
+        Ok(expr)
+    }
+}
+
+/// Recursive descent parser.
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .input
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip whitespace and consume the given character if it is next.
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.term()?;
+        loop {
+            let op = if self.eat(b'+') {
+                BinaryOp::Add
+            } else if self.eat(b'-') {
+                BinaryOp::Sub
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(Box::new(left), op, Box::new(self.term()?));
+        }
+    }
+
+    /// term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat(b'*') {
+                BinaryOp::Mul
+            } else if self.eat(b'/') {
+                BinaryOp::Div
+            } else if self.eat(b'%') {
+                BinaryOp::Rem
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(Box::new(left), op, Box::new(self.unary()?));
+        }
+    }
+
+    /// unary := '-' unary | primary
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat(b'-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    /// primary := number | key | '{' key '}' | function '(' args ')' | '(' expr ')'
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.input.get(self.pos) {
+            Some(b'(') => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                if !self.eat(b')') {
+                    return Err(self.error("expected ')'"));
+                }
+                Ok(expr)
+            }
+            Some(b'{') => {
+                let Some(len) = self.input[start..].iter().position(|&c| c == b'}') else {
+                    return Err(self.error("expected '}'"));
+                };
+                self.pos += len + 1;
+                let key = String::from_utf8_lossy(&self.input[start + 1..start + len]);
+                Ok(Expr::Sensor(key.trim().to_string()))
+            }
+            Some(c) if c.is_ascii_digit() || *c == b'.' => {
+                self.take_while(|c| c.is_ascii_alphanumeric() || c == b'.');
+                // exponent sign, e.g. 1e-3
+                if matches!(self.input[self.pos - 1], b'e' | b'E')
+                    && matches!(self.input.get(self.pos), Some(b'+' | b'-'))
+                {
+                    self.pos += 1;
+                    self.take_while(|c| c.is_ascii_digit());
+                }
+                let number = String::from_utf8_lossy(&self.input[start..self.pos]);
+                number.parse().map(Expr::Number).map_err(|_| ParseError {
+                    position: start,
+                    message: format!("invalid number '{number}'"),
+                })
+            }
+            Some(c) if c.is_ascii_alphabetic() || *c == b'_' => {
+                self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'.');
+                let name = String::from_utf8_lossy(&self.input[start..self.pos]).to_string();
+                if !self.eat(b'(') {
+                    return Ok(Expr::Sensor(name));
+                }
+                let (function, arity) = match name.as_str() {
+                    "min" => (Function::Min, None),
+                    "max" => (Function::Max, None),
+                    "abs" => (Function::Abs, Some(1)),
+                    "round" => (Function::Round, Some(1)),
+                    "floor" => (Function::Floor, Some(1)),
+                    "ceil" => (Function::Ceil, Some(1)),
+                    _ => {
+                        return Err(ParseError {
+                            position: start,
+                            message: format!("unknown function '{name}'"),
+                        });
+                    }
+                };
+                let mut args = vec![self.expr()?];
+                while self.eat(b',') {
+                    args.push(self.expr()?);
+                }
+                if !self.eat(b')') {
+                    return Err(self.error("expected ')'"));
+                }
+                if arity.is_some_and(|arity| arity != args.len()) {
+                    return Err(ParseError {
+                        position: start,
+                        message: format!("function '{name}' expects one argument"),
+                    });
+                }
+                Ok(Expr::Call(function, args))
+            }
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of expression")),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(u8) -> bool) {
+        while self.input.get(self.pos).is_some_and(|&c| predicate(c)) {
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> SensorStore {
+        SensorStore::from_iter([
+            ("memory_used".to_string(), "4".to_string()),
+            ("memory_total".to_string(), "16".to_string()),
+            ("temperature_nvme-0".to_string(), "41.5".to_string()),
+            ("network_state".to_string(), "up".to_string()),
+        ])
+    }
+
+    fn eval(expr: &str) -> Option<f64> {
+        expr.parse::<Expr>().unwrap().eval(&values())
+    }
+
+    #[test]
+    fn evaluate_expressions() {
+        assert_eq!(Some(25.0), eval("memory_used / memory_total * 100"));
+        assert_eq!(Some(12.0), eval("memory_total - memory_used"));
+        assert_eq!(Some(7.0), eval("1 + 2 * 3"));
+        assert_eq!(Some(9.0), eval("(1 + 2) * 3"));
+        assert_eq!(Some(-2.0), eval("-memory_used / 2"));
+        assert_eq!(Some(1.0), eval("10 % 3"));
+        assert_eq!(Some(0.001), eval("1e-3"));
+        assert_eq!(Some(42.0), eval("round({temperature_nvme-0})"));
+        assert_eq!(
+            Some(41.5),
+            eval("max(memory_total, {temperature_nvme-0}, 3)")
+        );
+        assert_eq!(Some(4.0), eval("abs(min(-4, 2))"));
+
+        assert_eq!(None, eval("memory_free / memory_total"));
+        assert_eq!(None, eval("network_state * 2"));
+        assert_eq!(None, eval("memory_used / 0"));
+    }
+
+    #[test]
+    fn collect_sensor_keys() {
+        let expr: Expr = "max(memory_used, -{temperature_nvme-0}) / memory_total + 1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            vec!["memory_used", "temperature_nvme-0", "memory_total"],
+            expr.sensor_keys()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        for expr in [
+            "",
+            "1 +",
+            "(1 + 2",
+            "memory used",
+            "foo(1)",
+            "abs(1, 2)",
+            "1..2",
+            "{key",
+        ] {
+            assert!(expr.parse::<Expr>().is_err(), "{expr}");
+        }
+        assert_eq!(
+            "unexpected character at position 7",
+            "1 + 2 ) ".parse::<Expr>().unwrap_err().to_string()
+        );
+    }
+}
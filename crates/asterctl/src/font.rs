@@ -2,26 +2,57 @@
 // SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
 // SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
 
-//! Font handling and caching.
+//! Font handling, caching, font fallback and text shaping.
 
-use ab_glyph::{FontArc, FontRef, FontVec};
+use ab_glyph::{
+    CodepointIdIter, Font, FontArc, FontRef, FontVec, Glyph, GlyphId, Outline, OutlineCurve, Point,
+    PxScale, Rect, ScaleFont, point, v2,
+};
 use anyhow::{Context, anyhow};
+use image::{Rgba, RgbaImage};
+use imageproc::pixelops::weighted_sum;
 use log::warn;
 use once_cell::sync::Lazy;
+use rustybuzz::{Direction, UnicodeBuffer};
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use unicode_bidi::BidiInfo;
 
-static DEFAULT_TTF_FONT: Lazy<FontArc> = Lazy::new(|| {
-    FontArc::new(
+/// Name of the built-in default font.
+pub const DEFAULT_FONT_NAME: &str = "DejaVu Sans";
+
+static DEFAULT_TTF_FONT: Lazy<TextFont> = Lazy::new(|| {
+    TextFont::new(vec![FontArc::new(
         FontRef::try_from_slice(include_bytes!("../../../fonts/DejaVuSans.ttf"))
             .expect("Failed to load default font"),
-    )
+    )])
 });
 
+/// A font of a font family list which can't be loaded, and the font used instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontSubstitution {
+    /// Name of the missing font.
+    pub font: String,
+    /// Reason why the font can't be loaded.
+    pub error: String,
+    /// Next font of the list, or the built-in default font.
+    pub substitute: String,
+}
+
+impl fmt::Display for FontSubstitution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, using {}", self.error, self.substitute)
+    }
+}
+
 pub struct FontHandler {
     ttf_path: PathBuf,
     ttf_cache: HashMap<String, FontArc>,
+    fallback_cache: HashMap<(String, String), TextFont>,
 }
 
 impl FontHandler {
@@ -29,18 +60,89 @@ impl FontHandler {
         Self {
             ttf_path: ttf_path.into(),
             ttf_cache: Default::default(),
+            fallback_cache: Default::default(),
         }
     }
 
-    pub fn default_font() -> FontArc {
+    pub fn default_font() -> TextFont {
         DEFAULT_TTF_FONT.clone()
     }
 
-    pub fn get_ttf_font_or_default(&mut self, name: &str) -> FontArc {
-        self.get_ttf_font(name).unwrap_or_else(|e| {
-            warn!("Failed to load font: {e}. Using default");
+    /// Get a font from a comma-separated list of font names, e.g. `Roboto, NotoSansCJK`.
+    ///
+    /// Glyphs missing in the first font are taken from the next font in the list, and finally from
+    /// the built-in default font. Fonts which cannot be loaded are skipped with a warning, a page
+    /// is still rendered if all fonts are missing.
+    pub fn get_ttf_font_or_default(&mut self, names: &str) -> TextFont {
+        self.get_text_font(Some(names), None)
+    }
+
+    /// Get a font from a comma-separated list of font names like [Self::get_ttf_font_or_default],
+    /// with additional fallback fonts, e.g. the `fontFallback` fonts of a panel.
+    ///
+    /// The font chain consists of the fonts of `names`, or the built-in default font if not set,
+    /// followed by the `fallback` fonts and the built-in default font.
+    pub fn get_text_font(&mut self, names: Option<&str>, fallback: Option<&str>) -> TextFont {
+        let key = (
+            names.unwrap_or_default().to_string(),
+            fallback.unwrap_or_default().to_string(),
+        );
+        if let Some(font) = self.fallback_cache.get(&key) {
+            return font.clone();
+        }
+
+        let (mut fonts, substitutions) = self.load_fonts(&key.0);
+        let (fallback_fonts, fallback_substitutions) = self.load_fonts(&key.1);
+        for substitution in substitutions.into_iter().chain(fallback_substitutions) {
+            warn!("Failed to load font: {substitution}");
+        }
+        let font = if fonts.is_empty() && fallback_fonts.is_empty() {
             FontHandler::default_font()
-        })
+        } else {
+            if fonts.is_empty() {
+                fonts.push(DEFAULT_TTF_FONT.fonts[0].clone());
+            }
+            fonts.extend(fallback_fonts);
+            fonts.push(DEFAULT_TTF_FONT.fonts[0].clone());
+            TextFont::new(fonts)
+        };
+
+        self.fallback_cache.insert(key, font.clone());
+
+        font
+    }
+
+    /// Fonts of a comma-separated list of font names which can't be loaded, and their
+    /// substitutes.
+    pub fn substitutions(&mut self, names: &str) -> Vec<FontSubstitution> {
+        self.load_fonts(names).1
+    }
+
+    fn load_fonts(&mut self, names: &str) -> (Vec<FontArc>, Vec<FontSubstitution>) {
+        let mut fonts = Vec::new();
+        let mut missing = Vec::new();
+        for name in font_names(names) {
+            match self.get_ttf_font(name) {
+                Ok(font) => fonts.push((name, font)),
+                Err(e) => missing.push((fonts.len(), name, e)),
+            }
+        }
+
+        let substitutions = missing
+            .into_iter()
+            .map(|(idx, name, error)| FontSubstitution {
+                font: name.to_string(),
+                error: format!("{error:#}"),
+                substitute: fonts.get(idx).map_or_else(
+                    || format!("the built-in {DEFAULT_FONT_NAME}"),
+                    |(name, _)| name.to_string(),
+                ),
+            })
+            .collect();
+        (
+            fonts.into_iter().map(|(_, font)| font).collect(),
+            substitutions,
+        )
     }
 
     pub fn get_ttf_font(&mut self, name: &str) -> anyhow::Result<FontArc> {
@@ -68,5 +170,574 @@ impl FontHandler {
     #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.ttf_cache.clear();
+        self.fallback_cache.clear();
+    }
+}
+
+/// Split a comma-separated font family list into the individual font names.
+pub fn font_names(names: &str) -> impl Iterator<Item = &str> {
+    names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Glyph ids of the fallback fonts, mapped into the unused id range of the primary font.
+#[derive(Default)]
+struct FallbackGlyphs {
+    ids: HashMap<(usize, GlyphId), GlyphId>,
+    glyphs: Vec<(usize, GlyphId)>,
+}
+
+/// Font chain taking glyphs missing in the primary font from the fallback fonts.
+///
+/// Glyph ids of the primary font are used as is. Glyphs of a fallback font get a new id after the
+/// last glyph of the primary font when they are first used, and their metrics and outlines are
+/// scaled to the height of the primary font.
+struct FallbackFont {
+    fonts: Vec<FontArc>,
+    glyphs: Mutex<FallbackGlyphs>,
+}
+
+impl FallbackFont {
+    fn new(fonts: Vec<FontArc>) -> Self {
+        assert!(!fonts.is_empty(), "FallbackFont requires a primary font");
+        Self {
+            fonts,
+            glyphs: Default::default(),
+        }
+    }
+
+    fn primary(&self) -> &FontArc {
+        &self.fonts[0]
+    }
+
+    /// Resolve a glyph id to the font index and the glyph id within that font.
+    fn resolve(&self, id: GlyphId) -> (usize, GlyphId) {
+        let index = (id.0 as usize).checked_sub(self.primary().glyph_count());
+        index
+            .and_then(|index| {
+                let glyphs = self.glyphs.lock().expect("fallback glyphs lock poisoned");
+                glyphs.glyphs.get(index).copied()
+            })
+            .unwrap_or((0, id))
+    }
+
+    /// Scale factor from the units of the given font to the units of the primary font.
+    fn factor(&self, font: usize) -> f32 {
+        if font == 0 {
+            return 1.0;
+        }
+        let height = self.fonts[font].height_unscaled();
+        if height == 0.0 {
+            1.0
+        } else {
+            self.primary().height_unscaled() / height
+        }
+    }
+
+    /// Apply a metric function of the glyph's font, scaled to the units of the primary font.
+    fn metric(&self, id: GlyphId, f: impl Fn(&FontArc, GlyphId) -> f32) -> f32 {
+        let (font, id) = self.resolve(id);
+        f(&self.fonts[font], id) * self.factor(font)
+    }
+}
+
+impl Font for FallbackFont {
+    fn units_per_em(&self) -> Option<f32> {
+        self.primary().units_per_em()
+    }
+
+    fn ascent_unscaled(&self) -> f32 {
+        self.primary().ascent_unscaled()
+    }
+
+    fn descent_unscaled(&self) -> f32 {
+        self.primary().descent_unscaled()
+    }
+
+    fn line_gap_unscaled(&self) -> f32 {
+        self.primary().line_gap_unscaled()
+    }
+
+    fn italic_angle(&self) -> f32 {
+        self.primary().italic_angle()
+    }
+
+    fn glyph_id(&self, c: char) -> GlyphId {
+        let id = self.primary().glyph_id(c);
+        if id.0 != 0 {
+            return id;
+        }
+        let Some((font, id)) = self
+            .fonts
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(font, f)| (font, f.glyph_id(c)))
+            .find(|(_, id)| id.0 != 0)
+        else {
+            return id;
+        };
+
+        let mut glyphs = self.glyphs.lock().expect("fallback glyphs lock poisoned");
+        if let Some(id) = glyphs.ids.get(&(font, id)) {
+            return *id;
+        }
+        let Ok(new_id) = u16::try_from(self.primary().glyph_count() + glyphs.glyphs.len()) else {
+            // glyph id range exhausted
+            return GlyphId(0);
+        };
+        glyphs.glyphs.push((font, id));
+        glyphs.ids.insert((font, id), GlyphId(new_id));
+        GlyphId(new_id)
+    }
+
+    fn h_advance_unscaled(&self, id: GlyphId) -> f32 {
+        self.metric(id, |font, id| font.h_advance_unscaled(id))
+    }
+
+    fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.metric(id, |font, id| font.h_side_bearing_unscaled(id))
+    }
+
+    fn v_advance_unscaled(&self, id: GlyphId) -> f32 {
+        self.metric(id, |font, id| font.v_advance_unscaled(id))
+    }
+
+    fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.metric(id, |font, id| font.v_side_bearing_unscaled(id))
+    }
+
+    fn kern_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
+        let (font, first) = self.resolve(first);
+        let (second_font, second) = self.resolve(second);
+        if font != second_font {
+            return 0.0;
+        }
+        self.fonts[font].kern_unscaled(first, second) * self.factor(font)
+    }
+
+    fn outline(&self, id: GlyphId) -> Option<Outline> {
+        let (font, id) = self.resolve(id);
+        let outline = self.fonts[font].outline(id)?;
+        let factor = self.factor(font);
+        if factor == 1.0 {
+            return Some(outline);
+        }
+
+        let scale = |p: Point| point(p.x * factor, p.y * factor);
+        Some(Outline {
+            bounds: Rect {
+                min: scale(outline.bounds.min),
+                max: scale(outline.bounds.max),
+            },
+            curves: outline
+                .curves
+                .into_iter()
+                .map(|curve| match curve {
+                    OutlineCurve::Line(p0, p1) => OutlineCurve::Line(scale(p0), scale(p1)),
+                    OutlineCurve::Quad(p0, p1, p2) => {
+                        OutlineCurve::Quad(scale(p0), scale(p1), scale(p2))
+                    }
+                    OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                        OutlineCurve::Cubic(scale(p0), scale(p1), scale(p2), scale(p3))
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    fn glyph_count(&self) -> usize {
+        let glyphs = self.glyphs.lock().expect("fallback glyphs lock poisoned");
+        self.primary().glyph_count() + glyphs.glyphs.len()
+    }
+
+    fn codepoint_ids(&self) -> CodepointIdIter<'_> {
+        self.primary().codepoint_ids()
+    }
+
+    fn glyph_raster_image2(&self, id: GlyphId, pixel_size: u16) -> Option<v2::GlyphImage<'_>> {
+        // raster images of fallback fonts can't be scaled to the primary font
+        match self.resolve(id) {
+            (0, id) => self.primary().glyph_raster_image2(id, pixel_size),
+            _ => None,
+        }
+    }
+
+    fn font_data(&self) -> &[u8] {
+        self.primary().font_data()
+    }
+}
+
+/// Font of a font chain for drawing text: the glyphs missing in the first font are taken from the
+/// following fonts.
+///
+/// The [Font] implementation combines the fonts of the chain into a single font. Text drawn with
+/// [draw_text_mut] is shaped font by font instead, see [TextFont::shape].
+#[derive(Clone)]
+pub struct TextFont {
+    fonts: Arc<[FontArc]>,
+    font: FontArc,
+}
+
+/// Glyph of shaped text: the index of its font in the font chain and the positioned glyph.
+#[derive(Debug, Clone)]
+struct ShapedGlyph {
+    font: usize,
+    glyph: Glyph,
+}
+
+impl TextFont {
+    fn new(fonts: Vec<FontArc>) -> Self {
+        let font = if fonts.len() == 1 {
+            fonts[0].clone()
+        } else {
+            FontArc::new(FallbackFont::new(fonts.clone()))
+        };
+        Self {
+            fonts: fonts.into(),
+            font,
+        }
+    }
+
+    /// Shape a single line of text for drawing from left to right.
+    ///
+    /// The line is split into runs of the same direction with the Unicode bidirectional algorithm
+    /// (UAX #9), and into runs of the same font: a character uses the first font of the chain
+    /// containing it. Marks, joiners and other characters without letters and digits stay in the
+    /// font of the preceding character. The runs are shaped with rustybuzz, which applies the
+    /// contextual forms of Arabic letters, mark positioning, ligatures and kerning of the font.
+    ///
+    /// returns: the glyphs positioned relative to the top left of the text, and the text width.
+    fn shape(&self, scale: PxScale, line: &str) -> (Vec<ShapedGlyph>, f32) {
+        let mut glyphs = Vec::new();
+        let mut x = 0.0;
+        let bidi = BidiInfo::new(line, None);
+        for paragraph in &bidi.paragraphs {
+            let (levels, runs) = bidi.visual_runs(paragraph, paragraph.range.clone());
+            for run in runs {
+                let rtl = levels[run.start].is_rtl();
+                let mut font_runs = self.font_runs(&line[run.clone()], run.start);
+                if rtl {
+                    font_runs.reverse();
+                }
+                for (range, font) in font_runs {
+                    self.shape_run(font, &line[range], rtl, scale, &mut x, &mut glyphs);
+                }
+            }
+        }
+        (glyphs, x)
+    }
+
+    /// Split text into runs of the same font.
+    ///
+    /// returns: the byte ranges, starting at `offset`, and the font index of the runs.
+    fn font_runs(&self, text: &str, offset: usize) -> Vec<(Range<usize>, usize)> {
+        let mut runs: Vec<(Range<usize>, usize)> = Vec::new();
+        for (idx, c) in text.char_indices() {
+            let has_glyph = |font: usize| self.fonts[font].glyph_id(c).0 != 0;
+            let current = runs.last().map(|(_, font)| *font);
+            let font = match current {
+                Some(font) if !c.is_alphanumeric() && has_glyph(font) => font,
+                _ => (0..self.fonts.len())
+                    .find(|font| has_glyph(*font))
+                    .or(current)
+                    .unwrap_or_default(),
+            };
+            let end = offset + idx + c.len_utf8();
+            match runs.last_mut() {
+                Some((range, run_font)) if *run_font == font => range.end = end,
+                _ => runs.push((offset + idx..end, font)),
+            }
+        }
+        runs
+    }
+
+    /// Shape a run of a single font and direction, starting at position `x`.
+    fn shape_run(
+        &self,
+        font_idx: usize,
+        text: &str,
+        rtl: bool,
+        scale: PxScale,
+        x: &mut f32,
+        glyphs: &mut Vec<ShapedGlyph>,
+    ) {
+        let font = self.fonts[font_idx].as_scaled(scale);
+        let (h_factor, v_factor) = (font.h_scale_factor(), font.v_scale_factor());
+        // the baseline of all fonts is at the ascent of the first font
+        let ascent = self.fonts[0].as_scaled(scale).ascent();
+        let mut push = |id: GlyphId, x: f32, y: f32| {
+            glyphs.push(ShapedGlyph {
+                font: font_idx,
+                glyph: id.with_scale_and_position(scale, point(x, ascent + y)),
+            })
+        };
+
+        let Some(face) = rustybuzz::Face::from_slice(font.font().font_data(), 0) else {
+            // font not supported by the shaper: draw the characters without shaping
+            let chars: Vec<char> = text.chars().collect();
+            let visual: Box<dyn Iterator<Item = &char>> = if rtl {
+                Box::new(chars.iter().rev())
+            } else {
+                Box::new(chars.iter())
+            };
+            for c in visual {
+                let id = font.glyph_id(*c);
+                push(id, *x, 0.0);
+                *x += font.h_advance(id);
+            }
+            return;
+        };
+
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.set_direction(if rtl {
+            Direction::RightToLeft
+        } else {
+            Direction::LeftToRight
+        });
+        buffer.guess_segment_properties();
+        let output = rustybuzz::shape(&face, &[], buffer);
+        for (info, pos) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+            // glyph ids of OpenType fonts are 16 bit
+            let id = GlyphId(info.glyph_id as u16);
+            push(
+                id,
+                *x + pos.x_offset as f32 * h_factor,
+                -pos.y_offset as f32 * v_factor,
+            );
+            *x += pos.x_advance as f32 * h_factor;
+        }
+    }
+}
+
+impl Font for TextFont {
+    fn units_per_em(&self) -> Option<f32> {
+        self.font.units_per_em()
+    }
+
+    fn ascent_unscaled(&self) -> f32 {
+        self.font.ascent_unscaled()
+    }
+
+    fn descent_unscaled(&self) -> f32 {
+        self.font.descent_unscaled()
+    }
+
+    fn line_gap_unscaled(&self) -> f32 {
+        self.font.line_gap_unscaled()
+    }
+
+    fn italic_angle(&self) -> f32 {
+        self.font.italic_angle()
+    }
+
+    fn glyph_id(&self, c: char) -> GlyphId {
+        self.font.glyph_id(c)
+    }
+
+    fn h_advance_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.h_advance_unscaled(id)
+    }
+
+    fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.h_side_bearing_unscaled(id)
+    }
+
+    fn v_advance_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.v_advance_unscaled(id)
+    }
+
+    fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.v_side_bearing_unscaled(id)
+    }
+
+    fn kern_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
+        self.font.kern_unscaled(first, second)
+    }
+
+    fn outline(&self, id: GlyphId) -> Option<Outline> {
+        self.font.outline(id)
+    }
+
+    fn glyph_count(&self) -> usize {
+        self.font.glyph_count()
+    }
+
+    fn codepoint_ids(&self) -> CodepointIdIter<'_> {
+        self.font.codepoint_ids()
+    }
+
+    fn glyph_raster_image2(&self, id: GlyphId, pixel_size: u16) -> Option<v2::GlyphImage<'_>> {
+        self.font.glyph_raster_image2(id, pixel_size)
+    }
+
+    fn font_data(&self) -> &[u8] {
+        self.font.font_data()
+    }
+}
+
+/// Get the width and height of a shaped line of text, see [TextFont::shape].
+///
+/// Like [imageproc::drawing::text_size], the height is the height of the highest glyph.
+pub fn text_size(scale: impl Into<PxScale> + Copy, font: &TextFont, text: &str) -> (u32, u32) {
+    let (glyphs, width) = font.shape(scale.into(), text);
+    let height = glyphs
+        .into_iter()
+        .filter_map(|g| font.fonts[g.font].outline_glyph(g.glyph))
+        .map(|g| g.px_bounds().height())
+        .fold(0.0, f32::max);
+    (width as u32, height as u32)
+}
+
+/// Draw a shaped line of text, see [TextFont::shape].
+///
+/// Like [imageproc::drawing::draw_text_mut], the text is drawn with the top of the font ascent at
+/// `y`. Glyph bitmaps, e.g. of color emoji fonts, are not drawn.
+pub fn draw_text_mut(
+    image: &mut RgbaImage,
+    color: Rgba<u8>,
+    x: i32,
+    y: i32,
+    scale: impl Into<PxScale> + Copy,
+    font: &TextFont,
+    text: &str,
+) {
+    let (glyphs, _) = font.shape(scale.into(), text);
+    let (width, height) = (image.width() as i32, image.height() as i32);
+    for shaped in glyphs {
+        let Some(glyph) = font.fonts[shaped.font].outline_glyph(shaped.glyph) else {
+            continue;
+        };
+        let bounds = glyph.px_bounds();
+        glyph.draw(|gx, gy, coverage| {
+            let image_x = gx as i32 + x + bounds.min.x.round() as i32;
+            let image_y = gy as i32 + y + bounds.min.y.round() as i32;
+            if (0..width).contains(&image_x) && (0..height).contains(&image_y) {
+                let coverage = coverage.clamp(0.0, 1.0);
+                let pixel = image.get_pixel_mut(image_x as u32, image_y as u32);
+                *pixel = weighted_sum(*pixel, color, 1.0 - coverage, coverage);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font_handler() -> FontHandler {
+        FontHandler::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../../fonts"))
+    }
+
+    #[test]
+    fn fallback_font_uses_glyphs_of_next_font() {
+        let mut handler = font_handler();
+        let primary = FontHandler::default_font();
+        assert_eq!(0, primary.glyph_id('中').0);
+
+        let font = handler.get_ttf_font_or_default("DejaVuSans, HarmonyOS_Sans_SC_Bold");
+        // glyphs of the primary font are unchanged
+        let id = font.glyph_id('A');
+        assert_eq!(primary.glyph_id('A'), id);
+        assert_eq!(primary.h_advance_unscaled(id), font.h_advance_unscaled(id));
+
+        let id = font.glyph_id('中');
+        assert!(id.0 as usize >= primary.glyph_count());
+        assert_eq!(id, font.glyph_id('中'));
+        assert!(font.h_advance_unscaled(id) > 0.0);
+        assert!(font.outline(id).is_some());
+    }
+
+    #[test]
+    fn fallback_font_skips_missing_fonts() {
+        let mut handler = font_handler();
+        let font = handler.get_ttf_font_or_default("missing, HarmonyOS_Sans_SC_Bold");
+        assert_ne!(0, font.glyph_id('中').0);
+        // built-in default font is the last fallback
+        assert_ne!(0, font.glyph_id('♥').0);
+
+        let font = handler.get_ttf_font_or_default("missing, ");
+        assert_eq!(
+            FontHandler::default_font().glyph_id('A'),
+            font.glyph_id('A')
+        );
+    }
+
+    #[test]
+    fn substitutions_of_missing_fonts() {
+        let mut handler = font_handler();
+        assert!(handler.substitutions("DejaVuSans").is_empty());
+
+        let substitutions = handler.substitutions("missing, HarmonyOS_Sans_SC_Bold, other");
+        assert_eq!(2, substitutions.len());
+        assert_eq!(
+            "missing.ttf not found, using HarmonyOS_Sans_SC_Bold",
+            substitutions[0].to_string()
+        );
+        assert_eq!("other", substitutions[1].font);
+        assert_eq!("the built-in DejaVu Sans", substitutions[1].substitute);
+    }
+
+    /// Font index and glyph id of the shaped glyphs.
+    fn shaped(font: &TextFont, text: &str) -> Vec<(usize, GlyphId)> {
+        let (glyphs, _) = font.shape(PxScale::from(20.0), text);
+        glyphs.into_iter().map(|g| (g.font, g.glyph.id)).collect()
+    }
+
+    /// Font index and glyph id of the characters without shaping.
+    fn unshaped(font: &TextFont, font_idx: usize, text: &str) -> Vec<(usize, GlyphId)> {
+        let chars = text.chars();
+        chars
+            .map(|c| (font_idx, font.fonts[font_idx].glyph_id(c)))
+            .collect()
+    }
+
+    #[test]
+    fn shape_bidirectional_text() {
+        let font = FontHandler::default_font();
+        assert_eq!(unshaped(&font, 0, "CPU 42 °C"), shaped(&font, "CPU 42 °C"));
+        // right-to-left words in left-to-right text, numbers keep their order
+        assert_eq!(
+            unshaped(&font, 0, "Disk 80 ןושאר"),
+            shaped(&font, "Disk ראשון 80")
+        );
+        // right-to-left paragraph with mirrored brackets
+        assert_eq!(unshaped(&font, 0, "(ב) א"), shaped(&font, "א (ב)"));
+    }
+
+    #[test]
+    fn shape_arabic_letters_and_combining_marks() {
+        let font = FontHandler::default_font();
+        // joining forms instead of the isolated letters
+        let glyphs = shaped(&font, "ببب");
+        assert_eq!(3, glyphs.len());
+        assert!(glyphs.iter().all(|(_, id)| id.0 != 0));
+        assert_ne!(unshaped(&font, 0, "ببب"), glyphs);
+
+        // combining marks don't advance the text
+        let scale = PxScale::from(20.0);
+        let width = |text: &str| text_size(scale, &font, text).0;
+        assert_eq!(width("q"), width("q\u{301}"));
+        assert_eq!(2, shaped(&font, "q\u{301}").len());
+        // or are composed with their base letter
+        assert_eq!(unshaped(&font, 0, "é"), shaped(&font, "e\u{301}"));
+    }
+
+    #[test]
+    fn shape_with_fallback_fonts() {
+        let mut handler = font_handler();
+        let font = handler.get_ttf_font_or_default("DejaVuSans, HarmonyOS_Sans_SC_Bold");
+        let glyphs = shaped(&font, "CPU 中文");
+        assert_eq!(unshaped(&font, 0, "CPU "), glyphs[..4]);
+        assert_eq!(unshaped(&font, 1, "中文"), glyphs[4..]);
+
+        // panel fallback fonts after the built-in default font
+        let font = handler.get_text_font(None, Some("HarmonyOS_Sans_SC_Bold"));
+        assert_eq!(3, font.fonts.len());
+        assert_eq!(unshaped(&font, 1, "中"), shaped(&font, "中"));
+        assert_eq!(unshaped(&font, 0, "A"), shaped(&font, "A"));
     }
 }
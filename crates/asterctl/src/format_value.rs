@@ -3,6 +3,11 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
 
 //! Sensor value format functions based on the AOOSTAR-X application.
+//!
+//! Besides the fixed point formatting of the AOOSTAR-X application, values can be converted to
+//! other units and formatted with a locale specific decimal separator, see [ValueFormat].
+
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub enum IntegerDigits {
@@ -34,6 +39,199 @@ impl From<Option<i32>> for IntegerDigits {
     }
 }
 
+/// Unit conversion and number format options.
+///
+/// The options are configured globally with `valueFormat` in `setup` and can be overridden per
+/// sensor element. Units are only converted if a value's unit is recognized, all other values are
+/// formatted as they are.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueFormat {
+    /// Temperature unit of values in `°C` or `°F`.
+    pub temperature: Option<TemperatureUnit>,
+    /// Prefixes of data sizes: binary `KiB`, `MiB` or decimal `KB`, `MB`.
+    pub data_size: Option<DataSizeUnit>,
+    /// Data rates in bytes per second `MB/s` or bits per second `Mbit/s`.
+    pub data_rate: Option<DataRateUnit>,
+    /// Locale for the decimal separator, e.g. `de-CH` or `fr`.
+    pub locale: Option<String>,
+    /// Decimal separator, takes priority over `locale`.
+    pub decimal_separator: Option<char>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataSizeUnit {
+    /// Powers of 1024: KiB, MiB, GiB
+    Binary,
+    /// Powers of 1000: KB, MB, GB
+    Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataRateUnit {
+    Bytes,
+    Bits,
+}
+
+/// Languages using a decimal comma.
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv",
+    "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk", "vi",
+];
+
+/// Prefixes of data units in increasing powers.
+const DATA_PREFIXES: [&str; 6] = ["", "K", "M", "G", "T", "P"];
+
+impl ValueFormat {
+    /// Merge with the options of an element: options set in `other` take priority.
+    pub fn merge(&self, other: Option<&ValueFormat>) -> ValueFormat {
+        let Some(other) = other else {
+            return self.clone();
+        };
+        ValueFormat {
+            temperature: other.temperature.or(self.temperature),
+            data_size: other.data_size.or(self.data_size),
+            data_rate: other.data_rate.or(self.data_rate),
+            locale: other.locale.clone().or_else(|| self.locale.clone()),
+            decimal_separator: other.decimal_separator.or(self.decimal_separator),
+        }
+    }
+
+    /// The decimal separator of the configured separator or locale. Default: `.`
+    pub fn decimal_separator(&self) -> char {
+        if let Some(separator) = self.decimal_separator {
+            return separator;
+        }
+        // e.g. `de_CH.UTF-8`
+        let locale = self.locale.as_deref().unwrap_or_default();
+        let locale = locale.split('.').next().unwrap_or_default().to_lowercase();
+        let (language, region) = locale.split_once(['-', '_']).unwrap_or((&locale, ""));
+        match (language, region) {
+            // Swiss German and Italian use a decimal point
+            ("de" | "it", "ch") => '.',
+            (language, _) if DECIMAL_COMMA_LANGUAGES.contains(&language) => ',',
+            _ => '.',
+        }
+    }
+
+    /// Convert a value to the configured units.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: number to convert
+    /// * `unit`: unit of the value, e.g. `°C`, `MiB` or `MB/s`
+    ///
+    /// returns: the converted value and its unit, or the unchanged value and unit if the unit is
+    /// not recognized or already matches the configuration.
+    pub fn convert(&self, value: f64, unit: &str) -> (f64, String) {
+        let symbol = unit.trim_start();
+        let space = &unit[..unit.len() - symbol.len()];
+
+        match (symbol, self.temperature) {
+            ("°C" | "℃" | "C", Some(TemperatureUnit::Fahrenheit)) => {
+                return (value * 9.0 / 5.0 + 32.0, format!("{space}°F"));
+            }
+            ("°F" | "℉" | "F", Some(TemperatureUnit::Celsius)) => {
+                return ((value - 32.0) * 5.0 / 9.0, format!("{space}°C"));
+            }
+            _ => {}
+        }
+
+        let Some(mut data) = DataUnit::parse(symbol) else {
+            return (value, unit.to_string());
+        };
+        let bytes = value * data.factor();
+        if data.per_second
+            && let Some(rate) = self.data_rate
+        {
+            data.bits = rate == DataRateUnit::Bits;
+        }
+        if data.bits {
+            // bit rates use decimal prefixes
+            data.binary = false;
+        } else if let Some(size) = self.data_size {
+            data.binary = size == DataSizeUnit::Binary;
+        }
+        let converted = data.to_string();
+        if converted == symbol {
+            return (value, unit.to_string());
+        }
+        (bytes / data.factor(), format!("{space}{converted}"))
+    }
+}
+
+/// A data size or rate unit, e.g. `MiB` or `Mbit/s`.
+#[derive(Debug, PartialEq)]
+struct DataUnit {
+    /// Index in [DATA_PREFIXES]
+    power: usize,
+    binary: bool,
+    bits: bool,
+    per_second: bool,
+}
+
+impl DataUnit {
+    fn parse(unit: &str) -> Option<DataUnit> {
+        let (unit, per_second) = match unit.strip_suffix("/s") {
+            Some(unit) => (unit, true),
+            None => (unit, false),
+        };
+        let (prefix, bits) = if let Some(prefix) = unit
+            .strip_suffix("bit")
+            .or_else(|| unit.strip_suffix("Bit"))
+        {
+            (prefix, true)
+        } else {
+            (unit.strip_suffix('B')?, false)
+        };
+        let (prefix, binary) = match prefix.strip_suffix('i') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let prefix = prefix.to_uppercase();
+        let power = DATA_PREFIXES.iter().position(|p| *p == prefix)?;
+        Some(DataUnit {
+            power,
+            binary,
+            bits,
+            per_second,
+        })
+    }
+
+    /// Number of bytes of one unit.
+    fn factor(&self) -> f64 {
+        let base: f64 = if self.binary { 1024.0 } else { 1000.0 };
+        let bytes = base.powi(self.power as i32);
+        if self.bits { bytes / 8.0 } else { bytes }
+    }
+}
+
+impl std::fmt::Display for DataUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let prefix = match (self.power, self.bits) {
+            (1, true) => "k",
+            (power, _) => DATA_PREFIXES[power],
+        };
+        let binary = if self.binary && self.power > 0 {
+            "i"
+        } else {
+            ""
+        };
+        let symbol = if self.bits { "bit" } else { "B" };
+        let per_second = if self.per_second { "/s" } else { "" };
+        write!(f, "{prefix}{binary}{symbol}{per_second}")
+    }
+}
+
 /// Format a sensor value in string format to the specified fixed point number.
 ///
 /// # Arguments
@@ -56,11 +254,67 @@ pub fn format_value(
     integer_digits: IntegerDigits,
     decimal_digits: usize,
     unit: &str,
+) -> String {
+    format_value_with(
+        value,
+        integer_digits,
+        decimal_digits,
+        unit,
+        &ValueFormat::default(),
+    )
+}
+
+/// Format a sensor value like [format_value], with unit conversion and decimal separator.
+///
+/// Values with an included unit like `1.50 MB/s` are converted as well, if no separate unit is
+/// given.
+///
+/// # Examples
+///
+/// ```
+/// use asterctl::{IntegerDigits, TemperatureUnit, ValueFormat, format_value_with};
+///
+/// let format = ValueFormat {
+///     temperature: Some(TemperatureUnit::Fahrenheit),
+///     locale: Some("de-DE".into()),
+///     ..Default::default()
+/// };
+/// let value = format_value_with("21.5", IntegerDigits::Auto, 1, "°C", &format);
+/// assert_eq!(value, "70,7°F");
+/// ```
+pub fn format_value_with(
+    value: &str,
+    integer_digits: IntegerDigits,
+    decimal_digits: usize,
+    unit: &str,
+    format: &ValueFormat,
 ) -> String {
     let num = match value.parse::<f64>() {
         Ok(n) => n,
-        Err(_) => return format!("{}{}", value, unit),
+        Err(_) => {
+            // value with an included unit, e.g. `1.50 MB/s`
+            if unit.is_empty()
+                && let Some((number, unit)) = value.split_once(' ')
+                && let Ok(number) = number.parse::<f64>()
+                && format != &ValueFormat::default()
+            {
+                let (number, unit) = format.convert(number, unit);
+                let decimals = value
+                    .split_once(' ')
+                    .and_then(|(n, _)| n.split_once('.'))
+                    .map_or(0, |(_, d)| d.len());
+                return format_value_with(
+                    &number.to_string(),
+                    IntegerDigits::Auto,
+                    decimals,
+                    &format!(" {unit}"),
+                    format,
+                );
+            }
+            return format!("{}{}", value, unit);
+        }
     };
+    let (num, unit) = format.convert(num, unit);
 
     // Round number to the specified decimal digits
     let factor = 10f64.powi(decimal_digits as i32);
@@ -101,7 +355,12 @@ pub fn format_value(
     };
 
     let formatted = if decimal_digits > 0 {
-        format!("{}.{}", integer_filled, decimal_part)
+        format!(
+            "{}{}{}",
+            integer_filled,
+            format.decimal_separator(),
+            decimal_part
+        )
     } else {
         integer_filled
     };
@@ -198,4 +457,50 @@ mod tests {
         let result = format_value(input, IntegerDigits::from(digits), decimals, unit);
         assert_eq!(output, result);
     }
+
+    fn value_format(json: &str) -> ValueFormat {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[rstest]
+    #[case(r#"{"temperature": "fahrenheit"}"#, "21.5", 1, "°C", "70.7°F")]
+    #[case(r#"{"temperature": "fahrenheit"}"#, "70.7", 1, "°F", "70.7°F")]
+    #[case(r#"{"temperature": "celsius"}"#, "212", 0, " °F", "100 °C")]
+    #[case(r#"{"dataSize": "decimal"}"#, "1024", 0, "MiB", "1074MB")]
+    #[case(r#"{"dataSize": "binary"}"#, "1000", 1, " GB", "931.3 GiB")]
+    #[case(r#"{"dataSize": "binary"}"#, "1000", 0, "GiB", "1000GiB")]
+    #[case(r#"{"dataRate": "bits"}"#, "12.5", 0, "MB/s", "100Mbit/s")]
+    #[case(r#"{"dataRate": "bits"}"#, "1", 1, "KiB/s", "8.2kbit/s")]
+    #[case(r#"{"dataRate": "bytes"}"#, "866.7", 1, "MBit/s", "108.3MB/s")]
+    #[case(r#"{"dataRate": "bits"}"#, "2.50 MB/s", 0, "", "20.00 Mbit/s")]
+    #[case(r#"{"locale": "de-DE"}"#, "1.999", 2, "%", "2,00%")]
+    #[case(r#"{"locale": "de_CH.UTF-8"}"#, "1.5", 1, "", "1.5")]
+    #[case(r#"{"locale": "fr", "decimalSeparator": "."}"#, "1.5", 1, "", "1.5")]
+    #[case(r#"{"temperature": "fahrenheit"}"#, "N/A", 1, "°C", "N/A°C")]
+    fn test_format_value_with_format(
+        #[case] format: &str,
+        #[case] input: &str,
+        #[case] decimals: usize,
+        #[case] unit: &str,
+        #[case] output: &str,
+    ) {
+        let result = format_value_with(
+            input,
+            IntegerDigits::Auto,
+            decimals,
+            unit,
+            &value_format(format),
+        );
+        assert_eq!(output, result);
+    }
+
+    #[test]
+    fn element_value_format_overrides_setup() {
+        let setup = value_format(r#"{"temperature": "fahrenheit", "locale": "de"}"#);
+        let element = value_format(r#"{"temperature": "celsius"}"#);
+        let merged = setup.merge(Some(&element));
+        assert_eq!(Some(TemperatureUnit::Celsius), merged.temperature);
+        assert_eq!(',', merged.decimal_separator());
+        assert_eq!(setup, setup.merge(None));
+    }
 }
@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Frame counters and timings for the on-screen debug overlay.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Time window of the frame rate measurement.
+const FPS_WINDOW: Duration = Duration::from_secs(5);
+
+/// Global and per-page frame counters with render and transfer times.
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    /// Number of rendered frames.
+    frames: u64,
+    /// Current pages of all slots.
+    pages: Vec<String>,
+    /// Number of rendered frames since the last page change.
+    page_frames: u64,
+    render_time: Duration,
+    /// Transfer time of the last sent frame.
+    transfer_time: Option<Duration>,
+    /// Render times of the frames within the [FPS_WINDOW].
+    frame_times: VecDeque<Instant>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a rendered frame showing the given pages.
+    pub fn frame_rendered(&mut self, pages: Vec<String>, render_time: Duration, now: Instant) {
+        self.frames += 1;
+        if pages == self.pages {
+            self.page_frames += 1;
+        } else {
+            self.pages = pages;
+            self.page_frames = 1;
+        }
+        self.render_time = render_time;
+
+        while self
+            .frame_times
+            .front()
+            .is_some_and(|time| now.duration_since(*time) > FPS_WINDOW)
+        {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(now);
+    }
+
+    /// Record the transfer time of a frame sent to the display.
+    pub fn frame_sent(&mut self, transfer_time: Duration) {
+        self.transfer_time = Some(transfer_time);
+    }
+
+    /// Total number of rendered frames.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Number of rendered frames of the current pages.
+    pub fn page_frames(&self) -> u64 {
+        self.page_frames
+    }
+
+    /// Average frame rate of the last seconds.
+    pub fn fps(&self) -> f32 {
+        match (self.frame_times.front(), self.frame_times.back()) {
+            (Some(first), Some(last)) if self.frame_times.len() > 1 => {
+                let elapsed = last.duration_since(*first).as_secs_f32();
+                (self.frame_times.len() - 1) as f32 / elapsed.max(f32::EPSILON)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// One-line summary for the debug overlay, with the transfer time of the previous frame.
+    pub fn summary(&self) -> String {
+        let transfer = self
+            .transfer_time
+            .map_or("-".to_string(), |time| format!("{}ms", time.as_millis()));
+        format!(
+            "#{} page #{} | render {}ms | transfer {transfer} | {:.1} fps",
+            self.frames,
+            self.page_frames,
+            self.render_time.as_millis(),
+            self.fps()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_frames_per_page() {
+        let mut stats = FrameStats::new();
+        let start = Instant::now();
+        let pages = |name: &str| vec![name.to_string()];
+
+        stats.frame_rendered(pages("cpu"), Duration::from_millis(12), start);
+        assert_eq!(
+            "#1 page #1 | render 12ms | transfer - | 0.0 fps",
+            stats.summary()
+        );
+
+        stats.frame_sent(Duration::from_millis(340));
+        for i in 1..5 {
+            stats.frame_rendered(
+                pages("cpu"),
+                Duration::from_millis(10),
+                start + i * Duration::from_millis(500),
+            );
+        }
+        assert_eq!((5, 5), (stats.frames(), stats.page_frames()));
+        assert_eq!(2.0, stats.fps());
+
+        stats.frame_rendered(
+            pages("gpu"),
+            Duration::from_millis(10),
+            start + Duration::from_secs(10),
+        );
+        assert_eq!((6, 1), (stats.frames(), stats.page_frames()));
+        assert_eq!(
+            "#6 page #1 | render 10ms | transfer 340ms | 0.0 fps",
+            stats.summary(),
+            "frames outside of the time window are ignored"
+        );
+    }
+}
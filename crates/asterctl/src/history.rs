@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! In-memory sensor value history.
+//!
+//! Numeric sensor values are aggregated into fixed time buckets, by default one-minute buckets
+//! for the last 24 hours. The history is used for charts and min/max values on history pages.
+
+use crate::sensors::SensorStore;
+use log::info;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Aggregated values of one time bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistorySample {
+    /// Start of the bucket in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Average value in the bucket.
+    pub avg: f32,
+    /// Minimum value in the bucket.
+    pub min: f32,
+    /// Maximum value in the bucket.
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start: u64,
+    sum: f32,
+    count: u32,
+    min: f32,
+    max: f32,
+}
+
+impl Bucket {
+    fn new(start: u64, value: f32) -> Self {
+        Self {
+            start,
+            sum: value,
+            count: 1,
+            min: value,
+            max: value,
+        }
+    }
+
+    fn add(&mut self, value: f32) {
+        self.sum += value;
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn sample(&self) -> HistorySample {
+        HistorySample {
+            timestamp: self.start,
+            avg: self.sum / self.count as f32,
+            min: self.min,
+            max: self.max,
+        }
+    }
+}
+
+/// Time-bucketed history of all numeric sensor values.
+#[derive(Debug)]
+pub struct SensorHistory {
+    retention: u64,
+    resolution: u64,
+    series: HashMap<String, VecDeque<Bucket>>,
+}
+
+impl Default for SensorHistory {
+    /// 24 hours of one-minute buckets.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(24 * 3600), Duration::from_secs(60))
+    }
+}
+
+impl SensorHistory {
+    /// Create a new sensor history.
+    ///
+    /// # Arguments
+    ///
+    /// * `retention`: how long values are kept.
+    /// * `resolution`: bucket size, values within a bucket are aggregated.
+    pub fn new(retention: Duration, resolution: Duration) -> Self {
+        Self {
+            retention: retention.as_secs().max(1),
+            resolution: resolution.as_secs().max(1),
+            series: HashMap::new(),
+        }
+    }
+
+    /// History retention time.
+    pub fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention)
+    }
+
+    /// Record all numeric sensor values with the current time.
+    pub fn record(&mut self, values: &SensorStore) {
+        self.record_at(values, unix_time());
+    }
+
+    /// Record all numeric sensor values at the given time in seconds since the Unix epoch.
+    ///
+    /// Non-numeric values are ignored.
+    pub fn record_at(&mut self, values: &SensorStore, timestamp: u64) {
+        let bucket_start = timestamp - timestamp % self.resolution;
+        let oldest = timestamp.saturating_sub(self.retention);
+
+        for (key, sensor) in values.iter() {
+            let Ok(value) = sensor.value.trim().parse::<f32>() else {
+                continue;
+            };
+            if !value.is_finite() {
+                continue;
+            }
+
+            let buckets = self.series.entry(key.clone()).or_default();
+            match buckets.back_mut() {
+                Some(bucket) if bucket.start == bucket_start => bucket.add(value),
+                Some(bucket) if bucket.start > bucket_start => {}
+                _ => buckets.push_back(Bucket::new(bucket_start, value)),
+            }
+        }
+
+        for buckets in self.series.values_mut() {
+            while buckets.front().is_some_and(|b| b.start < oldest) {
+                buckets.pop_front();
+            }
+        }
+        self.series.retain(|_, buckets| !buckets.is_empty());
+    }
+
+    /// Recorded samples of a sensor, oldest first.
+    pub fn samples(&self, key: &str) -> Vec<HistorySample> {
+        self.series
+            .get(key)
+            .map(|buckets| buckets.iter().map(Bucket::sample).collect())
+            .unwrap_or_default()
+    }
+
+    /// Minimum and maximum recorded value of a sensor.
+    pub fn min_max(&self, key: &str) -> Option<(f32, f32)> {
+        self.series.get(key)?.iter().fold(None, |acc, b| match acc {
+            None => Some((b.min, b.max)),
+            Some((min, max)) => Some((b.min.min(min), b.max.max(max))),
+        })
+    }
+}
+
+/// Current time in seconds since the Unix epoch.
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Start a background thread recording the shared sensor values into the history.
+///
+/// # Arguments
+///
+/// * `values`: shared sensor values.
+/// * `history`: shared sensor history.
+/// * `interval`: record interval.
+///
+/// returns: Result<(), Error>
+pub fn start_history_recorder(
+    values: Arc<RwLock<SensorStore>>,
+    history: Arc<RwLock<SensorHistory>>,
+    interval: Duration,
+) -> anyhow::Result<()> {
+    info!(
+        "Starting sensor history recorder with interval={}ms",
+        interval.as_millis()
+    );
+
+    std::thread::Builder::new()
+        .name("history".into())
+        .spawn(move || {
+            loop {
+                let start = Instant::now();
+                {
+                    let values = values.read().expect("Poisoned sensor RwLock");
+                    let mut history = history.write().expect("Poisoned history RwLock");
+                    history.record(&values);
+                }
+                let elapsed = start.elapsed();
+                if interval > elapsed {
+                    std::thread::sleep(interval - elapsed);
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(value: &str) -> SensorStore {
+        SensorStore::from_iter([
+            ("temperature_cpu".to_string(), value.to_string()),
+            ("temperature_cpu#unit".to_string(), "°C".to_string()),
+            ("cpu_name".to_string(), "Ryzen".to_string()),
+        ])
+    }
+
+    #[test]
+    fn values_are_aggregated_into_buckets() {
+        let mut history = SensorHistory::new(Duration::from_secs(3600), Duration::from_secs(60));
+        history.record_at(&values("40"), 6000);
+        history.record_at(&values("50"), 6030);
+        history.record_at(&values("45"), 6060);
+
+        assert_eq!(
+            vec![
+                HistorySample {
+                    timestamp: 6000,
+                    avg: 45.0,
+                    min: 40.0,
+                    max: 50.0
+                },
+                HistorySample {
+                    timestamp: 6060,
+                    avg: 45.0,
+                    min: 45.0,
+                    max: 45.0
+                }
+            ],
+            history.samples("temperature_cpu")
+        );
+        assert_eq!(Some((40.0, 50.0)), history.min_max("temperature_cpu"));
+        assert!(history.samples("temperature_cpu#unit").is_empty());
+        assert!(history.samples("cpu_name").is_empty());
+    }
+
+    #[test]
+    fn old_values_are_removed() {
+        let mut history = SensorHistory::new(Duration::from_secs(600), Duration::from_secs(60));
+        history.record_at(&values("90"), 6000);
+        history.record_at(&values("40"), 6300);
+        history.record_at(&values("42"), 6660);
+
+        assert_eq!(2, history.samples("temperature_cpu").len());
+        assert_eq!(Some((40.0, 42.0)), history.min_max("temperature_cpu"));
+        assert_eq!(None, history.min_max("unknown"));
+    }
+}
@@ -0,0 +1,654 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! HTTP control API of the running sensor panel.
+//!
+//! A small embedded HTTP server allows scripts and home automation systems to control the display
+//! remotely and to read the current sensor values. Only available with the `http-api` feature.
+
+use crate::cfg::HttpApiConfig;
+use crate::control::{ControlCommand, ControlHandle, show_duration};
+use crate::logging::{log_filter, recent_logs, set_log_filter};
+use crate::sensors::{SensorStore, SourceHealth};
+use crate::timer::parse_duration;
+use anyhow::anyhow;
+use log::{debug, info, warn};
+use serde_json::{Map, Value, json};
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+use tiny_http::{Header, ListenAddr, Method, Request, Response, Server};
+
+/// Maximum accepted request body size.
+const MAX_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Default listen address of the HTTP control API.
+pub const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:8686";
+
+/// Number of request handler threads. Commands wait for the display loop, a few threads keep
+/// status requests responsive without starting a thread per request.
+const WORKER_THREADS: usize = 4;
+
+/// Shared state of the request handlers.
+struct ApiContext {
+    control: ControlHandle,
+    values: Arc<RwLock<SensorStore>>,
+    health: SourceHealth,
+    token: Option<String>,
+    addr: SocketAddr,
+}
+
+/// Error response with HTTP status code.
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Start the HTTP control API server.
+///
+/// Endpoints:
+/// - `GET /api/status`: display state and sensor source health.
+/// - `GET /api/sensors`: all sensor values.
+/// - `GET /api/sensors/{key}`: a single sensor value with its unit, description, source and update
+///   time.
+/// - `POST /api/display/on`, `POST /api/display/off`: switch the display on or off.
+/// - `POST /api/page/next`: switch to the next page.
+/// - `POST /api/page`: switch to a page, body: `{"page": "temperature_cpu"}`.
+/// - `POST /api/image?duration=10`: show the image in the request body, PNG or JPEG.
+/// - `POST /api/message`: show a message page, body: `{"text": "Build finished", "duration": 30}`.
+/// - `POST /api/timer`: start a countdown timer, body: `{"duration": "25m", "label": "Focus"}`.
+/// - `POST /api/timer/cancel`: cancel the running timer.
+/// - `POST /api/brightness`: set the brightness in percent, body: `{"brightness": 50}`.
+/// - `POST /api/alert/ack`: acknowledge the active alerts.
+/// - `POST /api/statistics/reset`: reset the min/max/average statistics, optional body:
+///   `{"sensor": "temperature_cpu"}` to reset a single sensor.
+/// - `GET /api/log`: current log filter.
+/// - `GET /api/log/recent`: most recent log lines.
+/// - `POST /api/log`: change the log filter, body: `{"filter": "debug"}`, or `null` to restore it.
+///
+/// # Arguments
+///
+/// * `cfg`: listen address and optional access token.
+/// * `control`: control channel of the display loop.
+/// * `values`: a shared, reader-writer lock protected sensor store
+/// * `health`: source health registry.
+///
+/// returns: the bound listen address
+pub fn start_http_api(
+    cfg: &HttpApiConfig,
+    control: ControlHandle,
+    values: Arc<RwLock<SensorStore>>,
+    health: SourceHealth,
+) -> anyhow::Result<SocketAddr> {
+    let listen = cfg.listen.as_deref().unwrap_or(DEFAULT_LISTEN_ADDR);
+    let server = Server::http(listen)
+        .map_err(|e| anyhow!("Failed to start HTTP control API on {listen}: {e}"))?;
+    let addr = match server.server_addr() {
+        ListenAddr::IP(addr) => addr,
+        #[allow(unreachable_patterns)]
+        _ => return Err(anyhow!("Unsupported listen address {listen}")),
+    };
+    if cfg.token.is_none() && !addr.ip().is_loopback() {
+        warn!("HTTP control API on {addr} is accessible without an access token");
+    }
+
+    let ctx = Arc::new(ApiContext {
+        control,
+        values,
+        health,
+        token: cfg.token.clone(),
+        addr,
+    });
+
+    info!("Starting HTTP control API on http://{addr}");
+
+    let server = Arc::new(server);
+    for _ in 0..WORKER_THREADS {
+        let server = server.clone();
+        let ctx = ctx.clone();
+        std::thread::Builder::new()
+            .name("http-api".into())
+            .spawn(move || {
+                for request in server.incoming_requests() {
+                    handle_request(request, &ctx);
+                }
+            })?;
+    }
+
+    Ok(addr)
+}
+
+fn handle_request(mut request: Request, ctx: &ApiContext) {
+    debug!("HTTP API request: {} {}", request.method(), request.url());
+    let result = check_origin(&request, ctx)
+        .and_then(|_| authorize(&request, ctx))
+        .and_then(|_| route(&mut request, ctx));
+    let (status, body) = match result {
+        Ok(body) => (200, body),
+        Err(e) => (e.status, json!({ "error": e.message })),
+    };
+
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        debug!("Failed to send HTTP API response: {e}");
+    }
+}
+
+/// Custom panel directory of a panel request: `{"path": "..."}`.
+fn panel_path(request: &mut Request) -> Result<PathBuf, ApiError> {
+    let body = read_json(request)?;
+    body["path"]
+        .as_str()
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| ApiError::new(400, "Missing panel path"))
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+/// Reject requests of web browsers.
+///
+/// Browsers send an `Origin` header with cross-site requests, so web pages can't send commands
+/// with simple no-cors requests. Without an access token, the `Host` header must name the listen
+/// address or `localhost`, so web pages can't read sensor values with DNS rebinding.
+fn check_origin(request: &Request, ctx: &ApiContext) -> Result<(), ApiError> {
+    if header(request, "Origin").is_some() {
+        return Err(ApiError::new(403, "Cross-origin requests are not allowed"));
+    }
+    if ctx.token.is_some() {
+        return Ok(());
+    }
+    match header(request, "Host") {
+        Some(host) if allowed_host(host, ctx.addr) => Ok(()),
+        _ => Err(ApiError::new(403, "Invalid Host header")),
+    }
+}
+
+/// Check the host name of a `Host` header: `localhost`, the listen IP address, or any IP address
+/// if listening on all interfaces. Host names of other DNS records are rejected.
+fn allowed_host(host: &str, addr: SocketAddr) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split_once(']').map_or(ipv6, |(name, _)| name),
+        None => host.split_once(':').map_or(host, |(name, _)| name),
+    };
+    if name.eq_ignore_ascii_case("localhost") {
+        return true;
+    }
+    name.parse::<IpAddr>()
+        .is_ok_and(|ip| ip == addr.ip() || addr.ip().is_unspecified())
+}
+
+/// Require a request body media type, e.g. `application/json`, or `image/` for all image types.
+fn require_content_type(request: &Request, media_type: &str) -> Result<(), ApiError> {
+    let content_type = header(request, "Content-Type")
+        .and_then(|value| value.split(';').next())
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let matches = match media_type.ends_with('/') {
+        true => content_type.starts_with(media_type),
+        false => content_type == media_type,
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(ApiError::new(
+            415,
+            format!("Content-Type {media_type} required"),
+        ))
+    }
+}
+
+fn authorize(request: &Request, ctx: &ApiContext) -> Result<(), ApiError> {
+    let Some(token) = &ctx.token else {
+        return Ok(());
+    };
+    // constant-time comparison, the response time doesn't reveal matching token prefixes
+    let authorized = header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|bearer| bool::from(bearer.as_bytes().ct_eq(token.as_bytes())));
+    if authorized {
+        Ok(())
+    } else {
+        Err(ApiError::new(401, "Missing or invalid access token"))
+    }
+}
+
+fn route(request: &mut Request, ctx: &ApiContext) -> Result<Value, ApiError> {
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    let path = path.trim_end_matches('/').to_string();
+    let query = query.to_string();
+
+    let command = match (request.method(), path.as_str()) {
+        (Method::Get, "/api/status") => return Ok(status(ctx)),
+        (Method::Get, "/api/sensors") => {
+            let values = ctx.values.read().expect("Poisoned sensor RwLock");
+            let values: Map<String, Value> = values
+                .iter()
+                .map(|(key, sensor)| (key.clone(), json!(sensor.value)))
+                .collect();
+            return Ok(Value::Object(values));
+        }
+        (Method::Get, key) if key.starts_with("/api/sensors/") => {
+            return sensor_value(ctx, &key["/api/sensors/".len()..]);
+        }
+        (Method::Post, "/api/display/on") => ControlCommand::On,
+        (Method::Post, "/api/display/off") => ControlCommand::Off,
+        (Method::Post, "/api/page/next") => ControlCommand::NextPage,
+        (Method::Post, "/api/page") => {
+            let body = read_json(request)?;
+            let page = body["page"]
+                .as_str()
+                .ok_or_else(|| ApiError::new(400, "Missing page"))?;
+            ControlCommand::ShowPage(page.to_string())
+        }
+        (Method::Post, "/api/image") => {
+            require_content_type(request, "image/")?;
+            let duration = query_param(&query, "duration")
+                .map(|d| d.parse::<f64>().ok().and_then(show_duration))
+                .map(|d| d.ok_or_else(|| ApiError::new(400, "Invalid duration")))
+                .transpose()?;
+            let image = image::load_from_memory(&read_body(request)?)
+                .map_err(|e| ApiError::new(400, format!("Invalid image: {e}")))?
+                .to_rgb8();
+            ControlCommand::ShowImage { image, duration }
+        }
+        (Method::Post, "/api/message") => {
+            let body = read_json(request)?;
+            let duration = match &body["duration"] {
+                Value::Null => None,
+                duration => Some(
+                    duration
+                        .as_f64()
+                        .and_then(show_duration)
+                        .ok_or_else(|| ApiError::new(400, "Invalid duration"))?,
+                ),
+            };
+            let message = serde_json::from_value(body)
+                .map_err(|e| ApiError::new(400, format!("Invalid message: {e}")))?;
+            ControlCommand::ShowMessage {
+                message: Box::new(message),
+                duration,
+            }
+        }
+        (Method::Post, "/api/timer") => {
+            let body = read_json(request)?;
+            let duration = match &body["duration"] {
+                Value::String(duration) => {
+                    parse_duration(duration).map_err(|e| ApiError::new(400, e))?
+                }
+                duration => duration
+                    .as_f64()
+                    .filter(|d| *d > 0.0 && *d <= u32::MAX as f64)
+                    .map(Duration::from_secs_f64)
+                    .ok_or_else(|| ApiError::new(400, "Missing or invalid duration"))?,
+            };
+            let label = body["label"].as_str().map(str::to_string);
+            ControlCommand::StartTimer { duration, label }
+        }
+        (Method::Post, "/api/timer/cancel") => ControlCommand::CancelTimer,
+        (Method::Post, "/api/brightness") => {
+            let body = read_json(request)?;
+            let brightness = body["brightness"]
+                .as_u64()
+                .filter(|b| *b <= 100)
+                .ok_or_else(|| ApiError::new(400, "Brightness must be 0 to 100"))?;
+            ControlCommand::SetBrightness(brightness as u8)
+        }
+        (Method::Post, "/api/alert/ack") => ControlCommand::AcknowledgeAlert,
+        (Method::Post, "/api/statistics/reset") => {
+            // the body is optional
+            let body = match read_body(request)? {
+                body if body.trim_ascii().is_empty() => Value::Null,
+                body => {
+                    require_content_type(request, "application/json")?;
+                    serde_json::from_slice(&body)
+                        .map_err(|e| ApiError::new(400, format!("Invalid JSON body: {e}")))?
+                }
+            };
+            match &body["sensor"] {
+                Value::Null => ControlCommand::ResetStatistics(None),
+                Value::String(sensor) if !sensor.is_empty() => {
+                    ControlCommand::ResetStatistics(Some(sensor.clone()))
+                }
+                _ => return Err(ApiError::new(400, "Invalid sensor key")),
+            }
+        }
+        (Method::Post, "/api/panel/load") => ControlCommand::LoadPanel(panel_path(request)?),
+        (Method::Post, "/api/panel/unload") => ControlCommand::UnloadPanel(panel_path(request)?),
+        (Method::Get, "/api/log") => return Ok(json!({ "filter": log_filter() })),
+        (Method::Get, "/api/log/recent") => return Ok(json!({ "lines": recent_logs() })),
+        (Method::Post, "/api/log") => {
+            let body = read_json(request)?;
+            match &body["filter"] {
+                Value::Null => set_log_filter(None),
+                Value::String(filter) if !filter.trim().is_empty() => {
+                    set_log_filter(Some(filter.trim()))
+                }
+                _ => return Err(ApiError::new(400, "Invalid log filter")),
+            }
+            let filter = log_filter();
+            info!(
+                "Log filter changed to {}",
+                filter.as_deref().unwrap_or_default()
+            );
+            return Ok(json!({ "filter": filter }));
+        }
+        (_, path) if path.starts_with("/api/") => {
+            return Err(ApiError::new(404, format!("Unknown endpoint {path}")));
+        }
+        _ => return Err(ApiError::new(404, "Not found")),
+    };
+
+    ctx.control
+        .send(command)
+        .map_err(|e| ApiError::new(409, format!("{e:#}")))?;
+    Ok(json!({ "status": "ok" }))
+}
+
+fn status(ctx: &ApiContext) -> Value {
+    let sources: Map<String, Value> = ctx
+        .health
+        .snapshot()
+        .into_iter()
+        .map(|(name, status)| {
+            let value = json!({
+                "state": status.state(),
+                "lastSuccess": status.last_success.map(|t| t.to_rfc3339()),
+                "consecutiveFailures": status.consecutive_failures,
+                "lastError": status.last_error,
+            });
+            (name, value)
+        })
+        .collect();
+
+    json!({
+        "display": ctx.control.status(),
+        "sources": sources,
+    })
+}
+
+fn sensor_value(ctx: &ApiContext, key: &str) -> Result<Value, ApiError> {
+    let values = ctx.values.read().expect("Poisoned sensor RwLock");
+    let sensor = values
+        .get(key)
+        .ok_or_else(|| ApiError::new(404, format!("Unknown sensor {key}")))?;
+    Ok(json!({
+        "key": key,
+        "value": sensor.value,
+        "unit": sensor.unit,
+        "description": sensor.description,
+        "source": sensor.source,
+        "updatedAt": sensor.updated_at.to_rfc3339(),
+    }))
+}
+
+fn read_body(request: &mut Request) -> Result<Vec<u8>, ApiError> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_SIZE + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| ApiError::new(400, format!("Failed to read request body: {e}")))?;
+    if body.len() as u64 > MAX_BODY_SIZE {
+        return Err(ApiError::new(413, "Request body too large"));
+    }
+    Ok(body)
+}
+
+fn read_json(request: &mut Request) -> Result<Value, ApiError> {
+    require_content_type(request, "application/json")?;
+    serde_json::from_slice(&read_body(request)?)
+        .map_err(|e| ApiError::new(400, format!("Invalid JSON body: {e}")))
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::control_channel;
+    use std::collections::HashMap;
+
+    #[test]
+    fn http_api_endpoints() {
+        let (control, receiver) = control_channel();
+        let mut values = SensorStore::new();
+        values.update(
+            "sysinfo",
+            &HashMap::from([
+                ("temperature_cpu".to_string(), "42".to_string()),
+                ("temperature_cpu#unit".to_string(), "°C".to_string()),
+            ]),
+        );
+        let values = Arc::new(RwLock::new(values));
+        let cfg = HttpApiConfig {
+            listen: Some("127.0.0.1:0".into()),
+            token: Some("secret".into()),
+        };
+        let addr = start_http_api(&cfg, control, values, SourceHealth::new()).unwrap();
+
+        let display_loop = std::thread::spawn(move || {
+            let mut brightness = None;
+            receiver.handle_next(Duration::from_secs(5), |command| {
+                if let ControlCommand::SetBrightness(b) = command {
+                    brightness = Some(b);
+                }
+                Ok(())
+            });
+            brightness
+        });
+
+        let url = |path: &str| format!("http://{addr}{path}");
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        let response = agent
+            .get(url("/api/sensors/temperature_cpu"))
+            .call()
+            .unwrap();
+        assert_eq!(401, response.status());
+        for token in ["Bearer secreT", "Bearer secret2", "Bearer ", "secret"] {
+            let response = agent
+                .get(url("/api/sensors/temperature_cpu"))
+                .header("Authorization", token)
+                .call()
+                .unwrap();
+            assert_eq!(401, response.status());
+        }
+
+        let mut response = agent
+            .get(url("/api/sensors/temperature_cpu"))
+            .header("Authorization", "Bearer secret")
+            .call()
+            .unwrap();
+        assert_eq!(200, response.status());
+        let sensor = response.body_mut().read_json::<Value>().unwrap();
+        assert_eq!("42", sensor["value"]);
+        assert_eq!("°C", sensor["unit"]);
+        assert_eq!(Value::Null, sensor["description"]);
+        assert_eq!("sysinfo", sensor["source"]);
+        assert!(sensor["updatedAt"].is_string());
+
+        let mut response = agent
+            .get(url("/api/sensors"))
+            .header("Authorization", "Bearer secret")
+            .call()
+            .unwrap();
+        assert_eq!(
+            json!({ "temperature_cpu": "42" }),
+            response.body_mut().read_json::<Value>().unwrap()
+        );
+
+        let response = agent
+            .post(url("/api/brightness"))
+            .header("Authorization", "Bearer secret")
+            .send_json(json!({ "brightness": 30 }))
+            .unwrap();
+        assert_eq!(200, response.status());
+        assert_eq!(Some(30), display_loop.join().unwrap());
+
+        let response = agent
+            .post(url("/api/brightness"))
+            .header("Authorization", "Bearer secret")
+            .send_json(json!({ "brightness": 300 }))
+            .unwrap();
+        assert_eq!(400, response.status());
+
+        let response = agent
+            .post(url("/api/message"))
+            .header("Authorization", "Bearer secret")
+            .send_json(json!({ "title": "CI", "duration": 30 }))
+            .unwrap();
+        assert_eq!(400, response.status());
+
+        for duration in ["inf", "NaN", "1e19", "-1"] {
+            let mut response = agent
+                .post(url(&format!("/api/image?duration={duration}")))
+                .header("Authorization", "Bearer secret")
+                .header("Content-Type", "image/png")
+                .send(&[])
+                .unwrap();
+            assert_eq!(400, response.status());
+            let error = response.body_mut().read_json::<Value>().unwrap();
+            assert_eq!("Invalid duration", error["error"]);
+        }
+
+        for duration in [json!(1e19), json!(-1), json!("30")] {
+            let response = agent
+                .post(url("/api/message"))
+                .header("Authorization", "Bearer secret")
+                .send_json(json!({ "text": "CI", "duration": duration }))
+                .unwrap();
+            assert_eq!(400, response.status());
+        }
+
+        let response = agent
+            .post(url("/api/timer"))
+            .header("Authorization", "Bearer secret")
+            .send_json(json!({ "duration": "25 minutes" }))
+            .unwrap();
+        assert_eq!(400, response.status());
+
+        let response = agent
+            .post(url("/api/log"))
+            .header("Authorization", "Bearer secret")
+            .send_json(json!({ "filter": " " }))
+            .unwrap();
+        assert_eq!(400, response.status());
+
+        // no-cors requests of web pages
+        let response = agent
+            .post(url("/api/log"))
+            .header("Authorization", "Bearer secret")
+            .header("Content-Type", "text/plain")
+            .send(r#"{"filter": "trace"}"#)
+            .unwrap();
+        assert_eq!(415, response.status());
+        let response = agent
+            .post(url("/api/image"))
+            .header("Authorization", "Bearer secret")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .send(&[])
+            .unwrap();
+        assert_eq!(415, response.status());
+        let response = agent
+            .post(url("/api/statistics/reset"))
+            .header("Authorization", "Bearer secret")
+            .send(r#"{"sensor": "temperature_cpu"}"#)
+            .unwrap();
+        assert_eq!(415, response.status());
+        let response = agent
+            .get(url("/api/sensors"))
+            .header("Authorization", "Bearer secret")
+            .header("Origin", "https://example.com")
+            .call()
+            .unwrap();
+        assert_eq!(403, response.status());
+    }
+
+    #[test]
+    fn http_api_without_token_rejects_browser_requests() {
+        let (control, _receiver) = control_channel();
+        let values = Arc::new(RwLock::new(SensorStore::new()));
+        let cfg = HttpApiConfig {
+            listen: Some("127.0.0.1:0".into()),
+            token: None,
+        };
+        let addr = start_http_api(&cfg, control, values, SourceHealth::new()).unwrap();
+
+        let url = format!("http://{addr}/api/sensors");
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into();
+        let get = |host: Option<String>, origin: Option<&str>| {
+            let mut request = agent.get(&url);
+            if let Some(host) = host {
+                request = request.header("Host", host);
+            }
+            if let Some(origin) = origin {
+                request = request.header("Origin", origin);
+            }
+            request.call().unwrap().status()
+        };
+
+        let port = addr.port();
+        assert_eq!(200, get(None, None));
+        assert_eq!(200, get(Some(format!("localhost:{port}")), None));
+        assert_eq!(200, get(Some("LOCALHOST".into()), None));
+        // DNS rebinding of an attacker's host name to the loopback address
+        assert_eq!(403, get(Some(format!("attacker.example:{port}")), None));
+        assert_eq!(403, get(Some(format!("10.0.0.1:{port}")), None));
+        assert_eq!(403, get(None, Some("http://attacker.example")));
+        assert_eq!(403, get(None, Some("null")));
+    }
+
+    #[test]
+    fn allowed_host_names() {
+        let loopback: SocketAddr = "127.0.0.1:8686".parse().unwrap();
+        assert!(allowed_host("127.0.0.1:8686", loopback));
+        assert!(allowed_host("127.0.0.1", loopback));
+        assert!(allowed_host("localhost:8686", loopback));
+        assert!(!allowed_host("127.0.0.2:8686", loopback));
+        assert!(!allowed_host("localhost.attacker.example:8686", loopback));
+
+        let ipv6: SocketAddr = "[::1]:8686".parse().unwrap();
+        assert!(allowed_host("[::1]:8686", ipv6));
+        assert!(!allowed_host("[::2]:8686", ipv6));
+
+        let any: SocketAddr = "0.0.0.0:8686".parse().unwrap();
+        assert!(allowed_host("192.168.1.20:8686", any));
+        assert!(!allowed_host("nas.local:8686", any));
+    }
+}
@@ -4,12 +4,17 @@
 
 //! Image helper functions.
 
-use image::imageops::FilterType;
-use image::{DynamicImage, GenericImageView, ImageBuffer, ImageReader, Rgba, RgbaImage};
+use crate::cfg::FontColor;
+use image::imageops::{self, FilterType};
+use image::{
+    DynamicImage, GenericImageView, ImageBuffer, ImageReader, Pixel, Rgb, Rgba, RgbaImage,
+};
 use imageproc::geometric_transformations::{Interpolation, rotate};
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::f32::consts::PI;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 
 /// Width, height type
@@ -20,6 +25,11 @@ where
     P: AsRef<Path>,
 {
     let img = ImageReader::open(path)?.decode()?;
+    Ok(resize_image(img, size))
+}
+
+/// Resize an image to the expected size, if set and different. The aspect ratio is ignored.
+pub fn resize_image(img: DynamicImage, size: Option<Size>) -> DynamicImage {
     debug!(
         "Image dimensions: {:?}, {:?}",
         img.dimensions(),
@@ -34,9 +44,110 @@ where
             img.dimensions(),
             size
         );
-        Ok(img.resize_exact(size.0, size.1, FilterType::Lanczos3))
+        img.resize_exact(size.0, size.1, FilterType::Lanczos3)
     } else {
-        Ok(img)
+        img
+    }
+}
+
+/// Image adjustments applied to a loaded image, e.g. to dim a panel background.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageAdjust {
+    /// Brightness factor: `0.5` darkens the image to half the brightness. Default: 1.0
+    pub brightness: Option<f32>,
+    /// Contrast change in percent: negative values reduce the contrast. Default: 0
+    pub contrast: Option<f32>,
+    /// Saturation factor: `0.0` is grayscale, values above 1.0 increase the saturation. Default: 1.0
+    pub saturation: Option<f32>,
+    /// Gaussian blur radius (sigma) in pixels. Default: 0
+    pub blur: Option<f32>,
+}
+
+impl Eq for ImageAdjust {}
+
+impl Hash for ImageAdjust {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for value in [self.brightness, self.contrast, self.saturation, self.blur] {
+            value.map(f32::to_bits).hash(state);
+        }
+    }
+}
+
+/// Apply the image adjustments in the order blur, saturation, contrast and brightness.
+///
+/// The color adjustments don't modify the alpha channel.
+pub fn adjust_image(image: &mut RgbaImage, adjust: &ImageAdjust) {
+    if let Some(sigma) = adjust.blur.filter(|sigma| *sigma > 0.0) {
+        *image = imageops::blur(image, sigma);
+    }
+    let saturation = adjust.saturation.unwrap_or(1.0).max(0.0);
+    let contrast = ((100.0 + adjust.contrast.unwrap_or_default()) / 100.0)
+        .max(0.0)
+        .powi(2);
+    let brightness = adjust.brightness.unwrap_or(1.0).max(0.0);
+    if saturation == 1.0 && contrast == 1.0 && brightness == 1.0 {
+        return;
+    }
+    for pixel in image.pixels_mut() {
+        let [r, g, b, _] = pixel.0.map(f32::from);
+        // Rec. 601 luma
+        let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+        for (channel, value) in pixel.0.iter_mut().zip([r, g, b]) {
+            let value = luma + (value - luma) * saturation;
+            let value = (value - 127.5) * contrast + 127.5;
+            *channel = (value * brightness).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Color calibration of the display, applied to all display images.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorCalibration {
+    /// Gamma correction: values above 1.0 brighten the mid-tones, values below 1.0 darken them.
+    /// Default: 1.0
+    pub gamma: Option<f32>,
+    /// Color shown for white, e.g. `#fff0e0` for a warmer or `#e8f0ff` for a cooler display.
+    /// Default: `#ffffff`
+    pub white_point: Option<FontColor>,
+}
+
+/// Color lookup table for the red, green and blue channels.
+#[derive(Debug, Clone)]
+pub struct ColorLut {
+    channels: [[u8; 256]; 3],
+}
+
+impl ColorLut {
+    /// Create the lookup table of a color calibration, `None` if it doesn't change any color.
+    pub fn new(calibration: &ColorCalibration) -> Option<Self> {
+        let gamma = calibration.gamma.unwrap_or(1.0).max(0.01);
+        let white = Rgb::from(calibration.white_point.unwrap_or_default()).0;
+        if gamma == 1.0 && white == [255, 255, 255] {
+            return None;
+        }
+
+        let mut channels = [[0; 256]; 3];
+        for (lut, white) in channels.iter_mut().zip(white) {
+            for (value, out) in lut.iter_mut().enumerate() {
+                let corrected = (value as f32 / 255.0).powf(1.0 / gamma) * f32::from(white);
+                *out = corrected.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+        Some(Self { channels })
+    }
+
+    /// Apply the lookup table to an RGB or RGBA image, the alpha channel is not modified.
+    pub fn apply<P>(&self, image: &mut ImageBuffer<P, Vec<u8>>)
+    where
+        P: Pixel<Subpixel = u8>,
+    {
+        for pixel in image.pixels_mut() {
+            for (channel, lut) in pixel.channels_mut().iter_mut().zip(&self.channels) {
+                *channel = lut[*channel as usize];
+            }
+        }
     }
 }
 
@@ -44,6 +155,7 @@ where
 pub struct ImageCache {
     img_path: PathBuf,
     cache: HashMap<PathBuf, Option<RgbaImage>>,
+    adjusted: HashMap<(PathBuf, ImageAdjust), RgbaImage>,
 }
 
 impl ImageCache {
@@ -51,17 +163,13 @@ impl ImageCache {
         Self {
             img_path: img_path.into(),
             cache: HashMap::new(),
+            adjusted: HashMap::new(),
         }
     }
 
     /// Load and cache an image, returns None if loading fails
     pub fn get<P: AsRef<Path>>(&mut self, path: P, size: Option<Size>) -> Option<&RgbaImage> {
-        let path = path.as_ref();
-        let path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            self.img_path.join(path)
-        };
+        let path = self.resolve(path.as_ref());
 
         if !self.cache.contains_key(&path) {
             let image_result = match load_image(&path, size) {
@@ -78,9 +186,34 @@ impl ImageCache {
         self.cache.get(&path).and_then(|opt| opt.as_ref())
     }
 
+    /// Load and cache an image with the given adjustments, returns None if loading fails
+    pub fn get_adjusted<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        size: Option<Size>,
+        adjust: &ImageAdjust,
+    ) -> Option<&RgbaImage> {
+        let key = (self.resolve(path.as_ref()), adjust.clone());
+        if !self.adjusted.contains_key(&key) {
+            let mut image = self.get(&key.0, size)?.clone();
+            adjust_image(&mut image, adjust);
+            self.adjusted.insert(key.clone(), image);
+        }
+        self.adjusted.get(&key)
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.img_path.join(path)
+        }
+    }
+
     #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.cache.clear();
+        self.adjusted.clear();
     }
 }
 
@@ -174,3 +307,55 @@ pub fn rotate_180_degrees(image: &RgbaImage) -> RgbaImage {
 
     rotated
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjust_image_keeps_alpha() {
+        let mut image = RgbaImage::from_pixel(4, 4, Rgba([200, 100, 50, 128]));
+        adjust_image(&mut image, &ImageAdjust::default());
+        assert_eq!(&Rgba([200, 100, 50, 128]), image.get_pixel(0, 0));
+
+        let dim = ImageAdjust {
+            brightness: Some(0.5),
+            ..Default::default()
+        };
+        adjust_image(&mut image, &dim);
+        assert_eq!(&Rgba([100, 50, 25, 128]), image.get_pixel(0, 0));
+
+        let grayscale = ImageAdjust {
+            saturation: Some(0.0),
+            ..Default::default()
+        };
+        adjust_image(&mut image, &grayscale);
+        assert_eq!(&Rgba([62, 62, 62, 128]), image.get_pixel(0, 0));
+
+        let contrast = ImageAdjust {
+            contrast: Some(-100.0),
+            blur: Some(1.0),
+            ..Default::default()
+        };
+        adjust_image(&mut image, &contrast);
+        assert_eq!(&Rgba([128, 128, 128, 128]), image.get_pixel(2, 2));
+    }
+
+    #[test]
+    fn color_calibration() {
+        assert!(ColorLut::new(&ColorCalibration::default()).is_none());
+
+        let calibration = ColorCalibration {
+            gamma: Some(2.0),
+            white_point: Some(FontColor::try_from("#ff8000").unwrap()),
+        };
+        let lut = ColorLut::new(&calibration).unwrap();
+        let mut image = image::RgbImage::from_pixel(2, 2, Rgb([64, 255, 255]));
+        lut.apply(&mut image);
+        assert_eq!(&Rgb([128, 128, 0]), image.get_pixel(0, 0));
+
+        let mut image = RgbaImage::from_pixel(2, 2, Rgba([255, 16, 0, 100]));
+        lut.apply(&mut image);
+        assert_eq!(&Rgba([255, 32, 0, 100]), image.get_pixel(1, 1));
+    }
+}
@@ -5,11 +5,40 @@
 #![forbid(non_ascii_idents)]
 #![deny(unsafe_code)]
 
+pub mod alerts;
+pub mod animation;
 pub mod cfg;
+pub mod check;
+pub mod control;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+pub mod digits;
+pub mod engine;
+pub mod expr;
 pub mod font;
 mod format_value;
+pub mod frame_stats;
+pub mod history;
+#[cfg(feature = "http-api")]
+pub mod http_api;
 pub mod img;
+pub mod logging;
+pub mod output;
+pub mod package;
+pub mod pages;
+pub mod power_state;
+pub mod preview;
+#[cfg(feature = "preview-window")]
+pub mod preview_window;
+pub mod refresh;
+pub mod remote_image;
 pub mod render;
+pub mod report;
+pub mod secrets;
 pub mod sensors;
+pub mod smoothing;
+pub mod ticker;
+pub mod timer;
+pub mod transition;
 
 pub use format_value::*;
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Logging with a log filter changeable at runtime.
+//!
+//! The log filter uses the `RUST_LOG` syntax of `env_logger`, e.g. `debug` or
+//! `info,asterctl_lcd=debug`. It can be changed while the sensor panel is running, e.g. to
+//! temporarily enable debug logging of the display protocol without resetting the display.
+//!
+//! The most recent log lines are kept in memory for error reports, see [recent_logs].
+
+use log::{Log, Metadata, Record, info};
+use once_cell::sync::OnceCell;
+use std::collections::VecDeque;
+use std::sync::{Mutex, RwLock};
+
+/// Log filter toggled by [toggle_debug_logging].
+pub const DEBUG_FILTER: &str = "debug";
+
+/// Number of log lines kept in memory.
+const RECENT_LOG_LINES: usize = 1000;
+
+static LOGGER: OnceCell<RuntimeLogger> = OnceCell::new();
+
+struct RuntimeLogger {
+    /// Log filter from the `RUST_LOG` environment variable or the default filter.
+    default_filter: String,
+    default: env_logger::Logger,
+    /// Log filter set at runtime.
+    current: RwLock<Option<(String, env_logger::Logger)>>,
+    recent: Mutex<VecDeque<String>>,
+}
+
+impl RuntimeLogger {
+    fn remember(&self, record: &Record) {
+        let line = format!(
+            "{} {:<5} {}: {}",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        let mut recent = self.recent.lock().expect("Mutex is poisoned");
+        if recent.len() == RECENT_LOG_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(line);
+    }
+}
+
+impl Log for RuntimeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        match &*self.current.read().expect("RwLock is poisoned") {
+            Some((_, logger)) => logger.enabled(metadata),
+            None => self.default.enabled(metadata),
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        let current = self.current.read().expect("RwLock is poisoned");
+        let logger = match &*current {
+            Some((_, logger)) => logger,
+            None => &self.default,
+        };
+        if logger.matches(record) {
+            logger.log(record);
+            self.remember(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.default.flush();
+    }
+}
+
+fn build_logger(filter: &str) -> env_logger::Logger {
+    let mut builder = env_logger::Builder::new();
+    builder.parse_filters(filter);
+    if let Ok(write_style) = std::env::var("RUST_LOG_STYLE") {
+        builder.parse_write_style(&write_style);
+    }
+    builder.build()
+}
+
+/// Initialize the global logger with the `RUST_LOG` environment variable, or with the given
+/// default filter if not set.
+///
+/// # Panics
+///
+/// Panics if a global logger is already set.
+pub fn init(default_filter: &str) {
+    let default_filter = std::env::var("RUST_LOG").unwrap_or_else(|_| default_filter.to_string());
+    let logger = LOGGER.get_or_init(|| RuntimeLogger {
+        default: build_logger(&default_filter),
+        default_filter,
+        current: RwLock::new(None),
+        recent: Mutex::new(VecDeque::with_capacity(RECENT_LOG_LINES)),
+    });
+    log::set_logger(logger).expect("Logger already initialized");
+    log::set_max_level(logger.default.filter());
+}
+
+/// Current log filter.
+pub fn log_filter() -> Option<String> {
+    let logger = LOGGER.get()?;
+    let current = logger.current.read().expect("RwLock is poisoned");
+    Some(match &*current {
+        Some((filter, _)) => filter.clone(),
+        None => logger.default_filter.clone(),
+    })
+}
+
+/// Most recent log lines, oldest first.
+pub fn recent_logs() -> Vec<String> {
+    LOGGER.get().map_or_else(Vec::new, |logger| {
+        let recent = logger.recent.lock().expect("Mutex is poisoned");
+        recent.iter().cloned().collect()
+    })
+}
+
+/// Change the log filter at runtime, or restore the initial filter with `None`.
+///
+/// Does nothing if the logger hasn't been initialized with [init].
+pub fn set_log_filter(filter: Option<&str>) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    let mut current = logger.current.write().expect("RwLock is poisoned");
+    *current = filter.map(|filter| (filter.to_string(), build_logger(filter)));
+    let max_level = match &*current {
+        Some((_, logger)) => logger.filter(),
+        None => logger.default.filter(),
+    };
+    log::set_max_level(max_level);
+}
+
+/// Switch between [DEBUG_FILTER] and the initial log filter.
+pub fn toggle_debug_logging() {
+    if log_filter().as_deref() == Some(DEBUG_FILTER) {
+        set_log_filter(None);
+    } else {
+        set_log_filter(Some(DEBUG_FILTER));
+    }
+}
+
+/// Toggle debug logging with the `SIGUSR1` signal, see [toggle_debug_logging].
+#[cfg(unix)]
+pub fn start_signal_handler() -> anyhow::Result<()> {
+    use signal_hook::consts::SIGUSR1;
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGUSR1])?;
+    std::thread::Builder::new()
+        .name("log-signal".into())
+        .spawn(move || {
+            for _ in signals.forever() {
+                toggle_debug_logging();
+                info!("Log filter changed to {}", log_filter().unwrap_or_default());
+            }
+        })?;
+
+    Ok(())
+}
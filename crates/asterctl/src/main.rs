@@ -5,25 +5,66 @@
 #![forbid(non_ascii_idents)]
 #![deny(unsafe_code)]
 
-use asterctl::cfg::{MonitorConfig, Sensor, load_custom_panel};
-use asterctl::render::PanelRenderer;
-use asterctl::sensors::start_sensor_poller;
+use aster_sysinfo::on_battery;
+use asterctl::animation::Animation;
+#[cfg(feature = "dbus")]
+use asterctl::cfg::DbusBus;
+use asterctl::cfg::{
+    AnimationConfig, ConfigFormat, LowPowerConfig, MonitorConfig, SetupOverride, load_custom_panel,
+};
+use asterctl::check::{check_config, check_panel};
+use asterctl::control::{ControlCommand, ControlReceiver, DisplayStatus, control_channel};
+#[cfg(feature = "dbus")]
+use asterctl::dbus::start_dbus_service;
+use asterctl::engine::PanelEngine;
+use asterctl::frame_stats::FrameStats;
+use asterctl::history::start_history_recorder;
+#[cfg(feature = "http-api")]
+use asterctl::http_api::start_http_api;
+use asterctl::img::{ColorLut, Size};
+use asterctl::output::FrameOutputs;
+use asterctl::package::{PANELS_DIR, export_panel, install_panel};
+use asterctl::power_state::PowerState;
+use asterctl::preview::{fake_sensor_values, load_sensor_values, render_pages};
+use asterctl::refresh::RefreshController;
+use asterctl::remote_image::{RemoteImage, is_url};
+use asterctl::report::{Report, sanitize_config, sanitize_log, system_info};
+#[cfg(unix)]
+use asterctl::sensors::start_push_socket;
+use asterctl::sensors::{
+    ENERGY_STATE_FILE, ExecSensorSource, HomeAssistantSensorSource, HttpSensorSource,
+    JournalSensorSource, PingSensorSource, ReplaySensorSource, SUMMARY_STATE_FILE, SensorAliases,
+    SensorRecording, SensorSource, SensorSources, SensorStatistics, SensorStore, SourceHealth,
+    SystemSensorSource, WeatherSensorSource, start_daily_summary, start_derived_sensors,
+    start_energy_meter, start_sensor_statistics,
+};
+use asterctl::timer::parse_duration;
+use asterctl::transition::Transition;
 use asterctl::{cfg, img};
-use asterctl_lcd::{AooScreen, AooScreenBuilder, DISPLAY_SIZE};
+use asterctl_lcd::{
+    AooScreen, AooScreenBuilder, DISPLAY_SIZE, KNOWN_DEVICES, PixelFormat, Rotation, ToRgb565,
+    TraceRecorder, find_display_devices, list_serial_ports,
+};
 
-use anyhow::anyhow;
+use anyhow::{Context, anyhow, bail};
 use chrono::Timelike;
-use clap::Parser;
-use env_logger::Env;
+use clap::{Parser, Subcommand};
+use image::buffer::ConvertBuffer;
+use image::imageops::{self, FilterType};
+use image::{RgbImage, RgbaImage};
 use log::{debug, error, info, warn};
-use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::mpsc::{Receiver, SyncSender, channel, sync_channel};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
+/// Interval to check if the host runs on battery power.
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 /// AOOSTAR WTR MAX and GEM12+ PRO screen control.
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -32,10 +73,52 @@ struct Args {
     #[arg(short, long)]
     device: Option<String>,
 
-    /// USB serial UART "vid:pid" in hex notation (lsusb output). Default: 416:90A1
+    /// USB serial UART "vid:pid" in hex notation (lsusb output). Default: the first known AOOSTAR
+    /// display, see --list-devices
     #[arg(short, long)]
     usb: Option<String>,
 
+    /// Pixel format of the display: rgb565le (default), rgb565be, bgr565le or bgr565be.
+    ///
+    /// Use bgr565le if red and blue are swapped on the display.
+    #[arg(long)]
+    pixel_format: Option<PixelFormat>,
+
+    /// Experimental: send run-length encoded images.
+    ///
+    /// Only used with --simulate, none of the known display models supports it. Falls back to
+    /// uncompressed transfers if the display doesn't acknowledge them.
+    #[arg(long)]
+    compression: bool,
+
+    /// Rotate the display images clockwise: 0, 90, 180 or 270 degrees.
+    ///
+    /// Takes priority over setup.rotation of the configuration, e.g. for a vertically mounted
+    /// chassis.
+    #[arg(long)]
+    rotate: Option<Rotation>,
+
+    /// Mirror the display images horizontally.
+    #[arg(long)]
+    mirror: bool,
+
+    /// Record the serial communication with the display into a protocol trace file.
+    ///
+    /// Traces of real devices can be replayed in the asterctl-lcd conformance tests.
+    #[arg(long)]
+    record_trace: Option<PathBuf>,
+
+    /// List the serial ports and the detected AOOSTAR displays, then exit.
+    #[arg(long)]
+    list_devices: bool,
+
+    /// File to persist the display power state set with --on, --off and control commands.
+    ///
+    /// Default: display_power in $STATE_DIRECTORY, $XDG_STATE_HOME/asterctl or
+    /// ~/.local/state/asterctl
+    #[arg(long)]
+    state_file: Option<PathBuf>,
+
     /// Switch display on and exit. This will show the last displayed image.
     #[arg(long)]
     on: bool,
@@ -44,10 +127,15 @@ struct Args {
     #[arg(long)]
     off: bool,
 
-    /// Image to display, other sizes than 960x376 will be scaled.
+    /// Image file or HTTP(S) URL to display, other sizes than 960x376 will be scaled.
     #[arg(short, long)]
     image: Option<String>,
 
+    /// Fetch the image URL again every n seconds until terminated, e.g. for a remotely rendered
+    /// dashboard. Unchanged images are detected with the ETag and Last-Modified headers.
+    #[arg(long, requires = "image")]
+    image_refresh: Option<f32>,
+
     /// AOOSTAR-X json configuration file to parse.
     ///
     /// The configuration file will be loaded from the `config_dir` directory if no full path is
@@ -61,6 +149,14 @@ struct Args {
     #[arg(short, long)]
     panels: Option<Vec<PathBuf>>,
 
+    /// Override a setup value of the configuration, for example, "setup.refresh=1.5". Can be
+    /// repeated.
+    ///
+    /// Takes priority over the configuration file and the `ASTERCTL_SETUP__` environment
+    /// variables, for example, `ASTERCTL_SETUP__REFRESH=1.5`.
+    #[arg(long = "set", value_name = "SETUP.FIELD=VALUE")]
+    set: Vec<SetupOverride>,
+
     /// Configuration directory containing configuration files and background images
     /// specified in the `config` file.
     #[arg(long, default_value_t = String::from("cfg"))]
@@ -82,19 +178,334 @@ struct Args {
     #[arg(short, long)]
     save: bool,
 
+    /// Show frame counters, render and transfer times and the frame rate on the display.
+    #[arg(long)]
+    debug_overlay: bool,
+
     /// Simulate serial port for testing and development, `--device` and `--usb` options are ignored.
     #[arg(long)]
     simulate: bool,
+
+    /// Listen address of the HTTP control API, for example, "127.0.0.1:8686".
+    ///
+    /// Takes priority over the `httpApi.listen` configuration.
+    #[cfg(feature = "http-api")]
+    #[arg(long)]
+    http_api: Option<String>,
+
+    /// Provide the D-Bus interface for desktop control on the session or system bus.
+    ///
+    /// Takes priority over the `dbus` configuration.
+    #[cfg(feature = "dbus")]
+    #[arg(long)]
+    dbus: Option<DbusBus>,
+
+    /// Open a desktop window showing the frames sent to the display, e.g. with --simulate.
+    ///
+    /// Same as a `window` frame output in the `outputs` configuration.
+    #[cfg(feature = "preview-window")]
+    #[arg(long)]
+    preview_window: bool,
+
+    /// Unix domain socket to receive sensor value updates from external programs.
+    ///
+    /// Takes priority over the `pushSocket` configuration.
+    #[arg(long)]
+    push_socket: Option<PathBuf>,
+
+    /// Play back a sensor recording instead of reading the system sensors, e.g. to reproduce a
+    /// rendering problem. The recording starts over at the end.
+    ///
+    /// Takes priority over the `replay` configuration.
+    #[arg(long)]
+    replay: Option<PathBuf>,
+
+    /// Playback speed factor of --replay, for example, 10 for ten times faster. Default: 1
+    #[arg(long, requires = "replay")]
+    replay_speed: Option<f32>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show a message on the running sensor panel using its HTTP control API, then exit.
+    Message(MessageArgs),
+    /// Show an image on the running sensor panel using its HTTP control API, then exit.
+    ShowImage(ShowImageArgs),
+    /// Start a countdown timer page on the running sensor panel using its HTTP control API, then
+    /// exit.
+    Timer(TimerArgs),
+    /// Change the log filter of the running sensor panel using its HTTP control API, then exit.
+    Log(LogArgs),
+    /// Load, reload or unload a custom panel of the running sensor panel using its HTTP control
+    /// API, or install and export panel packages, then exit.
+    Panel(PanelArgs),
+    /// Collect version information, the sanitized configuration and diagnostics of the running
+    /// sensor panel into an archive for bug reports, then exit.
+    Report(ReportArgs),
+    /// Check the configuration given with --config and the custom panels for problems, e.g.
+    /// missing fonts or images, without opening the display, then exit.
+    #[command(visible_alias = "validate")]
+    Check,
+    /// Render every page of the configuration given with --config and the custom panels into PNG
+    /// files, with fake or recorded sensor values, without opening the display, then exit.
+    Preview(PreviewArgs),
+    /// Convert a configuration file between the AOOSTAR-X JSON and the native TOML format, then
+    /// exit. The formats are selected by the file extensions.
+    Convert(ConvertArgs),
+    /// Upgrade the configuration given with --config to the current schema version and write it
+    /// back, then exit.
+    MigrateConfig(MigrateConfigArgs),
+}
+
+/// Connection to the HTTP control API of a running sensor panel.
+#[derive(clap::Args, Debug)]
+struct ApiArgs {
+    /// Address of the HTTP control API of the running sensor panel.
+    #[arg(long, default_value_t = String::from("127.0.0.1:8686"))]
+    api: String,
+
+    /// Access token of the HTTP control API. Default: `ASTERCTL_API_TOKEN` environment variable
+    #[arg(long)]
+    token: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct MessageArgs {
+    /// Message text, `\n` starts a new line.
+    text: String,
+
+    /// Optional title above the message text.
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Display time in seconds. Without duration, the message is shown until the next page command.
+    #[arg(long)]
+    duration: Option<f32>,
+
+    /// Font color in `#RRGGBB` notation or a color name, for example, "green".
+    #[arg(long)]
+    color: Option<String>,
+
+    /// Background color in `#RRGGBB` notation or a color name.
+    #[arg(long)]
+    background: Option<String>,
+
+    #[command(flatten)]
+    api: ApiArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct TimerArgs {
+    /// Timer duration, for example, "25m", "1h30m" or "90s".
+    #[arg(required_unless_present = "cancel", value_parser = parse_duration)]
+    duration: Option<Duration>,
+
+    /// Optional label shown below the remaining time.
+    label: Option<String>,
+
+    /// Cancel the running timer.
+    #[arg(long, conflicts_with_all = ["duration", "label"])]
+    cancel: bool,
+
+    #[command(flatten)]
+    api: ApiArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct LogArgs {
+    /// Log filter in `RUST_LOG` syntax, for example, "debug" or "info,asterctl_lcd=debug".
+    #[arg(required_unless_present = "reset")]
+    filter: Option<String>,
+
+    /// Restore the log filter the sensor panel was started with.
+    #[arg(long, conflicts_with = "filter")]
+    reset: bool,
+
+    #[command(flatten)]
+    api: ApiArgs,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct PanelArgs {
+    #[command(subcommand)]
+    command: Option<PanelCommand>,
+
+    /// Custom panel directory. A panel loaded from the same directory is replaced.
+    #[arg(required = true)]
+    dir: Option<PathBuf>,
+
+    /// Unload the custom panel instead.
+    #[arg(long)]
+    unload: bool,
+
+    #[command(flatten)]
+    api: ApiArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum PanelCommand {
+    /// Install a panel package into the `panels` subdirectory of the configuration directory.
+    Install(PanelInstallArgs),
+    /// Export a custom panel into a panel package.
+    Export(PanelExportArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct PanelInstallArgs {
+    /// Panel package: ZIP archive, custom panel directory or HTTP(S) URL of a ZIP archive.
+    source: String,
+
+    /// Name of the installed panel directory. Default: the archive or directory name
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Replace an installed panel with the same name.
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct PanelExportArgs {
+    /// Installed panel name in the `panels` subdirectory of the configuration directory, or a
+    /// custom panel directory.
+    name: PathBuf,
+
+    /// Panel package to create. Default: `<name>.zip`
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ReportArgs {
+    /// Report archive to create. Default: `asterctl-report-<date>-<time>.tar.gz`
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Record a display protocol trace of the running sensor panel for the given duration, for
+    /// example, "30s".
+    #[arg(long, value_parser = parse_duration)]
+    trace: Option<Duration>,
+
+    #[command(flatten)]
+    api: ApiArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct PreviewArgs {
+    /// Output directory of the page images.
+    #[arg(short, long, default_value = "preview")]
+    output: PathBuf,
+
+    /// JSON file with recorded sensor values, for example, saved from `GET /api/sensors` of the
+    /// HTTP control API. Default: fake values for the sensor templates
+    #[arg(long)]
+    values: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ConvertArgs {
+    /// Configuration file to convert, for example, `monitor.json`.
+    input: PathBuf,
+
+    /// Converted configuration file, for example, `monitor.toml`.
+    output: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+struct MigrateConfigArgs {
+    /// Migrated configuration file. Default: replace the configuration file, keeping the
+    /// original as `<file>.bak`
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ShowImageArgs {
+    /// Image to display, other sizes than 960x376 will be scaled.
+    image: PathBuf,
+
+    /// Display time in seconds. Without duration, the image is shown until the next page command.
+    #[arg(long)]
+    duration: Option<f32>,
+
+    #[command(flatten)]
+    api: ApiArgs,
 }
 
 fn main() -> anyhow::Result<()> {
-    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+    asterctl::logging::init("info");
 
     let args = Args::parse();
 
+    match args.command {
+        Some(Command::Message(message)) => return send_message(message),
+        Some(Command::ShowImage(image)) => return send_image(image),
+        Some(Command::Timer(timer)) => return send_timer(timer),
+        Some(Command::Log(log)) => return send_log_filter(log),
+        Some(Command::Panel(panel)) => {
+            return match panel.command {
+                Some(PanelCommand::Install(install)) => {
+                    install_panel_package(install, &args.config_dir, &args.font_dir)
+                }
+                Some(PanelCommand::Export(export)) => {
+                    export_panel_package(export, &args.config_dir)
+                }
+                None => send_panel(panel),
+            };
+        }
+        Some(Command::Report(report)) => {
+            return create_report(report, args.config, &args.config_dir);
+        }
+        Some(Command::Check) => {
+            return check_configuration(
+                args.config,
+                &args.config_dir,
+                &args.font_dir,
+                args.panels,
+                &args.set,
+                args.rotate,
+            );
+        }
+        Some(Command::Preview(preview)) => {
+            return preview_panels(
+                preview,
+                args.config,
+                &args.config_dir,
+                &args.font_dir,
+                args.panels,
+                &args.set,
+                args.rotate,
+            );
+        }
+        Some(Command::Convert(convert)) => return convert_config(convert),
+        Some(Command::MigrateConfig(migrate)) => {
+            return migrate_configuration(migrate, args.config, &args.config_dir);
+        }
+        None => {}
+    }
+
+    if args.list_devices {
+        return list_devices();
+    }
+
     // initialize display with given UART port parameter
     let mut builder = AooScreenBuilder::new();
     builder.no_init_check(args.write_only);
+    if let Some(pixel_format) = args.pixel_format {
+        builder.pixel_format(pixel_format);
+    }
+    builder.compression(args.compression);
+    if let Some(rotation) = args.rotate {
+        builder.rotation(rotation);
+    }
+    builder.mirror(args.mirror);
+    if let Some(path) = &args.record_trace {
+        builder.record_trace(TraceRecorder::create(path)?);
+    }
     let mut screen = if args.simulate {
         builder.simulate()?
     } else if let Some(device) = args.device {
@@ -106,18 +517,26 @@ fn main() -> anyhow::Result<()> {
     };
 
     // process simple commands
+    let power_state = PowerState::new(args.state_file);
     if args.off {
         screen.off()?;
+        power_state.save(true);
         return Ok(());
     } else if args.on {
         screen.on()?;
+        power_state.save(false);
         return Ok(());
     }
 
-    // switch on screen for remaining commands
-    screen.init()?;
-
     if let Some(config) = args.config {
+        // the sensor panel mode keeps a display switched off by the user switched off
+        if power_state.is_off() {
+            info!("Display switched off by the user, not switching it on");
+            screen.off()?;
+        } else {
+            screen.init()?;
+        }
+
         info!("Starting sensor panel mode");
         let img_save_path = if args.save {
             let img_save_path = PathBuf::from("out");
@@ -129,20 +548,63 @@ fn main() -> anyhow::Result<()> {
 
         let cfg_dir = PathBuf::from(args.config_dir);
         let font_dir = PathBuf::from(args.font_dir);
-        let cfg = load_configuration(&config, &cfg_dir, args.panels)?;
+        let mut cfg = load_configuration(&config, &cfg_dir, args.panels, &args.set)?;
+        if args.rotate.is_none()
+            && let Some(rotation) = cfg.setup.rotation
+        {
+            screen.set_rotation(rotation);
+        }
+        if cfg.setup.mirror == Some(true) {
+            screen.set_mirror(true);
+        }
+        if screen.rotation() != Rotation::Rotate0 || screen.is_mirrored() {
+            info!(
+                "Display rotation: {}, mirrored: {}",
+                screen.rotation(),
+                screen.is_mirrored()
+            );
+        }
+        if args.push_socket.is_some() {
+            cfg.push_socket = args.push_socket;
+        }
+        if let Some(file) = args.replay {
+            cfg.replay = Some(cfg::ReplayConfig {
+                file,
+                speed: args.replay_speed,
+            });
+        }
+        #[cfg(feature = "http-api")]
+        if let Some(listen) = args.http_api {
+            cfg.http_api.get_or_insert_default().listen = Some(listen);
+        }
+        #[cfg(feature = "dbus")]
+        if args.dbus.is_some() {
+            cfg.dbus = args.dbus;
+        }
+        #[cfg(feature = "preview-window")]
+        if args.preview_window {
+            cfg.outputs.push(cfg::OutputConfig::Window);
+        }
         run_sensor_panel(
-            &mut screen,
+            screen,
             cfg,
             cfg_dir,
             font_dir,
             img_save_path,
+            args.debug_overlay,
+            power_state,
         )?;
         return Ok(());
     }
 
-    if let Some(image) = args.image {
+    // switch on screen for remaining commands
+    screen.init()?;
+
+    if let Some(image) = args.image.as_deref().filter(|image| is_url(image)) {
+        show_remote_image(&mut screen, image, args.image_refresh)?;
+    } else if let Some(image) = args.image {
         info!("Loading and displaying background image {image}...");
-        let rgb_img = img::load_image(&image, Some(DISPLAY_SIZE))?.to_rgb8();
+        let rgb_img = img::load_image(&image, Some(screen.size()))?.to_rgb8();
         let timestamp = Instant::now();
         screen.send_image(&rgb_img)?;
         debug!("Image sent in {}ms", timestamp.elapsed().as_millis());
@@ -159,18 +621,597 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Show an image fetched from an HTTP(S) URL.
+///
+/// With a refresh interval, the image is fetched again until asterctl is terminated. Fetch errors
+/// are logged and the last image stays on the display.
+fn show_remote_image(
+    screen: &mut AooScreen,
+    url: &str,
+    refresh: Option<f32>,
+) -> anyhow::Result<()> {
+    info!("Fetching and displaying image {url}...");
+    let mut remote = RemoteImage::new(url);
+    let image = remote
+        .fetch(Some(screen.size()))?
+        .ok_or_else(|| anyhow!("No image received from {url}"))?;
+    screen.send_image(&image.to_rgb8())?;
+
+    let Some(refresh) = refresh else {
+        return Ok(());
+    };
+    let refresh = Duration::from_secs_f32(refresh.max(1.0));
+    info!("Refreshing image every {}s", refresh.as_secs_f32());
+    loop {
+        sleep(refresh);
+        match remote.fetch(Some(screen.size())) {
+            Ok(Some(image)) => {
+                let timestamp = Instant::now();
+                match screen.send_image(&image.to_rgb8()) {
+                    Ok(()) => debug!("Image sent in {}ms", timestamp.elapsed().as_millis()),
+                    Err(e) => error!("Error sending image to display: {e:#}"),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("{e:#}"),
+        }
+    }
+}
+
+/// Send a message to the HTTP control API of the running sensor panel.
+fn send_message(args: MessageArgs) -> anyhow::Result<()> {
+    let body = serde_json::json!({
+        "text": args.text.replace("\\n", "\n"),
+        "title": args.title,
+        "duration": args.duration,
+        "fontColor": args.color,
+        "background": args.background,
+    });
+    let response = args
+        .api
+        .post("/api/message")
+        .send_json(&body)
+        .with_context(|| args.api.connect_error())?;
+    check_api_response(response, "Failed to show message")
+}
+
+/// Send an image to the HTTP control API of the running sensor panel.
+///
+/// The running sensor panel owns the serial port, so the image is shown without opening the
+/// display a second time.
+fn send_image(args: ShowImageArgs) -> anyhow::Result<()> {
+    let image = fs::read(&args.image)
+        .with_context(|| format!("Failed to read image {}", args.image.display()))?;
+    let format = image::guess_format(&image)
+        .with_context(|| format!("Unsupported image {}", args.image.display()))?;
+    let mut request = args
+        .api
+        .post("/api/image")
+        .header("Content-Type", format.to_mime_type());
+    if let Some(duration) = args.duration {
+        request = request.query("duration", duration.to_string());
+    }
+    let response = request
+        .send(&image[..])
+        .with_context(|| args.api.connect_error())?;
+    check_api_response(response, "Failed to show image")
+}
+
+/// List the serial ports and the detected AOOSTAR displays.
+fn list_devices() -> anyhow::Result<()> {
+    println!("Serial ports:");
+    for port in list_serial_ports()? {
+        println!("  {port}");
+    }
+    println!("Known AOOSTAR displays:");
+    for device in KNOWN_DEVICES {
+        println!("  {device}");
+    }
+    match find_display_devices()?.first() {
+        Some((port, device)) => println!("Default display: {port} ({})", device.name),
+        None => println!("No AOOSTAR display found"),
+    }
+    Ok(())
+}
+
+/// Start or cancel a countdown timer with the HTTP control API of the running sensor panel.
+fn send_timer(args: TimerArgs) -> anyhow::Result<()> {
+    let response = match args.duration {
+        Some(duration) => {
+            let body = serde_json::json!({
+                "duration": duration.as_secs_f64(),
+                "label": args.label,
+            });
+            args.api.post("/api/timer").send_json(&body)
+        }
+        None => args.api.post("/api/timer/cancel").send_empty(),
+    }
+    .with_context(|| args.api.connect_error())?;
+    check_api_response(response, "Failed to control timer")
+}
+
+fn send_log_filter(args: LogArgs) -> anyhow::Result<()> {
+    let body = serde_json::json!({ "filter": args.filter });
+    let response = args
+        .api
+        .post("/api/log")
+        .send_json(&body)
+        .with_context(|| args.api.connect_error())?;
+    check_api_response(response, "Failed to change log filter")
+}
+
+fn send_panel(args: PanelArgs) -> anyhow::Result<()> {
+    let dir = args.dir.context("A custom panel directory is required")?;
+    // the running sensor panel may have a different working directory, an unloaded panel
+    // directory may have been removed already
+    let dir = fs::canonicalize(&dir)
+        .or_else(|e| match args.unload {
+            true => std::path::absolute(&dir),
+            false => Err(e),
+        })
+        .with_context(|| format!("Invalid panel directory {}", dir.display()))?;
+    let path = if args.unload {
+        "/api/panel/unload"
+    } else {
+        "/api/panel/load"
+    };
+    let response = args
+        .api
+        .post(path)
+        .send_json(serde_json::json!({ "path": dir }))
+        .with_context(|| args.api.connect_error())?;
+    check_api_response(response, "Failed to change panel")
+}
+
+fn install_panel_package(
+    args: PanelInstallArgs,
+    config_dir: &str,
+    font_dir: &str,
+) -> anyhow::Result<()> {
+    let panels_dir = Path::new(config_dir).join(PANELS_DIR);
+    let (dir, panel) = install_panel(&args.source, &panels_dir, args.name.as_deref(), args.force)?;
+
+    for finding in check_panel(&panel, Path::new(font_dir), DISPLAY_SIZE) {
+        println!("warning: {finding}");
+        if let Some(fix) = &finding.fix {
+            println!("  fix: {fix}");
+        }
+    }
+    println!(
+        "Installed panel '{}' into {}, load it with --panels {}",
+        panel.friendly_name(),
+        dir.display(),
+        dir.display()
+    );
+    Ok(())
+}
+
+fn export_panel_package(args: PanelExportArgs, config_dir: &str) -> anyhow::Result<()> {
+    let installed = Path::new(config_dir).join(PANELS_DIR).join(&args.name);
+    let dir = if installed.is_dir() {
+        installed
+    } else {
+        args.name
+    };
+    let name = fs::canonicalize(&dir)
+        .ok()
+        .and_then(|dir| Some(dir.file_name()?.to_os_string()))
+        .with_context(|| format!("Custom panel {} not found", dir.display()))?;
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(name).with_extension("zip"));
+
+    let files = export_panel(&dir, &output)?;
+    println!(
+        "Exported panel {} with {files} files into {}",
+        dir.display(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn create_report(
+    args: ReportArgs,
+    config: Option<PathBuf>,
+    config_dir: &str,
+) -> anyhow::Result<()> {
+    let mut report = Report::default();
+    // parts which couldn't be collected
+    let mut errors = Vec::new();
+
+    let mut system = system_info();
+    match list_serial_ports() {
+        Ok(ports) => {
+            system.push_str("serial ports:\n");
+            for port in ports {
+                system.push_str(&format!("  {port}\n"));
+            }
+        }
+        Err(e) => errors.push(format!("Serial ports: {e}")),
+    }
+    report.add("system.txt", system);
+
+    if let Some(config) = config {
+        let path = if config.is_absolute() {
+            config
+        } else {
+            Path::new(config_dir).join(config)
+        };
+        match read_sanitized_config(&path) {
+            Ok(cfg) => report.add("config/monitor.json", cfg),
+            Err(e) => errors.push(format!("Configuration: {e:#}")),
+        }
+    }
+
+    if let Err(e) = collect_panel_diagnostics(&args, &mut report) {
+        errors.push(format!("Running sensor panel: {e:#}"));
+    }
+
+    if !errors.is_empty() {
+        report.add("errors.txt", errors.join("\n") + "\n");
+    }
+
+    let output = args.output.unwrap_or_else(|| {
+        let now = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        PathBuf::from(format!("asterctl-report-{now}.tar.gz"))
+    });
+    report.write_archive(&output)?;
+    let files = report.file_names().collect::<Vec<_>>().join(", ");
+    info!("Report saved to {}: {files}", output.display());
+
+    Ok(())
+}
+
+fn check_configuration(
+    config: Option<PathBuf>,
+    config_dir: &str,
+    font_dir: &str,
+    panels: Option<Vec<PathBuf>>,
+    overrides: &[SetupOverride],
+    rotate: Option<Rotation>,
+) -> anyhow::Result<()> {
+    let config = config.context("A configuration file is required: --config <CONFIG>")?;
+    let cfg = load_configuration(config.as_path(), Path::new(config_dir), panels, overrides)?;
+    let size = rotate
+        .or(cfg.setup.rotation)
+        .unwrap_or_default()
+        .rotate_size(DISPLAY_SIZE);
+
+    // current system sensor keys for the sensor templates, rates like network speeds require a
+    // second reading
+    let mut system_sensors = SystemSensorSource::new(
+        Duration::from_secs(1),
+        &Default::default(),
+        cfg.hwmon_labels.clone(),
+        cfg.cpu_temperature_sources.clone(),
+    );
+    let mut sensors = HashMap::new();
+    if let Err(e) = system_sensors.init(&mut sensors) {
+        warn!("Failed to read system sensors: {e:#}");
+    }
+    sleep(Duration::from_millis(500));
+    if let Err(e) = system_sensors.poll(&mut sensors) {
+        warn!("Failed to read system sensors: {e:#}");
+    }
+
+    let findings = check_config(
+        &cfg,
+        Path::new(config_dir),
+        Path::new(font_dir),
+        size,
+        sensors.keys(),
+    );
+    for finding in &findings {
+        println!("warning: {finding}");
+        if let Some(fix) = &finding.fix {
+            println!("  fix: {fix}");
+        }
+    }
+    println!(
+        "Checked {}: {} panels, {} warnings",
+        config.display(),
+        cfg.panels.len(),
+        findings.len()
+    );
+    Ok(())
+}
+
+fn preview_panels(
+    args: PreviewArgs,
+    config: Option<PathBuf>,
+    config_dir: &str,
+    font_dir: &str,
+    panels: Option<Vec<PathBuf>>,
+    overrides: &[SetupOverride],
+    rotate: Option<Rotation>,
+) -> anyhow::Result<()> {
+    let config = config.context("A configuration file is required: --config <CONFIG>")?;
+    let cfg = load_configuration(config.as_path(), Path::new(config_dir), panels, overrides)?;
+    let size = rotate
+        .or(cfg.setup.rotation)
+        .unwrap_or_default()
+        .rotate_size(DISPLAY_SIZE);
+    let values = match &args.values {
+        Some(path) => load_sensor_values(path)?,
+        None => fake_sensor_values(&cfg),
+    };
+
+    let mut engine = PanelEngine::new(&cfg, size, font_dir, config_dir);
+    let pages = render_pages(&mut engine, &values)
+        .map_err(|e| anyhow!("Failed to render preview: {e:?}"))?;
+    if pages.is_empty() {
+        bail!("No pages to preview (no sensors matched any template)");
+    }
+
+    fs::create_dir_all(&args.output)
+        .with_context(|| format!("Failed to create {}", args.output.display()))?;
+    for (idx, page) in pages.iter().enumerate() {
+        let path = args.output.join(page.file_name(idx + 1));
+        page.image
+            .save(&path)
+            .with_context(|| format!("Failed to save {}", path.display()))?;
+        println!("{}: {}", path.display(), page.pages.join(", "));
+    }
+    println!(
+        "Rendered {} previews of {} pages into {}",
+        pages.len(),
+        engine.page_count(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+fn convert_config(args: ConvertArgs) -> anyhow::Result<()> {
+    let input = fs::read_to_string(&args.input)
+        .with_context(|| format!("Failed to read {}", args.input.display()))?;
+    let cfg = cfg::parse_config(&input, ConfigFormat::from_path(&args.input))
+        .with_context(|| format!("Failed to parse {}", args.input.display()))?;
+    let output = cfg::write_config(&cfg, ConfigFormat::from_path(&args.output))?;
+    fs::write(&args.output, output)
+        .with_context(|| format!("Failed to write {}", args.output.display()))?;
+    println!(
+        "Converted {} to {}",
+        args.input.display(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+fn migrate_configuration(
+    args: MigrateConfigArgs,
+    config: Option<PathBuf>,
+    config_dir: &str,
+) -> anyhow::Result<()> {
+    let config = config.context("A configuration file is required: --config <CONFIG>")?;
+    let path = Path::new(config_dir).join(config);
+    let format = ConfigFormat::from_path(&path);
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut cfg = cfg::parse_config(&text, format)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let Some(version) = cfg::migrate_config(&mut cfg)? else {
+        println!(
+            "{} is up to date: version {}",
+            path.display(),
+            cfg::CONFIG_VERSION
+        );
+        return Ok(());
+    };
+
+    let output = match args.output {
+        Some(output) => output,
+        None => {
+            let mut backup = path.clone().into_os_string();
+            backup.push(".bak");
+            fs::copy(&path, &backup)
+                .with_context(|| format!("Failed to back up {}", path.display()))?;
+            path.clone()
+        }
+    };
+    let migrated = cfg::write_config(&cfg, ConfigFormat::from_path(&output))?;
+    fs::write(&output, migrated)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    println!(
+        "Migrated {} from version {version} to {}: {}",
+        path.display(),
+        cfg::CONFIG_VERSION,
+        output.display()
+    );
+    Ok(())
+}
+
+fn read_sanitized_config(path: &Path) -> anyhow::Result<String> {
+    let cfg =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut cfg = cfg::parse_config(&cfg, ConfigFormat::from_path(path))
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    sanitize_config(&mut cfg);
+    Ok(serde_json::to_string_pretty(&cfg)?)
+}
+
+/// Add the status, sensor keys and recent log of the running sensor panel to the report.
+fn collect_panel_diagnostics(args: &ReportArgs, report: &mut Report) -> anyhow::Result<()> {
+    let mut status = args.api.get_json("/api/status")?;
+    sanitize_config(&mut status);
+    report.add("status.json", serde_json::to_string_pretty(&status)?);
+
+    // sensor values might be personal, e.g. IP addresses or SSIDs
+    let sensors = args.api.get_json("/api/sensors")?;
+    let mut keys = sensors
+        .as_object()
+        .map(|values| {
+            values
+                .keys()
+                .filter(|key| !key.contains('#'))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    keys.sort();
+    report.add("sensor-keys.txt", keys.join("\n") + "\n");
+
+    if let Some(trace) = args.trace {
+        let log = args.api.get_json("/api/log")?;
+        let filter = log["filter"].as_str().unwrap_or("info").to_string();
+        info!("Recording display protocol trace for {}s", trace.as_secs());
+        args.api
+            .set_log_filter(&format!("{filter},asterctl_lcd=debug"))?;
+        let log_filter = LogFilterGuard {
+            api: &args.api,
+            filter: Some(filter),
+        };
+        if !wait_for_trace(trace)? {
+            warn!("Display protocol trace interrupted");
+        }
+        log_filter.restore()?;
+    }
+
+    let log = args.api.get_json("/api/log/recent")?;
+    let lines = log["lines"]
+        .as_array()
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(|line| line.as_str())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    report.add("recent.log", sanitize_log(&lines.join("\n")) + "\n");
+
+    Ok(())
+}
+
+/// Restores the log filter of the running sensor panel, also if the report fails.
+struct LogFilterGuard<'a> {
+    api: &'a ApiArgs,
+    /// Log filter to restore, `None` if restored.
+    filter: Option<String>,
+}
+
+impl LogFilterGuard<'_> {
+    fn restore(mut self) -> anyhow::Result<()> {
+        match self.filter.take() {
+            Some(filter) => self.api.set_log_filter(&filter),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for LogFilterGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(filter) = self.filter.take()
+            && let Err(e) = self.api.set_log_filter(&filter)
+        {
+            warn!("Failed to restore the log filter {filter}: {e:#}");
+        }
+    }
+}
+
+/// Wait for the duration of a display protocol trace.
+///
+/// returns: false if interrupted with `SIGINT` or `SIGTERM`, e.g. Ctrl+C.
+#[cfg(unix)]
+fn wait_for_trace(duration: Duration) -> anyhow::Result<bool> {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+    let handle = signals.handle();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let waiter = std::thread::spawn(move || {
+        let _ = sender.send(signals.forever().next());
+    });
+    let interrupted = matches!(receiver.recv_timeout(duration), Ok(Some(_)));
+    handle.close();
+    let _ = waiter.join();
+    Ok(!interrupted)
+}
+
+#[cfg(not(unix))]
+fn wait_for_trace(duration: Duration) -> anyhow::Result<bool> {
+    sleep(duration);
+    Ok(true)
+}
+
+impl ApiArgs {
+    fn agent() -> ureq::Agent {
+        ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into()
+    }
+
+    fn token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| std::env::var("ASTERCTL_API_TOKEN").ok())
+    }
+
+    fn post(&self, path: &str) -> ureq::RequestBuilder<ureq::typestate::WithBody> {
+        let mut request = Self::agent().post(format!("http://{}{path}", self.api));
+        if let Some(token) = self.token() {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        request
+    }
+
+    fn get_json(&self, path: &str) -> anyhow::Result<serde_json::Value> {
+        let mut request = Self::agent().get(format!("http://{}{path}", self.api));
+        if let Some(token) = self.token() {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        let mut response = request.call().with_context(|| self.connect_error())?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("{path}: {status}");
+        }
+        Ok(response.body_mut().read_json()?)
+    }
+
+    fn set_log_filter(&self, filter: &str) -> anyhow::Result<()> {
+        let response = self
+            .post("/api/log")
+            .send_json(serde_json::json!({ "filter": filter }))
+            .with_context(|| self.connect_error())?;
+        check_api_response(response, "Failed to change log filter")
+    }
+
+    fn connect_error(&self) -> String {
+        format!("Failed to connect to the HTTP control API on {}", self.api)
+    }
+}
+
+/// Turn an error response of the HTTP control API into an error with the returned message.
+fn check_api_response(
+    mut response: ureq::http::Response<ureq::Body>,
+    context: &str,
+) -> anyhow::Result<()> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let error = response
+        .body_mut()
+        .read_json::<serde_json::Value>()
+        .ok()
+        .and_then(|body| body["error"].as_str().map(str::to_string))
+        .unwrap_or_else(|| status.to_string());
+    bail!("{context}: {error}")
+}
+
 fn load_configuration<P: AsRef<Path>>(
     config: P,
     config_dir: P,
     panels: Option<Vec<PathBuf>>,
+    overrides: &[SetupOverride],
 ) -> anyhow::Result<MonitorConfig> {
     let config = config.as_ref();
     let config_dir = config_dir.as_ref();
 
     let mut cfg = if config.is_absolute() {
-        cfg::load_cfg(config)?
+        cfg::load_cfg_with_overrides(config, overrides)?
     } else {
-        cfg::load_cfg(config_dir.join(config))?
+        cfg::load_cfg_with_overrides(config_dir.join(config), overrides)?
     };
 
     if let Some(panels) = panels {
@@ -178,42 +1219,137 @@ fn load_configuration<P: AsRef<Path>>(
             cfg.include_custom_panel(load_custom_panel(panel)?);
         }
     }
+    cfg.add_summary_page();
 
     // Compile sensor filter regexes from inline config
     if cfg.compile_sensor_filters() {
         info!("Using sensor filter from config");
     }
 
+    cfg.resolve_secrets()?;
+
     Ok(cfg)
 }
 
 fn run_sensor_panel<B: Into<PathBuf>>(
-    screen: &mut AooScreen,
-    cfg: MonitorConfig,
+    mut screen: AooScreen,
+    mut cfg: MonitorConfig,
     config_dir: B,
     font_dir: B,
     img_save_path: Option<B>,
+    debug_overlay: bool,
+    power_state: PowerState,
 ) -> anyhow::Result<()> {
     let font_dir = font_dir.into();
     let config_dir = config_dir.into();
     let img_save_path = img_save_path.map(|p| p.into());
 
-    let mut renderer = PanelRenderer::new(DISPLAY_SIZE, &font_dir, &config_dir);
+    let mut engine = PanelEngine::new(&cfg, screen.size(), &font_dir, &config_dir);
+    info!("Compiled {} sensor templates", engine.template_count());
     if let Some(img_save_path) = &img_save_path {
-        renderer.set_img_save_path(img_save_path);
-        renderer.set_save_render_img(true);
-        // renderer.set_save_processed_pic(true);
-        // renderer.set_save_progress_layer(true);
+        engine.set_img_save_path(img_save_path);
     }
 
-    let sensor_values: Arc<RwLock<HashMap<String, String>>> = Arc::new(RwLock::new(HashMap::new()));
+    let sensor_values = Arc::new(RwLock::new(SensorStore::new()));
 
+    let health = SourceHealth::new();
     let poller_refresh = Duration::from_millis((cfg.setup.refresh * 1000f32) as u64);
-    start_sensor_poller(
+    let aliases = SensorAliases::new(&cfg.sensor_aliases);
+    let sources = SensorSources::new(sensor_values.clone(), &cfg.sensor_filter, &aliases, &health);
+    // a sensor recording replaces the system sensors
+    match &cfg.replay {
+        Some(replay) => sources.register(ReplaySensorSource::new(
+            SensorRecording::load(&replay.file)?,
+            replay.speed.unwrap_or(1.0),
+        ))?,
+        None => sources.register(SystemSensorSource::new(
+            poller_refresh,
+            &cfg.sensor_intervals,
+            cfg.hwmon_labels.clone(),
+            cfg.cpu_temperature_sources.clone(),
+        ))?,
+    }
+    if !cfg.ping_hosts.is_empty() {
+        sources.register(PingSensorSource::new(
+            cfg.ping_hosts.clone(),
+            cfg.ping_interval,
+            poller_refresh,
+        ))?;
+    }
+    for source in cfg.http_sources.clone() {
+        sources.register(HttpSensorSource::new(source)?)?;
+    }
+    for source in cfg.exec_sources.clone() {
+        sources.register(ExecSensorSource::new(source, poller_refresh)?)?;
+    }
+    if let Some(ha_cfg) = cfg.home_assistant_config() {
+        sources.register(HomeAssistantSensorSource::new(ha_cfg)?)?;
+    }
+    if let Some(weather_cfg) = cfg.weather.clone() {
+        sources.register(WeatherSensorSource::new(weather_cfg))?;
+    }
+    if let Some(journal_cfg) = &cfg.journal {
+        sources.register(JournalSensorSource::new(journal_cfg))?;
+    }
+
+    #[cfg(unix)]
+    if let Some(push_socket) = cfg.push_socket.clone() {
+        start_push_socket(
+            sensor_values.clone(),
+            push_socket,
+            &cfg.sensor_filter,
+            &aliases,
+        )?;
+    }
+
+    start_derived_sensors(sensor_values.clone(), &cfg.derived, poller_refresh)?;
+    let statistics = start_sensor_statistics(
         sensor_values.clone(),
+        cfg.statistics.as_ref(),
         poller_refresh,
-        cfg.sensor_filter.clone(),
     )?;
+    // the energy totals and the daily summary are saved next to the display power state
+    let energy_state = power_state
+        .path()
+        .and_then(Path::parent)
+        .map(|dir| dir.join(ENERGY_STATE_FILE));
+    start_energy_meter(
+        sensor_values.clone(),
+        cfg.energy.as_ref(),
+        energy_state,
+        poller_refresh,
+    )?;
+    let summary_state = power_state
+        .path()
+        .and_then(Path::parent)
+        .map(|dir| dir.join(SUMMARY_STATE_FILE));
+    start_daily_summary(
+        sensor_values.clone(),
+        cfg.daily_summary.as_ref(),
+        summary_state,
+        poller_refresh,
+    )?;
+    start_history_recorder(sensor_values.clone(), engine.history(), poller_refresh)?;
+
+    #[cfg(unix)]
+    asterctl::logging::start_signal_handler()?;
+
+    let (control, control_receiver) = control_channel();
+    #[cfg(feature = "http-api")]
+    if let Some(http_api) = &cfg.http_api {
+        start_http_api(
+            http_api,
+            control.clone(),
+            sensor_values.clone(),
+            health.clone(),
+        )?;
+    }
+    #[cfg(feature = "dbus")]
+    if let Some(bus) = cfg.dbus {
+        start_dbus_service(bus, control.clone())?;
+    }
+    // without control interfaces, the display loop just waits for the next refresh
+    drop(control);
 
     let refresh = Duration::from_millis((cfg.setup.refresh * 1000f32) as u64);
     let sensor_page_time =
@@ -222,12 +1358,44 @@ fn run_sensor_panel<B: Into<PathBuf>>(
         cfg.setup.time_page_time.unwrap_or(cfg.setup.sensor_page_time.unwrap_or(10.0)),
     );
 
-    // Compile sensor template patterns from active panels
-    let templates = compile_sensor_templates(&cfg);
-    info!("Compiled {} sensor templates", templates.len());
+    // the sensor sources gather their initial values while the startup animation is playing
+    let startup = Instant::now();
+    let off = power_state.is_off();
+    let color_lut = cfg.setup.color_calibration.as_ref().and_then(ColorLut::new);
+    let size = screen.size();
+    if is_display_active(&cfg)
+        && !off
+        && let Some(animation) = cfg.setup.startup_animation.as_ref().and_then(|animation| {
+            load_animation(animation, "startup", &config_dir, size, color_lut.as_ref())
+        })
+    {
+        info!("Playing startup animation");
+        animation.play(&mut screen);
+    }
+    let shutdown_animation = cfg.setup.shutdown_animation.as_ref().and_then(|animation| {
+        load_animation(animation, "shutdown", &config_dir, size, color_lut.as_ref())
+    });
+
+    let screen = Arc::new(Mutex::new(screen));
+    #[cfg(unix)]
+    if let Some(animation) = shutdown_animation {
+        start_shutdown_handler(screen.clone(), animation, power_state.clone())?;
+    }
+    #[cfg(not(unix))]
+    if shutdown_animation.is_some() {
+        warn!("Shutdown animations are only supported on Unix systems");
+    }
+    let outputs = FrameOutputs::new(&cfg.outputs);
+    let (frame_sender, transfers) = start_display_transmitter(screen.clone(), outputs)?;
+
+    // Show a first frame right away instead of a blank display, the page lists are rebuilt once
+    // the sensor sources delivered their initial values
+    if is_display_active(&cfg) && !off {
+        send_initial_frame(&mut engine, &sensor_values, &frame_sender);
+    }
 
     // Wait for initial sensor data to be available
-    sleep(Duration::from_millis(1500));
+    sleep(Duration::from_millis(1500).saturating_sub(startup.elapsed()));
 
     // Log all discovered sensor keys
     {
@@ -236,25 +1404,36 @@ fn run_sensor_panel<B: Into<PathBuf>>(
         keys.sort();
         info!("Discovered {} sensor keys:", keys.len());
         for key in &keys {
-            info!("  {}: {}", key, values.get(*key).map(|v| v.as_str()).unwrap_or("N/A"));
+            info!("  {}: {}", key, values.value(key).unwrap_or("N/A"));
         }
+
+        // Build initial page lists from discovered sensors
+        engine.rebuild(&values);
     }
 
-    // Build initial page list from discovered sensors
-    let mut pages = build_pages(&templates, &sensor_values, &cfg);
-    if pages.is_empty() {
+    if engine.page_count() == 0 {
         return Err(anyhow!("No pages to display (no sensors matched any template)"));
     }
 
     info!(
-        "Sensor page mode: {} pages, sensor={:.1}s, time={:.1}s",
-        pages.len(),
+        "Sensor page mode: {} pages in {} slots, sensor={:.1}s, time={:.1}s",
+        engine.page_count(),
+        engine.slot_count(),
         sensor_page_time.as_secs_f32(),
         time_page_time.as_secs_f32()
     );
 
-    let time_font_size = cfg.setup.time_page_font_size;
     let mut display_off = false;
+    let mut low_power = cfg.setup.low_power.clone().map(LowPowerMode::new);
+    let mut refresh_controller = RefreshController::new();
+    let mut frame_stats = debug_overlay.then(FrameStats::new);
+    let mut panel = PanelControl {
+        off,
+        values: sensor_values.clone(),
+        power_state,
+        statistics,
+        ..Default::default()
+    };
 
     if cfg.setup.display_on_hour.is_some() || cfg.setup.display_off_hour.is_some() {
         info!(
@@ -264,224 +1443,515 @@ fn run_sensor_panel<B: Into<PathBuf>>(
         );
     }
 
-    // page cycling loop
-    let mut page_idx = 0;
+    // refresh loop, every slot cycles through its own pages
     loop {
-        // Rebuild pages periodically to pick up new sensors
-        if page_idx == 0 {
-            let new_pages = build_pages(&templates, &sensor_values, &cfg);
-            if !new_pages.is_empty() {
-                pages = new_pages;
+        let upd_start_time = Instant::now();
+        for transfer_time in transfers.try_iter() {
+            refresh_controller.record(transfer_time);
+            if let Some(stats) = &mut frame_stats {
+                stats.frame_sent(transfer_time);
             }
         }
-
-        if page_idx >= pages.len() {
-            page_idx = 0;
+        let refresh = low_power
+            .as_mut()
+            .map_or(refresh, |mode| mode.update(&mut engine, refresh));
+        let refresh = refresh_controller.interval(refresh);
+
+        // Check display schedule: turn display on/off based on hour range
+        let display_on = is_display_active(&cfg);
+        if !display_on {
+            if !display_off {
+                info!("Display schedule: turning off");
+                // a disconnected display is switched off with the next schedule check
+                match screen.lock().expect("Mutex is poisoned").off() {
+                    Ok(()) => display_off = true,
+                    Err(e) => error!("{e:#}"),
+                }
+            }
+            control_receiver.set_status(panel.status(false, &engine));
+            let deadline = upd_start_time + Duration::from_secs(30);
+            panel.wait_for_commands(&control_receiver, deadline, &screen, &mut engine, &mut cfg);
+            continue;
+        } else if display_off {
+            info!("Display schedule: turning on");
+            if !panel.off
+                && let Err(e) = screen.lock().expect("Mutex is poisoned").on()
+            {
+                // reconnecting the display with the next frame switches it on
+                error!("{e:#}");
+            }
+            display_off = false;
         }
 
-        let page = &pages[page_idx];
+        if !panel.off {
+            // waits until the transmitter has taken over the previous frame
+            if let Some(image) = panel.current_image() {
+                frame_sender
+                    .send(Frame::Image(image.clone()))
+                    .map_err(|_| anyhow!("Display transmitter stopped"))?;
+            } else {
+                let render_start = Instant::now();
+                let rendered = {
+                    let values = sensor_values.read().expect("RwLock is poisoned");
+                    engine.render(&values)
+                };
 
-        match page {
-            PageKind::Sensor(sp) => {
-                let value = sensor_values
-                    .read()
-                    .expect("RwLock is poisoned")
-                    .get(&sp.sensor_key)
-                    .cloned()
-                    .unwrap_or_else(|| "N/A".to_string());
-                info!(
-                    "Page {}/{}: '{}' [{}] = {}",
-                    page_idx + 1,
-                    pages.len(),
-                    sp.display_name,
-                    sp.sensor_key,
-                    value
-                );
-            }
-            PageKind::Time(label) => {
-                info!("Page {}/{}: time ({})", page_idx + 1, pages.len(), label);
+                match rendered {
+                    Ok(mut image) => {
+                        if let Some(stats) = &mut frame_stats {
+                            let render_time = render_start.elapsed();
+                            stats.frame_rendered(
+                                engine.current_pages(),
+                                render_time,
+                                Instant::now(),
+                            );
+                            engine.draw_debug_overlay(&mut image, &stats.summary());
+                        }
+                        let frame = Frame::Rendered(image, engine.take_transition());
+                        frame_sender
+                            .send(frame)
+                            .map_err(|_| anyhow!("Display transmitter stopped"))?;
+                    }
+                    Err(e) => error!("Error rendering page: {e:?}"),
+                }
             }
         }
 
-        let page_start = Instant::now();
-        let mut refresh_count = 1;
-
-        // refresh loop for current page
-        loop {
-            let upd_start_time = Instant::now();
+        control_receiver.set_status(panel.status(!panel.off, &engine));
+        panel.wait_for_commands(
+            &control_receiver,
+            upd_start_time + refresh,
+            &screen,
+            &mut engine,
+            &mut cfg,
+        );
+    }
+}
 
-            if img_save_path.is_some() {
-                renderer.set_img_suffix(format!("-{refresh_count:02}"));
-            }
+/// Load a startup or shutdown animation. Errors are logged, the sensor panel runs without the
+/// animation.
+fn load_animation(
+    cfg: &AnimationConfig,
+    name: &str,
+    config_dir: &Path,
+    size: Size,
+    color_lut: Option<&ColorLut>,
+) -> Option<Animation> {
+    match Animation::load(cfg, config_dir, size, color_lut) {
+        Ok(animation) => {
+            debug!(
+                "Loaded {name} animation of {:.1}s",
+                animation.duration().as_secs_f32()
+            );
+            Some(animation)
+        }
+        Err(e) => {
+            error!("Failed to load {name} animation: {e:#}");
+            None
+        }
+    }
+}
 
-            // Check display schedule: turn display on/off based on hour range
-            let display_on = is_display_active(&cfg);
-            if !display_on {
-                if !display_off {
-                    info!("Display schedule: turning off");
-                    screen.off()?;
-                    display_off = true;
-                }
-                let page_duration = match page {
-                    PageKind::Sensor(_) => sensor_page_time,
-                    PageKind::Time(_) => time_page_time,
-                };
-                sleep(Duration::from_secs(30));
-                if page_start.elapsed() >= page_duration {
-                    break;
+/// Play the shutdown animation when the sensor panel is terminated with `SIGTERM` or `SIGINT`,
+/// then exit.
+///
+/// The animation isn't played if the user switched off the display.
+#[cfg(unix)]
+fn start_shutdown_handler(
+    screen: Arc<Mutex<AooScreen>>,
+    animation: Animation,
+    power_state: PowerState,
+) -> anyhow::Result<()> {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+    std::thread::Builder::new()
+        .name("shutdown".into())
+        .spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                info!("Received signal {signal}, shutting down");
+                if !power_state.is_off() {
+                    // waits for the transfer of the current frame
+                    let mut screen = screen.lock().expect("Mutex is poisoned");
+                    animation.play(&mut screen);
                 }
-                continue;
-            } else if display_off {
-                info!("Display schedule: turning on");
-                screen.on()?;
-                display_off = false;
+                info!("Bye bye!");
+                std::process::exit(0);
             }
+        })?;
 
-            let rendered = match page {
-                PageKind::Sensor(sp) => {
-                    let values = sensor_values.read().expect("RwLock is poisoned");
-                    renderer.render_sensor_page_from_template(
-                        &sp.template,
-                        &sp.sensor_key,
-                        &sp.display_name,
-                        &values,
-                        cfg.setup.sensor_page_label.as_ref(),
-                    )
-                }
-                PageKind::Time(label) => {
-                    renderer.render_time_page(label, time_font_size)
-                }
-            };
+    Ok(())
+}
+
+/// Render and send a frame with the sensor values available so far, e.g. only the system sensors.
+///
+/// Does nothing if no page can be shown without further sensor values.
+fn send_initial_frame(
+    engine: &mut PanelEngine,
+    sensor_values: &RwLock<SensorStore>,
+    frame_sender: &SyncSender<Frame>,
+) {
+    let rendered = {
+        let values = sensor_values.read().expect("RwLock is poisoned");
+        engine.rebuild(&values);
+        if engine.page_count() == 0 {
+            debug!("No pages for the initial frame, waiting for sensor data");
+            return;
+        }
+        engine.render(&values)
+    };
 
-            match rendered {
-                Ok(image) => {
-                    screen.send_image(&image)?;
+    match rendered {
+        Ok(image) => {
+            debug!("Sending initial frame");
+            let _ = frame_sender.send(Frame::Rendered(image, None));
+        }
+        Err(e) => error!("Error rendering initial frame: {e:?}"),
+    }
+}
+
+/// Display image handed over to the display transmitter.
+enum Frame {
+    /// Image shown instead of the sensor pages.
+    Image(RgbImage),
+    /// Rendered sensor pages with an optional page transition to show before.
+    Rendered(RgbaImage, Option<Transition>),
+}
+
+/// Start a thread sending the frames to the display and the additional frame outputs, so that the
+/// next frame can be rendered while the previous frame is transferred.
+///
+/// Frames are handed over without buffering: sending a frame blocks until the transmitter has
+/// finished the previous frame. The transfer times of the sent frames are returned in the second
+/// channel.
+fn start_display_transmitter(
+    screen: Arc<Mutex<AooScreen>>,
+    mut outputs: FrameOutputs,
+) -> anyhow::Result<(SyncSender<Frame>, Receiver<Duration>)> {
+    let (frame_sender, frames) = sync_channel::<Frame>(0);
+    let (transfer_sender, transfers) = channel();
+
+    std::thread::Builder::new()
+        .name("display".into())
+        .spawn(move || {
+            let mut skipper = FrameSkipper::default();
+            for frame in frames {
+                let mut screen = screen.lock().expect("Mutex is poisoned");
+                // the display is automatically re-initialized after repeated failures
+                let result = match &frame {
+                    Frame::Image(image) => skipper.send(&mut screen, image),
+                    Frame::Rendered(image, transition) => {
+                        if let Some(transition) = transition {
+                            play_transition(&mut screen, transition);
+                            skipper.reset();
+                        }
+                        skipper.send(&mut screen, image)
+                    }
+                };
+                let connected = screen.is_connected();
+                drop(screen);
+
+                // the outputs also receive the frames while the display is disconnected
+                if !outputs.is_empty() {
+                    match &frame {
+                        Frame::Image(image) => outputs.send(image),
+                        Frame::Rendered(image, _) => outputs.send(&image.convert()),
+                    }
                 }
-                Err(e) => error!("Error rendering page: {e:?}"),
-            }
 
-            let elapsed = upd_start_time.elapsed();
-            if refresh > elapsed {
-                sleep(refresh - elapsed);
+                match result {
+                    Ok(Some(transfer_time)) => {
+                        let _ = transfer_sender.send(transfer_time);
+                    }
+                    Ok(None) => {}
+                    // reconnect attempts are logged by the screen
+                    Err(e) if !connected => debug!("{e:#}"),
+                    Err(e) => error!("Error sending image to display: {e:#}"),
+                }
             }
+        })?;
 
-            let page_duration = match page {
-                PageKind::Sensor(_) => sensor_page_time,
-                PageKind::Time(_) => time_page_time,
-            };
-            if page_start.elapsed() >= page_duration {
-                break;
-            }
+    Ok((frame_sender, transfers))
+}
 
-            refresh_count += 1;
+/// Send the intermediate frames of a page transition, limited to the transition frame rate.
+///
+/// Frames are skipped if sending takes longer than the frame interval.
+fn play_transition(screen: &mut AooScreen, transition: &Transition) {
+    let start = Instant::now();
+    let interval = transition.frame_interval();
+    for (idx, frame) in transition.frames().enumerate() {
+        let frame_start = start + interval * idx as u32;
+        let now = Instant::now();
+        if now > frame_start + interval {
+            continue;
+        }
+        sleep(frame_start.saturating_duration_since(now));
+        if let Err(e) = screen.send_image(&frame) {
+            error!("Error sending transition frame to display: {e:#}");
+            return;
         }
-
-        page_idx = (page_idx + 1) % pages.len();
     }
+    sleep((start + interval * transition.frame_count()).saturating_duration_since(Instant::now()));
 }
 
-enum PageKind {
-    Sensor(SensorPage),
-    Time(String),
+/// Skip the transmission of frames which are identical to the last sent frame.
+#[derive(Default)]
+struct FrameSkipper {
+    /// Hash of the last frame sent to the display.
+    last_frame: Option<u64>,
 }
 
-struct SensorPage {
-    sensor_key: String,
-    display_name: String,
-    template: Sensor,
+impl FrameSkipper {
+    /// Send the image to the display if it changed since the last sent frame.
+    ///
+    /// Returns the transfer time, or `None` if the frame was skipped.
+    fn send<I: ToRgb565 + Hash>(
+        &mut self,
+        screen: &mut AooScreen,
+        image: I,
+    ) -> anyhow::Result<Option<Duration>> {
+        let mut hasher = DefaultHasher::new();
+        image.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.last_frame == Some(hash) {
+            debug!("Skipping unchanged frame");
+            return Ok(None);
+        }
+
+        let transfer_start = Instant::now();
+        match screen.send_image(image) {
+            Ok(()) => {
+                self.last_frame = Some(hash);
+                Ok(Some(transfer_start.elapsed()))
+            }
+            Err(e) => {
+                self.last_frame = None;
+                Err(e)
+            }
+        }
+    }
+
+    /// Forget the last sent frame, e.g. after sending other frames to the display.
+    fn reset(&mut self) {
+        self.last_frame = None;
+    }
 }
 
-struct CompiledTemplate {
-    regex: Regex,
-    sensor: Sensor,
+/// Power saving policy while the host runs on battery power, see [LowPowerConfig].
+struct LowPowerMode {
+    cfg: LowPowerConfig,
+    /// Minimum refresh interval on battery power.
+    refresh: Duration,
+    on_battery: bool,
+    next_check: Instant,
 }
 
-/// Compile regex patterns from sensor templates in active panels.
-fn compile_sensor_templates(cfg: &MonitorConfig) -> Vec<CompiledTemplate> {
-    let mut templates = Vec::new();
-    for &active in &cfg.active_panels {
-        if active == 0 || active > cfg.panels.len() as u32 {
-            continue;
+impl LowPowerMode {
+    fn new(cfg: LowPowerConfig) -> Self {
+        let refresh = match cfg.refresh {
+            Some(refresh) if !refresh.is_finite() => {
+                warn!("Invalid lowPower.refresh {refresh}, using 10s");
+                10.0
+            }
+            refresh => refresh.unwrap_or(10.0),
+        };
+        Self {
+            cfg,
+            refresh: Duration::from_secs_f32(refresh.clamp(1.0, u32::MAX as f32)),
+            on_battery: false,
+            next_check: Instant::now(),
         }
-        let panel = &cfg.panels[active as usize - 1];
-        for sensor in &panel.sensor {
-            if let Some(pattern) = &sensor.match_pattern {
-                match Regex::new(pattern) {
-                    Ok(re) => templates.push(CompiledTemplate {
-                        regex: re,
-                        sensor: sensor.clone(),
-                    }),
-                    Err(e) => warn!("Invalid sensor match pattern '{pattern}': {e}"),
+    }
+
+    /// Check the power supply state and return the display refresh interval.
+    ///
+    /// Animations are disabled while on battery power, unless enabled in the configuration.
+    fn update(&mut self, engine: &mut PanelEngine, refresh: Duration) -> Duration {
+        if self.next_check <= Instant::now() {
+            self.next_check = Instant::now() + POWER_CHECK_INTERVAL;
+            let on_battery = on_battery();
+            if on_battery != self.on_battery {
+                if on_battery {
+                    info!("Running on battery power: switching to low-power mode");
+                } else {
+                    info!("Running on mains power: leaving low-power mode");
                 }
+                self.on_battery = on_battery;
+                engine.set_animations(!on_battery || self.cfg.animations);
             }
         }
+
+        if self.on_battery {
+            refresh.max(self.refresh)
+        } else {
+            refresh
+        }
     }
-    templates
 }
 
-/// Build pages by matching available sensor keys against compiled templates.
-/// Templates are matched in order; each sensor key matches at most one template.
-fn build_pages(
-    templates: &[CompiledTemplate],
-    sensor_values: &Arc<RwLock<HashMap<String, String>>>,
-    cfg: &MonitorConfig,
-) -> Vec<PageKind> {
-    let values = sensor_values.read().expect("RwLock is poisoned");
-    let mut sensor_keys: Vec<&String> = values.keys().collect();
-    sensor_keys.sort();
+/// Display state changed by control commands.
+#[derive(Default)]
+struct PanelControl {
+    /// Display switched off by a control command.
+    off: bool,
+    /// Image shown instead of the sensor pages.
+    image: Option<RgbImage>,
+    /// End of the image display time, `None` until the next page command.
+    image_until: Option<Instant>,
+    /// Sensor values for placeholders in messages.
+    values: Arc<RwLock<SensorStore>>,
+    /// Persisted power state of the display.
+    power_state: PowerState,
+    /// Tracked sensor statistics to reset.
+    statistics: SensorStatistics,
+}
 
-    // For each template (in order), find all matching sensor keys.
-    // This preserves template order as the primary sort.
-    let mut matched_keys: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut pages: Vec<PageKind> = Vec::new();
+impl PanelControl {
+    /// Handle control commands until the deadline or until a command has been handled.
+    fn wait_for_commands(
+        &mut self,
+        receiver: &ControlReceiver,
+        deadline: Instant,
+        screen: &Mutex<AooScreen>,
+        engine: &mut PanelEngine,
+        cfg: &mut MonitorConfig,
+    ) {
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            if receiver.handle_next(timeout, |command| self.handle(command, screen, engine, cfg)) {
+                break;
+            }
+        }
+    }
 
-    for tmpl in templates {
-        let mut matches: Vec<(&String, String)> = Vec::new();
-        for key in &sensor_keys {
-            if matched_keys.contains(*key) {
-                continue;
+    fn handle(
+        &mut self,
+        command: ControlCommand,
+        screen: &Mutex<AooScreen>,
+        engine: &mut PanelEngine,
+        cfg: &mut MonitorConfig,
+    ) -> anyhow::Result<()> {
+        debug!("Control command: {command:?}");
+        match command {
+            ControlCommand::On => {
+                screen.lock().expect("Mutex is poisoned").on()?;
+                self.off = false;
+                self.power_state.save(false);
             }
-            if let Some(caps) = tmpl.regex.captures(key) {
-                let display_name = expand_template_name(&tmpl.sensor, &caps);
-                matches.push((key, display_name));
+            ControlCommand::Off => {
+                screen.lock().expect("Mutex is poisoned").off()?;
+                self.off = true;
+                self.power_state.save(true);
+            }
+            ControlCommand::NextPage => {
+                self.image = None;
+                engine.next_page();
+            }
+            ControlCommand::ShowPage(selector) => {
+                if !engine.show_page(&selector) {
+                    return Err(anyhow!("No page matching '{selector}'"));
+                }
+                self.image = None;
+            }
+            ControlCommand::ShowImage {
+                mut image,
+                duration,
+            } => {
+                let (width, height) = screen.lock().expect("Mutex is poisoned").size();
+                if image.dimensions() != (width, height) {
+                    image = imageops::resize(&image, width, height, FilterType::Lanczos3);
+                }
+                engine.calibrate_image(&mut image);
+                self.image = Some(image);
+                self.image_until =
+                    duration.and_then(|duration| Instant::now().checked_add(duration));
+            }
+            ControlCommand::ShowMessage { message, duration } => {
+                let values = self.values.read().expect("RwLock is poisoned");
+                let image = engine
+                    .render_message(&message, &values)
+                    .map_err(|e| anyhow!("Error rendering message: {e:?}"))?;
+                drop(values);
+                self.image = Some(image);
+                self.image_until =
+                    duration.and_then(|duration| Instant::now().checked_add(duration));
+            }
+            ControlCommand::StartTimer { duration, label } => {
+                engine.start_timer(duration, label);
+                self.image = None;
+            }
+            ControlCommand::CancelTimer => {
+                if !engine.cancel_timer() {
+                    return Err(anyhow!("No running timer"));
+                }
+            }
+            ControlCommand::SetBrightness(brightness) => engine.set_brightness(brightness),
+            ControlCommand::AcknowledgeAlert => {
+                if !engine.acknowledge_alerts() {
+                    return Err(anyhow!("No active alert"));
+                }
+            }
+            ControlCommand::ResetStatistics(sensor) => {
+                if !self.statistics.reset(sensor.as_deref()) {
+                    return Err(match sensor {
+                        Some(sensor) => anyhow!("No statistics of sensor {sensor}"),
+                        None => anyhow!("No sensor statistics"),
+                    });
+                }
+                info!(
+                    "Reset statistics of {}",
+                    sensor.as_deref().unwrap_or("all sensors")
+                );
+            }
+            ControlCommand::LoadPanel(dir) => {
+                let panel = load_custom_panel(&dir)?;
+                info!("Loaded custom panel {}", panel.friendly_name());
+                cfg.replace_custom_panel(panel);
+                self.reload_panels(engine, cfg);
+            }
+            ControlCommand::UnloadPanel(dir) => {
+                if !cfg.remove_custom_panel(&dir) {
+                    return Err(anyhow!("No custom panel loaded from {}", dir.display()));
+                }
+                info!("Unloaded custom panel {}", dir.display());
+                self.reload_panels(engine, cfg);
             }
         }
-        for (key, display_name) in matches {
-            matched_keys.insert(key.clone());
-            pages.push(PageKind::Sensor(SensorPage {
-                sensor_key: key.clone(),
-                display_name,
-                template: tmpl.sensor.clone(),
-            }));
-        }
+        Ok(())
     }
 
-    // Add optional time page at the end
-    if let Some(time_label) = &cfg.setup.time_page {
-        pages.push(PageKind::Time(time_label.clone()));
+    /// Recreate the page rotations after the custom panels changed.
+    fn reload_panels(&mut self, engine: &mut PanelEngine, cfg: &MonitorConfig) {
+        engine.reload_panels(cfg);
+        engine.rebuild(&self.values.read().expect("RwLock is poisoned"));
+        if engine.page_count() == 0 {
+            warn!("No pages to display (no sensors matched any template)");
+        }
+        self.image = None;
     }
 
-    info!("Built {} pages from {} sensor keys", pages.len(), sensor_keys.len());
-    pages
-}
-
-/// Expand the template display name using regex capture groups.
-/// `{1}`, `{2}`, etc. in the sensor `name` are replaced with capture group values.
-fn expand_template_name(sensor: &Sensor, caps: &regex::Captures) -> String {
-    let base_name = sensor
-        .name
-        .as_deref()
-        .or(sensor.item_name.as_deref())
-        .unwrap_or("Sensor");
+    /// The image to show instead of the sensor pages, if its display time is not over.
+    fn current_image(&mut self) -> Option<&RgbImage> {
+        if let Some(until) = self.image_until
+            && until <= Instant::now()
+        {
+            self.image = None;
+            self.image_until = None;
+        }
+        self.image.as_ref()
+    }
 
-    let mut result = base_name.to_string();
-    for i in 1..=9 {
-        let placeholder = format!("{{{i}}}");
-        if let Some(m) = caps.get(i) {
-            result = result.replace(&placeholder, m.as_str());
+    fn status(&self, on: bool, engine: &PanelEngine) -> DisplayStatus {
+        DisplayStatus {
+            on,
+            brightness: engine.brightness(),
+            pages: engine.current_pages(),
+            showing_image: self.image.is_some(),
+            alert: engine.current_alert(),
+            timer_end: engine.timer_end(),
         }
     }
-    result
 }
 
 /// Check if the display should be active based on the configured hour range.
@@ -0,0 +1,470 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Additional outputs of the rendered frames.
+//!
+//! Besides the LCD, every new frame of the sensor panel can be sent to secondary outputs, e.g. to
+//! show the same panel in a browser kiosk:
+//! - a PNG file in a directory
+//! - an MJPEG HTTP stream
+//! - a Linux framebuffer device `/dev/fb*`
+//! - a desktop window, see [crate::preview_window]
+
+use crate::cfg::OutputConfig;
+use anyhow::{Context, anyhow, bail};
+use image::codecs::jpeg::JpegEncoder;
+use image::{ImageFormat, RgbImage};
+use log::{debug, error, info, warn};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Default listen address of the MJPEG stream.
+pub const DEFAULT_MJPEG_LISTEN_ADDR: &str = "127.0.0.1:8687";
+
+/// Multipart boundary of the MJPEG stream.
+const MJPEG_BOUNDARY: &str = "asterctl-frame";
+
+/// A secondary output of the rendered frames.
+pub trait FrameOutput: Send {
+    /// Output name for logging.
+    fn name(&self) -> &str;
+
+    /// Output a new frame. Unchanged frames are not sent again.
+    fn send(&mut self, image: &RgbImage) -> anyhow::Result<()>;
+}
+
+/// Create a frame output from its configuration.
+pub fn create_output(cfg: &OutputConfig) -> anyhow::Result<Box<dyn FrameOutput>> {
+    Ok(match cfg {
+        OutputConfig::Png { dir, keep } => Box::new(PngOutput::new(dir, keep.unwrap_or(0))?),
+        OutputConfig::Mjpeg { listen, quality } => Box::new(MjpegOutput::new(
+            listen.as_deref().unwrap_or(DEFAULT_MJPEG_LISTEN_ADDR),
+            quality.unwrap_or(80),
+        )?),
+        OutputConfig::Framebuffer { device } => Box::new(FramebufferOutput::new(device)?),
+        #[cfg(feature = "preview-window")]
+        OutputConfig::Window => Box::new(crate::preview_window::WindowOutput::new()?),
+        #[cfg(not(feature = "preview-window"))]
+        OutputConfig::Window => bail!("asterctl was built without the preview-window feature"),
+    })
+}
+
+/// All secondary frame outputs, unchanged frames are skipped.
+#[derive(Default)]
+pub struct FrameOutputs {
+    outputs: Vec<Box<dyn FrameOutput>>,
+    /// Hash of the last sent frame.
+    last_frame: Option<u64>,
+}
+
+impl FrameOutputs {
+    /// Create the configured frame outputs. Outputs which can't be created are logged and
+    /// skipped.
+    pub fn new(cfg: &[OutputConfig]) -> Self {
+        let outputs = cfg
+            .iter()
+            .filter_map(|cfg| match create_output(cfg) {
+                Ok(output) => {
+                    info!("Sending frames to {}", output.name());
+                    Some(output)
+                }
+                Err(e) => {
+                    error!("Failed to create frame output: {e:#}");
+                    None
+                }
+            })
+            .collect();
+        Self {
+            outputs,
+            last_frame: None,
+        }
+    }
+
+    /// Add a frame output.
+    pub fn add(&mut self, output: Box<dyn FrameOutput>) {
+        self.outputs.push(output);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    /// Send a frame to all outputs if it changed since the last frame. Errors are logged.
+    pub fn send(&mut self, image: &RgbImage) {
+        let mut hasher = DefaultHasher::new();
+        image.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.last_frame == Some(hash) {
+            return;
+        }
+        self.last_frame = Some(hash);
+
+        for output in &mut self.outputs {
+            if let Err(e) = output.send(image) {
+                warn!("Error sending frame to {}: {e:#}", output.name());
+            }
+        }
+    }
+}
+
+/// Write the latest frame as `latest.png` into a directory, optionally keeping the last frames.
+pub struct PngOutput {
+    name: String,
+    dir: PathBuf,
+    keep: usize,
+    /// Kept frame files, oldest first.
+    frames: VecDeque<PathBuf>,
+    counter: u64,
+}
+
+impl PngOutput {
+    /// Create a PNG output.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`: output directory, created if it doesn't exist.
+    /// * `keep`: number of previous frames to keep as `frame-{n}.png`, none if 0.
+    pub fn new(dir: impl Into<PathBuf>, keep: usize) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create PNG output directory {}", dir.display()))?;
+        Ok(Self {
+            name: format!("PNG directory {}", dir.display()),
+            dir,
+            keep,
+            frames: VecDeque::new(),
+            counter: 0,
+        })
+    }
+}
+
+impl FrameOutput for PngOutput {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, image: &RgbImage) -> anyhow::Result<()> {
+        // replace the file atomically, so readers never see a partially written frame
+        let tmp_path = self.dir.join(".latest.png.tmp");
+        image.save_with_format(&tmp_path, ImageFormat::Png)?;
+        fs::rename(&tmp_path, self.dir.join("latest.png"))?;
+
+        if self.keep > 0 {
+            self.counter += 1;
+            let path = self.dir.join(format!("frame-{:06}.png", self.counter));
+            fs::copy(self.dir.join("latest.png"), &path)?;
+            self.frames.push_back(path);
+            while self.frames.len() > self.keep {
+                if let Some(old) = self.frames.pop_front() {
+                    let _ = fs::remove_file(old);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Latest JPEG frame shared with the stream clients.
+#[derive(Default)]
+struct MjpegFrame {
+    /// Frame counter, increased with every new frame.
+    seq: u64,
+    jpeg: Option<Arc<Vec<u8>>>,
+}
+
+/// MJPEG HTTP stream of the frames, e.g. for an `<img>` element of a browser kiosk.
+pub struct MjpegOutput {
+    name: String,
+    quality: u8,
+    frame: Arc<(Mutex<MjpegFrame>, Condvar)>,
+}
+
+impl MjpegOutput {
+    /// Start the MJPEG stream server.
+    ///
+    /// # Arguments
+    ///
+    /// * `listen`: listen address, e.g. `127.0.0.1:8687`.
+    /// * `quality`: JPEG quality 1..100.
+    pub fn new(listen: &str, quality: u8) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(listen)
+            .with_context(|| format!("Failed to bind MJPEG stream to {listen}"))?;
+        let frame = Arc::new((Mutex::new(MjpegFrame::default()), Condvar::new()));
+
+        let shared = frame.clone();
+        std::thread::Builder::new()
+            .name("mjpeg".into())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            warn!("Failed to accept MJPEG stream connection: {e}");
+                            continue;
+                        }
+                    };
+                    let frame = shared.clone();
+                    if let Err(e) = std::thread::Builder::new()
+                        .name("mjpeg-client".into())
+                        .spawn(move || {
+                            if let Err(e) = stream_frames(stream, &frame) {
+                                debug!("MJPEG stream client disconnected: {e}");
+                            }
+                        })
+                    {
+                        warn!("Failed to start MJPEG stream client thread: {e}");
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            name: format!("MJPEG stream http://{listen}/"),
+            quality: quality.clamp(1, 100),
+            frame,
+        })
+    }
+}
+
+impl FrameOutput for MjpegOutput {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, image: &RgbImage) -> anyhow::Result<()> {
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, self.quality).encode_image(image)?;
+
+        let (lock, condvar) = &*self.frame;
+        let mut frame = lock.lock().expect("Poisoned MJPEG frame mutex");
+        frame.seq += 1;
+        frame.jpeg = Some(Arc::new(jpeg));
+        condvar.notify_all();
+        Ok(())
+    }
+}
+
+/// Send the current and all following frames to a stream client until it disconnects.
+fn stream_frames(
+    mut stream: TcpStream,
+    frame: &(Mutex<MjpegFrame>, Condvar),
+) -> anyhow::Result<()> {
+    // the request is ignored, every path returns the stream
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" && line != "\n" {
+        line.clear();
+    }
+    stream.write_all(
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary={MJPEG_BOUNDARY}\r\n\
+             Cache-Control: no-cache\r\nConnection: close\r\n\r\n"
+        )
+        .as_bytes(),
+    )?;
+
+    let (lock, condvar) = frame;
+    let mut last_seq = 0;
+    loop {
+        let (seq, jpeg) = {
+            let mut frame = lock.lock().expect("Poisoned MJPEG frame mutex");
+            while frame.seq == last_seq {
+                // repeat the frame now and then, so dead clients are detected
+                let (next, timeout) = condvar
+                    .wait_timeout(frame, Duration::from_secs(30))
+                    .expect("Poisoned MJPEG frame mutex");
+                frame = next;
+                if timeout.timed_out() {
+                    break;
+                }
+            }
+            (frame.seq, frame.jpeg.clone())
+        };
+        last_seq = seq;
+        let Some(jpeg) = jpeg else {
+            continue;
+        };
+        stream.write_all(
+            format!(
+                "--{MJPEG_BOUNDARY}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                jpeg.len()
+            )
+            .as_bytes(),
+        )?;
+        stream.write_all(&jpeg)?;
+        stream.write_all(b"\r\n")?;
+    }
+}
+
+/// Pixel layout of a framebuffer device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FramebufferGeometry {
+    width: u32,
+    height: u32,
+    bits_per_pixel: u32,
+    /// Bytes per line.
+    stride: u32,
+}
+
+impl FramebufferGeometry {
+    /// Read the geometry of a framebuffer device from `/sys/class/graphics`.
+    fn read(device: &Path) -> anyhow::Result<Self> {
+        let name = device
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid framebuffer device {}", device.display()))?;
+        let sys = Path::new("/sys/class/graphics").join(name);
+        let read = |attr: &str| {
+            fs::read_to_string(sys.join(attr))
+                .with_context(|| format!("Failed to read {attr} of {}", device.display()))
+                .map(|value| value.trim().to_string())
+        };
+
+        let size = read("virtual_size")?;
+        let (width, height) = size
+            .split_once(',')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+            .ok_or_else(|| anyhow!("Invalid framebuffer size: {size}"))?;
+        let bits_per_pixel = read("bits_per_pixel")?.parse()?;
+        let stride = match read("stride") {
+            Ok(stride) => stride.parse()?,
+            Err(_) => width * bits_per_pixel / 8,
+        };
+        if !matches!(bits_per_pixel, 16 | 24 | 32) {
+            bail!("Unsupported framebuffer depth: {bits_per_pixel} bits per pixel");
+        }
+
+        Ok(Self {
+            width,
+            height,
+            bits_per_pixel,
+            stride,
+        })
+    }
+
+    /// Encode an image into the framebuffer memory layout: RGB565, BGR or BGRX in little
+    /// endian. The image is drawn at the top left corner and cropped to the framebuffer.
+    fn encode(&self, image: &RgbImage) -> Vec<u8> {
+        let bytes_per_pixel = (self.bits_per_pixel / 8) as usize;
+        let mut buffer = vec![0u8; self.stride as usize * self.height as usize];
+        let width = image.width().min(self.width);
+        let height = image.height().min(self.height);
+        for y in 0..height {
+            let line = y as usize * self.stride as usize;
+            for x in 0..width {
+                let [r, g, b] = image.get_pixel(x, y).0;
+                let offset = line + x as usize * bytes_per_pixel;
+                let pixel = &mut buffer[offset..offset + bytes_per_pixel];
+                match bytes_per_pixel {
+                    2 => {
+                        let rgb565 =
+                            ((r as u16 & 0xf8) << 8) | ((g as u16 & 0xfc) << 3) | (b as u16 >> 3);
+                        pixel.copy_from_slice(&rgb565.to_le_bytes());
+                    }
+                    3 => pixel.copy_from_slice(&[b, g, r]),
+                    _ => pixel.copy_from_slice(&[b, g, r, 0xff]),
+                }
+            }
+        }
+        buffer
+    }
+}
+
+/// Linux framebuffer device output, e.g. `/dev/fb0` of a secondary HDMI display.
+pub struct FramebufferOutput {
+    name: String,
+    file: File,
+    geometry: FramebufferGeometry,
+}
+
+impl FramebufferOutput {
+    /// Open a framebuffer device.
+    pub fn new(device: &Path) -> anyhow::Result<Self> {
+        let geometry = FramebufferGeometry::read(device)?;
+        let file = OpenOptions::new()
+            .write(true)
+            .open(device)
+            .with_context(|| format!("Failed to open framebuffer {}", device.display()))?;
+        info!(
+            "Framebuffer {}: {}x{}, {} bits per pixel",
+            device.display(),
+            geometry.width,
+            geometry.height,
+            geometry.bits_per_pixel
+        );
+        Ok(Self {
+            name: format!("framebuffer {}", device.display()),
+            file,
+            geometry,
+        })
+    }
+}
+
+impl FrameOutput for FramebufferOutput {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, image: &RgbImage) -> anyhow::Result<()> {
+        let buffer = self.geometry.encode(image);
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&buffer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn encode_framebuffer_pixels() {
+        let image = RgbImage::from_pixel(3, 2, Rgb([255, 128, 0]));
+        let geometry = FramebufferGeometry {
+            width: 2,
+            height: 3,
+            bits_per_pixel: 16,
+            stride: 6,
+        };
+        let buffer = geometry.encode(&image);
+        assert_eq!(18, buffer.len());
+        // RGB565 0xfc00, cropped to 2 pixels per line and padded to the stride
+        assert_eq!([0x00, 0xfc, 0x00, 0xfc, 0, 0], buffer[..6]);
+        assert!(buffer[12..].iter().all(|b| *b == 0));
+
+        let geometry = FramebufferGeometry {
+            width: 4,
+            height: 2,
+            bits_per_pixel: 32,
+            stride: 16,
+        };
+        let buffer = geometry.encode(&image);
+        assert_eq!([0, 128, 255, 0xff], buffer[..4]);
+        assert_eq!([0, 0, 0, 0], buffer[12..16]);
+    }
+
+    #[test]
+    fn png_output_keeps_last_frames() {
+        let dir = std::env::temp_dir().join(format!("asterctl-png-{}", std::process::id()));
+        let mut outputs = FrameOutputs::default();
+        outputs.add(Box::new(PngOutput::new(&dir, 2).unwrap()));
+
+        for value in [0, 0, 1, 2] {
+            outputs.send(&RgbImage::from_pixel(2, 2, Rgb([value, 0, 0])));
+        }
+        // the unchanged second frame is skipped
+        assert!(dir.join("latest.png").exists());
+        assert!(!dir.join("frame-000001.png").exists());
+        assert!(dir.join("frame-000002.png").exists());
+        assert!(dir.join("frame-000003.png").exists());
+        assert!(!dir.join("frame-000004.png").exists());
+        let latest = image::open(dir.join("latest.png")).unwrap().to_rgb8();
+        assert_eq!(Rgb([2, 0, 0]), *latest.get_pixel(0, 0));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,462 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Custom panel packages of `asterctl panel install` and `asterctl panel export`.
+//!
+//! A panel package is a ZIP archive of a custom panel directory: the `panel.json` configuration
+//! with the `img` and `fonts` subdirectories, see [load_custom_panel]. The files may also be in a
+//! single top-level directory of the archive. Installed panels are stored in the [PANELS_DIR]
+//! subdirectory of the configuration directory.
+
+use crate::cfg::{Panel, load_custom_panel};
+use crate::remote_image::is_url;
+use anyhow::{Context, bail};
+use log::{info, warn};
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Subdirectory of the configuration directory with the installed panels.
+pub const PANELS_DIR: &str = "panels";
+
+/// Configuration file of a custom panel.
+const PANEL_FILE: &str = "panel.json";
+
+/// Subdirectories of a custom panel in a package.
+const PANEL_DIRS: [&str; 2] = ["img", "fonts"];
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Maximum size of a downloaded panel package.
+const MAX_PACKAGE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Maximum total size of the unpacked files of a panel package.
+const MAX_UNPACKED_SIZE: u64 = 200 * 1024 * 1024;
+
+/// Install a panel package.
+///
+/// The package is unpacked into a temporary directory next to the installed panels and validated
+/// by loading the panel, before it's moved into place.
+///
+/// # Arguments
+///
+/// * `source`: ZIP archive, custom panel directory or HTTP(S) URL of a ZIP archive.
+/// * `panels_dir`: directory of the installed panels.
+/// * `name`: name of the installed panel directory. Default: the archive or directory name.
+/// * `replace`: replace an installed panel with the same name.
+///
+/// returns: Result<(PathBuf, Panel), Error>, the installed panel directory and the loaded panel.
+pub fn install_panel(
+    source: &str,
+    panels_dir: &Path,
+    name: Option<&str>,
+    replace: bool,
+) -> anyhow::Result<(PathBuf, Panel)> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => package_name(source)
+            .with_context(|| format!("No panel name in {source}, use --name"))?,
+    };
+    if !is_valid_name(&name) {
+        bail!("Invalid panel name '{name}'");
+    }
+    let target = panels_dir.join(&name);
+    if target.exists() && !replace {
+        bail!(
+            "Panel '{name}' is already installed in {}, use --force to replace it",
+            target.display()
+        );
+    }
+
+    fs::create_dir_all(panels_dir)
+        .with_context(|| format!("Failed to create {}", panels_dir.display()))?;
+    let staging = panels_dir.join(format!(".install-{name}"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    let root = match unpack(source, &staging).and_then(|()| validate(&staging)) {
+        Ok(root) => root,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    if target.exists() {
+        info!("Replacing panel {}", target.display());
+        fs::remove_dir_all(&target)?;
+    }
+    fs::rename(&root, &target)
+        .with_context(|| format!("Failed to install panel into {}", target.display()))?;
+    // the package files were in a subdirectory
+    let _ = fs::remove_dir_all(&staging);
+
+    let panel = load_custom_panel(&target)?;
+    Ok((target, panel))
+}
+
+/// Export a custom panel directory into a panel package.
+///
+/// The package contains `panel.json` and the `img` and `fonts` subdirectories, other files of
+/// the directory are left out.
+///
+/// returns: Result<usize, Error>, the number of files in the package.
+pub fn export_panel(dir: &Path, output: &Path) -> anyhow::Result<usize> {
+    load_custom_panel(dir).context("Invalid custom panel")?;
+
+    let mut files = vec![PathBuf::from(PANEL_FILE)];
+    for sub_dir in PANEL_DIRS {
+        collect_files(dir, Path::new(sub_dir), &mut files)?;
+    }
+
+    let file =
+        File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for sub_dir in PANEL_DIRS {
+        // also empty directories, which are required to load the panel
+        zip.add_directory(sub_dir, options)?;
+    }
+    for file in &files {
+        let name: Vec<_> = file
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+        zip.start_file(name.join("/"), options)?;
+        io::copy(&mut File::open(dir.join(file))?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(files.len())
+}
+
+/// Default panel name of a package source: the archive file name without extension, or the
+/// directory name.
+fn package_name(source: &str) -> Option<String> {
+    let name = if is_url(source) {
+        let url = source.split(['?', '#']).next().unwrap_or_default();
+        Path::new(url.rsplit('/').next()?)
+            .file_stem()?
+            .to_os_string()
+    } else {
+        let path = fs::canonicalize(source).unwrap_or_else(|_| PathBuf::from(source));
+        match path.is_dir() {
+            true => path.file_name()?.to_os_string(),
+            false => path.file_stem()?.to_os_string(),
+        }
+    };
+    Some(name.to_string_lossy().to_string()).filter(|name| !name.is_empty())
+}
+
+/// A panel name is a single, not hidden, directory name.
+fn is_valid_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+}
+
+/// Unpack a package source into a new directory.
+fn unpack(source: &str, dir: &Path) -> anyhow::Result<()> {
+    if is_url(source) {
+        return extract(Cursor::new(download(source)?), dir, MAX_UNPACKED_SIZE);
+    }
+    let path = Path::new(source);
+    if path.is_dir() {
+        copy_panel(path, dir)
+    } else {
+        let file = File::open(path).with_context(|| format!("Failed to open {source}"))?;
+        extract(file, dir, MAX_UNPACKED_SIZE)
+    }
+}
+
+fn download(url: &str) -> anyhow::Result<Vec<u8>> {
+    info!("Downloading panel package {url}");
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(DOWNLOAD_TIMEOUT))
+        .user_agent(concat!("asterctl/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .into();
+    agent
+        .get(url)
+        .call()
+        .with_context(|| format!("Failed to download {url}"))?
+        .body_mut()
+        .with_config()
+        .limit(MAX_PACKAGE_SIZE)
+        .read_to_vec()
+        .with_context(|| format!("Failed to download {url}"))
+}
+
+/// Extract a ZIP archive.
+///
+/// Symbolic links, entries outside the target directory and archives with more than `max_size`
+/// unpacked bytes are rejected. The size is counted while extracting, the sizes in the archive
+/// may be forged.
+fn extract(archive: impl Read + Seek, dir: &Path, max_size: u64) -> anyhow::Result<()> {
+    let mut archive = ZipArchive::new(archive).context("Invalid panel package")?;
+    fs::create_dir_all(dir)?;
+    let mut remaining = max_size;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).context("Invalid panel package")?;
+        if entry.is_symlink() {
+            bail!("Invalid panel package: symbolic link {}", entry.name());
+        }
+        let Some(name) = entry.enclosed_name() else {
+            bail!(
+                "Invalid panel package: {} is outside the package",
+                entry.name()
+            );
+        };
+        let path = dir.join(name);
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file =
+            File::create(&path).with_context(|| format!("Failed to extract {}", path.display()))?;
+        let size = io::copy(&mut (&mut entry).take(remaining + 1), &mut file)
+            .with_context(|| format!("Failed to extract {}", path.display()))?;
+        if size > remaining {
+            bail!("Invalid panel package: the unpacked files are larger than {max_size} bytes");
+        }
+        remaining -= size;
+    }
+    Ok(())
+}
+
+/// Copy the package files of a custom panel directory.
+fn copy_panel(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to)?;
+    let mut files = Vec::new();
+    if file_type(&from.join(PANEL_FILE)).is_some_and(|file_type| file_type.is_file()) {
+        files.push(PathBuf::from(PANEL_FILE));
+    }
+    for sub_dir in PANEL_DIRS {
+        if file_type(&from.join(sub_dir)).is_some_and(|file_type| file_type.is_dir()) {
+            fs::create_dir_all(to.join(sub_dir))?;
+            collect_files(from, Path::new(sub_dir), &mut files)?;
+        }
+    }
+    for file in files {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(to.join(parent))?;
+        }
+        fs::copy(from.join(&file), to.join(&file))
+            .with_context(|| format!("Failed to copy {}", from.join(&file).display()))?;
+    }
+    Ok(())
+}
+
+/// Add the files of a subdirectory, relative to `base`, in name order.
+///
+/// Symbolic links are skipped: they could add files from outside the panel directory.
+fn collect_files(base: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let path = base.join(dir);
+    if file_type(&path).is_some_and(|file_type| file_type.is_symlink()) {
+        warn!("Skipping symbolic link {}", path.display());
+        return Ok(());
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return Ok(());
+    };
+    let mut entries = entries.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = dir.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            warn!("Skipping symbolic link {}", base.join(path).display());
+        } else if file_type.is_dir() {
+            collect_files(base, &path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// File type of a path without following symbolic links.
+fn file_type(path: &Path) -> Option<fs::FileType> {
+    fs::symlink_metadata(path)
+        .ok()
+        .map(|metadata| metadata.file_type())
+}
+
+/// Validate an unpacked package by loading the panel.
+///
+/// returns: Result<PathBuf, Error>, the panel directory: the package directory, or its single
+/// subdirectory with the panel files.
+fn validate(dir: &Path) -> anyhow::Result<PathBuf> {
+    let root = if dir.join(PANEL_FILE).is_file() {
+        dir.to_path_buf()
+    } else {
+        let mut roots = fs::read_dir(dir)?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.join(PANEL_FILE).is_file());
+        match (roots.next(), roots.next()) {
+            (Some(root), None) => root,
+            _ => bail!("Invalid panel package: no {PANEL_FILE} file"),
+        }
+    };
+    for sub_dir in PANEL_DIRS {
+        if !root.join(sub_dir).is_dir() {
+            bail!("Invalid panel package: no {sub_dir} directory");
+        }
+    }
+    load_custom_panel(&root).context("Invalid panel package")?;
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_and_install_panel() {
+        let dir = std::env::temp_dir().join(format!("asterctl-package-{}", std::process::id()));
+        let panel_dir = dir.join("my_panel");
+        fs::create_dir_all(panel_dir.join("img/icons")).unwrap();
+        fs::create_dir_all(panel_dir.join("fonts")).unwrap();
+        fs::write(
+            panel_dir.join(PANEL_FILE),
+            r#"{ "name": "My Panel", "img": "bg.png", "sensor": [] }"#,
+        )
+        .unwrap();
+        fs::write(panel_dir.join("img/bg.png"), "png").unwrap();
+        fs::write(panel_dir.join("img/icons/fan.png"), "png").unwrap();
+        fs::write(panel_dir.join("notes.txt"), "not packaged").unwrap();
+
+        let package = dir.join("my_panel.zip");
+        assert_eq!(3, export_panel(&panel_dir, &package).unwrap());
+
+        let panels_dir = dir.join(PANELS_DIR);
+        let source = package.to_str().unwrap();
+        let (installed, panel) = install_panel(source, &panels_dir, None, false).unwrap();
+        assert_eq!(panels_dir.join("my_panel"), installed);
+        assert_eq!(Some("My Panel"), panel.name.as_deref());
+        assert!(installed.join("img/icons/fan.png").is_file());
+        assert!(installed.join("fonts").is_dir());
+        assert!(!installed.join("notes.txt").exists());
+
+        let error = install_panel(source, &panels_dir, None, false).unwrap_err();
+        assert!(error.to_string().contains("already installed"), "{error}");
+        let dir_source = panel_dir.to_str().unwrap();
+        let (installed, _) = install_panel(dir_source, &panels_dir, None, true).unwrap();
+        assert_eq!(panels_dir.join("my_panel"), installed);
+        assert_eq!(vec![installed.clone()], list_dirs(&panels_dir));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reject_invalid_packages() {
+        let dir = std::env::temp_dir().join(format!("asterctl-package-bad-{}", std::process::id()));
+        let panel_dir = dir.join("no_img");
+        fs::create_dir_all(panel_dir.join("fonts")).unwrap();
+        fs::write(panel_dir.join(PANEL_FILE), r#"{ "sensor": [] }"#).unwrap();
+        let panels_dir = dir.join(PANELS_DIR);
+
+        let error = install_panel(panel_dir.to_str().unwrap(), &panels_dir, None, false);
+        assert_eq!(
+            "Invalid panel package: no img directory",
+            error.unwrap_err().to_string()
+        );
+        assert!(list_dirs(&panels_dir).is_empty());
+        assert!(install_panel("x.zip", &panels_dir, Some("../x"), false).is_err());
+        assert_eq!(
+            Some("gauges".to_string()),
+            package_name("https://example.com/panels/gauges.zip?download=1")
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reject_symbolic_link_entries() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-package-link-{}", std::process::id()));
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.add_directory("img", options).unwrap();
+        zip.add_symlink("fonts", "/root/.cargo", options).unwrap();
+        let archive = zip.finish().unwrap();
+
+        let error = extract(archive, &dir, MAX_UNPACKED_SIZE).unwrap_err();
+
+        assert_eq!(
+            "Invalid panel package: symbolic link fonts",
+            error.to_string()
+        );
+        assert!(file_type(&dir.join("fonts")).is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reject_oversized_archives() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-package-size-{}", std::process::id()));
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file("img/bg.png", options).unwrap();
+        io::copy(&mut io::repeat(0).take(4096), &mut zip).unwrap();
+        zip.start_file("img/fan.png", options).unwrap();
+        io::copy(&mut io::repeat(0).take(4096), &mut zip).unwrap();
+        let archive = zip.finish().unwrap();
+
+        let error = extract(archive.clone(), &dir.join("small"), 8191).unwrap_err();
+        assert_eq!(
+            "Invalid panel package: the unpacked files are larger than 8191 bytes",
+            error.to_string()
+        );
+        extract(archive, &dir.join("fits"), 8192).unwrap();
+        assert_eq!(
+            4096,
+            fs::metadata(dir.join("fits/img/fan.png")).unwrap().len()
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skip_symbolic_links_of_panel_directories() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-package-skip-{}", std::process::id()));
+        let panel_dir = dir.join("linked");
+        fs::create_dir_all(panel_dir.join("img")).unwrap();
+        fs::create_dir_all(dir.join("outside")).unwrap();
+        fs::write(dir.join("outside/secret.txt"), "secret").unwrap();
+        fs::write(panel_dir.join(PANEL_FILE), r#"{ "sensor": [] }"#).unwrap();
+        fs::write(panel_dir.join("img/bg.png"), "png").unwrap();
+        std::os::unix::fs::symlink(dir.join("outside"), panel_dir.join("fonts")).unwrap();
+        std::os::unix::fs::symlink(dir.join("outside/secret.txt"), panel_dir.join("img/s.png"))
+            .unwrap();
+
+        let mut files = Vec::new();
+        for sub_dir in PANEL_DIRS {
+            collect_files(&panel_dir, Path::new(sub_dir), &mut files).unwrap();
+        }
+        assert_eq!(vec![PathBuf::from("img/bg.png")], files);
+
+        copy_panel(&panel_dir, &dir.join("copy")).unwrap();
+        assert!(dir.join("copy/img/bg.png").is_file());
+        assert!(file_type(&dir.join("copy/img/s.png")).is_none());
+        assert!(file_type(&dir.join("copy/fonts")).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn list_dirs(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect()
+    }
+}
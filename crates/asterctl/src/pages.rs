@@ -0,0 +1,600 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Sensor page rotation.
+//!
+//! Sensor pages are built dynamically by matching the available sensor keys against the sensor
+//! templates of the configured panels. Each [PageRotation] cycles through its own pages, which
+//! allows multiple independent rotations side by side on the display.
+//!
+//! Scheduled message pages are added during their schedule.
+//!
+//! The `showIf` conditions of the panels and sensor templates are evaluated with the live sensor
+//! values whenever the page list is rebuilt, i.e. at the start of every rotation cycle.
+
+use crate::cfg::{Condition, MessagePage, MonitorConfig, Sensor};
+use crate::sensors::SensorStore;
+use chrono::{Local, NaiveDateTime};
+use log::{info, warn};
+use regex::Regex;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// A single page in the page rotation.
+pub enum PageKind {
+    Sensor(Box<SensorPage>),
+    Message(Box<MessagePage>),
+    Time(String),
+}
+
+impl PageKind {
+    /// Page name: the sensor key of a sensor page, the name of a message page, or `time` for the
+    /// time page.
+    pub fn name(&self) -> &str {
+        match self {
+            PageKind::Sensor(sp) => &sp.sensor_key,
+            PageKind::Message(message) => message.name(),
+            PageKind::Time(_) => "time",
+        }
+    }
+
+    /// Check if the page matches a page selector: the page name or the display name of a sensor
+    /// page, ignoring case.
+    pub fn matches(&self, selector: &str) -> bool {
+        match self {
+            PageKind::Sensor(sp) => {
+                sp.sensor_key.eq_ignore_ascii_case(selector)
+                    || sp.display_name.eq_ignore_ascii_case(selector)
+            }
+            PageKind::Message(message) => message.name().eq_ignore_ascii_case(selector),
+            PageKind::Time(_) => selector.eq_ignore_ascii_case("time"),
+        }
+    }
+}
+
+/// A sensor page created from a sensor template and a matching sensor key.
+pub struct SensorPage {
+    pub sensor_key: String,
+    pub display_name: String,
+    pub template: Sensor,
+}
+
+struct CompiledTemplate {
+    regex: Regex,
+    sensor: Sensor,
+    /// `showIf` condition of the panel of the template.
+    panel_condition: Option<Condition>,
+}
+
+/// Page rotation state of a set of panels.
+pub struct PageRotation {
+    name: Option<String>,
+    templates: Vec<CompiledTemplate>,
+    time_page: Option<String>,
+    messages: Vec<MessagePage>,
+    sensor_page_time: Duration,
+    time_page_time: Duration,
+    pages: Vec<PageKind>,
+    page_idx: usize,
+    page_start: Option<Instant>,
+    refresh_count: u32,
+}
+
+impl PageRotation {
+    /// Create a new page rotation for the given panels.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: optional rotation name used for logging, e.g. the slot name.
+    /// * `cfg`: the monitor configuration containing the panel definitions.
+    /// * `panels`: 1-based panel indexes into `cfg.panels`. Sensor templates of these panels are used.
+    /// * `time_page`: optional date/time label for a time page at the end of the rotation.
+    /// * `messages`: scheduled message pages, added after the sensor pages during their schedule.
+    /// * `sensor_page_time`: display time of each sensor page.
+    /// * `time_page_time`: display time of the time page.
+    pub fn new(
+        name: Option<String>,
+        cfg: &MonitorConfig,
+        panels: &[u32],
+        time_page: Option<String>,
+        messages: &[MessagePage],
+        sensor_page_time: Duration,
+        time_page_time: Duration,
+    ) -> Self {
+        Self {
+            name,
+            templates: compile_sensor_templates(cfg, panels),
+            time_page,
+            messages: messages.to_vec(),
+            sensor_page_time,
+            time_page_time,
+            pages: Vec::new(),
+            page_idx: 0,
+            page_start: None,
+            refresh_count: 0,
+        }
+    }
+
+    /// Number of compiled sensor templates.
+    pub fn template_count(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// Number of pages in the current rotation cycle.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Rebuild the page list from the available sensor keys and the current local time.
+    ///
+    /// The previous page list is kept if no pages could be built.
+    pub fn rebuild(&mut self, values: &SensorStore) {
+        self.rebuild_at(values, Local::now().naive_local());
+    }
+
+    fn rebuild_at(&mut self, values: &SensorStore, now: NaiveDateTime) {
+        let pages = build_pages(
+            &self.templates,
+            values,
+            &self.messages,
+            now,
+            self.time_page.as_deref(),
+        );
+        if !pages.is_empty() {
+            self.pages = pages;
+        }
+        if self.page_idx >= self.pages.len() {
+            self.page_idx = 0;
+        }
+    }
+
+    /// Number of refreshes of the current page, starting with 1.
+    pub fn refresh_count(&self) -> u32 {
+        self.refresh_count
+    }
+
+    /// The current page, or `None` if there are no pages.
+    pub fn current(&self) -> Option<&PageKind> {
+        self.pages.get(self.page_idx)
+    }
+
+    /// Switch to the next page immediately.
+    pub fn next_page(&mut self) {
+        if self.pages.is_empty() {
+            return;
+        }
+        self.page_idx = (self.page_idx + 1) % self.pages.len();
+        self.restart_page();
+    }
+
+    /// Switch to the first page matching the page selector, see [PageKind::matches].
+    ///
+    /// Returns false if no page matches.
+    pub fn show_page(&mut self, selector: &str) -> bool {
+        let Some(idx) = self.pages.iter().position(|page| page.matches(selector)) else {
+            return false;
+        };
+        self.page_idx = idx;
+        self.restart_page();
+        true
+    }
+
+    /// Restart the display time of the current page, the next [PageRotation::tick] shows it.
+    fn restart_page(&mut self) {
+        self.page_start = Some(Instant::now());
+        self.refresh_count = 0;
+        if let Some(page) = self.pages.get(self.page_idx) {
+            info!(
+                "Switching to page {}/{}: {}",
+                self.page_idx + 1,
+                self.pages.len(),
+                page.name()
+            );
+        }
+    }
+
+    /// Advance to the next page if the display time of the current page is over.
+    ///
+    /// Must be called once per display refresh. The page list is rebuilt at the start of every
+    /// rotation cycle to pick up new sensors.
+    pub fn tick(&mut self, values: &SensorStore) {
+        match self.page_start {
+            None => {
+                self.page_idx = 0;
+                self.rebuild(values);
+            }
+            Some(page_start) if page_start.elapsed() >= self.current_page_time() => {
+                self.page_idx = (self.page_idx + 1) % self.pages.len().max(1);
+                if self.page_idx == 0 {
+                    self.rebuild(values);
+                }
+            }
+            Some(_) => {
+                self.refresh_count += 1;
+                return;
+            }
+        }
+
+        self.page_start = Some(Instant::now());
+        self.refresh_count = 1;
+        self.log_page(values);
+    }
+
+    fn current_page_time(&self) -> Duration {
+        match self.pages.get(self.page_idx) {
+            Some(PageKind::Time(_)) => self.time_page_time,
+            Some(PageKind::Message(message)) => message
+                .page_time
+                .map(Duration::from_secs_f32)
+                .unwrap_or(self.sensor_page_time),
+            _ => self.sensor_page_time,
+        }
+    }
+
+    fn log_page(&self, values: &SensorStore) {
+        let prefix = self
+            .name
+            .as_ref()
+            .map(|name| format!("[{name}] "))
+            .unwrap_or_default();
+        match self.pages.get(self.page_idx) {
+            Some(PageKind::Sensor(sp)) => {
+                let value = values.value(&sp.sensor_key).unwrap_or("N/A");
+                info!(
+                    "{prefix}Page {}/{}: '{}' [{}] = {}",
+                    self.page_idx + 1,
+                    self.pages.len(),
+                    sp.display_name,
+                    sp.sensor_key,
+                    value
+                );
+            }
+            Some(PageKind::Message(message)) => {
+                info!(
+                    "{prefix}Page {}/{}: message '{}'",
+                    self.page_idx + 1,
+                    self.pages.len(),
+                    message.name()
+                );
+            }
+            Some(PageKind::Time(label)) => {
+                info!(
+                    "{prefix}Page {}/{}: time ({})",
+                    self.page_idx + 1,
+                    self.pages.len(),
+                    label
+                );
+            }
+            None => {}
+        }
+    }
+}
+
+/// Compile regex patterns from sensor templates of the given panels.
+fn compile_sensor_templates(cfg: &MonitorConfig, panels: &[u32]) -> Vec<CompiledTemplate> {
+    let mut templates = Vec::new();
+    for &active in panels {
+        if active == 0 || active > cfg.panels.len() as u32 {
+            continue;
+        }
+        let panel = &cfg.panels[active as usize - 1];
+        for sensor in &panel.sensor {
+            if let Some(pattern) = &sensor.match_pattern {
+                match Regex::new(pattern) {
+                    Ok(re) => templates.push(CompiledTemplate {
+                        regex: re,
+                        sensor: sensor.clone(),
+                        panel_condition: panel.show_if.clone(),
+                    }),
+                    Err(e) => warn!("Invalid sensor match pattern '{pattern}': {e}"),
+                }
+            }
+        }
+    }
+    templates
+}
+
+/// Build pages by matching available sensor keys against compiled templates, followed by the
+/// scheduled message pages and the optional time page.
+/// Templates are matched in order; each sensor key matches at most one template. Templates whose
+/// own or panel `showIf` condition is not met are skipped.
+fn build_pages(
+    templates: &[CompiledTemplate],
+    values: &SensorStore,
+    messages: &[MessagePage],
+    now: NaiveDateTime,
+    time_page: Option<&str>,
+) -> Vec<PageKind> {
+    let mut sensor_keys: Vec<&String> = values.keys().collect();
+    sensor_keys.sort();
+
+    // For each template (in order), find all matching sensor keys.
+    // This preserves template order as the primary sort.
+    let mut matched_keys: HashSet<String> = HashSet::new();
+    let mut pages: Vec<PageKind> = Vec::new();
+
+    for tmpl in templates {
+        let hidden = [&tmpl.panel_condition, &tmpl.sensor.show_if]
+            .into_iter()
+            .flatten()
+            .any(|condition| !condition.is_met(values));
+        if hidden {
+            continue;
+        }
+        let mut matches: Vec<(&String, String)> = Vec::new();
+        for key in &sensor_keys {
+            if matched_keys.contains(*key) {
+                continue;
+            }
+            if let Some(caps) = tmpl.regex.captures(key) {
+                let display_name = expand_template_name(&tmpl.sensor, &caps);
+                matches.push((key, display_name));
+            }
+        }
+        for (key, display_name) in matches {
+            matched_keys.insert(key.clone());
+            pages.push(PageKind::Sensor(Box::new(SensorPage {
+                sensor_key: key.clone(),
+                display_name,
+                template: tmpl.sensor.clone(),
+            })));
+        }
+    }
+
+    pages.extend(
+        messages
+            .iter()
+            .filter(|message| message.is_scheduled(now))
+            .map(|message| PageKind::Message(Box::new(message.clone()))),
+    );
+
+    // Add optional time page at the end
+    if let Some(time_label) = time_page {
+        pages.push(PageKind::Time(time_label.to_string()));
+    }
+
+    info!(
+        "Built {} pages from {} sensor keys",
+        pages.len(),
+        sensor_keys.len()
+    );
+    pages
+}
+
+/// Expand the template display name using regex capture groups.
+/// `{1}`, `{2}`, etc. in the sensor `name` are replaced with capture group values.
+fn expand_template_name(sensor: &Sensor, caps: &regex::Captures) -> String {
+    let base_name = sensor
+        .name
+        .as_deref()
+        .or(sensor.item_name.as_deref())
+        .unwrap_or("Sensor");
+
+    let mut result = base_name.to_string();
+    for i in 1..=9 {
+        let placeholder = format!("{{{i}}}");
+        if let Some(m) = caps.get(i) {
+            result = result.replace(&placeholder, m.as_str());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MonitorConfig {
+        serde_json::from_str(
+            r#"{
+              "setup": { "refresh": 1 },
+              "mianban": [1, 2],
+              "diy": [
+                { "sensor": [
+                  { "mode": 1, "match": "^temperature_(.+)$", "name": "Temp {1}", "x": 0, "y": 0 }
+                ] },
+                { "sensor": [
+                  { "mode": 1, "match": "^storage_(.+)_usage$", "name": "Disk {1}", "x": 0, "y": 0 }
+                ] }
+              ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn values() -> SensorStore {
+        SensorStore::from_iter([
+            ("temperature_cpu".to_string(), "42".to_string()),
+            ("temperature_gpu".to_string(), "50".to_string()),
+            ("storage_nvme_usage".to_string(), "12".to_string()),
+        ])
+    }
+
+    fn page_names(rotation: &PageRotation) -> Vec<String> {
+        rotation
+            .pages
+            .iter()
+            .map(|page| match page {
+                PageKind::Sensor(sp) => sp.display_name.clone(),
+                PageKind::Message(message) => message.name().to_string(),
+                PageKind::Time(label) => label.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rotation_only_uses_templates_of_its_panels() {
+        let cfg = config();
+        let mut left = PageRotation::new(
+            None,
+            &cfg,
+            &[1],
+            Some("DATE_h_m_3".into()),
+            &[],
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+        );
+        let mut right = PageRotation::new(
+            None,
+            &cfg,
+            &[2],
+            None,
+            &[],
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+        );
+        left.rebuild(&values());
+        right.rebuild(&values());
+
+        assert_eq!(
+            vec!["Temp cpu", "Temp gpu", "DATE_h_m_3"],
+            page_names(&left)
+        );
+        assert_eq!(vec!["Disk nvme"], page_names(&right));
+    }
+
+    #[test]
+    fn tick_advances_after_page_time() {
+        let cfg = config();
+        let mut rotation = PageRotation::new(
+            None,
+            &cfg,
+            &[1, 2],
+            None,
+            &[],
+            Duration::ZERO,
+            Duration::ZERO,
+        );
+
+        let mut keys = Vec::new();
+        for _ in 0..4 {
+            rotation.tick(&values());
+            match rotation.current() {
+                Some(PageKind::Sensor(sp)) => keys.push(sp.sensor_key.clone()),
+                _ => panic!("expected a sensor page"),
+            }
+        }
+        assert_eq!(
+            vec![
+                "temperature_cpu",
+                "temperature_gpu",
+                "storage_nvme_usage",
+                "temperature_cpu"
+            ],
+            keys
+        );
+        assert_eq!(1, rotation.refresh_count());
+    }
+    #[test]
+    fn show_page_by_selector() {
+        let cfg = config();
+        let mut rotation = PageRotation::new(
+            None,
+            &cfg,
+            &[1, 2],
+            None,
+            &[],
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+        );
+        rotation.tick(&values());
+
+        assert!(rotation.show_page("disk NVME"));
+        rotation.tick(&values());
+        assert_eq!(
+            Some("storage_nvme_usage"),
+            rotation.current().map(PageKind::name)
+        );
+        assert_eq!(1, rotation.refresh_count());
+
+        rotation.next_page();
+        rotation.tick(&values());
+        assert_eq!(
+            Some("temperature_cpu"),
+            rotation.current().map(PageKind::name)
+        );
+
+        assert!(!rotation.show_page("unknown"));
+        assert_eq!(
+            Some("temperature_cpu"),
+            rotation.current().map(PageKind::name)
+        );
+    }
+
+    #[test]
+    fn show_if_conditions_filter_pages() {
+        let cfg: MonitorConfig = serde_json::from_str(
+            r#"{
+              "setup": { "refresh": 1 },
+              "mianban": [1, 2],
+              "diy": [
+                { "showIf": "temperature_gpu", "sensor": [
+                  { "mode": 1, "match": "^temperature_(.+)$", "name": "Temp {1}", "x": 0, "y": 0 }
+                ] },
+                { "sensor": [
+                  { "mode": 1, "match": "^storage_(.+)_usage$", "name": "Disk {1}", "x": 0, "y": 0,
+                    "showIf": "battery_ac_online == 0" }
+                ] }
+              ]
+            }"#,
+        )
+        .unwrap();
+        let mut rotation = PageRotation::new(
+            None,
+            &cfg,
+            &[1, 2],
+            None,
+            &[],
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+        );
+
+        let mut values = values();
+        values.set("battery_ac_online", "0", "");
+        rotation.rebuild(&values);
+        assert_eq!(
+            vec!["Temp cpu", "Temp gpu", "Disk nvme"],
+            page_names(&rotation)
+        );
+
+        values.remove("temperature_gpu");
+        rotation.rebuild(&values);
+        assert_eq!(vec!["Disk nvme"], page_names(&rotation));
+
+        values.set("temperature_gpu", "50", "");
+        values.set("battery_ac_online", "1", "");
+        rotation.rebuild(&values);
+        assert_eq!(vec!["Temp cpu", "Temp gpu"], page_names(&rotation));
+    }
+
+    #[test]
+    fn scheduled_message_pages() {
+        let cfg = config();
+        let messages: Vec<MessagePage> = serde_json::from_str(
+            r#"[{ "title": "Backup", "text": "Backup window", "from": "02:00", "to": "04:00" }]"#,
+        )
+        .unwrap();
+        let mut rotation = PageRotation::new(
+            None,
+            &cfg,
+            &[2],
+            Some("DATE_h_m_3".into()),
+            &messages,
+            Duration::from_secs(10),
+            Duration::from_secs(10),
+        );
+        let at = |time: &str| {
+            NaiveDateTime::parse_from_str(&format!("2026-10-16 {time}"), "%Y-%m-%d %H:%M").unwrap()
+        };
+
+        rotation.rebuild_at(&values(), at("01:00"));
+        assert_eq!(vec!["Disk nvme", "DATE_h_m_3"], page_names(&rotation));
+
+        rotation.rebuild_at(&values(), at("03:00"));
+        assert_eq!(
+            vec!["Disk nvme", "Backup", "DATE_h_m_3"],
+            page_names(&rotation)
+        );
+        assert!(rotation.show_page("backup"));
+    }
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Persisted display power state.
+//!
+//! A display switched off by the user, with `asterctl --off` or a control command, stays off
+//! after restarting the sensor panel. The display schedule doesn't change the persisted state.
+
+use log::{debug, warn};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the power state in the state directory.
+const STATE_FILE: &str = "display_power";
+
+/// Display power state file. Without a file, the display is always switched on at startup.
+#[derive(Debug, Clone, Default)]
+pub struct PowerState {
+    path: Option<PathBuf>,
+}
+
+impl PowerState {
+    /// Use the given state file, or the default state file if not set.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path: path.or_else(default_state_file),
+        }
+    }
+
+    /// Location of the state file, `None` if the power state isn't persisted.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Check if the user switched off the display. Missing or unreadable files count as on.
+    pub fn is_off(&self) -> bool {
+        let Some(path) = &self.path else {
+            return false;
+        };
+        match fs::read_to_string(path) {
+            Ok(state) => state.trim() == "off",
+            Err(e) => {
+                debug!("No display power state in {path:?}: {e}");
+                false
+            }
+        }
+    }
+
+    /// Save the power state set by the user.
+    ///
+    /// Errors are only logged, e.g. in a read-only sandbox: the display is switched anyway.
+    pub fn save(&self, off: bool) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, if off { "off\n" } else { "on\n" }));
+        if let Err(e) = result {
+            warn!("Failed to save display power state to {path:?}: {e}");
+        }
+    }
+}
+
+/// Default state file: in the systemd `STATE_DIRECTORY`, or in `$XDG_STATE_HOME/asterctl`,
+/// defaulting to `~/.local/state/asterctl`.
+fn default_state_file() -> Option<PathBuf> {
+    let dir = if let Some(dir) = env::var_os("STATE_DIRECTORY") {
+        // multiple directories are separated by colons
+        let dir = dir.to_string_lossy();
+        PathBuf::from(dir.split(':').next().unwrap_or_default())
+    } else if let Some(dir) = env::var_os("XDG_STATE_HOME") {
+        PathBuf::from(dir).join("asterctl")
+    } else {
+        PathBuf::from(env::var_os("HOME")?).join(".local/state/asterctl")
+    };
+    Some(dir.join(STATE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persist_power_state() {
+        let dir = env::temp_dir().join(format!("asterctl-state-{}", std::process::id()));
+        let state = PowerState::new(Some(dir.join(STATE_FILE)));
+        assert!(!state.is_off());
+
+        state.save(true);
+        assert!(state.is_off());
+        assert!(PowerState::new(Some(dir.join(STATE_FILE))).is_off());
+        state.save(false);
+        assert!(!state.is_off());
+
+        fs::remove_dir_all(dir).unwrap();
+        assert!(!PowerState::default().is_off());
+    }
+}
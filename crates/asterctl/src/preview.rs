@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Panel previews of `asterctl preview`.
+//!
+//! Every page of the configured panels is rendered without a display, either with recorded sensor
+//! values, e.g. saved from `GET /api/sensors` of the HTTP control API, or with fake values. The
+//! fake values provide an example sensor key for each sensor template, and a value for each
+//! sensor placeholder of the message pages and the ticker.
+
+use crate::cfg::{MonitorConfig, Sensor};
+use crate::engine::PanelEngine;
+use crate::render::ImageProcessingError;
+use crate::sensors::SensorStore;
+use crate::ticker::placeholder_keys;
+use anyhow::Context;
+use image::RgbImage;
+use log::warn;
+use regex::Regex;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// Source name of the recorded and fake sensor values.
+pub const PREVIEW_SOURCE: &str = "preview";
+
+/// Rendered display image of a preview.
+pub struct PreviewPage {
+    /// Names of the shown pages of all slots, see [crate::pages::PageKind::name].
+    pub pages: Vec<String>,
+    pub image: RgbImage,
+}
+
+impl PreviewPage {
+    /// PNG file name with the page number and the page names, e.g. `01-temperature_cpu.png`.
+    pub fn file_name(&self, number: usize) -> String {
+        let names = self.pages.join("+");
+        let names: String = names
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '+' => c,
+                _ => '_',
+            })
+            .collect();
+        format!("{number:02}-{names}.png")
+    }
+}
+
+/// Load recorded sensor values from a JSON object of sensor keys and values, e.g. the response of
+/// `GET /api/sensors`.
+pub fn load_sensor_values(path: &Path) -> anyhow::Result<SensorStore> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read sensor values {}", path.display()))?;
+    let recorded: Map<String, Value> = serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse sensor values {}", path.display()))?;
+
+    let mut values = SensorStore::new();
+    for (key, value) in recorded {
+        let value = match value {
+            Value::String(value) => value,
+            Value::Null => continue,
+            value => value.to_string(),
+        };
+        values.set(&key, value, PREVIEW_SOURCE);
+    }
+    Ok(values)
+}
+
+/// Fake sensor values for the sensor templates of all panels, and the sensor placeholders of the
+/// message pages and the ticker.
+///
+/// The sensor key of a template is an example key matching its `match` pattern, e.g.
+/// `temperature_1` for `^temperature_(.+)$`.
+pub fn fake_sensor_values(cfg: &MonitorConfig) -> SensorStore {
+    let mut values = SensorStore::new();
+    let templates = cfg.panels.iter().flat_map(|panel| &panel.sensor);
+    for sensor in templates {
+        let Some(pattern) = &sensor.match_pattern else {
+            continue;
+        };
+        match example_key(pattern) {
+            Some(key) if !values.contains_key(&key) => {
+                values.set(&key, fake_value(sensor), PREVIEW_SOURCE)
+            }
+            Some(_) => {}
+            None => warn!("No example sensor key for the match pattern '{pattern}'"),
+        }
+    }
+
+    let messages = cfg
+        .messages
+        .iter()
+        .chain(cfg.slots.iter().flat_map(|slot| &slot.messages));
+    let texts = messages
+        .flat_map(|message| message.title.iter().chain([&message.text]))
+        .chain(cfg.setup.ticker.iter().flat_map(|ticker| &ticker.items));
+    for key in texts.flat_map(|text| placeholder_keys(text)) {
+        if !values.contains_key(key) {
+            values.set(key, "42", PREVIEW_SOURCE);
+        }
+    }
+    values
+}
+
+/// Fake value in the upper half of the value range of the sensor element, to show the progress
+/// and pointer indicators partially filled.
+fn fake_value(sensor: &Sensor) -> String {
+    let min = sensor.min_value.unwrap_or(0.0);
+    let max = sensor.max_value.unwrap_or(100.0);
+    format!("{}", (min + (max - min) * 0.62).round())
+}
+
+/// Example sensor key matching a regex pattern, `None` if the pattern can't be satisfied.
+fn example_key(pattern: &str) -> Option<String> {
+    let hir = regex_syntax::parse(pattern).ok()?;
+    let mut key = String::new();
+    write_example(&hir, &mut key)?;
+    Regex::new(pattern).ok()?.is_match(&key).then_some(key)
+}
+
+/// Append the shortest non-empty example text of a regex to `text`, preferring digits and lower
+/// case letters in character classes.
+fn write_example(hir: &Hir, text: &mut String) -> Option<()> {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => text.push_str(std::str::from_utf8(&literal.0).ok()?),
+        HirKind::Class(Class::Unicode(class)) => {
+            let ranges = class.ranges();
+            let c = ['1', 'a']
+                .into_iter()
+                .find(|c| ranges.iter().any(|r| (r.start()..=r.end()).contains(c)))
+                .or_else(|| ranges.first().map(|r| r.start()))?;
+            text.push(c);
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let ranges = class.ranges();
+            let b = [b'1', b'a']
+                .into_iter()
+                .find(|b| ranges.iter().any(|r| (r.start()..=r.end()).contains(b)))
+                .or_else(|| ranges.first().map(|r| r.start()))?;
+            text.push(char::from(b));
+        }
+        HirKind::Repetition(repetition) => {
+            let max = repetition.max.unwrap_or(u32::MAX);
+            let count = repetition.min.max(1).min(max);
+            for _ in 0..count {
+                write_example(&repetition.sub, text)?;
+            }
+        }
+        HirKind::Capture(capture) => write_example(&capture.sub, text)?,
+        HirKind::Concat(hirs) => {
+            for hir in hirs {
+                write_example(hir, text)?;
+            }
+        }
+        HirKind::Alternation(hirs) => write_example(hirs.first()?, text)?,
+    }
+    Some(())
+}
+
+/// Render every page of all panel slots.
+///
+/// Each image shows the next page of every slot, slots with fewer pages start over. The number
+/// of images is the page count of the slot with the most pages.
+pub fn render_pages(
+    engine: &mut PanelEngine,
+    values: &SensorStore,
+) -> Result<Vec<PreviewPage>, ImageProcessingError> {
+    engine.rebuild(values);
+    let mut pages = Vec::new();
+    for idx in 0..engine.max_slot_page_count() {
+        if idx > 0 {
+            engine.next_page();
+        }
+        let image = engine.render_to_image(values)?;
+        pages.push(PreviewPage {
+            pages: engine.current_pages(),
+            image,
+        });
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MonitorConfig {
+        serde_json::from_str(
+            r#"{
+              "setup": { "refresh": 1, "timePage": "%H:%M" },
+              "mianban": [1],
+              "diy": [
+                { "sensor": [
+                  { "mode": 1, "match": "^temperature_(.+)$", "name": "Temp {1}", "x": 0, "y": 0 },
+                  { "mode": 1, "match": "^(cpu|gpu)_usage_percent$", "x": 0, "y": 0,
+                    "minValue": 0, "maxValue": 50 },
+                  { "mode": 1, "match": "^storage_[a-z]+\\d?_usage$", "x": 0, "y": 0 }
+                ] }
+              ],
+              "messages": [ { "name": "status", "text": "Uptime {uptime}" } ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fake_values_match_templates() {
+        let values = fake_sensor_values(&config());
+
+        assert_eq!(Some("62"), values.value("temperature_1"));
+        assert_eq!(Some("31"), values.value("cpu_usage_percent"));
+        assert_eq!(Some("62"), values.value("storage_a1_usage"));
+        assert_eq!(Some("42"), values.value("uptime"));
+        assert_eq!(4, values.len());
+    }
+
+    #[test]
+    fn render_every_page() {
+        let cfg = config();
+        let values = fake_sensor_values(&cfg);
+        let mut engine = PanelEngine::new(&cfg, (960, 376), "fonts", "cfg");
+
+        let pages = render_pages(&mut engine, &values).unwrap();
+
+        let names: Vec<_> = pages
+            .iter()
+            .enumerate()
+            .map(|(idx, page)| page.file_name(idx + 1))
+            .collect();
+        assert_eq!(
+            vec![
+                "01-temperature_1.png",
+                "02-cpu_usage_percent.png",
+                "03-storage_a1_usage.png",
+                "04-status.png",
+                "05-time.png",
+            ],
+            names
+        );
+        assert_eq!((960, 376), pages[0].image.dimensions());
+    }
+}
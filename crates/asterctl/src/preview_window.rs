@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Desktop preview window of the rendered frames.
+//!
+//! The window shows the frames sent to the display, e.g. while developing a panel with
+//! `--simulate`. It's a frame output with its own event loop thread, which is only supported on
+//! Linux, the BSDs and Windows: macOS requires the event loop on the main thread.
+
+use crate::output::FrameOutput;
+use anyhow::{Context as _, anyhow, bail};
+use image::RgbImage;
+use log::{error, info};
+use softbuffer::{Context, Surface};
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use std::sync::mpsc::sync_channel;
+use winit::application::ApplicationHandler;
+use winit::dpi::PhysicalSize;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy};
+use winit::window::{Window, WindowId};
+
+/// Frame output to a desktop window.
+pub struct WindowOutput {
+    proxy: EventLoopProxy<RgbImage>,
+    /// The window was closed by the user, further frames are dropped.
+    closed: bool,
+}
+
+impl WindowOutput {
+    /// Start the event loop thread of the preview window.
+    ///
+    /// The window is opened with the size of the first frame.
+    pub fn new() -> anyhow::Result<Self> {
+        let (proxy_sender, proxy) = sync_channel(1);
+        std::thread::Builder::new()
+            .name("preview-window".into())
+            .spawn(move || {
+                let event_loop = match build_event_loop() {
+                    Ok(event_loop) => event_loop,
+                    Err(e) => {
+                        let _ = proxy_sender.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = proxy_sender.send(Ok(event_loop.create_proxy()));
+                if let Err(e) = event_loop.run_app(&mut PreviewApp::default()) {
+                    error!("Preview window failed: {e}");
+                }
+            })?;
+
+        let proxy = proxy
+            .recv()
+            .map_err(|_| anyhow!("Preview window thread terminated"))?
+            .context("Failed to open the preview window")?;
+        Ok(Self {
+            proxy,
+            closed: false,
+        })
+    }
+}
+
+impl FrameOutput for WindowOutput {
+    fn name(&self) -> &str {
+        "preview window"
+    }
+
+    fn send(&mut self, image: &RgbImage) -> anyhow::Result<()> {
+        if !self.closed && self.proxy.send_event(image.clone()).is_err() {
+            info!("Preview window closed");
+            self.closed = true;
+        }
+        Ok(())
+    }
+}
+
+/// Event loop which may run outside the main thread.
+fn build_event_loop() -> anyhow::Result<EventLoop<RgbImage>> {
+    if cfg!(target_os = "macos") {
+        bail!("The preview window isn't supported on macOS");
+    }
+    let mut builder = EventLoop::with_user_event();
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        winit::platform::x11::EventLoopBuilderExtX11::with_any_thread(&mut builder, true);
+        winit::platform::wayland::EventLoopBuilderExtWayland::with_any_thread(&mut builder, true);
+    }
+    #[cfg(target_os = "windows")]
+    winit::platform::windows::EventLoopBuilderExtWindows::with_any_thread(&mut builder, true);
+    Ok(builder.build()?)
+}
+
+/// Window with a software rendered surface.
+struct PreviewWindow {
+    window: Rc<Window>,
+    surface: Surface<Rc<Window>, Rc<Window>>,
+}
+
+#[derive(Default)]
+struct PreviewApp {
+    window: Option<PreviewWindow>,
+    /// Last received frame, drawn at the next redraw.
+    frame: Option<RgbImage>,
+}
+
+impl PreviewApp {
+    fn open_window(
+        event_loop: &ActiveEventLoop,
+        size: (u32, u32),
+    ) -> anyhow::Result<PreviewWindow> {
+        let attributes = Window::default_attributes()
+            .with_title("asterctl")
+            .with_inner_size(PhysicalSize::new(size.0, size.1));
+        let window = Rc::new(event_loop.create_window(attributes)?);
+        let context = Context::new(window.clone()).map_err(|e| anyhow!("{e}"))?;
+        let surface = Surface::new(&context, window.clone()).map_err(|e| anyhow!("{e}"))?;
+        Ok(PreviewWindow { window, surface })
+    }
+
+    /// Draw the last frame scaled to the window size.
+    fn redraw(&mut self) -> anyhow::Result<()> {
+        let (Some(preview), Some(frame)) = (&mut self.window, &self.frame) else {
+            return Ok(());
+        };
+        let size = preview.window.inner_size();
+        let (Some(width), Some(height)) =
+            (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+        else {
+            return Ok(());
+        };
+        preview
+            .surface
+            .resize(width, height)
+            .map_err(|e| anyhow!("{e}"))?;
+        let mut buffer = preview.surface.buffer_mut().map_err(|e| anyhow!("{e}"))?;
+        let (frame_width, frame_height) = frame.dimensions();
+        for (idx, pixel) in buffer.iter_mut().enumerate() {
+            let x = (idx as u32 % size.width) as u64 * frame_width as u64 / size.width as u64;
+            let y = (idx as u32 / size.width) as u64 * frame_height as u64 / size.height as u64;
+            let [r, g, b] = frame.get_pixel(x as u32, y as u32).0;
+            *pixel = u32::from(r) << 16 | u32::from(g) << 8 | u32::from(b);
+        }
+        buffer.present().map_err(|e| anyhow!("{e}"))
+    }
+}
+
+impl ApplicationHandler<RgbImage> for PreviewApp {
+    fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
+
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, frame: RgbImage) {
+        if self.window.is_none() {
+            match Self::open_window(event_loop, frame.dimensions()) {
+                Ok(window) => self.window = Some(window),
+                Err(e) => {
+                    error!("Failed to open the preview window: {e:#}");
+                    event_loop.exit();
+                    return;
+                }
+            }
+        }
+        self.frame = Some(frame);
+        if let Some(preview) = &self.window {
+            preview.window.request_redraw();
+        }
+    }
+
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        _window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        match event {
+            WindowEvent::CloseRequested => {
+                self.window = None;
+                event_loop.exit();
+            }
+            WindowEvent::RedrawRequested => {
+                if let Err(e) = self.redraw() {
+                    error!("Failed to draw the preview window: {e:#}");
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Adaptive display refresh.
+//!
+//! A full frame transfer over a slow serial link can take longer than the configured refresh
+//! interval. Instead of sending frames back-to-back and drifting behind the schedule, the
+//! refresh interval is lowered to the measured transfer time, and restored once transfers are
+//! fast enough again.
+
+use log::{info, warn};
+use std::time::Duration;
+
+/// Weight of a new measurement in the moving average of the transfer time.
+const SMOOTHING: f32 = 0.25;
+/// Time reserve for rendering and control commands, relative to the transfer time.
+const HEADROOM: f32 = 1.25;
+/// Slow transfers are reported as resolved once the required interval drops below this share of
+/// the configured interval.
+const RECOVERY: f32 = 0.8;
+/// Granularity of the adjusted refresh interval, avoiding constant small changes.
+const STEP: Duration = Duration::from_millis(100);
+
+/// Adjust the display refresh interval to the measured frame transfer time.
+#[derive(Debug, Default)]
+pub struct RefreshController {
+    /// Moving average of the frame transfer time.
+    transfer_time: Option<Duration>,
+    /// The refresh interval has been lowered.
+    throttled: bool,
+}
+
+impl RefreshController {
+    /// Create a controller without measurements, using the configured interval.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Average frame transfer time, `None` until the first measurement.
+    pub fn transfer_time(&self) -> Option<Duration> {
+        self.transfer_time
+    }
+
+    /// Record the transfer time of a frame.
+    pub fn record(&mut self, transfer_time: Duration) {
+        self.transfer_time = Some(match self.transfer_time {
+            Some(avg) => avg.mul_f32(1.0 - SMOOTHING) + transfer_time.mul_f32(SMOOTHING),
+            None => transfer_time,
+        });
+    }
+
+    /// Effective refresh interval for the configured interval.
+    ///
+    /// Logs a warning when the refresh rate is lowered, and when the configured interval is
+    /// restored.
+    pub fn interval(&mut self, refresh: Duration) -> Duration {
+        let Some(transfer_time) = self.transfer_time else {
+            return refresh;
+        };
+        let required = transfer_time.mul_f32(HEADROOM);
+
+        if required > refresh {
+            if !self.throttled {
+                warn!(
+                    "Frame transfer takes {}ms, longer than the refresh interval of {}ms: \
+                    reducing refresh rate",
+                    transfer_time.as_millis(),
+                    refresh.as_millis()
+                );
+                self.throttled = true;
+            }
+            return round_up(required);
+        }
+
+        if self.throttled && required < refresh.mul_f32(RECOVERY) {
+            info!(
+                "Frame transfer takes {}ms: restoring refresh interval of {}ms",
+                transfer_time.as_millis(),
+                refresh.as_millis()
+            );
+            self.throttled = false;
+        }
+        refresh
+    }
+
+    /// The refresh rate is currently lowered because of slow frame transfers.
+    pub fn is_throttled(&self) -> bool {
+        self.throttled
+    }
+}
+
+fn round_up(interval: Duration) -> Duration {
+    let steps = interval.as_nanos().div_ceil(STEP.as_nanos());
+    STEP * steps as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REFRESH: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn configured_interval_for_fast_transfers() {
+        let mut controller = RefreshController::new();
+        assert_eq!(REFRESH, controller.interval(REFRESH));
+
+        controller.record(Duration::from_millis(300));
+        assert_eq!(REFRESH, controller.interval(REFRESH));
+    }
+
+    #[test]
+    fn slow_transfers_lower_refresh_rate() {
+        let mut controller = RefreshController::new();
+        controller.record(Duration::from_millis(1500));
+        // 1.5s transfer time with 25% headroom
+        assert_eq!(Duration::from_millis(1900), controller.interval(REFRESH));
+        assert!(controller.is_throttled());
+
+        // back to the configured interval, but still close to the limit
+        for _ in 0..8 {
+            controller.record(Duration::from_millis(700));
+        }
+        assert_eq!(REFRESH, controller.interval(REFRESH));
+        assert!(controller.is_throttled());
+
+        for _ in 0..10 {
+            controller.record(Duration::from_millis(500));
+        }
+        assert_eq!(REFRESH, controller.interval(REFRESH));
+        assert!(!controller.is_throttled());
+    }
+}
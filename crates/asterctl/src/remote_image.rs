@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Display images fetched from an HTTP(S) URL, e.g. a dashboard rendered by Grafana.
+//!
+//! Repeated fetches use the `ETag` and `Last-Modified` response headers of the previous response,
+//! so that unchanged images are neither downloaded nor sent to the display again.
+
+use crate::img::{Size, resize_image};
+use anyhow::Context;
+use image::DynamicImage;
+use log::debug;
+use std::time::Duration;
+use ureq::http::StatusCode;
+
+/// Request timeout of an image fetch.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+/// Maximum image size in bytes.
+const MAX_IMAGE_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Check if an image location is an HTTP or HTTPS URL instead of a file.
+pub fn is_url(location: &str) -> bool {
+    let location = location.to_ascii_lowercase();
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Image URL with the cache validators of the last fetched image.
+pub struct RemoteImage {
+    agent: ureq::Agent,
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl RemoteImage {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            agent: ureq::Agent::config_builder()
+                .timeout_global(Some(FETCH_TIMEOUT))
+                .user_agent(concat!("asterctl/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .into(),
+            url: url.into(),
+            etag: None,
+            last_modified: None,
+        }
+    }
+
+    /// Fetch the image and scale it to the given size.
+    ///
+    /// Returns `None` if the image didn't change since the last fetch.
+    pub fn fetch(&mut self, size: Option<Size>) -> anyhow::Result<Option<DynamicImage>> {
+        let mut request = self.agent.get(&self.url);
+        if let Some(etag) = &self.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+        let mut response = request
+            .call()
+            .with_context(|| format!("Failed to fetch image {}", self.url))?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            debug!("Image {} not modified", self.url);
+            return Ok(None);
+        }
+
+        let header = |name: &str| {
+            let value = response.headers().get(name)?.to_str().ok()?;
+            Some(value.to_string())
+        };
+        let etag = header("ETag");
+        let last_modified = header("Last-Modified");
+        let data = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_IMAGE_SIZE)
+            .read_to_vec()
+            .with_context(|| format!("Failed to download image {}", self.url))?;
+        let image = image::load_from_memory(&data)
+            .with_context(|| format!("Invalid image {}", self.url))?;
+
+        // only remember the validators of a valid image, so that a broken image is fetched again
+        self.etag = etag;
+        self.last_modified = last_modified;
+        Ok(Some(resize_image(image, size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbImage};
+    use std::io::{BufRead, BufReader, Cursor, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn fetch_with_etag() {
+        let mut png = Cursor::new(Vec::new());
+        RgbImage::new(4, 2)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        let png = png.into_inner();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/render.png", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut conditional = Vec::new();
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut if_none_match = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("if-none-match:") {
+                        if_none_match = Some(value.trim().to_string());
+                    }
+                }
+                if if_none_match.as_deref() == Some("\"v1\"") {
+                    stream
+                        .write_all(b"HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\n\r\n")
+                        .unwrap();
+                } else {
+                    let header = format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Type: image/png\r\n\
+                         Content-Length: {}\r\n\r\n",
+                        png.len()
+                    );
+                    stream.write_all(header.as_bytes()).unwrap();
+                    stream.write_all(&png).unwrap();
+                }
+                conditional.push(if_none_match.is_some());
+            }
+            conditional
+        });
+
+        assert!(is_url(&url));
+        assert!(!is_url("img/background.png"));
+        let mut remote = RemoteImage::new(&url);
+        let image = remote.fetch(Some((8, 4))).unwrap().unwrap();
+        assert_eq!((8, 4), (image.width(), image.height()));
+        assert!(remote.fetch(Some((8, 4))).unwrap().is_none());
+        assert_eq!(vec![false, true], server.join().unwrap());
+    }
+}
@@ -4,21 +4,92 @@
 
 //! Sensor panel rendering logic. Create an RGBa image from a panel configuration and sensor values.
 
-use crate::cfg::{Panel, Sensor, SensorDirection, SensorMode, SensorPageLabel, TextAlign};
-use crate::font::FontHandler;
-use crate::format_value;
+use crate::cfg::{
+    AlertRule, ClockType, DigitStyle, MessagePage, Panel, Sensor, SensorDirection, SensorMode,
+    SensorPageLabel, StaleAction, StaleSensors, TextAlign, TickerConfig, TimePageStyle,
+    VerticalAlign,
+};
+use crate::digits::{self, draw_digits};
+use crate::font::{FontHandler, TextFont, draw_text_mut, text_size};
+use crate::history::{HistorySample, SensorHistory, unix_time};
 use crate::img::{ImageCache, Size, rotate_image};
-use crate::sensors::get_date_time_value;
-use ab_glyph::Font;
-use chrono::{DateTime, Local};
-use image::{ImageBuffer, Rgba, RgbaImage};
-use imageproc::drawing::{draw_text_mut, text_size};
+use crate::sensors::{SensorStore, SensorValue, get_date_time_value};
+use crate::smoothing::ValueSmoother;
+use crate::ticker::expand_placeholders;
+use crate::timer::format_remaining;
+use crate::{IntegerDigits, ValueFormat, format_value_with};
+use ab_glyph::{Font, PxScale};
+use chrono::{DateTime, Local, Timelike};
+use image::{ImageBuffer, Pixel, Rgba, RgbaImage, imageops};
+use imageproc::drawing::{
+    draw_filled_circle_mut, draw_filled_rect_mut, draw_line_segment_mut, draw_polygon_mut,
+};
+use imageproc::point::Point;
+use imageproc::rect::Rect;
 use log::{debug, error};
 use std::collections::HashMap;
 use std::f32::consts::PI;
 use std::fs;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use unicode_linebreak::{BreakOpportunity, linebreaks};
+
+/// Split the text into lines at line breaks and, if `max_width` is set, wrap the lines to fit into
+/// `max_width`.
+///
+/// Lines are wrapped at the line break opportunities of the Unicode line breaking algorithm
+/// (UAX #14), e.g. at spaces or between CJK ideographs, but not before closing punctuation.
+/// Segments wider than `max_width` are broken between characters.
+fn wrap_text(text: &str, max_width: Option<u32>, measure: impl Fn(&str) -> u32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let Some(max_width) = max_width else {
+            lines.push(paragraph.to_string());
+            continue;
+        };
+        let mut line = String::new();
+        let mut start = 0;
+        for (end, opportunity) in linebreaks(paragraph) {
+            let segment = &paragraph[start..end];
+            start = end;
+            let candidate = format!("{line}{segment}");
+            if measure(candidate.trim_end()) <= max_width {
+                line = candidate;
+            } else {
+                if !line.trim().is_empty() {
+                    lines.push(line.trim_end().to_string());
+                }
+                line.clear();
+                for c in segment.chars() {
+                    line.push(c);
+                    if measure(line.trim_end()) > max_width && line.chars().count() > 1 {
+                        line.pop();
+                        lines.push(line.trim_end().to_string());
+                        line = c.to_string();
+                    }
+                }
+            }
+            if opportunity == BreakOpportunity::Mandatory && end < paragraph.len() {
+                lines.push(std::mem::take(&mut line).trim_end().to_string());
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+/// Reduce the font scale if the text is wider than `max_width`.
+fn fit_text_scale(scale: PxScale, font: &TextFont, text: &str, max_width: u32) -> PxScale {
+    let width = text_size(scale, font, text).0;
+    if width <= max_width || width == 0 {
+        return scale;
+    }
+    let factor = max_width as f32 / width as f32;
+    PxScale {
+        x: scale.x * factor,
+        y: scale.y * factor,
+    }
+}
 
 /// Error type for image processing operations
 #[derive(Debug)]
@@ -52,8 +123,62 @@ pub struct PanelRenderer {
     save_progress_layer: bool,
     img_save_path: PathBuf,
     img_suffix: Option<String>,
+    value_format: ValueFormat,
+    /// Default display policy of stale sensor values.
+    stale_sensors: Option<StaleSensors>,
+    /// Smoothing and color hysteresis state of the sensor elements.
+    smoother: ValueSmoother,
+    /// Time base of scrolling text values.
+    start: Instant,
+    /// Scrolling text values are drawn at their start position if disabled.
+    animations: bool,
+}
+
+/// A hand or hour mark of an analog clock, lengths are measured from the clock center.
+struct ClockHand {
+    /// Start of the hand, negative values extend the hand beyond the center.
+    start: f32,
+    end: f32,
+    width: f32,
+}
+
+impl ClockHand {
+    /// Draw the hand rotated clockwise from 12 o'clock by `angle` degrees.
+    fn draw(&self, image: &mut RgbaImage, center: (f32, f32), angle: f32, color: Rgba<u8>) {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        // direction of the hand and its perpendicular in image coordinates
+        let (dx, dy) = (sin, -cos);
+        let (nx, ny) = (cos * self.width / 2.0, sin * self.width / 2.0);
+        let point = |dist: f32, side: f32| {
+            Point::new(
+                (center.0 + dx * dist + nx * side).round() as i32,
+                (center.1 + dy * dist + ny * side).round() as i32,
+            )
+        };
+        let polygon = [
+            point(self.start, -1.0),
+            point(self.end, -1.0),
+            point(self.end, 1.0),
+            point(self.start, 1.0),
+        ];
+        if polygon[0] != polygon[3] {
+            draw_polygon_mut(image, &polygon, color);
+        } else {
+            draw_line_segment_mut(
+                image,
+                (polygon[0].x as f32, polygon[0].y as f32),
+                (polygon[1].x as f32, polygon[1].y as f32),
+                color,
+            );
+        }
+    }
 }
 
+/// Space in pixels between the end and the repeated start of a scrolling text value.
+const MARQUEE_GAP: u32 = 48;
+/// Displayed value of stale sensors with the `dash` action.
+const STALE_VALUE: &str = "--";
+
 impl PanelRenderer {
     /// Create a new image processor instance for a given display size.
     ///
@@ -75,6 +200,11 @@ impl PanelRenderer {
             save_progress_layer: false,
             img_save_path: PathBuf::from("out"),
             img_suffix: None,
+            value_format: ValueFormat::default(),
+            stale_sensors: None,
+            smoother: ValueSmoother::new(),
+            start: Instant::now(),
+            animations: true,
         }
     }
 
@@ -106,19 +236,37 @@ impl PanelRenderer {
     pub fn set_img_suffix(&mut self, img_suffix: impl Into<String>) {
         self.img_suffix = Some(img_suffix.into());
     }
+    /// Set the unit conversion and decimal separator of all sensor values.
+    ///
+    /// Sensor elements can override the options with their `value_format`.
+    pub fn set_value_format(&mut self, value_format: ValueFormat) {
+        self.value_format = value_format;
+    }
+
+    /// Set the display policy of sensor values which weren't updated for a while.
+    ///
+    /// Sensor elements can override the policy with their `stale` option.
+    pub fn set_stale_sensors(&mut self, stale_sensors: StaleSensors) {
+        self.stale_sensors = Some(stale_sensors);
+    }
+
+    /// Enable or disable animations. Enabled by default.
+    pub fn set_animations(&mut self, enabled: bool) {
+        self.animations = enabled;
+    }
 
     /// Render a sensor panel with the given values and return the final panel image.
     ///
     /// # Arguments
     ///
     /// * `panel`: the panel configuration
-    /// * `values`: current values for the defined panel sensors in a shared HashMap
+    /// * `values`: current values for the defined panel sensors
     ///
     /// returns: a rendered panel image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
     pub fn render(
         &mut self,
         panel: &Panel,
-        values: &HashMap<String, String>,
+        values: &SensorStore,
     ) -> Result<RgbaImage, ImageProcessingError> {
         debug!(
             "Rendering panel {}...",
@@ -129,9 +277,13 @@ impl PanelRenderer {
         );
 
         let now = Instant::now();
-        let background = if let Some(img) = &panel.img
-            && let Some(background) = self.image_cache.get(img, Some(self.size))
-        {
+        let size = Some(self.size);
+        let background = match (&panel.img, &panel.img_adjust) {
+            (Some(img), Some(adjust)) => self.image_cache.get_adjusted(img, size, adjust),
+            (Some(img), None) => self.image_cache.get(img, size),
+            (None, _) => None,
+        };
+        let background = if let Some(background) = background {
             background.clone()
         } else {
             RgbaImage::new(self.size.0, self.size.1)
@@ -156,37 +308,54 @@ impl PanelRenderer {
         Ok(final_image)
     }
 
-    /// Render a dedicated time page: centered date/time text on a black background.
+    /// Render a dedicated time page: centered date/time text or an analog clock on a black
+    /// background.
     ///
     /// # Arguments
     ///
     /// * `label`: a date/time label recognized by [get_date_time_value], e.g. "DATE_h_m_s_1".
+    /// * `time_font_size`: font size of the date/time text. Default: 64
+    /// * `style`: optional clock style, the date/time text is shown if not set.
     ///
     /// returns: a rendered time page image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
     pub fn render_time_page(
         &mut self,
         label: &str,
         time_font_size: Option<f32>,
+        style: Option<&TimePageStyle>,
     ) -> Result<RgbaImage, ImageProcessingError> {
         let now_dt: DateTime<Local> = Local::now();
-        let value = get_date_time_value(label, &now_dt)
-            .unwrap_or_else(|| "??:??".to_string());
+        if let Some(style) = style.filter(|style| style.clock == ClockType::Analog) {
+            debug!("Rendering analog time page");
+            return Ok(self.render_analog_clock(style, &now_dt));
+        }
+
+        let value = get_date_time_value(label, &now_dt).unwrap_or_else(|| "??:??".to_string());
 
         debug!("Rendering time page: {label} = {value}");
 
         let mut image = RgbaImage::new(self.size.0, self.size.1);
 
-        let font = FontHandler::default_font();
         let font_size = time_font_size.unwrap_or(64.0);
-        let adjustment_hack = 0.75;
-        let scale = font.pt_to_px_scale(font_size * adjustment_hack).unwrap();
         let color = Rgba([255, 255, 255, 255]);
 
-        let text_sz = text_size(scale, &font, &value);
-        let x = (self.size.0 as i32 - text_sz.0 as i32) / 2;
-        let y = (self.size.1 as i32 - (text_sz.1 as f32 * 1.3333 / 2.0) as i32) / 2;
+        if let Some(digit_style) = style.and_then(|style| style.digit_style.as_ref()) {
+            let height = font_size.round() as u32;
+            let width = digits::text_width(digit_style, &value, height);
+            let x = (self.size.0 as i32 - width as i32) / 2;
+            let y = (self.size.1 as i32 - height as i32) / 2;
+            draw_digits(&mut image, digit_style, &value, x, y, height, color);
+        } else {
+            let font = FontHandler::default_font();
+            let adjustment_hack = 0.75;
+            let scale = font.pt_to_px_scale(font_size * adjustment_hack).unwrap();
 
-        draw_text_mut(&mut image, color, x, y, scale, &font, &value);
+            let text_sz = text_size(scale, &font, &value);
+            let x = (self.size.0 as i32 - text_sz.0 as i32) / 2;
+            let y = (self.size.1 as i32 - (text_sz.1 as f32 * 1.3333 / 2.0) as i32) / 2;
+
+            draw_text_mut(&mut image, color, x, y, scale, &font, &value);
+        }
 
         if self.save_render_img {
             let name = format!(
@@ -201,6 +370,375 @@ impl PanelRenderer {
         Ok(image)
     }
 
+    /// Render an analog clock centered on the time page.
+    fn render_analog_clock(&mut self, style: &TimePageStyle, time: &impl Timelike) -> RgbaImage {
+        const MARGIN: u32 = 8;
+        let mut image = RgbaImage::new(self.size.0, self.size.1);
+        let size = self.size.0.min(self.size.1).saturating_sub(2 * MARGIN);
+        let x = (self.size.0 - size) / 2;
+        let y = (self.size.1 - size) / 2;
+
+        let face = style
+            .face
+            .as_ref()
+            .and_then(|face| self.image_cache.get(face, Some((size, size))));
+        match face {
+            Some(face) => imageops::overlay(&mut image, face, x as i64, y as i64),
+            None => Self::draw_clock_dial(&mut image, x, y, size),
+        }
+
+        Self::draw_clock_hands(&mut image, x, y, size, style, time);
+        image
+    }
+
+    /// Draw a default clock dial with hour marks.
+    fn draw_clock_dial(image: &mut RgbaImage, x: u32, y: u32, size: u32) {
+        let color = Rgba([200, 200, 200, 255]);
+        let radius = size as f32 / 2.0;
+        let center = (x as f32 + radius, y as f32 + radius);
+        for hour in 0..12 {
+            // longer and thicker marks for the quarter hours
+            let (length, width) = if hour % 3 == 0 {
+                (0.14, 0.035)
+            } else {
+                (0.07, 0.018)
+            };
+            let angle = hour as f32 * 30.0;
+            let hand = ClockHand {
+                start: (1.0 - length) * radius,
+                end: radius,
+                width: width * radius,
+            };
+            hand.draw(image, center, angle, color);
+        }
+    }
+
+    /// Draw the hour, minute and optional second hand.
+    fn draw_clock_hands(
+        image: &mut RgbaImage,
+        x: u32,
+        y: u32,
+        size: u32,
+        style: &TimePageStyle,
+        time: &impl Timelike,
+    ) {
+        let white = Rgba([255, 255, 255, 255]);
+        let radius = size as f32 / 2.0;
+        let center = (x as f32 + radius, y as f32 + radius);
+        let mut seconds = time.second() as f32;
+        if style.smooth_seconds {
+            seconds += time.nanosecond().min(999_999_999) as f32 / 1e9;
+        }
+        let minutes = time.minute() as f32 + seconds / 60.0;
+        let hours = (time.hour() % 12) as f32 + minutes / 60.0;
+
+        let hour_hand = ClockHand {
+            start: -0.1 * radius,
+            end: 0.5 * radius,
+            width: 0.06 * radius,
+        };
+        let hour_color = style.hour_color.map_or(white, Rgba::from);
+        hour_hand.draw(image, center, hours * 30.0, hour_color);
+
+        let minute_hand = ClockHand {
+            start: -0.1 * radius,
+            end: 0.8 * radius,
+            width: 0.04 * radius,
+        };
+        let minute_color = style.minute_color.map_or(white, Rgba::from);
+        minute_hand.draw(image, center, minutes * 6.0, minute_color);
+
+        let mut cap_color = minute_color;
+        if style.seconds.unwrap_or(true) {
+            let second_hand = ClockHand {
+                start: -0.2 * radius,
+                end: 0.88 * radius,
+                width: 0.015 * radius,
+            };
+            cap_color = style
+                .second_color
+                .map_or(Rgba([0xff, 0x3d, 0, 255]), Rgba::from);
+            second_hand.draw(image, center, seconds * 6.0, cap_color);
+        }
+
+        let cap_radius = (0.05 * radius).round().max(1.0) as i32;
+        let center = (center.0.round() as i32, center.1.round() as i32);
+        draw_filled_circle_mut(image, center, cap_radius, cap_color);
+    }
+
+    /// Render a countdown timer page: a progress ring with the remaining time and the optional
+    /// label in its center.
+    ///
+    /// The ring empties with the remaining time and turns red when the timer is over.
+    ///
+    /// # Arguments
+    ///
+    /// * `label`: optional label below the remaining time.
+    /// * `remaining`: remaining time of the timer.
+    /// * `progress`: remaining part of the timer duration, from 1.0 to 0.0.
+    /// * `time_font_size`: font size of the remaining time, the time page font size. Default: 64
+    ///
+    /// returns: a rendered timer page image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
+    pub fn render_timer_page(
+        &mut self,
+        label: Option<&str>,
+        remaining: Duration,
+        progress: f32,
+        time_font_size: Option<f32>,
+    ) -> Result<RgbaImage, ImageProcessingError> {
+        let value = format_remaining(remaining);
+        debug!("Rendering timer page: {value}");
+
+        let mut image = RgbaImage::new(self.size.0, self.size.1);
+        let font = FontHandler::default_font();
+        let adjustment_hack = 0.75;
+        let color = if remaining.is_zero() {
+            Rgba([0xff, 0, 0, 255])
+        } else {
+            Rgba([0, 0xc8, 0x53, 255])
+        };
+
+        let margin = 16;
+        let ring_size = self.size.1.min(self.size.0).saturating_sub(2 * margin);
+        let ring_x = (self.size.0 - ring_size) / 2;
+        Self::draw_ring_gauge(&mut image, ring_x, margin, ring_size, progress, color);
+
+        let center_y = (margin + ring_size / 2) as i32;
+        let scale = font
+            .pt_to_px_scale(time_font_size.unwrap_or(64.0) * adjustment_hack)
+            .unwrap();
+        let scale = fit_text_scale(scale, &font, &value, ring_size * 2 / 3);
+        let size = text_size(scale, &font, &value);
+        let x = (self.size.0 as i32 - size.0 as i32) / 2;
+        let y = center_y - (size.1 as f32 * 1.3333 / 2.0) as i32;
+        let white = Rgba([255, 255, 255, 255]);
+        draw_text_mut(&mut image, white, x, y, scale, &font, &value);
+
+        if let Some(label) = label {
+            let scale = font.pt_to_px_scale(28.0 * adjustment_hack).unwrap();
+            let scale = fit_text_scale(scale, &font, label, ring_size * 2 / 3);
+            let label_size = text_size(scale, &font, label);
+            let x = (self.size.0 as i32 - label_size.0 as i32) / 2;
+            let y = y + (size.1 as f32 * 1.3333) as i32;
+            let gray = Rgba([0xb4, 0xb4, 0xb4, 255]);
+            draw_text_mut(&mut image, gray, x, y, scale, &font, label);
+        }
+
+        if self.save_render_img {
+            let name = format!(
+                "render_timer{}.png",
+                self.img_suffix.as_deref().unwrap_or_default()
+            );
+            if let Err(e) = image.save(self.img_save_path.join(name)) {
+                error!("Error saving timer page image: {e}");
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Render an alert page with the alert name and the sensor value in large letters.
+    ///
+    /// # Arguments
+    ///
+    /// * `alert`: the alert rule
+    /// * `values`: current sensor values
+    /// * `highlight`: fill the background with the alert color. Otherwise, the background is black
+    ///   and the text uses the alert color, to let flashing alerts alternate between both.
+    ///
+    /// returns: a rendered alert page image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
+    pub fn render_alert_page(
+        &mut self,
+        alert: &AlertRule,
+        values: &SensorStore,
+        highlight: bool,
+    ) -> Result<RgbaImage, ImageProcessingError> {
+        let alert_color: Rgba<u8> = alert
+            .background
+            .map(|c| c.into())
+            .unwrap_or(Rgba([0xd5, 0, 0, 255]));
+        let font_color: Rgba<u8> = alert.font_color.unwrap_or_default().into();
+        let (background, color) = if highlight {
+            (alert_color, font_color)
+        } else {
+            (Rgba([0, 0, 0, 255]), alert_color)
+        };
+
+        let mut image = RgbaImage::from_pixel(self.size.0, self.size.1, background);
+        let font = FontHandler::default_font();
+        let adjustment_hack = 0.75;
+        let max_width = self.size.0.saturating_sub(40);
+
+        let name = alert.name().to_uppercase();
+        let scale = font.pt_to_px_scale(36.0 * adjustment_hack).unwrap();
+        let scale = fit_text_scale(scale, &font, &name, max_width);
+        let name_size = text_size(scale, &font, &name);
+        let x = (self.size.0 as i32 - name_size.0 as i32) / 2;
+        draw_text_mut(&mut image, color, x, 40, scale, &font, &name);
+
+        let value = values.value(&alert.sensor).unwrap_or("N/A");
+        let unit = alert
+            .unit
+            .as_deref()
+            .or_else(|| values.unit(&alert.sensor))
+            .unwrap_or_default()
+            .to_string();
+        let decimal_digits = match alert.decimal_digits {
+            Some(digits) => digits.max(0) as usize,
+            None => value
+                .split_once('.')
+                .map_or(0, |(_, decimals)| decimals.len()),
+        };
+        let text = format_value_with(
+            value,
+            IntegerDigits::Auto,
+            decimal_digits,
+            &unit,
+            &self.value_format,
+        );
+        let scale = font.pt_to_px_scale(160.0 * adjustment_hack).unwrap();
+        let scale = fit_text_scale(scale, &font, &text, max_width);
+        let size = text_size(scale, &font, &text);
+        let x = (self.size.0 as i32 - size.0 as i32) / 2;
+        let y = (self.size.1 as i32 - (size.1 as f32 * 1.3333 / 2.0) as i32) / 2 + 30;
+        draw_text_mut(&mut image, color, x, y, scale, &font, &text);
+
+        Ok(image)
+    }
+
+    /// Render a scheduled message page: the optional title at the top and the centered message
+    /// text. Lines wider than the page are scaled down to fit.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: the message page configuration
+    /// * `values`: current sensor values for placeholders in the message text
+    ///
+    /// returns: a rendered message page image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
+    pub fn render_message_page(
+        &mut self,
+        message: &MessagePage,
+        values: &SensorStore,
+    ) -> Result<RgbaImage, ImageProcessingError> {
+        let background: Rgba<u8> = message
+            .background
+            .map(|c| c.into())
+            .unwrap_or(Rgba([0, 0, 0, 255]));
+        let color: Rgba<u8> = message.font_color.unwrap_or_default().into();
+        let mut image = RgbaImage::from_pixel(self.size.0, self.size.1, background);
+        let font = if let Some(font_family) = &message.font_family {
+            self.font_handler.get_ttf_font_or_default(font_family)
+        } else {
+            FontHandler::default_font()
+        };
+        let adjustment_hack = 0.75;
+        let max_width = self.size.0.saturating_sub(40);
+
+        let mut top = 0;
+        if let Some(title) = &message.title {
+            let scale = font.pt_to_px_scale(36.0 * adjustment_hack).unwrap();
+            let scale = fit_text_scale(scale, &font, title, max_width);
+            let size = text_size(scale, &font, title);
+            let x = (self.size.0 as i32 - size.0 as i32) / 2;
+            draw_text_mut(&mut image, color, x, 30, scale, &font, title);
+            top = 30 + size.1 as i32;
+        }
+
+        let text = expand_placeholders(&message.text, values);
+        let lines: Vec<&str> = text.lines().collect();
+        let font_size = message.font_size.unwrap_or(48.0);
+        let scale = font.pt_to_px_scale(font_size * adjustment_hack).unwrap();
+        let line_height = (scale.y * 1.2) as i32;
+        let mut y = top + (self.size.1 as i32 - top - line_height * lines.len() as i32) / 2;
+        for line in lines {
+            let scale = fit_text_scale(scale, &font, line, max_width);
+            let width = text_size(scale, &font, line).0;
+            let x = (self.size.0 as i32 - width as i32) / 2;
+            draw_text_mut(&mut image, color, x, y, scale, &font, line);
+            y += line_height;
+        }
+
+        if self.save_render_img {
+            let name = format!(
+                "render_message{}.png",
+                self.img_suffix.as_deref().unwrap_or_default()
+            );
+            if let Err(e) = image.save(self.img_save_path.join(name)) {
+                error!("Error saving message page image: {e}");
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Draw the ticker region with the given text at the bottom of a rendered page.
+    ///
+    /// # Arguments
+    ///
+    /// * `image`: the rendered page image to draw the ticker on
+    /// * `ticker`: the ticker configuration
+    /// * `text`: the current ticker text
+    pub fn render_ticker(&mut self, image: &mut RgbaImage, ticker: &TickerConfig, text: &str) {
+        let height = ticker.height.unwrap_or(40).min(image.height());
+        if height == 0 {
+            return;
+        }
+        let top = (image.height() - height) as i32;
+        let background: Rgba<u8> = ticker
+            .background
+            .map(|c| c.into())
+            .unwrap_or(Rgba([0, 0, 0, 255]));
+        draw_filled_rect_mut(
+            image,
+            Rect::at(0, top).of_size(image.width(), height),
+            background,
+        );
+
+        let font = if let Some(font_family) = ticker.font_family.as_deref() {
+            self.font_handler.get_ttf_font_or_default(font_family)
+        } else {
+            FontHandler::default_font()
+        };
+        let adjustment_hack = 0.75;
+        let scale = font
+            .pt_to_px_scale(ticker.font_size.unwrap_or(24.0) * adjustment_hack)
+            .unwrap();
+        let color: Rgba<u8> = ticker.font_color.unwrap_or_default().into();
+
+        let size = text_size(scale, &font, text);
+        let padding = 10;
+        let x = match ticker.text_align.unwrap_or(TextAlign::Center) {
+            TextAlign::Left => padding,
+            TextAlign::Center => (image.width() as i32 - size.0 as i32) / 2,
+            TextAlign::Right => image.width() as i32 - size.0 as i32 - padding,
+        };
+        let y = top + height as i32 / 2 - (size.1 as f32 * 1.3333 / 2.0) as i32;
+
+        draw_text_mut(image, color, x, y, scale, &font, text);
+    }
+
+    /// Draw a line of debug information in the top left corner of a rendered image.
+    pub fn render_debug_overlay(&mut self, image: &mut RgbaImage, text: &str) {
+        let font = FontHandler::default_font();
+        let scale = PxScale::from(16.0);
+        let size = text_size(scale, &font, text);
+        let padding = 4;
+        draw_filled_rect_mut(
+            image,
+            Rect::at(0, 0).of_size(size.0 + 2 * padding, size.1 + 2 * padding),
+            Rgba([0, 0, 0, 255]),
+        );
+        draw_text_mut(
+            image,
+            Rgba([0xff, 0xff, 0, 255]),
+            padding as i32,
+            padding as i32,
+            scale,
+            &font,
+            text,
+        );
+    }
+
     /// Render a single sensor page from a template and a matched sensor key.
     ///
     /// # Arguments
@@ -208,7 +746,7 @@ impl PanelRenderer {
     /// * `sensor`: the sensor display template (font, position, unit, etc.)
     /// * `sensor_key`: the actual sensor key to look up in `values`
     /// * `display_name`: the resolved display name for the sensor label
-    /// * `values`: current sensor values in a shared HashMap
+    /// * `values`: current sensor values
     /// * `label_cfg`: optional label configuration for the sensor name
     ///
     /// returns: a rendered sensor page image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
@@ -217,7 +755,7 @@ impl PanelRenderer {
         sensor: &Sensor,
         sensor_key: &str,
         display_name: &str,
-        values: &HashMap<String, String>,
+        values: &SensorStore,
         label_cfg: Option<&SensorPageLabel>,
     ) -> Result<RgbaImage, ImageProcessingError> {
         debug!("Rendering sensor page: {display_name} [{sensor_key}]");
@@ -226,46 +764,17 @@ impl PanelRenderer {
         let mut final_image = RgbaImage::new(self.size.0, self.size.1);
         self.composite_layer_map.clear();
 
-        let value = values.get(sensor_key).cloned();
+        let value = values.get(sensor_key);
         let unit = values
-            .get(&format!("{sensor_key}#unit"))
-            .cloned()
-            .or_else(|| sensor.unit.clone())
+            .unit(sensor_key)
+            .or(sensor.unit.as_deref())
             .unwrap_or_default();
 
         // Draw sensor name label above the value
-        let name_font = if let Some(font_family) = label_cfg.and_then(|c| c.font_family.as_deref()) {
-            self.font_handler.get_ttf_font_or_default(font_family)
-        } else {
-            FontHandler::default_font()
-        };
-        let name_font_size = label_cfg.and_then(|c| c.font_size).unwrap_or(28.0);
-        let adjustment_hack = 0.75;
-        let name_scale = name_font
-            .pt_to_px_scale(name_font_size * adjustment_hack)
-            .unwrap();
-        let name_color: Rgba<u8> = label_cfg
-            .and_then(|c| c.font_color)
-            .map(|c| c.into())
-            .unwrap_or(Rgba([180, 180, 180, 255]));
-        let label_text = format!("[ {} ]", display_name.to_uppercase());
-        let name_sz = text_size(name_scale, &name_font, &label_text);
-        let name_x = label_cfg
-            .and_then(|c| c.x)
-            .unwrap_or_else(|| (self.size.0 as i32 - name_sz.0 as i32) / 2);
-        let name_y = label_cfg.and_then(|c| c.y).unwrap_or(40);
-        draw_text_mut(
-            &mut final_image,
-            name_color,
-            name_x,
-            name_y,
-            name_scale,
-            &name_font,
-            &label_text,
-        );
+        self.draw_page_label(&mut final_image, display_name, label_cfg);
 
         if let Some(value) = value {
-            self.render_sensor(&mut final_image, sensor, &value, &unit)?;
+            self.render_stored_sensor(&mut final_image, sensor, sensor_key, value, unit)?;
         } else {
             self.render_sensor(&mut final_image, sensor, "N/A", "")?;
         }
@@ -288,13 +797,165 @@ impl PanelRenderer {
         Ok(final_image)
     }
 
+    /// Render a built-in history page for a sensor: a gauge with the current value, a chart of
+    /// the recorded values and the minimum and maximum value of the history period.
+    ///
+    /// The layout scales with the renderer size. The sensor template provides the value format,
+    /// the gauge range with `min_value` and `max_value`, and the colors.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor`: the sensor display template
+    /// * `sensor_key`: the actual sensor key to look up in `values` and `history`
+    /// * `display_name`: the resolved display name for the sensor label
+    /// * `values`: current sensor values
+    /// * `history`: recorded sensor values
+    /// * `label_cfg`: optional label configuration for the sensor name
+    ///
+    /// returns: a rendered history page image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
+    pub fn render_history_page(
+        &mut self,
+        sensor: &Sensor,
+        sensor_key: &str,
+        display_name: &str,
+        values: &SensorStore,
+        history: &SensorHistory,
+        label_cfg: Option<&SensorPageLabel>,
+    ) -> Result<RgbaImage, ImageProcessingError> {
+        debug!("Rendering history page: {display_name} [{sensor_key}]");
+
+        let (width, height) = self.size;
+        let mut image = RgbaImage::new(width, height);
+        self.draw_page_label(&mut image, display_name, label_cfg);
+
+        let value = values.value(sensor_key).unwrap_or("N/A");
+        let unit = values
+            .unit(sensor_key)
+            .or(sensor.unit.as_deref())
+            .unwrap_or_default();
+        let value_format = self.value_format.merge(sensor.value_format.as_ref());
+        let format = |value: &str| {
+            format_value_with(
+                value,
+                sensor.integer_digits.into(),
+                sensor.decimal_digits.unwrap_or_default() as usize,
+                unit,
+                &value_format,
+            )
+        };
+        let color = sensor.resolve_color(value);
+        let font = self.font_handler.get_text_font(
+            sensor.font_family.as_deref(),
+            sensor.font_fallback.as_deref(),
+        );
+        let adjustment_hack = 0.75;
+
+        // content area below the label
+        let margin = 24;
+        let top = height * 3 / 10;
+        let content_height = height.saturating_sub(top + margin / 2);
+        let gauge_size = content_height.min(width / 3);
+        if gauge_size == 0 {
+            return Ok(image);
+        }
+
+        // gauge with the current value
+        let min_value = sensor.min_value.unwrap_or(0.0);
+        let max_value = sensor.max_value.unwrap_or(100.0);
+        let progress = value
+            .parse::<f32>()
+            .map(|v| ((v - min_value) / (max_value - min_value)).clamp(0.0, 1.0))
+            .unwrap_or_default();
+        let gauge_x = margin;
+        let gauge_y = top + (content_height - gauge_size) / 2;
+        Self::draw_ring_gauge(&mut image, gauge_x, gauge_y, gauge_size, progress, color);
+
+        let text = format(value);
+        let scale = font
+            .pt_to_px_scale(gauge_size as f32 / 5.0 * adjustment_hack)
+            .unwrap();
+        let scale = fit_text_scale(scale, &font, &text, gauge_size * 2 / 3);
+        let size = text_size(scale, &font, &text);
+        let x = (gauge_x + gauge_size / 2) as i32 - size.0 as i32 / 2;
+        let y = (gauge_y + gauge_size / 2) as i32 - (size.1 as f32 * 1.3333 / 2.0) as i32;
+        draw_text_mut(&mut image, color, x, y, scale, &font, &text);
+
+        // chart of the recorded values with min / max below
+        let chart_x = gauge_x + gauge_size + margin + margin / 2;
+        let chart_width = width.saturating_sub(chart_x + margin);
+        let legend_height = content_height / 6;
+        let chart_height = content_height.saturating_sub(legend_height + margin / 2);
+        if chart_width == 0 || chart_height == 0 {
+            return Ok(image);
+        }
+        let samples = history.samples(sensor_key);
+        let chart = Rect::at(chart_x as i32, top as i32).of_size(chart_width, chart_height);
+        Self::draw_chart(&mut image, chart, &samples, history.retention(), color);
+
+        let legend_font = FontHandler::default_font();
+        let legend_color = Rgba([180, 180, 180, 255]);
+        let legend_y = (top + chart_height + margin / 2) as i32;
+        let min_max = history
+            .min_max(sensor_key)
+            .map(|(min, max)| {
+                format!(
+                    "MIN {}   MAX {}",
+                    format(&min.to_string()),
+                    format(&max.to_string())
+                )
+            })
+            .unwrap_or_else(|| "MIN N/A   MAX N/A".to_string());
+        let period = format!("{} H", history.retention().as_secs() / 3600);
+        let legend_scale = legend_font
+            .pt_to_px_scale(legend_height as f32 * adjustment_hack)
+            .unwrap();
+        let legend_scale = fit_text_scale(
+            legend_scale,
+            &legend_font,
+            &format!("{min_max}    {period}"),
+            chart_width,
+        );
+        draw_text_mut(
+            &mut image,
+            legend_color,
+            chart_x as i32,
+            legend_y,
+            legend_scale,
+            &legend_font,
+            &min_max,
+        );
+        let period_size = text_size(legend_scale, &legend_font, &period);
+        draw_text_mut(
+            &mut image,
+            legend_color,
+            (chart_x + chart_width) as i32 - period_size.0 as i32,
+            legend_y,
+            legend_scale,
+            &legend_font,
+            &period,
+        );
+
+        if self.save_render_img {
+            let name = format!(
+                "render_history_{}{}.png",
+                sensor_key,
+                self.img_suffix.as_deref().unwrap_or_default()
+            );
+            if let Err(e) = image.save(self.img_save_path.join(name)) {
+                error!("Error saving rendered history page image: {e}");
+            }
+        }
+
+        Ok(image)
+    }
+
     /// Render a single sensor page: one sensor on the panel background.
     /// (Legacy method kept for backward compatibility)
     pub fn render_sensor_page(
         &mut self,
         panel: &Panel,
         sensor_index: usize,
-        values: &HashMap<String, String>,
+        values: &SensorStore,
         label_cfg: Option<&SensorPageLabel>,
     ) -> Result<RgbaImage, ImageProcessingError> {
         let sensor = &panel.sensor[sensor_index];
@@ -303,30 +964,43 @@ impl PanelRenderer {
             .as_deref()
             .or(sensor.item_name.as_deref())
             .unwrap_or(&sensor.label);
-        self.render_sensor_page_from_template(sensor, &sensor.label, display_name, values, label_cfg)
+        self.render_sensor_page_from_template(
+            sensor,
+            &sensor.label,
+            display_name,
+            values,
+            label_cfg,
+        )
     }
 
-    /// Render all panel sensors with the given values on a background image
+    /// Render all panel sensors with the given values on a background image.
+    ///
+    /// Sensors whose `showIf` condition is not met are skipped. Stale sensor values are shown
+    /// according to the `stale` option of the sensor or the default stale policy.
     pub fn render_all_sensors(
         &mut self,
         panel: &Panel,
-        values: &HashMap<String, String>,
+        values: &SensorStore,
         mut background: RgbaImage,
     ) -> Result<RgbaImage, ImageProcessingError> {
         let now: DateTime<Local> = Local::now();
 
         for sensor in &panel.sensor {
-            let value = values.get(&sensor.label).cloned();
+            if let Some(condition) = &sensor.show_if
+                && !condition.is_met(values)
+            {
+                continue;
+            }
+            let value = values.get(&sensor.label);
             let unit = values
-                .get(&format!("{}#unit", sensor.label))
-                .cloned()
-                .or_else(|| sensor.unit.clone())
+                .unit(&sensor.label)
+                .or(sensor.unit.as_deref())
                 .unwrap_or_default();
 
             if let Some(value) = value {
-                self.render_sensor(&mut background, sensor, &value, &unit)?;
+                self.render_stored_sensor(&mut background, sensor, &sensor.label, value, unit)?;
             } else if let Some(value) = get_date_time_value(&sensor.label, &now) {
-                self.render_sensor(&mut background, sensor, &value, &unit)?;
+                self.render_sensor(&mut background, sensor, &value, unit)?;
             }
         }
 
@@ -336,6 +1010,55 @@ impl PanelRenderer {
         Ok(background)
     }
 
+    /// Render a stored sensor value, applying the stale policy if the source didn't update the
+    /// value in time, or the smoothing and color hysteresis of the element.
+    fn render_stored_sensor(
+        &mut self,
+        background: &mut RgbaImage,
+        sensor: &Sensor,
+        sensor_key: &str,
+        value: &SensorValue,
+        unit: &str,
+    ) -> Result<(), ImageProcessingError> {
+        let now = Local::now();
+        let stale = sensor
+            .stale
+            .as_ref()
+            .or(self.stale_sensors.as_ref())
+            .filter(|stale| stale.is_stale(value.updated_at, now));
+        let Some(stale) = stale else {
+            let element = format!("{sensor_key}@{},{}", sensor.x, sensor.y);
+            let text = match &sensor.smoothing {
+                Some(smoothing) => self.smoother.smooth(&element, smoothing, value, now),
+                None => value.value.clone(),
+            };
+            if let Some(hysteresis) = sensor.hysteresis
+                && let Ok(number) = text.trim().parse::<f32>()
+            {
+                let color_value = self
+                    .smoother
+                    .color_value(&element, sensor, hysteresis, number);
+                let color = sensor.resolve_color(&color_value.to_string()).to_rgb();
+                return self.render_sensor(
+                    background,
+                    &sensor.with_color(color.into()),
+                    &text,
+                    unit,
+                );
+            }
+            return self.render_sensor(background, sensor, &text, unit);
+        };
+
+        match stale.action {
+            StaleAction::Grey => {
+                let greyed_out = sensor.greyed_out(stale.grey_color());
+                self.render_sensor(background, &greyed_out, &value.value, unit)
+            }
+            StaleAction::Dash => self.render_sensor(background, sensor, STALE_VALUE, ""),
+            StaleAction::Hide => Ok(()),
+        }
+    }
+
     /// Render a single sensor element based on its mode
     fn render_sensor(
         &mut self,
@@ -351,6 +1074,8 @@ impl PanelRenderer {
             SensorMode::Fan => self.render_fan(sensor, value, direction),
             SensorMode::Progress => self.render_progress(sensor, value, direction),
             SensorMode::Pointer => self.render_pointer(sensor, value, direction),
+            SensorMode::Icon => self.render_icon(background, sensor, value),
+            SensorMode::Bar => self.render_bar(background, sensor, value, direction),
         }
     }
 
@@ -362,11 +1087,15 @@ impl PanelRenderer {
         value: &str,
         unit: &str,
     ) -> Result<(), ImageProcessingError> {
-        let font = if let Some(font_family) = &sensor.font_family {
-            self.font_handler.get_ttf_font_or_default(font_family)
-        } else {
-            FontHandler::default_font()
-        };
+        if let Some(digit_style) = &sensor.digit_style {
+            self.render_digits(background, sensor, digit_style, value, unit);
+            return Ok(());
+        }
+
+        let font = self.font_handler.get_text_font(
+            sensor.font_family.as_deref(),
+            sensor.font_fallback.as_deref(),
+        );
         let font_size = sensor.font_size.unwrap_or(14) as f32;
         // TODO verify pixel scaling! Is font_size point size or pixel size?
         // TODO some font size calculation is missing, dpi scaling? internal padding?
@@ -375,38 +1104,147 @@ impl PanelRenderer {
         let adjustment_hack = 0.75;
         let scale = font.pt_to_px_scale(font_size * adjustment_hack).unwrap();
 
-        let text = format_value(
+        let text = format_value_with(
             value,
             sensor.integer_digits.into(),
             sensor.decimal_digits.unwrap_or_default() as usize,
             unit,
+            &self.value_format.merge(sensor.value_format.as_ref()),
         );
         let size = text_size(scale, &font, &text);
         let width = sensor.width.unwrap_or_default() as i32;
         let height = sensor.height.unwrap_or_default() as i32;
-        let x = match sensor.text_align.unwrap_or_default() {
-            TextAlign::Left => sensor.x,
-            TextAlign::Center => sensor.x + width / 2 - (size.0 / 2) as i32,
-            TextAlign::Right => sensor.x + width - size.0 as i32,
-        };
+        let font_color = sensor.resolve_color(value);
         // FIXME figure out font scaling factor / padding / dpi etc. See above for y-adjustment hack.
         // This work quite ok for most panels, but not all!
         // Some work better with `sensor.y + height / 2 - size.1 as i32;`
         // The y parameter in `draw_text_mut` is still a mystery: drawing text at position (0,0)
         // renders a huge gap at the top, about the size of half the font-height!?
-        let y = sensor.y + height / 2 - (size.1 as f32 * 1.3333 / 2f32) as i32;
+        let vertical_align = sensor.vertical_align.unwrap_or_default();
+        let y = match vertical_align {
+            VerticalAlign::Top => sensor.y,
+            VerticalAlign::Center => sensor.y + height / 2 - (size.1 as f32 * 1.3333 / 2f32) as i32,
+            VerticalAlign::Bottom => sensor.y + height - (size.1 as f32 * 1.3333) as i32,
+        };
+
+        let word_wrap = sensor.word_wrap.unwrap_or_default() && width > 0;
+        if text.contains('\n') || word_wrap && size.0 as i32 > width {
+            let max_width = word_wrap.then_some(width as u32);
+            let mut lines = wrap_text(&text, max_width, |line| text_size(scale, &font, line).0);
+            let line_height = (scale.y * sensor.line_spacing.unwrap_or(1.2)) as i32;
+            if height > 0 && line_height > 0 {
+                lines.truncate((height / line_height).max(1) as usize);
+            }
+            let total_height = line_height * lines.len() as i32;
+            let mut y = match vertical_align {
+                VerticalAlign::Top => sensor.y,
+                VerticalAlign::Center => sensor.y + (height - total_height) / 2,
+                VerticalAlign::Bottom => sensor.y + height - total_height,
+            };
+            for line in lines {
+                let line_width = text_size(scale, &font, &line).0 as i32;
+                let x = match sensor.text_align.unwrap_or_default() {
+                    TextAlign::Left => sensor.x,
+                    TextAlign::Center => sensor.x + (width - line_width) / 2,
+                    TextAlign::Right => sensor.x + width - line_width,
+                };
+                debug!(
+                    "Sensor({:03},{:03}), line({x:03},{y:03}): {line}",
+                    sensor.x, sensor.y
+                );
+                draw_text_mut(background, font_color, x, y, scale, &font, &line);
+                y += line_height;
+            }
+            return Ok(());
+        }
+
+        if let Some(speed) = sensor.scroll_speed
+            && width > 0
+            && size.0 as i32 > width
+        {
+            // scroll position from the elapsed time, independent of the refresh interval
+            let period = size.0 + MARQUEE_GAP;
+            let offset = if self.animations {
+                (self.start.elapsed().as_secs_f32() * speed.max(0.0)) as u32 % period
+            } else {
+                0
+            };
+            debug!(
+                "Sensor({:03},{:03}), scroll offset {offset}/{period}: {text}",
+                sensor.x, sensor.y
+            );
+            // draw on a copy of the element area to clip the text to the element width
+            let (left, top) = (sensor.x.max(0) as u32, y.max(0) as u32);
+            let area_width = (width as u32).min(background.width().saturating_sub(left));
+            let area_height = (size.1 * 2).min(background.height().saturating_sub(top));
+            let mut area =
+                imageops::crop_imm(background, left, top, area_width, area_height).to_image();
+            let text_x = sensor.x - left as i32 - offset as i32;
+            let text_y = y - top as i32;
+            for x in [text_x, text_x + period as i32] {
+                draw_text_mut(&mut area, font_color, x, text_y, scale, &font, &text);
+            }
+            imageops::replace(background, &area, left as i64, top as i64);
+            return Ok(());
+        }
+
+        let x = match sensor.text_align.unwrap_or_default() {
+            TextAlign::Left => sensor.x,
+            TextAlign::Center => sensor.x + width / 2 - (size.0 / 2) as i32,
+            TextAlign::Right => sensor.x + width - size.0 as i32,
+        };
 
         debug!(
             "Sensor({:03},{:03}), pixel({x:03},{y:03}), size{size:?}: {text}",
             sensor.x, sensor.y
         );
 
-        let font_color = sensor.resolve_color(value);
         draw_text_mut(background, font_color, x, y, scale, &font, &text);
 
         Ok(())
     }
 
+    /// Mode 1 - Text with built-in seven-segment or dot-matrix digits, the font size is the digit
+    /// height.
+    fn render_digits(
+        &mut self,
+        background: &mut RgbaImage,
+        sensor: &Sensor,
+        digit_style: &DigitStyle,
+        value: &str,
+        unit: &str,
+    ) {
+        let text = format_value_with(
+            value,
+            sensor.integer_digits.into(),
+            sensor.decimal_digits.unwrap_or_default() as usize,
+            unit,
+            &self.value_format.merge(sensor.value_format.as_ref()),
+        );
+        let digit_height = sensor.font_size.unwrap_or(14).max(0) as u32;
+        let text_width = digits::text_width(digit_style, &text, digit_height) as i32;
+        let width = sensor.width.unwrap_or_default() as i32;
+        let height = sensor.height.unwrap_or_default() as i32;
+
+        let x = match sensor.text_align.unwrap_or_default() {
+            TextAlign::Left => sensor.x,
+            TextAlign::Center => sensor.x + (width - text_width) / 2,
+            TextAlign::Right => sensor.x + width - text_width,
+        };
+        let y = match sensor.vertical_align.unwrap_or_default() {
+            VerticalAlign::Top => sensor.y,
+            VerticalAlign::Center => sensor.y + (height - digit_height as i32) / 2,
+            VerticalAlign::Bottom => sensor.y + height - digit_height as i32,
+        };
+
+        debug!(
+            "Sensor({:03},{:03}), digits({x:03},{y:03}): {text}",
+            sensor.x, sensor.y
+        );
+        let color = sensor.resolve_color(value);
+        draw_digits(background, digit_style, &text, x, y, digit_height, color);
+    }
+
     /// Mode 2 - Circular/Arc progress indicator
     fn render_fan(
         &mut self,
@@ -568,6 +1406,125 @@ impl PanelRenderer {
         Ok(())
     }
 
+    /// Mode 6 - Bar graph drawn in the sensor rectangle, without a progress image.
+    ///
+    /// The fill color is the font color, or the matching color threshold of the value.
+    fn render_bar(
+        &mut self,
+        background: &mut RgbaImage,
+        sensor: &Sensor,
+        value: &str,
+        direction: SensorDirection,
+    ) -> Result<(), ImageProcessingError> {
+        let (Some(width), Some(height)) = (
+            sensor.width.filter(|w| *w > 0),
+            sensor.height.filter(|h| *h > 0),
+        ) else {
+            return Err(ImageProcessingError::MathError(
+                "Bar requires width and height".to_string(),
+            ));
+        };
+
+        let min_val = sensor.min_value.unwrap_or(0.0);
+        let max_val = sensor.max_value.unwrap_or(100.0);
+        let current_value = value
+            .parse::<f32>()
+            .map_err(|_| ImageProcessingError::MathError("Invalid value".to_string()))?;
+        let progress = ((current_value - min_val) / (max_val - min_val)).clamp(0.0, 1.0);
+        // min == max
+        let progress = if progress.is_nan() { 0.0 } else { progress };
+
+        let style = sensor.bar.clone().unwrap_or_default();
+        let color = sensor.resolve_color(value);
+        PanelRenderer::draw_bar(
+            background,
+            Rect::at(sensor.x, sensor.y).of_size(width, height),
+            progress,
+            direction,
+            color,
+            style.gradient_color.map(Into::into).unwrap_or(color),
+            style.background_color.map(Into::into),
+            style.radius,
+        );
+
+        Ok(())
+    }
+
+    /// Draws a bar with rounded corners.
+    ///
+    /// # Arguments
+    ///
+    /// * `image`: Destination image.
+    /// * `rect`: Bar rectangle.
+    /// * `progress`: Filled part of the bar, from 0.0 to 1.0.
+    /// * `direction`: Fill direction.
+    /// * `start_color`, `end_color`: Gradient colors at the start and end of the bar.
+    /// * `track_color`: Optional color of the unfilled part.
+    /// * `radius`: Corner radius, limited to half the bar thickness.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_bar(
+        image: &mut RgbaImage,
+        rect: Rect,
+        progress: f32,
+        direction: SensorDirection,
+        start_color: Rgba<u8>,
+        end_color: Rgba<u8>,
+        track_color: Option<Rgba<u8>>,
+        radius: u32,
+    ) {
+        let (w, h) = (rect.width() as f32, rect.height() as f32);
+        let radius = (radius as f32).min(w / 2.0).min(h / 2.0);
+
+        for py in rect.top().max(0)..rect.bottom().min(image.height() as i32 - 1) + 1 {
+            for px in rect.left().max(0)..rect.right().min(image.width() as i32 - 1) + 1 {
+                // pixel center relative to the bar
+                let lx = px as f32 + 0.5 - rect.left() as f32;
+                let ly = py as f32 + 0.5 - rect.top() as f32;
+
+                // anti-aliased coverage of the rounded corners
+                let cx = lx.clamp(radius, w - radius);
+                let cy = ly.clamp(radius, h - radius);
+                let dist = ((lx - cx).powi(2) + (ly - cy).powi(2)).sqrt();
+                let coverage = if radius > 0.0 {
+                    (radius - dist + 0.5).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                if coverage <= 0.0 {
+                    continue;
+                }
+
+                // position along the bar in fill direction
+                let pos = match direction {
+                    SensorDirection::LeftToRight => lx / w,
+                    SensorDirection::RightToLeft => 1.0 - lx / w,
+                    SensorDirection::TopToBottom => ly / h,
+                    SensorDirection::BottomToTop => 1.0 - ly / h,
+                };
+                let color = if pos <= progress && progress > 0.0 {
+                    let mut color = start_color;
+                    for i in 0..4 {
+                        color[i] = (start_color[i] as f32
+                            + (end_color[i] as f32 - start_color[i] as f32) * pos)
+                            .round() as u8;
+                    }
+                    color
+                } else if let Some(track_color) = track_color {
+                    track_color
+                } else {
+                    continue;
+                };
+
+                let alpha = coverage * color[3] as f32 / 255.0;
+                let target = image.get_pixel_mut(px as u32, py as u32);
+                for i in 0..3 {
+                    target[i] = (color[i] as f32 * alpha + target[i] as f32 * (1.0 - alpha)) as u8;
+                }
+                target[3] = target[3].max((alpha * 255.0) as u8);
+            }
+        }
+    }
+
     /// Mode 4 - Rotating pointer/dial indicator
     /// TODO needs testing
     fn render_pointer(
@@ -743,6 +1700,182 @@ impl PanelRenderer {
         }
     }
 
+    /// Draw the sensor name label at the top of a sensor page.
+    fn draw_page_label(
+        &mut self,
+        image: &mut RgbaImage,
+        display_name: &str,
+        label_cfg: Option<&SensorPageLabel>,
+    ) {
+        let name_font = if let Some(font_family) = label_cfg.and_then(|c| c.font_family.as_deref())
+        {
+            self.font_handler.get_ttf_font_or_default(font_family)
+        } else {
+            FontHandler::default_font()
+        };
+        let name_font_size = label_cfg.and_then(|c| c.font_size).unwrap_or(28.0);
+        let adjustment_hack = 0.75;
+        let name_scale = name_font
+            .pt_to_px_scale(name_font_size * adjustment_hack)
+            .unwrap();
+        let name_color: Rgba<u8> = label_cfg
+            .and_then(|c| c.font_color)
+            .map(|c| c.into())
+            .unwrap_or(Rgba([180, 180, 180, 255]));
+        let label_text = format!("[ {} ]", display_name.to_uppercase());
+        let name_sz = text_size(name_scale, &name_font, &label_text);
+        let name_x = label_cfg
+            .and_then(|c| c.x)
+            .unwrap_or_else(|| (self.size.0 as i32 - name_sz.0 as i32) / 2);
+        let name_y = label_cfg.and_then(|c| c.y).unwrap_or(40);
+        draw_text_mut(
+            image,
+            name_color,
+            name_x,
+            name_y,
+            name_scale,
+            &name_font,
+            &label_text,
+        );
+    }
+
+    /// Draws a 270° ring gauge, open at the bottom, into a square at the given position.
+    ///
+    /// # Arguments
+    ///
+    /// * `image`: Destination image.
+    /// * `x`, `y`: Top left position of the gauge.
+    /// * `size`: Width and height of the gauge.
+    /// * `progress`: Filled part of the ring, from 0.0 to 1.0.
+    /// * `color`: Color of the filled part.
+    fn draw_ring_gauge(
+        image: &mut RgbaImage,
+        x: u32,
+        y: u32,
+        size: u32,
+        progress: f32,
+        color: Rgba<u8>,
+    ) {
+        const START_DEG: f32 = 135.0;
+        const SWEEP_DEG: f32 = 270.0;
+        let track_color = Rgba([60, 60, 60, 255]);
+        let outer = size as f32 / 2.0;
+        let inner = outer * 0.82;
+        let value_deg = SWEEP_DEG * progress.clamp(0.0, 1.0);
+
+        for py in y..(y + size).min(image.height()) {
+            for px in x..(x + size).min(image.width()) {
+                let dx = px as f32 + 0.5 - (x as f32 + outer);
+                let dy = py as f32 + 0.5 - (y as f32 + outer);
+                let dist = (dx * dx + dy * dy).sqrt();
+                if dist < inner || dist > outer {
+                    continue;
+                }
+                // angle measured from 3 o'clock, increasing clockwise
+                let angle = dy.atan2(dx).to_degrees().rem_euclid(360.0);
+                let rel = (angle - START_DEG).rem_euclid(360.0);
+                if rel > SWEEP_DEG {
+                    continue;
+                }
+                let px_color = if rel <= value_deg && progress > 0.0 {
+                    color
+                } else {
+                    track_color
+                };
+                image.put_pixel(px, py, px_color);
+            }
+        }
+    }
+
+    /// Draws a line chart of history samples into the given rectangle.
+    ///
+    /// The time axis covers the `period` up to now, the value axis is scaled to the sample range.
+    /// Gaps in the recorded samples are not connected.
+    fn draw_chart(
+        image: &mut RgbaImage,
+        rect: Rect,
+        samples: &[HistorySample],
+        period: Duration,
+        color: Rgba<u8>,
+    ) {
+        let grid_color = Rgba([50, 50, 50, 255]);
+        let left = rect.left() as f32;
+        let right = rect.right() as f32;
+        for i in 0..=2 {
+            let y = rect.top() as f32 + (rect.height() - 1) as f32 * i as f32 / 2.0;
+            draw_line_segment_mut(image, (left, y), (right, y), grid_color);
+        }
+        if samples.is_empty() {
+            return;
+        }
+
+        let (mut lo, mut hi) = samples.iter().fold((f32::MAX, f32::MIN), |(lo, hi), s| {
+            (lo.min(s.avg), hi.max(s.avg))
+        });
+        // minimum value range to not amplify noise of constant values
+        let min_range = 5.0;
+        if hi - lo < min_range {
+            let center = (hi + lo) / 2.0;
+            lo = center - min_range / 2.0;
+            hi = center + min_range / 2.0;
+        }
+
+        let now = unix_time();
+        let period = period.as_secs().max(1);
+        let start = now.saturating_sub(period);
+        let max_gap = (period / 100).max(120);
+        let point = |s: &HistorySample| {
+            let t = s.timestamp.saturating_sub(start) as f32 / period as f32;
+            let v = (s.avg - lo) / (hi - lo);
+            (
+                left + t.min(1.0) * (right - left),
+                rect.bottom() as f32 - v * (rect.height() - 1) as f32,
+            )
+        };
+
+        for pair in samples.windows(2) {
+            if pair[1].timestamp - pair[0].timestamp > max_gap {
+                continue;
+            }
+            let (x0, y0) = point(&pair[0]);
+            let (x1, y1) = point(&pair[1]);
+            draw_line_segment_mut(image, (x0, y0), (x1, y1), color);
+            draw_line_segment_mut(image, (x0, y0 - 1.0), (x1, y1 - 1.0), color);
+        }
+        if let [single] = samples {
+            let (x, y) = point(single);
+            draw_filled_rect_mut(
+                image,
+                Rect::at(x as i32 - 1, y as i32 - 1).of_size(3, 3),
+                color,
+            );
+        }
+    }
+
+    /// Mode 5 - Icon selected by the sensor value
+    ///
+    /// The `{value}` placeholder in the `pic` file name is replaced with the sensor value, e.g.
+    /// `weather/{value}.png`. Characters other than ASCII letters, digits, `-` and `.` are replaced
+    /// with `_` to keep the file name within the image directory.
+    fn render_icon(
+        &mut self,
+        background: &mut RgbaImage,
+        sensor: &Sensor,
+        value: &str,
+    ) -> Result<(), ImageProcessingError> {
+        let pic_path = sensor.resolve_icon(value).ok_or_else(|| {
+            ImageProcessingError::ImageLoadError("No picture specified".to_string())
+        })?;
+        let size = sensor.width.zip(sensor.height);
+
+        // missing icons are logged once by the image cache
+        if let Some(icon) = self.image_cache.get(&pic_path, size) {
+            imageops::overlay(background, icon, sensor.x as i64, sensor.y as i64);
+        }
+
+        Ok(())
+    }
+
     /// Apply progress mask to image based on crop rectangle and direction
     fn apply_progress_mask(
         &self,
@@ -896,3 +2029,144 @@ impl PanelRenderer {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::FontColor;
+    use chrono::NaiveTime;
+    use image::Rgb;
+
+    #[test]
+    fn wrap_mixed_cjk_text() {
+        // every character is 10 pixels wide
+        let measure = |line: &str| line.chars().count() as u32 * 10;
+
+        let lines = wrap_text("CPU温度が高いです。", Some(60), measure);
+        assert_eq!(vec!["CPU温度が", "高いです。"], lines);
+
+        let lines = wrap_text("2026年10月17日 Saturday", Some(90), measure);
+        assert_eq!(vec!["2026年10月", "17日", "Saturday"], lines);
+
+        let lines = wrap_text("Temperature", Some(50), measure);
+        assert_eq!(vec!["Tempe", "ratur", "e"], lines);
+    }
+
+    #[test]
+    fn analog_clock_hands() {
+        let mut renderer = PanelRenderer::new((200, 100), "fonts", "cfg");
+        let style = TimePageStyle {
+            clock: ClockType::Analog,
+            hour_color: Some(FontColor::from(Rgb([255, 0, 0]))),
+            minute_color: Some(FontColor::from(Rgb([0, 255, 0]))),
+            seconds: Some(false),
+            ..Default::default()
+        };
+        let time = NaiveTime::from_hms_opt(3, 0, 0).unwrap();
+        let image = renderer.render_analog_clock(&style, &time);
+
+        // clock radius is 42 pixels around the center at 100, 50
+        let empty = Rgba([0, 0, 0, 0]);
+        assert_eq!(Rgba([255, 0, 0, 255]), image[(115, 50)], "hour hand");
+        assert_eq!(Rgba([0, 255, 0, 255]), image[(100, 20)], "minute hand");
+        assert_eq!(empty, image[(100, 75)]);
+        assert_ne!(empty, image[(100, 89)], "hour mark at 6");
+    }
+
+    #[test]
+    fn stale_sensor_actions() {
+        let mut renderer = PanelRenderer::new((20, 10), "fonts", "cfg");
+        let mut sensor: Sensor = serde_json::from_str(
+            r##"{ "mode": 6, "label": "ups_load", "x": 0, "y": 0, "width": 20, "height": 10,
+              "fontColor": "#00ff00", "critAbove": 50 }"##,
+        )
+        .unwrap();
+        let value = SensorValue {
+            value: "80".to_string(),
+            unit: None,
+            description: None,
+            source: "exec-ups".to_string(),
+            updated_at: Local::now() - Duration::from_secs(60),
+        };
+        let render = |renderer: &mut PanelRenderer, sensor: &Sensor| {
+            let mut image = RgbaImage::new(20, 10);
+            renderer
+                .render_stored_sensor(&mut image, sensor, "ups_load", &value, "")
+                .unwrap();
+            image[(2, 5)]
+        };
+
+        assert_eq!(Rgba([255, 0, 0, 255]), render(&mut renderer, &sensor));
+
+        renderer.set_stale_sensors(StaleSensors {
+            after: 30.0,
+            action: StaleAction::Grey,
+            color: None,
+        });
+        assert_eq!(
+            Rgba([0x80, 0x80, 0x80, 255]),
+            render(&mut renderer, &sensor)
+        );
+
+        sensor.stale = Some(StaleSensors {
+            after: 30.0,
+            action: StaleAction::Hide,
+            color: None,
+        });
+        assert_eq!(Rgba([0, 0, 0, 0]), render(&mut renderer, &sensor));
+
+        sensor.stale.as_mut().unwrap().after = 0.0;
+        assert_eq!(Rgba([255, 0, 0, 255]), render(&mut renderer, &sensor));
+    }
+
+    #[test]
+    fn history_page_gauge_and_chart() {
+        let mut renderer = PanelRenderer::new((320, 160), "fonts", "cfg");
+        let sensor: Sensor = serde_json::from_str(
+            r##"{ "mode": 1, "match": "^temperature_(.+)$", "x": 0, "y": 0,
+              "fontColor": "#ff0000", "minValue": 0, "maxValue": 100 }"##,
+        )
+        .unwrap();
+        // rising from 20 to 80 during the last hour
+        let mut history = SensorHistory::new(Duration::from_secs(3600), Duration::from_secs(60));
+        let now = unix_time();
+        for minute in 0..=60 {
+            let value = 20 + minute;
+            let values =
+                SensorStore::from_iter([("temperature_cpu".to_string(), value.to_string())]);
+            history.record_at(&values, now - 3600 + minute * 60);
+        }
+        let values = SensorStore::from_iter([("temperature_cpu".to_string(), "50".to_string())]);
+
+        let image = renderer
+            .render_history_page(&sensor, "temperature_cpu", "CPU", &values, &history, None)
+            .unwrap();
+
+        // gauge of 100 pixels at 24,48: half filled from the bottom left to the top
+        let red = Rgba([255, 0, 0, 255]);
+        assert_eq!(red, image[(26, 98)], "filled gauge");
+        assert_eq!(Rgba([60, 60, 60, 255]), image[(121, 98)], "gauge track");
+
+        // chart of 136x72 pixels at 160,48: line from the bottom left to the top right
+        let chart_line = |x: u32| {
+            (48..120)
+                .filter(|&y| image[(x, y)] == red)
+                .collect::<Vec<_>>()
+        };
+        assert!(
+            chart_line(170).iter().all(|&y| y > 100),
+            "{:?}",
+            chart_line(170)
+        );
+        assert!(
+            chart_line(228).iter().any(|&y| (80..88).contains(&y)),
+            "{:?}",
+            chart_line(228)
+        );
+        assert!(
+            chart_line(290).iter().all(|&y| y < 56),
+            "{:?}",
+            chart_line(290)
+        );
+        assert!(!chart_line(290).is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Error report bundle for bug reports.
+//!
+//! A report collects system information, the sanitized configuration and diagnostic output of
+//! the running sensor panel into a single `.tar.gz` archive, which can be attached to an issue.
+//! API tokens, passwords and URL credentials are removed from the configuration and the log.
+
+use crate::secrets::is_secret_reference;
+use anyhow::Context;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::path::Path;
+
+/// Replacement of removed secrets.
+pub const REDACTED: &str = "<redacted>";
+
+/// Parts of configuration keys holding secrets, compared in lowercase without `_` and `-`.
+const SECRET_KEYS: [&str; 5] = ["token", "password", "secret", "apikey", "credential"];
+
+static URL_CREDENTIALS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"://[^/@\s]+@").expect("Invalid URL credentials regex"));
+
+/// Secret values of log lines: `key=value`, `"key": "value"` or `key: Some("value")` with a
+/// [SECRET_KEYS] key, and HTTP authorization headers.
+static LOG_SECRETS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?i)([\w.-]*(?:token|password|secret|api[_-]?key|credential)[\w.-]*"?\s*[:=]\s*(?:Some\()?"?|\b(?:bearer|basic)\s+)([^\s"',&)}]+)"#,
+    )
+    .expect("Invalid log secrets regex")
+});
+
+/// Files of an error report.
+#[derive(Debug, Default)]
+pub struct Report {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Report {
+    /// Add a file to the report.
+    pub fn add(&mut self, name: impl Into<String>, content: impl Into<Vec<u8>>) {
+        self.files.push((name.into(), content.into()));
+    }
+
+    /// Names of the added files.
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Write all files into a gzip-compressed tar archive, below an `asterctl-report` directory.
+    pub fn write_archive(&self, path: &Path) -> anyhow::Result<()> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create report {}", path.display()))?;
+        let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+
+        for (name, content) in &self.files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            archive.append_data(
+                &mut header,
+                format!("asterctl-report/{name}"),
+                content.as_slice(),
+            )?;
+        }
+
+        archive.into_inner()?.finish()?;
+        Ok(())
+    }
+}
+
+/// Version of asterctl, operating system and kernel information.
+pub fn system_info() -> String {
+    let mut info = format!(
+        "asterctl: {}\nos: {}\narch: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+
+    if let Ok(kernel) = fs::read_to_string("/proc/sys/kernel/osrelease") {
+        info.push_str(&format!("kernel: {}\n", kernel.trim()));
+    }
+    if let Ok(os_release) = fs::read_to_string("/etc/os-release")
+        && let Some(name) = os_release
+            .lines()
+            .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+    {
+        info.push_str(&format!("distribution: {}\n", name.trim_matches('"')));
+    }
+
+    info
+}
+
+/// Remove secrets from a JSON configuration.
+///
+/// String values of keys containing `token`, `password`, `secret`, `apiKey` or `credential` are
+/// replaced, unless they are secret references like `env:NAME`. User credentials are removed from
+/// all URLs.
+pub fn sanitize_config(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase().replace(['_', '-'], "");
+                match value {
+                    Value::String(s)
+                        if SECRET_KEYS.iter().any(|secret| key.contains(secret))
+                            && !s.is_empty()
+                            && !is_secret_reference(s) =>
+                    {
+                        *s = REDACTED.to_string();
+                    }
+                    _ => sanitize_config(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(sanitize_config),
+        Value::String(s) if URL_CREDENTIALS.is_match(s) => {
+            *s = URL_CREDENTIALS
+                .replace_all(s, format!("://{REDACTED}@"))
+                .into_owned();
+        }
+        _ => {}
+    }
+}
+
+/// Remove secrets from log lines, like [sanitize_config] from a configuration.
+pub fn sanitize_log(log: &str) -> String {
+    let log = URL_CREDENTIALS.replace_all(log, format!("://{REDACTED}@"));
+    LOG_SECRETS
+        .replace_all(&log, |captures: &regex::Captures| {
+            let value = &captures[2];
+            if is_secret_reference(value) {
+                captures[0].to_string()
+            } else {
+                format!("{}{REDACTED}", &captures[1])
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use serde_json::json;
+    use std::io::Read;
+
+    #[test]
+    fn sanitize_config_removes_secrets() {
+        let mut cfg = json!({
+            "setup": { "ha_token": "abc123", "ha_url": "http://homeassistant:8123" },
+            "httpApi": { "token": "env:ASTERCTL_TOKEN" },
+            "httpSources": [{ "url": "https://user:pw@example.com/api", "apiKey": "s3cr3t" }],
+            "sensorPageTime": 10,
+        });
+        sanitize_config(&mut cfg);
+
+        assert_eq!(REDACTED, cfg["setup"]["ha_token"]);
+        assert_eq!("http://homeassistant:8123", cfg["setup"]["ha_url"]);
+        assert_eq!("env:ASTERCTL_TOKEN", cfg["httpApi"]["token"]);
+        assert_eq!(
+            "https://<redacted>@example.com/api",
+            cfg["httpSources"][0]["url"]
+        );
+        assert_eq!(REDACTED, cfg["httpSources"][0]["apiKey"]);
+        assert_eq!(10, cfg["sensorPageTime"]);
+    }
+
+    #[test]
+    fn sanitize_log_removes_secrets() {
+        let log = [
+            "GET https://user:pw@example.com/api?token=abc123&page=1 failed",
+            "Authorization: Bearer xyz.789",
+            r#"Setup { ha_url: "http://ha:8123", ha_token: Some("abc"), refresh: 1.0 }"#,
+            r#"Invalid source {"apiKey": "s3cr3t", "url": "http://host"}"#,
+            "Using token=env:HA_TOKEN",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            [
+                "GET https://<redacted>@example.com/api?token=<redacted>&page=1 failed",
+                "Authorization: Bearer <redacted>",
+                r#"Setup { ha_url: "http://ha:8123", ha_token: Some("<redacted>"), refresh: 1.0 }"#,
+                r#"Invalid source {"apiKey": "<redacted>", "url": "http://host"}"#,
+                "Using token=env:HA_TOKEN",
+            ]
+            .join("\n"),
+            sanitize_log(&log)
+        );
+    }
+
+    #[test]
+    fn write_report_archive() {
+        let path =
+            std::env::temp_dir().join(format!("asterctl-report-{}.tar.gz", std::process::id()));
+        let mut report = Report::default();
+        report.add("system.txt", system_info());
+        report.add("config/monitor.json", "{}");
+        report.write_archive(&path).unwrap();
+
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(&path).unwrap()));
+        let mut files = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            files.push((entry.path().unwrap().display().to_string(), content));
+        }
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(2, files.len());
+        assert_eq!("asterctl-report/system.txt", files[0].0);
+        assert!(files[0].1.starts_with("asterctl: "));
+        assert_eq!(
+            ("asterctl-report/config/monitor.json".into(), "{}".into()),
+            files[1]
+        );
+    }
+}
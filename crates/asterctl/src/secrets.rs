@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Secret references for API tokens and passwords in the configuration.
+//!
+//! Configuration files often end up in dotfile repositories. Instead of a plain-text token, a
+//! configuration value can reference a secret stored elsewhere:
+//!
+//! - `env:NAME`: environment variable.
+//! - `file:/path/to/file`: content of a file, without trailing whitespace.
+//! - `systemd:NAME`: systemd service credential `NAME`, see `LoadCredential=` in
+//!   [systemd.exec](https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#Credentials).
+//! - `keyring:attribute=value[,attribute=value...]`: secret in the desktop keyring (GNOME Keyring,
+//!   KWallet) looked up with the libsecret `secret-tool` command.
+//!
+//! All other values are used as is.
+
+use anyhow::{Context, anyhow, bail};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Resolve a configuration value which might be a secret reference.
+///
+/// Returns the value itself if it is not a secret reference.
+pub fn resolve_secret(value: &str) -> anyhow::Result<String> {
+    let Some((scheme, reference)) = value.split_once(':') else {
+        return Ok(value.to_string());
+    };
+
+    match scheme {
+        "env" => env::var(reference)
+            .with_context(|| format!("Environment variable {reference} is not set")),
+        "file" => read_secret_file(PathBuf::from(reference)),
+        "systemd" => {
+            let dir = env::var_os("CREDENTIALS_DIRECTORY").ok_or_else(|| {
+                anyhow!("systemd credential {reference} is not available: CREDENTIALS_DIRECTORY is not set")
+            })?;
+            read_secret_file(PathBuf::from(dir).join(reference))
+        }
+        "keyring" => lookup_keyring(reference),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// Check if a configuration value is a secret reference instead of a plain-text secret.
+pub fn is_secret_reference(value: &str) -> bool {
+    value
+        .split_once(':')
+        .is_some_and(|(scheme, _)| matches!(scheme, "env" | "file" | "systemd" | "keyring"))
+}
+
+/// Resolve an optional configuration value which might be a secret reference.
+pub fn resolve_optional_secret(value: &mut Option<String>) -> anyhow::Result<()> {
+    if let Some(secret) = value {
+        *secret = resolve_secret(secret)?;
+    }
+    Ok(())
+}
+
+fn read_secret_file(path: PathBuf) -> anyhow::Result<String> {
+    let secret = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read secret file {}", path.display()))?;
+    Ok(secret.trim_end().to_string())
+}
+
+fn lookup_keyring(reference: &str) -> anyhow::Result<String> {
+    let mut args = vec!["lookup"];
+    for attribute in reference.split(',') {
+        let Some((name, value)) = attribute.split_once('=') else {
+            bail!("Invalid keyring attribute '{attribute}', expected attribute=value");
+        };
+        args.extend([name.trim(), value.trim()]);
+    }
+
+    let output = Command::new("secret-tool")
+        .args(&args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .context("Failed to run secret-tool, is libsecret installed?")?;
+    if !output.status.success() {
+        bail!("Secret '{reference}' not found in keyring");
+    }
+    Ok(String::from_utf8(output.stdout)
+        .context("Invalid keyring secret")?
+        .trim_end()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_secret_references() {
+        assert_eq!("abc123", resolve_secret("abc123").unwrap());
+        assert_eq!(
+            "Bearer abc",
+            resolve_secret("Bearer abc").unwrap(),
+            "values with an unknown scheme are used as is"
+        );
+        assert_eq!(
+            "http://x",
+            resolve_secret("http://x").unwrap(),
+            "URLs are not secret references"
+        );
+
+        let path = env::temp_dir().join(format!("asterctl-secret-{}", std::process::id()));
+        fs::write(&path, "s3cr3t\n").unwrap();
+        assert_eq!(
+            "s3cr3t",
+            resolve_secret(&format!("file:{}", path.display())).unwrap()
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert!(resolve_secret("env:ASTERCTL_TEST_UNDEFINED_SECRET").is_err());
+        assert!(resolve_secret("keyring:service").is_err());
+    }
+}
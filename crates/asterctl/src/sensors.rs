@@ -7,12 +7,67 @@
 //! Implementations:
 //! - internal date time sensors
 //! - direct system sensor polling via aster-sysinfo
+//! - host reachability with ping
+//! - generic HTTP/JSON polling
+//! - external commands
+//! - Home Assistant entity states
+//! - Open-Meteo weather
+//! - systemd journal error and warning counts
+//! - values pushed by external programs over a Unix domain socket
+//! - derived sensors computed from other sensor values
+//! - session min/max/average statistics of sensor values
+//! - energy consumption and electricity cost
+//! - daily summary of the previous day
+//! - playback of recorded sensor values
+//!
+//! Polled sources implement the [SensorSource] trait and are run by the [SensorSources]
+//! registry. All sources store their values with the unit and source name in a shared
+//! [SensorStore]. The health of each source is tracked in a shared [SourceHealth] registry.
+
+mod alias;
+mod derived;
+mod energy;
+mod exec;
+mod filter;
+mod health;
+mod home_assistant;
+mod http;
+mod journal;
+mod ping;
+#[cfg(unix)]
+mod push;
+mod replay;
+mod schedule;
+mod source;
+mod statistics;
+mod store;
+mod summary;
+mod system;
+mod weather;
+
+pub use alias::SensorAliases;
+pub use derived::{DERIVED_SOURCE, start_derived_sensors};
+pub use energy::{ENERGY_SOURCE, ENERGY_STATE_FILE, start_energy_meter};
+pub use exec::ExecSensorSource;
+pub use filter::{SensorFilter, SensorFilters};
+pub use health::{SourceHealth, SourceStatus};
+pub use home_assistant::HomeAssistantSensorSource;
+pub use http::HttpSensorSource;
+pub use journal::JournalSensorSource;
+pub use ping::PingSensorSource;
+#[cfg(unix)]
+pub use push::start_push_socket;
+pub use replay::{REPLAY_SOURCE, ReplaySensorSource, SensorRecording};
+pub use source::{SensorSource, SensorSources};
+pub use statistics::{STATISTICS_SOURCE, SensorStatistics, start_sensor_statistics};
+pub use store::{SensorStore, SensorValue};
+pub use summary::{SUMMARY_SOURCE, SUMMARY_STATE_FILE, start_daily_summary};
+pub use system::SystemSensorSource;
+pub use weather::{WeatherSensorSource, weather_condition};
 
 use chrono::{DateTime, Datelike, Local, Timelike};
-use log::{debug, info, warn};
 use regex::Regex;
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
 
 pub fn get_date_time_value(label: &str, now: &DateTime<Local>) -> Option<String> {
     if !label.starts_with("DATE_") {
@@ -58,93 +113,31 @@ fn is_filtered(key: &str, filters: &[Regex]) -> bool {
     filters.iter().any(|re| re.is_match(key))
 }
 
-/// Start a direct sensor poller using SysinfoSource, eliminating the need for external scripts
-/// and text files. Sensor values are read directly from the system and stored in the shared HashMap.
-///
-/// # Arguments
-///
-/// * `values`: a shared, reader-writer lock protected HashMap
-/// * `refresh`: sensor refresh interval
-/// * `sensor_filter`: Optional list of regex filters to filter out matching sensor keys.
-///
-/// returns: Result<(), Error>
-pub fn start_sensor_poller(
-    values: Arc<RwLock<HashMap<String, String>>>,
-    refresh: std::time::Duration,
-    sensor_filter: Option<Vec<Regex>>,
-) -> anyhow::Result<()> {
-    use aster_sysinfo::{SysinfoSource, update_linux_storage_sensors};
-    use std::thread::sleep;
-    use std::time::Instant;
-
-    let mut sysinfo_source = SysinfoSource::new();
-
-    // Initial sensor read
-    {
-        sysinfo_source.refresh();
-        let mut raw_sensors = HashMap::with_capacity(64);
-        if let Err(e) = sysinfo_source.update_sensors(&mut raw_sensors) {
-            warn!("Initial sensor update failed: {e}");
-        }
-        if let Err(e) = update_linux_storage_sensors(&mut raw_sensors, false) {
-            warn!("Initial storage sensor update failed: {e}");
-        }
-
-        let mut val = values.write().expect("Failed to lock values");
-        apply_sensor_values(&mut val, &raw_sensors, sensor_filter.as_deref());
-    }
-
-    info!("Starting direct sensor poller with refresh={}ms", refresh.as_millis());
-
-    std::thread::spawn(move || {
-        let disk_refresh = std::time::Duration::from_secs(300);
-        let mut disk_refresh_time = Instant::now();
-
-        loop {
-            let upd_start_time = Instant::now();
-
-            sysinfo_source.refresh();
-            let mut raw_sensors = HashMap::with_capacity(64);
-            if let Err(e) = sysinfo_source.update_sensors(&mut raw_sensors) {
-                warn!("Sensor update failed: {e}");
-            }
-
-            if disk_refresh_time.elapsed() > disk_refresh {
-                debug!("Refreshing individual disks");
-                if let Err(e) = update_linux_storage_sensors(&mut raw_sensors, false) {
-                    warn!("Storage sensor update failed: {e}");
-                }
-                disk_refresh_time = Instant::now();
-            }
-
-            {
-                let mut val = values.write().expect("Poisoned sensor RwLock");
-                apply_sensor_values(&mut val, &raw_sensors, sensor_filter.as_deref());
-            }
-
-            let elapsed = upd_start_time.elapsed();
-            if refresh > elapsed {
-                sleep(refresh - elapsed);
-            }
-        }
-    });
-
-    Ok(())
-}
-
+/// Store the sensor values of a source. Sensor keys are renamed with their alias, filtered
+/// sensor keys are dropped.
 fn apply_sensor_values(
-    target: &mut HashMap<String, String>,
+    target: &mut SensorStore,
+    source_name: &str,
     source: &HashMap<String, String>,
-    sensor_filter: Option<&[Regex]>,
+    sensor_filter: &SensorFilter,
+    aliases: &SensorAliases,
 ) {
-    for (key, value) in source {
-        if let Some(filter) = sensor_filter
-            && is_filtered(key, filter)
-        {
-            continue;
-        }
-        target.insert(key.clone(), value.clone());
+    if aliases.is_empty() {
+        target.update(
+            source_name,
+            source
+                .iter()
+                .filter(|(key, _)| !sensor_filter.is_filtered(key)),
+        );
+        return;
     }
+
+    let renamed: HashMap<String, String> = source
+        .iter()
+        .map(|(key, value)| (aliases.resolve(key).into_owned(), value.clone()))
+        .filter(|(key, _)| !sensor_filter.is_filtered(key))
+        .collect();
+    target.update(source_name, &renamed);
 }
 
 #[cfg(test)]
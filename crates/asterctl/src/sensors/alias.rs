@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Sensor key aliases.
+//!
+//! Raw sensor keys of the collectors vary between kernels and hardware, e.g. the hwmon chip of
+//! the CPU temperature. The `sensorAliases` configuration maps raw keys to stable names, so the
+//! sensor templates of a panel work on different machines.
+
+use super::store::{DESCRIPTION_SUFFIX, UNIT_SUFFIX};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Shared map of raw sensor keys to stable sensor keys.
+#[derive(Debug, Clone, Default)]
+pub struct SensorAliases {
+    aliases: Arc<HashMap<String, String>>,
+}
+
+impl SensorAliases {
+    pub fn new(aliases: &HashMap<String, String>) -> Self {
+        Self {
+            aliases: Arc::new(aliases.clone()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Stable sensor key of a raw sensor key, or the raw key if it has no alias.
+    ///
+    /// The `#unit` and `#description` metadata keys of an aliased sensor are renamed with it.
+    pub fn resolve<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        if let Some(alias) = self.aliases.get(key) {
+            return Cow::Owned(alias.clone());
+        }
+        for suffix in [UNIT_SUFFIX, DESCRIPTION_SUFFIX] {
+            if let Some(alias) = key
+                .strip_suffix(suffix)
+                .and_then(|sensor| self.aliases.get(sensor))
+            {
+                return Cow::Owned(format!("{alias}{suffix}"));
+            }
+        }
+        Cow::Borrowed(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_alias_with_metadata() {
+        let aliases = SensorAliases::new(&HashMap::from([(
+            "temperature_k10temp_Tctl".to_string(),
+            "cpu_temp".to_string(),
+        )]));
+
+        assert_eq!("cpu_temp", aliases.resolve("temperature_k10temp_Tctl"));
+        assert_eq!(
+            "cpu_temp#unit",
+            aliases.resolve("temperature_k10temp_Tctl#unit")
+        );
+        assert_eq!(
+            "cpu_temp#description",
+            aliases.resolve("temperature_k10temp_Tctl#description")
+        );
+        assert_eq!("fan_cpu", aliases.resolve("fan_cpu"));
+    }
+}
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Derived sensors computed from the values of other sensor sources.
+
+use super::SensorStore;
+use crate::cfg::DerivedSensor;
+use crate::expr::Expr;
+use crate::{IntegerDigits, format_value};
+use anyhow::{Context, bail};
+use log::info;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Source name of the derived sensors.
+pub const DERIVED_SOURCE: &str = "derived";
+
+/// A derived sensor with its parsed expression.
+#[derive(Debug)]
+struct Derived {
+    key: String,
+    expr: Expr,
+    sensor: DerivedSensor,
+}
+
+/// Start computing the derived sensors at the given interval.
+///
+/// The derived sensors can use the values of other derived sensors: each one is evaluated after
+/// the derived sensors it uses. A derived sensor is removed while its expression can't be
+/// evaluated, e.g. if a referenced sensor is missing.
+///
+/// # Arguments
+///
+/// * `values`: a shared, reader-writer lock protected sensor store
+/// * `derived`: derived sensor definitions: sensor key -> definition
+/// * `refresh`: evaluation interval
+///
+/// returns: Result<(), Error>, an error if an expression is invalid or derived sensors depend on
+/// each other.
+pub fn start_derived_sensors(
+    values: Arc<RwLock<SensorStore>>,
+    derived: &HashMap<String, DerivedSensor>,
+    refresh: Duration,
+) -> anyhow::Result<()> {
+    if derived.is_empty() {
+        return Ok(());
+    }
+    let derived = compile_derived_sensors(derived)?;
+
+    info!(
+        "Starting {} derived sensors with refresh={}ms",
+        derived.len(),
+        refresh.as_millis()
+    );
+
+    std::thread::Builder::new()
+        .name("derived".into())
+        .spawn(move || {
+            loop {
+                let start = Instant::now();
+                {
+                    let mut values = values.write().expect("Poisoned sensor RwLock");
+                    update_derived_sensors(&derived, &mut values);
+                }
+                let elapsed = start.elapsed();
+                if refresh > elapsed {
+                    std::thread::sleep(refresh - elapsed);
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+fn compile_derived_sensors(
+    derived: &HashMap<String, DerivedSensor>,
+) -> anyhow::Result<Vec<Derived>> {
+    let mut compiled = derived
+        .iter()
+        .map(|(key, sensor)| {
+            let expr = sensor
+                .expr
+                .parse()
+                .with_context(|| format!("Invalid expression of derived sensor {key}"))?;
+            Ok(Derived {
+                key: key.clone(),
+                expr,
+                sensor: sensor.clone(),
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    compiled.sort_by(|a, b| a.key.cmp(&b.key));
+    sort_by_dependencies(compiled)
+}
+
+/// Sort the derived sensors topologically: each derived sensor after the derived sensors used in
+/// its expression. Independent sensors keep the order of their sensor keys.
+///
+/// returns: Result<Vec<Derived>, Error>, an error with the cycle if derived sensors depend on each
+/// other.
+fn sort_by_dependencies(derived: Vec<Derived>) -> anyhow::Result<Vec<Derived>> {
+    let index: HashMap<&str, usize> = derived
+        .iter()
+        .enumerate()
+        .map(|(idx, derived)| (derived.key.as_str(), idx))
+        .collect();
+    let dependencies: Vec<Vec<usize>> = derived
+        .iter()
+        .map(|derived| {
+            let keys = derived.expr.sensor_keys();
+            keys.iter()
+                .filter_map(|key| index.get(key).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut state = vec![Visit::New; derived.len()];
+    let mut order = Vec::with_capacity(derived.len());
+    let mut path = Vec::new();
+    for idx in 0..derived.len() {
+        if let Err(cycle) = visit(idx, &dependencies, &mut state, &mut path, &mut order) {
+            let keys: Vec<_> = cycle.iter().map(|idx| derived[*idx].key.as_str()).collect();
+            bail!(
+                "Derived sensors depend on each other: {}",
+                keys.join(" -> ")
+            );
+        }
+    }
+
+    let mut derived: Vec<_> = derived.into_iter().map(Some).collect();
+    Ok(order
+        .into_iter()
+        .filter_map(|idx| derived[idx].take())
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+}
+
+/// Depth-first visit of a derived sensor, adding it to `order` after its dependencies.
+///
+/// returns: Result<(), Vec<usize>>, the derived sensors of a cycle, starting and ending with the
+/// same sensor.
+fn visit(
+    idx: usize,
+    dependencies: &[Vec<usize>],
+    state: &mut [Visit],
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+) -> Result<(), Vec<usize>> {
+    match state[idx] {
+        Visit::Done => return Ok(()),
+        Visit::InProgress => {
+            let start = path.iter().position(|&i| i == idx).unwrap_or_default();
+            let mut cycle = path[start..].to_vec();
+            cycle.push(idx);
+            return Err(cycle);
+        }
+        Visit::New => {}
+    }
+    state[idx] = Visit::InProgress;
+    path.push(idx);
+    for &dependency in &dependencies[idx] {
+        visit(dependency, dependencies, state, path, order)?;
+    }
+    path.pop();
+    state[idx] = Visit::Done;
+    order.push(idx);
+    Ok(())
+}
+
+fn update_derived_sensors(derived: &[Derived], values: &mut SensorStore) {
+    for derived in derived {
+        let Some(value) = derived.expr.eval(values) else {
+            values.remove(&derived.key);
+            continue;
+        };
+        let value = match derived.sensor.decimal_digits {
+            Some(digits) => format_value(
+                &value.to_string(),
+                IntegerDigits::from(derived.sensor.integer_digits),
+                digits.max(0) as usize,
+                "",
+            ),
+            None => value.to_string(),
+        };
+        values.set(&derived.key, value, DERIVED_SOURCE);
+        if let Some(unit) = &derived.sensor.unit {
+            values.set_unit(&derived.key, unit);
+        }
+        if let Some(description) = &derived.sensor.description {
+            values.set_description(&derived.key, description);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::MonitorConfig;
+
+    #[test]
+    fn derived_sensors_are_computed_in_dependency_order() {
+        let cfg: MonitorConfig = serde_json::from_str(
+            r#"{
+              "setup": { "refresh": 1 },
+              "mianban": [],
+              "diy": [],
+              "derived": {
+                "memory_used_pct": { "expr": "memory_used / memory_total * 100", "unit": "%", "decimalDigits": 1 },
+                "memory_x_free": "memory_total - memory_used",
+                "memory_a_free_half": "memory_x_free / 2",
+                "memory_b_free_quarter": "memory_a_free_half / 2"
+              }
+            }"#,
+        )
+        .unwrap();
+        let derived = compile_derived_sensors(&cfg.derived).unwrap();
+        let mut values = SensorStore::from_iter([("memory_used", "3"), ("memory_total", "16")]);
+
+        update_derived_sensors(&derived, &mut values);
+        assert_eq!(Some("18.8"), values.value("memory_used_pct"));
+        assert_eq!(Some("%"), values.unit("memory_used_pct"));
+        assert_eq!("derived", values.get("memory_used_pct").unwrap().source);
+        assert_eq!(Some("13"), values.value("memory_x_free"));
+        assert_eq!(Some("6.5"), values.value("memory_a_free_half"));
+        assert_eq!(Some("3.25"), values.value("memory_b_free_quarter"));
+
+        values.remove("memory_total");
+        update_derived_sensors(&derived, &mut values);
+        assert_eq!(None, values.get("memory_used_pct"));
+        assert_eq!(None, values.get("memory_a_free_half"));
+        assert_eq!(None, values.get("memory_b_free_quarter"));
+    }
+
+    #[test]
+    fn reject_dependency_cycles() {
+        let derived = |expr: &str| DerivedSensor {
+            expr: expr.into(),
+            unit: None,
+            description: None,
+            integer_digits: None,
+            decimal_digits: None,
+        };
+        let cycle = HashMap::from([
+            ("a".to_string(), derived("b + 1")),
+            ("b".to_string(), derived("min(c, memory_used)")),
+            ("c".to_string(), derived("a * 2")),
+            ("d".to_string(), derived("memory_used")),
+        ]);
+        let error = compile_derived_sensors(&cycle).unwrap_err();
+        assert_eq!(
+            "Derived sensors depend on each other: a -> b -> c -> a",
+            error.to_string()
+        );
+
+        let own_value = HashMap::from([("a".to_string(), derived("a + 1"))]);
+        let error = compile_derived_sensors(&own_value).unwrap_err();
+        assert_eq!(
+            "Derived sensors depend on each other: a -> a",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_expression() {
+        let derived = HashMap::from([(
+            "broken".to_string(),
+            DerivedSensor {
+                expr: "memory_used /".into(),
+                unit: None,
+                description: None,
+                integer_digits: None,
+                decimal_digits: None,
+            },
+        )]);
+        assert!(compile_derived_sensors(&derived).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Energy consumption and electricity cost sensors.
+//!
+//! The consumed energy is accumulated from a power sensor in watts, e.g. of a smart plug, or from
+//! the RAPL energy counters of the CPU packages. The daily and monthly totals are saved in a state
+//! file, so they survive a restart of the sensor panel.
+//!
+//! Sensors:
+//! - `energy_power`: current power in W
+//! - `energy_today`, `energy_month`: consumed energy of the current day and month in kWh
+//! - `energy_cost_today`, `energy_cost_month`: electricity cost, if a rate is configured
+
+use super::SensorStore;
+use crate::cfg::EnergyConfig;
+use chrono::{Datelike, Local, NaiveDate};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Source name of the energy sensors.
+pub const ENERGY_SOURCE: &str = "energy";
+/// File name of the energy totals in the state directory.
+pub const ENERGY_STATE_FILE: &str = "energy.json";
+
+const POWERCAP_PATH: &str = "/sys/class/powercap";
+/// Interval to save the energy totals.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// Gaps of more than this number of refresh intervals, e.g. a system suspend, aren't accumulated.
+const MAX_GAP_INTERVALS: u32 = 5;
+const DEFAULT_CURRENCY: &str = "€";
+
+/// Accumulated energy of the current day and month.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnergyTotals {
+    day: NaiveDate,
+    today_wh: f64,
+    month_wh: f64,
+}
+
+impl EnergyTotals {
+    fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            today_wh: 0.0,
+            month_wh: 0.0,
+        }
+    }
+
+    /// Add energy consumed on the given day, starting new totals on a new day or month.
+    fn add(&mut self, day: NaiveDate, energy_wh: f64) {
+        if day != self.day {
+            if (day.year(), day.month()) != (self.day.year(), self.day.month()) {
+                self.month_wh = 0.0;
+            }
+            self.today_wh = 0.0;
+            self.day = day;
+        }
+        self.today_wh += energy_wh;
+        self.month_wh += energy_wh;
+    }
+}
+
+/// Energy counter of a RAPL power domain.
+#[derive(Debug)]
+struct RaplDomain {
+    energy_file: PathBuf,
+    /// Counter value at which the counter wraps around.
+    max_range_uj: u64,
+    last_uj: Option<u64>,
+}
+
+impl RaplDomain {
+    /// Energy in µJ since the last reading, `None` for the first reading.
+    fn read_delta_uj(&mut self) -> Option<u64> {
+        let energy_uj = read_u64(&self.energy_file)?;
+        let delta = self.last_uj.map(|last| {
+            if energy_uj >= last {
+                energy_uj - last
+            } else {
+                energy_uj + self.max_range_uj - last
+            }
+        });
+        self.last_uj = Some(energy_uj);
+        delta
+    }
+}
+
+/// Source of the consumed energy.
+#[derive(Debug)]
+enum PowerInput {
+    /// Power sensor in watts.
+    Sensor(String),
+    /// RAPL energy counters of the CPU packages.
+    Rapl(Vec<RaplDomain>),
+}
+
+struct EnergyMeter {
+    input: PowerInput,
+    rate: Option<f64>,
+    currency: String,
+    totals: EnergyTotals,
+}
+
+impl EnergyMeter {
+    /// Accumulate the energy consumed in the elapsed time and update the energy sensors.
+    ///
+    /// # Arguments
+    ///
+    /// * `values`: sensor store with the power sensor
+    /// * `elapsed`: time since the last update, `None` for the first update or after a gap
+    /// * `day`: current day
+    fn update(&mut self, values: &mut SensorStore, elapsed: Option<Duration>, day: NaiveDate) {
+        let (power, energy_wh) = match &mut self.input {
+            PowerInput::Sensor(key) => {
+                let power = values
+                    .value(key)
+                    .and_then(|value| value.trim().parse::<f64>().ok())
+                    .filter(|power| power.is_finite() && *power >= 0.0);
+                let energy_wh = match (power, elapsed) {
+                    (Some(power), Some(elapsed)) => power * elapsed.as_secs_f64() / 3600.0,
+                    _ => 0.0,
+                };
+                (power, energy_wh)
+            }
+            PowerInput::Rapl(domains) => {
+                // all counters are read to keep their last value current
+                let deltas: Vec<_> = domains.iter_mut().map(|d| d.read_delta_uj()).collect();
+                match elapsed {
+                    Some(elapsed) if deltas.iter().all(Option::is_some) => {
+                        let joules = deltas.into_iter().flatten().sum::<u64>() as f64 / 1e6;
+                        (Some(joules / elapsed.as_secs_f64()), joules / 3600.0)
+                    }
+                    _ => (None, 0.0),
+                }
+            }
+        };
+        self.totals.add(day, energy_wh);
+
+        match power {
+            Some(power) => set_sensor(values, "energy_power", format!("{power:.1}"), "W"),
+            None => {
+                values.remove("energy_power");
+            }
+        }
+        let today_kwh = self.totals.today_wh / 1000.0;
+        let month_kwh = self.totals.month_wh / 1000.0;
+        set_sensor(values, "energy_today", format!("{today_kwh:.3}"), "kWh");
+        set_sensor(values, "energy_month", format!("{month_kwh:.3}"), "kWh");
+        if let Some(rate) = self.rate {
+            let today = format!("{:.2}", today_kwh * rate);
+            let month = format!("{:.2}", month_kwh * rate);
+            set_sensor(values, "energy_cost_today", today, &self.currency);
+            set_sensor(values, "energy_cost_month", month, &self.currency);
+        }
+    }
+}
+
+fn set_sensor(values: &mut SensorStore, key: &str, value: String, unit: &str) {
+    values.set(key, value, ENERGY_SOURCE);
+    values.set_unit(key, unit);
+}
+
+/// Start accumulating the consumed energy at the given interval.
+///
+/// # Arguments
+///
+/// * `values`: a shared, reader-writer lock protected sensor store
+/// * `cfg`: energy configuration, no energy sensors are provided if `None`
+/// * `state_file`: file to save the energy totals, the totals start at zero if `None`
+/// * `refresh`: update interval
+///
+/// returns: Result<(), Error>, an error if no RAPL energy counters are available without a power
+/// sensor.
+pub fn start_energy_meter(
+    values: Arc<RwLock<SensorStore>>,
+    cfg: Option<&EnergyConfig>,
+    state_file: Option<PathBuf>,
+    refresh: Duration,
+) -> anyhow::Result<()> {
+    let Some(cfg) = cfg else {
+        return Ok(());
+    };
+    let input = match &cfg.sensor {
+        Some(sensor) => PowerInput::Sensor(sensor.clone()),
+        None => {
+            let domains = rapl_domains(Path::new(POWERCAP_PATH));
+            if domains.is_empty() {
+                anyhow::bail!(
+                    "No readable RAPL energy counters in {POWERCAP_PATH}, configure a power sensor"
+                );
+            }
+            PowerInput::Rapl(domains)
+        }
+    };
+    let today = Local::now().date_naive();
+    let totals = state_file
+        .as_deref()
+        .and_then(load_totals)
+        .unwrap_or_else(|| EnergyTotals::new(today));
+    let mut meter = EnergyMeter {
+        input,
+        rate: cfg.rate,
+        currency: cfg
+            .currency
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string()),
+        totals,
+    };
+
+    info!(
+        "Starting energy meter with {} and refresh={}ms",
+        match &meter.input {
+            PowerInput::Sensor(sensor) => format!("power sensor {sensor}"),
+            PowerInput::Rapl(domains) => format!("{} RAPL domains", domains.len()),
+        },
+        refresh.as_millis()
+    );
+
+    std::thread::Builder::new()
+        .name("energy".into())
+        .spawn(move || {
+            let max_gap = refresh * MAX_GAP_INTERVALS;
+            let mut last_update: Option<Instant> = None;
+            let mut last_save = Instant::now();
+            loop {
+                let start = Instant::now();
+                let elapsed = last_update
+                    .map(|last| start - last)
+                    .filter(|elapsed| *elapsed <= max_gap);
+                last_update = Some(start);
+                {
+                    let mut values = values.write().expect("Poisoned sensor RwLock");
+                    meter.update(&mut values, elapsed, Local::now().date_naive());
+                }
+                if let Some(state_file) = &state_file
+                    && last_save.elapsed() >= SAVE_INTERVAL
+                {
+                    save_totals(state_file, &meter.totals);
+                    last_save = Instant::now();
+                }
+                let elapsed = start.elapsed();
+                if refresh > elapsed {
+                    std::thread::sleep(refresh - elapsed);
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+/// Readable energy counters of the CPU packages, e.g. `intel-rapl:0`. Subdomains like the cores
+/// are part of the package energy and ignored. AMD CPUs use the same RAPL interface.
+fn rapl_domains(powercap: &Path) -> Vec<RaplDomain> {
+    let Ok(entries) = fs::read_dir(powercap) else {
+        return Vec::new();
+    };
+    let mut domains: Vec<_> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("intel-rapl:") && name.matches(':').count() == 1
+        })
+        .filter_map(|entry| {
+            let path = entry.path();
+            let energy_file = path.join("energy_uj");
+            if read_u64(&energy_file).is_none() {
+                // only readable by root on most kernels
+                warn!("RAPL energy counter {energy_file:?} isn't readable");
+                return None;
+            }
+            Some(RaplDomain {
+                energy_file,
+                max_range_uj: read_u64(&path.join("max_energy_range_uj")).unwrap_or(u64::MAX),
+                last_uj: None,
+            })
+        })
+        .collect();
+    domains.sort_by(|a, b| a.energy_file.cmp(&b.energy_file));
+    domains
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn load_totals(path: &Path) -> Option<EnergyTotals> {
+    let totals = fs::read_to_string(path)
+        .map_err(|e| debug!("No energy totals in {path:?}: {e}"))
+        .ok()?;
+    serde_json::from_str(&totals)
+        .map_err(|e| warn!("Invalid energy totals in {path:?}: {e}"))
+        .ok()
+}
+
+/// Save the energy totals. Errors are only logged, the totals are kept in memory.
+fn save_totals(path: &Path, totals: &EnergyTotals) {
+    let tmp = path.with_extension("tmp");
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&tmp, serde_json::to_vec(totals).unwrap_or_default()))
+        .and_then(|_| fs::rename(&tmp, path));
+    if let Err(e) = result {
+        warn!("Failed to save energy totals to {path:?}: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulate_power_sensor() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 31).unwrap();
+        let mut meter = EnergyMeter {
+            input: PowerInput::Sensor("plug_power".to_string()),
+            rate: Some(0.3),
+            currency: DEFAULT_CURRENCY.to_string(),
+            totals: EnergyTotals::new(day),
+        };
+        let mut values = SensorStore::from_iter([("plug_power", "500")]);
+        let second = Some(Duration::from_secs(1));
+
+        meter.update(&mut values, None, day);
+        assert_eq!(Some("0.000"), values.value("energy_today"));
+        // 500 W for one hour
+        meter.update(&mut values, Some(Duration::from_secs(3600)), day);
+        assert_eq!(Some("500.0"), values.value("energy_power"));
+        assert_eq!(Some("W"), values.unit("energy_power"));
+        assert_eq!(Some("0.500"), values.value("energy_today"));
+        assert_eq!(Some("kWh"), values.unit("energy_today"));
+        assert_eq!(Some("0.15"), values.value("energy_cost_today"));
+        assert_eq!(Some("€"), values.unit("energy_cost_month"));
+
+        // a new day and month start new totals
+        let next_day = day.succ_opt().unwrap();
+        meter.update(&mut values, Some(Duration::from_secs(7200)), next_day);
+        assert_eq!(Some("1.000"), values.value("energy_today"));
+        assert_eq!(Some("1.000"), values.value("energy_month"));
+        meter.update(&mut values, Some(Duration::from_secs(3600)), next_day);
+        assert_eq!(Some("1.500"), values.value("energy_today"));
+        assert_eq!(Some("0.45"), values.value("energy_cost_today"));
+
+        // a missing power sensor doesn't add energy
+        values.remove("plug_power");
+        meter.update(&mut values, second, next_day);
+        assert!(!values.contains_key("energy_power"));
+        assert_eq!(Some("1.500"), values.value("energy_month"));
+    }
+
+    #[test]
+    fn rapl_counter_wraps_around() {
+        let dir = std::env::temp_dir().join(format!("asterctl-rapl-{}", std::process::id()));
+        let package = dir.join("intel-rapl:0");
+        fs::create_dir_all(&package).unwrap();
+        fs::create_dir_all(dir.join("intel-rapl:0:0")).unwrap();
+        fs::write(package.join("max_energy_range_uj"), "1000000\n").unwrap();
+        fs::write(package.join("energy_uj"), "900000\n").unwrap();
+
+        let mut domains = rapl_domains(&dir);
+        assert_eq!(1, domains.len());
+        assert_eq!(None, domains[0].read_delta_uj());
+        fs::write(package.join("energy_uj"), "950000\n").unwrap();
+        assert_eq!(Some(50000), domains[0].read_delta_uj());
+        fs::write(package.join("energy_uj"), "100000\n").unwrap();
+        assert_eq!(Some(150000), domains[0].read_delta_uj());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
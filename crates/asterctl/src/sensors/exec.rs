@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! External command sensor source.
+//!
+//! Runs a user-specified command at a fixed interval and parses its standard output into sensor
+//! values. This allows sensor providers written in any language, using the same `key: value` line
+//! format as the `aster-sysinfo` tool, or a JSON object.
+
+use super::SensorSource;
+use super::http::value_to_string;
+use crate::cfg::{ExecOutputFormat, ExecSource};
+use anyhow::{Context, anyhow, bail};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Command sensor source, named after the `name` of the source or the program name.
+///
+/// If a command fails `max_failures` times in a row, the sensor values reported by the command
+/// are removed until it succeeds again, instead of showing stale values.
+pub struct ExecSensorSource {
+    name: String,
+    source: ExecSource,
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl ExecSensorSource {
+    /// Create a new command sensor source.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: command source definition
+    /// * `refresh`: default run interval
+    ///
+    /// returns: Result<ExecSensorSource, Error>, an error if the command is missing.
+    pub fn new(source: ExecSource, refresh: Duration) -> anyhow::Result<Self> {
+        let Some(program) = source.command.first() else {
+            bail!("Missing command of exec sensor source");
+        };
+        let name = source.name.clone().unwrap_or_else(|| program.clone());
+        let interval = source
+            .interval
+            .map(|i| Duration::from_secs_f32(i.max(0.1)))
+            .unwrap_or(refresh);
+        let timeout = Duration::from_secs_f32(source.timeout.unwrap_or(10.0).max(0.1));
+
+        Ok(Self {
+            name,
+            source,
+            interval,
+            timeout,
+        })
+    }
+}
+
+impl SensorSource for ExecSensorSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        let output = run_command(&self.source.command, self.timeout)?;
+        values.extend(parse_output(&output, self.source.format)?);
+        Ok(())
+    }
+
+    fn max_failures(&self) -> Option<u32> {
+        Some(self.source.max_failures.unwrap_or(3).max(1))
+    }
+}
+
+/// Run a command and return its standard output. The command is killed after the timeout.
+pub(super) fn run_command(command: &[String], timeout: Duration) -> anyhow::Result<String> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow!("Missing command"))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+
+    // read in a separate thread, the command could block on a full pipe buffer otherwise
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("Timeout after {}ms", timeout.as_millis());
+        }
+        sleep(Duration::from_millis(10));
+    };
+
+    let output = reader
+        .join()
+        .map_err(|_| anyhow!("Failed to read output"))??;
+    if !status.success() {
+        bail!("Command exited with {status}");
+    }
+    Ok(output)
+}
+
+/// Parse the command output into sensor values.
+fn parse_output(output: &str, format: ExecOutputFormat) -> anyhow::Result<HashMap<String, String>> {
+    let json = match format {
+        ExecOutputFormat::Auto => output.trim_start().starts_with('{'),
+        ExecOutputFormat::Lines => false,
+        ExecOutputFormat::Json => true,
+    };
+
+    if json {
+        let document: Value = serde_json::from_str(output).context("Invalid JSON output")?;
+        if !document.is_object() {
+            bail!("JSON output is not an object");
+        }
+        let mut sensors = HashMap::new();
+        flatten_json("", &document, &mut sensors);
+        Ok(sensors)
+    } else {
+        Ok(parse_lines(output))
+    }
+}
+
+/// Parse `key: value` lines. Empty lines and comments starting with `#` are ignored.
+fn parse_lines(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let key = key.trim();
+            (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Flatten nested JSON objects and arrays into sensor keys joined with `_`.
+pub(super) fn flatten_json(prefix: &str, value: &Value, sensors: &mut HashMap<String, String>) {
+    let key = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}_{name}")
+        }
+    };
+    match value {
+        Value::Object(obj) => {
+            for (name, value) in obj {
+                flatten_json(&key(name), value, sensors);
+            }
+        }
+        Value::Array(values) => {
+            for (idx, value) in values.iter().enumerate() {
+                flatten_json(&key(&idx.to_string()), value, sensors);
+            }
+        }
+        value => {
+            if let Some(value) = value_to_string(value) {
+                sensors.insert(prefix.to_string(), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensors(values: &[(&str, &str)]) -> HashMap<String, String> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_line_and_json_output() {
+        let lines = "# UPS\nups_load: 23\nups_load#unit: %\n\nups_status: on line\ninvalid\n";
+        assert_eq!(
+            sensors(&[
+                ("ups_load", "23"),
+                ("ups_load#unit", "%"),
+                ("ups_status", "on line")
+            ]),
+            parse_output(lines, ExecOutputFormat::Auto).unwrap()
+        );
+
+        let json = r#"{ "ups": { "load": 23, "online": true, "cells": [3.9, 4.0] }, "ups_load#unit": "%", "none": null }"#;
+        assert_eq!(
+            sensors(&[
+                ("ups_load", "23"),
+                ("ups_online", "1"),
+                ("ups_cells_0", "3.9"),
+                ("ups_cells_1", "4.0"),
+                ("ups_load#unit", "%")
+            ]),
+            parse_output(json, ExecOutputFormat::Auto).unwrap()
+        );
+
+        assert!(parse_output("[1, 2]", ExecOutputFormat::Json).is_err());
+        assert!(parse_output("load: 1", ExecOutputFormat::Json).is_err());
+    }
+
+    #[test]
+    fn run_command_with_timeout() {
+        let command = |script: &str| vec!["sh".to_string(), "-c".to_string(), script.to_string()];
+        let timeout = Duration::from_secs(5);
+
+        assert_eq!(
+            "foo: 1\n",
+            run_command(&command("echo 'foo: 1'"), timeout).unwrap()
+        );
+        assert!(run_command(&command("exit 3"), timeout).is_err());
+
+        let start = Instant::now();
+        assert!(run_command(&command("sleep 5"), Duration::from_millis(100)).is_err());
+        assert!(start.elapsed() < Duration::from_secs(4));
+    }
+}
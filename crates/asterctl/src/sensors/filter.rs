@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Sensor key filters of the sensor sources.
+//!
+//! The filters are configured with the `sensorFilter` configuration, see [SensorFilterConfig].
+
+use super::is_filtered;
+use crate::cfg::{SensorFilterConfig, SensorFilterRules};
+use log::warn;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Compiled sensor key filter of a sensor source.
+#[derive(Debug, Clone, Default)]
+pub struct SensorFilter {
+    /// Drop sensor keys matching any pattern.
+    exclude: Vec<Regex>,
+    /// Allowlists, a sensor key must match a pattern of every list.
+    include: Vec<Vec<Regex>>,
+}
+
+impl SensorFilter {
+    fn new(rules: &SensorFilterRules) -> Self {
+        let include = compile(&rules.include);
+        Self {
+            exclude: compile(&rules.exclude),
+            include: if include.is_empty() {
+                Vec::new()
+            } else {
+                vec![include]
+            },
+        }
+    }
+
+    /// Check if the sensor key is dropped by the filter.
+    pub fn is_filtered(&self, key: &str) -> bool {
+        is_filtered(key, &self.exclude)
+            || self
+                .include
+                .iter()
+                .any(|include| !is_filtered(key, include))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.exclude.is_empty() && self.include.is_empty()
+    }
+
+    /// Combine the rules of both filters.
+    fn and(&self, other: &SensorFilter) -> SensorFilter {
+        SensorFilter {
+            exclude: [self.exclude.as_slice(), &other.exclude].concat(),
+            include: [self.include.as_slice(), &other.include].concat(),
+        }
+    }
+}
+
+/// Global and per-source sensor key filters.
+#[derive(Debug, Clone, Default)]
+pub struct SensorFilters {
+    global: SensorFilter,
+    /// Additional filters by source name.
+    sources: HashMap<String, SensorFilter>,
+}
+
+impl SensorFilters {
+    /// Compile the sensor filter configuration. Invalid patterns are logged and ignored.
+    pub fn new(cfg: &SensorFilterConfig) -> Self {
+        Self {
+            global: SensorFilter::new(&cfg.rules),
+            sources: cfg
+                .sources
+                .iter()
+                .map(|(name, rules)| (name.clone(), SensorFilter::new(rules)))
+                .collect(),
+        }
+    }
+
+    /// No sensor keys are filtered.
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.sources.values().all(SensorFilter::is_empty)
+    }
+
+    /// Filter of the sensor source with the given name: the global filter combined with the
+    /// filter of the source.
+    pub fn source(&self, name: &str) -> SensorFilter {
+        match self.sources.get(name) {
+            Some(filter) => self.global.and(filter),
+            None => self.global.clone(),
+        }
+    }
+}
+
+fn compile(patterns: &[String]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter_map(|p| match Regex::new(p) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                warn!("Invalid sensor filter regex '{p}': {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlist_per_source() {
+        let cfg: SensorFilterConfig = serde_json::from_str(
+            r##"{ "exclude": ["#unit$"],
+                  "sources": { "sysinfo": { "include": ["^temperature_", "^disk_"] } } }"##,
+        )
+        .unwrap();
+        let filters = SensorFilters::new(&cfg);
+
+        let sysinfo = filters.source("sysinfo");
+        assert!(!sysinfo.is_filtered("temperature_cpu"));
+        assert!(!sysinfo.is_filtered("disk_/dev/sda_free"));
+        assert!(sysinfo.is_filtered("temperature_cpu#unit"));
+        assert!(sysinfo.is_filtered("cpu_usage_percent"));
+
+        let weather = filters.source("weather");
+        assert!(!weather.is_filtered("weather_temperature"));
+        assert!(weather.is_filtered("weather_temperature#unit"));
+    }
+}
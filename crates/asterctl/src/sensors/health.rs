@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Sensor source health reporting.
+//!
+//! Every sensor source reports the result of each update. A failing source is visible with its
+//! synthetic status sensors instead of silently showing stale values.
+
+use super::SensorStore;
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+
+/// Health status of a sensor source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceStatus {
+    /// Time of the last successful update.
+    pub last_success: Option<DateTime<Local>>,
+    /// Number of consecutive failed updates.
+    pub consecutive_failures: u32,
+    /// Error message of the last failed update.
+    pub last_error: Option<String>,
+}
+
+impl SourceStatus {
+    /// Status name: `pending` before the first update, `ok` or `error` if the last update failed.
+    pub fn state(&self) -> &'static str {
+        if self.consecutive_failures > 0 {
+            "error"
+        } else if self.last_success.is_some() {
+            "ok"
+        } else {
+            "pending"
+        }
+    }
+}
+
+/// Shared health registry of all sensor sources.
+///
+/// Synthetic sensor keys of each source, with non-alphanumeric characters in the source name
+/// replaced with `_`:
+/// - `__source_{name}_status`: `pending`, `ok` or `error`
+/// - `__source_{name}_failures`: number of consecutive failures
+/// - `__source_{name}_error`: last error message, removed after a successful update
+#[derive(Debug, Clone, Default)]
+pub struct SourceHealth {
+    sources: Arc<RwLock<BTreeMap<String, SourceStatus>>>,
+}
+
+impl SourceHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a source with the `pending` state.
+    pub fn register(&self, name: &str, values: &RwLock<SensorStore>) {
+        let status = SourceStatus::default();
+        let mut values = values.write().expect("Poisoned sensor RwLock");
+        set_status_sensors(name, &status, &mut values);
+        self.sources
+            .write()
+            .expect("Poisoned source health RwLock")
+            .insert(name.to_string(), status);
+    }
+
+    /// Report a successful update of a source.
+    pub fn success(&self, name: &str, values: &mut SensorStore) {
+        self.update(name, values, |status| {
+            status.last_success = Some(Local::now());
+            status.consecutive_failures = 0;
+            status.last_error = None;
+        });
+    }
+
+    /// Report a failed update of a source.
+    pub fn failure(&self, name: &str, error: impl Display, values: &mut SensorStore) {
+        self.update(name, values, |status| {
+            status.consecutive_failures += 1;
+            status.last_error = Some(format!("{error:#}"));
+        });
+    }
+
+    /// Status of a source, `None` if the source is unknown.
+    pub fn status(&self, name: &str) -> Option<SourceStatus> {
+        self.sources
+            .read()
+            .expect("Poisoned source health RwLock")
+            .get(name)
+            .cloned()
+    }
+
+    /// Status of all sources, sorted by source name.
+    pub fn snapshot(&self) -> Vec<(String, SourceStatus)> {
+        self.sources
+            .read()
+            .expect("Poisoned source health RwLock")
+            .iter()
+            .map(|(name, status)| (name.clone(), status.clone()))
+            .collect()
+    }
+
+    fn update(&self, name: &str, values: &mut SensorStore, update: impl FnOnce(&mut SourceStatus)) {
+        let mut sources = self.sources.write().expect("Poisoned source health RwLock");
+        let status = sources.entry(name.to_string()).or_default();
+        update(status);
+        set_status_sensors(name, status, values);
+    }
+}
+
+fn set_status_sensors(name: &str, status: &SourceStatus, values: &mut SensorStore) {
+    let prefix = format!("__source_{}", sanitize_name(name));
+    values.set(&format!("{prefix}_status"), status.state(), name);
+    values.set(
+        &format!("{prefix}_failures"),
+        status.consecutive_failures.to_string(),
+        name,
+    );
+    match &status.last_error {
+        Some(error) => values.set(&format!("{prefix}_error"), error, name),
+        None => {
+            values.remove(&format!("{prefix}_error"));
+        }
+    }
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_status_sensors() {
+        let health = SourceHealth::new();
+        let values = RwLock::new(SensorStore::new());
+        health.register("exec-ups", &values);
+        let mut values = values.into_inner().unwrap();
+        assert_eq!("pending", values["__source_exec_ups_status"]);
+
+        health.failure("exec-ups", "timeout", &mut values);
+        health.failure("exec-ups", "exit status 1", &mut values);
+        assert_eq!("error", values["__source_exec_ups_status"]);
+        assert_eq!("2", values["__source_exec_ups_failures"]);
+        assert_eq!("exit status 1", values["__source_exec_ups_error"]);
+
+        health.success("exec-ups", &mut values);
+        assert_eq!("ok", values["__source_exec_ups_status"]);
+        assert_eq!("0", values["__source_exec_ups_failures"]);
+        assert!(!values.contains_key("__source_exec_ups_error"));
+
+        let status = health.status("exec-ups").unwrap();
+        assert!(status.last_success.is_some());
+        assert_eq!(1, health.snapshot().len());
+    }
+}
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Home Assistant entity state sensor source.
+//!
+//! Polls the entity states with the Home Assistant REST API and maps the states and selected
+//! attributes of the configured entities to sensor keys.
+
+use super::SensorSource;
+use super::http::{http_agent, value_to_string};
+use super::schedule::PollSchedule;
+use crate::cfg::{HomeAssistantConfig, HomeAssistantEntity};
+use anyhow::anyhow;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Home Assistant sensor source with the name `home_assistant`.
+///
+/// Sensor keys of each entity:
+/// - `{key}`: entity state. The `unit_of_measurement` attribute is stored as `{key}#unit`.
+/// - `{key}_{attribute}`: configured entity attributes.
+pub struct HomeAssistantSensorSource {
+    url: String,
+    auth: String,
+    entities: Vec<HomeAssistantEntity>,
+    schedule: PollSchedule,
+    agent: ureq::Agent,
+}
+
+impl HomeAssistantSensorSource {
+    /// Create a new Home Assistant sensor source.
+    ///
+    /// returns: Result<HomeAssistantSensorSource, Error>, an error if the URL or the access token
+    /// is not configured.
+    pub fn new(cfg: HomeAssistantConfig) -> anyhow::Result<Self> {
+        let url = cfg
+            .url
+            .as_deref()
+            .ok_or_else(|| anyhow!("Home Assistant URL is not configured"))?;
+        let url = format!("{}/api/states", url.trim_end_matches('/'));
+        let auth = format!(
+            "Bearer {}",
+            cfg.token
+                .as_deref()
+                .ok_or_else(|| anyhow!("Home Assistant access token is not configured"))?
+        );
+        let schedule = PollSchedule::new(
+            "home_assistant",
+            cfg.interval,
+            Duration::from_secs(10),
+            Duration::from_secs(2),
+            cfg.jitter,
+        );
+        let agent = http_agent(Duration::from_secs_f32(
+            cfg.timeout.unwrap_or(10.0).max(1.0),
+        ));
+
+        Ok(Self {
+            url,
+            auth,
+            entities: cfg.entities,
+            schedule,
+            agent,
+        })
+    }
+}
+
+impl SensorSource for HomeAssistantSensorSource {
+    fn name(&self) -> &str {
+        "home_assistant"
+    }
+
+    fn interval(&self) -> Duration {
+        self.schedule.interval()
+    }
+
+    fn jitter(&self) -> Duration {
+        self.schedule.jitter()
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        let states = self
+            .agent
+            .get(&self.url)
+            .header("Authorization", &self.auth)
+            .call()?
+            .body_mut()
+            .read_json::<Vec<Value>>()?;
+        values.extend(map_entity_states(&states, &self.entities));
+        Ok(())
+    }
+}
+
+/// Map the states of the configured entities to sensor values.
+///
+/// Unavailable entity states are ignored.
+fn map_entity_states(
+    states: &[Value],
+    entities: &[HomeAssistantEntity],
+) -> HashMap<String, String> {
+    let states: HashMap<&str, &Value> = states
+        .iter()
+        .filter_map(|state| Some((state.get("entity_id")?.as_str()?, state)))
+        .collect();
+
+    let mut sensors = HashMap::new();
+    for entity in entities {
+        let Some(state) = states.get(entity.entity.as_str()) else {
+            continue;
+        };
+        let key = entity
+            .key
+            .clone()
+            .unwrap_or_else(|| format!("ha_{}", entity.entity.replace('.', "_")));
+
+        if let Some(value) = state.get("state").and_then(value_to_string)
+            && value != "unavailable"
+            && value != "unknown"
+        {
+            sensors.insert(key.clone(), value);
+        }
+        let attributes = state.get("attributes");
+        if let Some(unit) = attributes
+            .and_then(|a| a.get("unit_of_measurement"))
+            .and_then(Value::as_str)
+        {
+            sensors.insert(format!("{key}#unit"), unit.to_string());
+        }
+        for attribute in &entity.attributes {
+            if let Some(value) = attributes
+                .and_then(|a| a.get(attribute))
+                .and_then(value_to_string)
+            {
+                sensors.insert(format!("{key}_{attribute}"), value);
+            }
+        }
+    }
+    sensors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn map_states_attributes_and_units() {
+        let states = json!([
+            {
+                "entity_id": "sensor.outdoor_temperature",
+                "state": "7.5",
+                "attributes": { "unit_of_measurement": "°C", "friendly_name": "Outdoor" }
+            },
+            {
+                "entity_id": "weather.home",
+                "state": "rainy",
+                "attributes": { "temperature": 8, "humidity": 93 }
+            },
+            { "entity_id": "sensor.offline", "state": "unavailable", "attributes": {} },
+            { "entity_id": "light.kitchen", "state": "on", "attributes": {} }
+        ]);
+        let entities: Vec<HomeAssistantEntity> = serde_json::from_value(json!([
+            { "entity": "sensor.outdoor_temperature", "key": "outdoor_temp" },
+            { "entity": "weather.home", "attributes": ["humidity", "missing"] },
+            { "entity": "sensor.offline" },
+            { "entity": "sensor.not_existing" }
+        ]))
+        .unwrap();
+
+        let sensors = map_entity_states(states.as_array().unwrap(), &entities);
+
+        assert_eq!(Some(&"7.5".to_string()), sensors.get("outdoor_temp"));
+        assert_eq!(Some(&"°C".to_string()), sensors.get("outdoor_temp#unit"));
+        assert_eq!(Some(&"rainy".to_string()), sensors.get("ha_weather_home"));
+        assert_eq!(
+            Some(&"93".to_string()),
+            sensors.get("ha_weather_home_humidity")
+        );
+        assert_eq!(4, sensors.len());
+    }
+}
@@ -0,0 +1,289 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Generic HTTP/JSON polling sensor source.
+//!
+//! Polls arbitrary URLs returning a JSON document and extracts sensor values with simple
+//! JSONPath / jq-like path expressions, e.g. `$.main.temp`, `.list[0].value` or `.items[-1]`.
+
+use super::SensorSource;
+use super::schedule::PollSchedule;
+use crate::cfg::HttpSource;
+use anyhow::{anyhow, bail};
+use log::debug;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// HTTP/JSON sensor source, named after the `name` of the source or the host of the URL.
+///
+/// The poll interval is limited to a minimum of 5 seconds.
+pub struct HttpSensorSource {
+    name: String,
+    source: HttpSource,
+    schedule: PollSchedule,
+    agent: ureq::Agent,
+}
+
+impl HttpSensorSource {
+    /// Create a new HTTP sensor source.
+    ///
+    /// returns: Result<HttpSensorSource, Error>, an error if a path expression is invalid.
+    pub fn new(source: HttpSource) -> anyhow::Result<Self> {
+        // validate paths at startup instead of failing at every poll
+        for (key, path) in &source.values {
+            parse_path(path).map_err(|e| anyhow!("Invalid path for sensor '{key}': {e}"))?;
+        }
+
+        let name = source
+            .name
+            .clone()
+            .unwrap_or_else(|| source_name(&source.url));
+        let schedule = PollSchedule::new(
+            &name,
+            source.interval,
+            Duration::from_secs(60),
+            Duration::from_secs(5),
+            source.jitter,
+        );
+        let agent = http_agent(Duration::from_secs_f32(
+            source.timeout.unwrap_or(10.0).max(1.0),
+        ));
+
+        Ok(Self {
+            name,
+            source,
+            schedule,
+            agent,
+        })
+    }
+}
+
+impl SensorSource for HttpSensorSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.schedule.interval()
+    }
+
+    fn jitter(&self) -> Duration {
+        self.schedule.jitter()
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        values.extend(poll_source(&self.agent, &self.source)?);
+        Ok(())
+    }
+}
+
+/// Default source name from the host of the URL, e.g. `http_api.example.com`.
+fn source_name(url: &str) -> String {
+    let host = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    // strip user info and port
+    let host = host.rsplit('@').next().unwrap_or(host);
+    let host = host.split(':').next().unwrap_or(host);
+    format!("http_{host}")
+}
+
+/// Create an HTTP client with the given request timeout.
+pub(super) fn http_agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .user_agent(concat!("asterctl/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .into()
+}
+
+fn poll_source(
+    agent: &ureq::Agent,
+    source: &HttpSource,
+) -> anyhow::Result<HashMap<String, String>> {
+    let mut request = agent.get(&source.url);
+    for (name, value) in &source.headers {
+        request = request.header(name, value);
+    }
+    let document: Value = request.call()?.body_mut().read_json()?;
+
+    Ok(extract_values(&document, source))
+}
+
+fn extract_values(document: &Value, source: &HttpSource) -> HashMap<String, String> {
+    let mut sensors = HashMap::with_capacity(source.values.len() * 2);
+    for (key, path) in &source.values {
+        let Some(value) = select(document, path).and_then(value_to_string) else {
+            debug!("No value for sensor {key} at path {path}");
+            continue;
+        };
+        sensors.insert(key.clone(), value);
+        if let Some(unit) = source.units.get(key) {
+            sensors.insert(format!("{key}#unit"), unit.clone());
+        }
+    }
+    sensors
+}
+
+/// Convert a JSON value to a sensor value. `null` values are ignored.
+pub(super) fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(u8::from(*b).to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+}
+
+/// Parse a path expression like `$.list[0]["feels like"].value` into segments.
+///
+/// The root `$` is optional.
+fn parse_path(path: &str) -> anyhow::Result<Vec<Segment>> {
+    let mut rest = path.trim();
+    rest = rest.strip_prefix('$').unwrap_or(rest);
+    let mut segments = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            if end > 0 {
+                segments.push(Segment::Key(r[..end].to_string()));
+            } else if !r.is_empty() && !r.starts_with('[') {
+                bail!("empty key in '{path}'");
+            }
+            rest = &r[end..];
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let end = r
+                .find(']')
+                .ok_or_else(|| anyhow!("missing ']' in '{path}'"))?;
+            let inner = r[..end].trim();
+            if let Some(key) = inner
+                .strip_prefix('"')
+                .and_then(|k| k.strip_suffix('"'))
+                .or_else(|| inner.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')))
+            {
+                segments.push(Segment::Key(key.to_string()));
+            } else {
+                let index = inner
+                    .parse()
+                    .map_err(|_| anyhow!("invalid index '{inner}' in '{path}'"))?;
+                segments.push(Segment::Index(index));
+            }
+            rest = &r[end + 1..];
+        } else {
+            // a plain key without leading dot, e.g. `main.temp`
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            segments.push(Segment::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Select a value in a JSON document with a path expression.
+///
+/// Negative array indexes count from the end: `[-1]` is the last element.
+pub(super) fn select<'a>(document: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = document;
+    for segment in parse_path(path).ok()? {
+        current = match segment {
+            Segment::Key(key) => current.get(key)?,
+            Segment::Index(index) => {
+                let array = current.as_array()?;
+                let index = if index < 0 {
+                    array.len().checked_sub(index.unsigned_abs() as usize)?
+                } else {
+                    index as usize
+                };
+                array.get(index)?
+            }
+        };
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn default_source_name() {
+        assert_eq!(
+            "http_api.example.com",
+            source_name("https://api.example.com/v1?q=1")
+        );
+        assert_eq!(
+            "http_10.0.0.2",
+            source_name("http://user:pw@10.0.0.2:8080/status")
+        );
+    }
+
+    #[test]
+    fn parse_path_expressions() {
+        assert_eq!(
+            vec![
+                Segment::Key("list".into()),
+                Segment::Index(0),
+                Segment::Key("feels like".into()),
+                Segment::Key("value".into())
+            ],
+            parse_path(r#"$.list[0]["feels like"].value"#).unwrap()
+        );
+        assert_eq!(
+            parse_path(".main.temp").unwrap(),
+            parse_path("main.temp").unwrap()
+        );
+        assert!(parse_path("$").unwrap().is_empty());
+        assert!(parse_path(".list[x]").is_err());
+        assert!(parse_path(".list[0").is_err());
+    }
+
+    #[test]
+    fn select_values() {
+        let doc = json!({
+            "main": { "temp": 21.5, "online": true },
+            "list": [ { "v": 1 }, { "v": 2 }, { "v": 3 } ],
+            "name": "router"
+        });
+        assert_eq!(Some(&json!(21.5)), select(&doc, "$.main.temp"));
+        assert_eq!(Some(&json!(3)), select(&doc, ".list[-1].v"));
+        assert_eq!(Some(&json!(1)), select(&doc, "list[0]['v']"));
+        assert_eq!(None, select(&doc, ".list[5].v"));
+        assert_eq!(None, select(&doc, ".main.missing"));
+
+        assert_eq!(
+            Some("1".to_string()),
+            value_to_string(&doc["main"]["online"])
+        );
+        assert_eq!(Some("router".to_string()), value_to_string(&doc["name"]));
+        assert_eq!(None, value_to_string(&Value::Null));
+    }
+
+    #[test]
+    fn extract_values_with_units() {
+        let source: HttpSource = serde_json::from_value(json!({
+            "url": "http://localhost",
+            "values": { "outside_temp": ".main.temp", "missing": ".foo" },
+            "units": { "outside_temp": "°C" }
+        }))
+        .unwrap();
+        let sensors = extract_values(&json!({ "main": { "temp": 7 } }), &source);
+
+        assert_eq!(Some(&"7".to_string()), sensors.get("outside_temp"));
+        assert_eq!(Some(&"°C".to_string()), sensors.get("outside_temp#unit"));
+        assert_eq!(2, sensors.len());
+    }
+}
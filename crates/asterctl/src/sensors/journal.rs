@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! systemd journal sensor source.
+//!
+//! Counts the error and warning messages of the recent journal entries with `journalctl`, e.g. for
+//! a "system errors" indicator catching problems which the other sensors miss.
+
+use super::SensorSource;
+use super::exec::run_command;
+use super::schedule::PollSchedule;
+use crate::cfg::JournalConfig;
+use anyhow::Context;
+use log::debug;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Priority of the error messages: emerg, alert, crit and err have a lower value.
+const PRIORITY_ERR: u8 = 3;
+/// Priority of the warning messages.
+const PRIORITY_WARNING: u8 = 4;
+
+/// Journal sensor source with the name `journal`.
+///
+/// The journal messages of the configured time window are counted once per interval. Sensor keys:
+/// - `journal_errors`: number of messages with priority `err` or higher
+/// - `journal_warnings`: number of messages with priority `warning`
+///
+/// Both values are `N/A` if `journalctl` fails.
+pub struct JournalSensorSource {
+    command: Vec<String>,
+    schedule: PollSchedule,
+}
+
+impl JournalSensorSource {
+    /// Create a new journal sensor source with the time window, unit filter and poll interval of
+    /// the journal configuration.
+    pub fn new(cfg: &JournalConfig) -> Self {
+        let schedule = PollSchedule::new(
+            "journal",
+            cfg.interval,
+            Duration::from_secs(60),
+            Duration::from_secs(10),
+            None,
+        );
+        Self {
+            command: journalctl_command(cfg),
+            schedule,
+        }
+    }
+}
+
+impl SensorSource for JournalSensorSource {
+    fn name(&self) -> &str {
+        "journal"
+    }
+
+    fn interval(&self) -> Duration {
+        self.schedule.interval()
+    }
+
+    fn jitter(&self) -> Duration {
+        self.schedule.jitter()
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        let result = run_command(&self.command, Duration::from_secs(10))
+            .and_then(|output| count_messages(&output));
+        let counts = result.as_ref().ok();
+        debug!("Journal errors and warnings: {counts:?}");
+        for (key, count) in [
+            ("journal_errors", counts.map(|c| c.0)),
+            ("journal_warnings", counts.map(|c| c.1)),
+        ] {
+            let value = count.map_or_else(|| "N/A".to_string(), |c| c.to_string());
+            values.insert(key.to_string(), value);
+        }
+
+        result.map(|_| ())
+    }
+}
+
+/// `journalctl` command listing the priorities of the error and warning messages in JSON format.
+fn journalctl_command(cfg: &JournalConfig) -> Vec<String> {
+    let mut command: Vec<String> = [
+        "journalctl",
+        "--quiet",
+        "--no-pager",
+        "--output=json",
+        "--output-fields=PRIORITY",
+        "--priority=warning",
+    ]
+    .map(String::from)
+    .into();
+    command.push(format!("--since=-{}min", cfg.minutes.unwrap_or(15).max(1)));
+    for unit in &cfg.units {
+        command.push(format!("--unit={unit}"));
+    }
+    command
+}
+
+/// Count the error and warning messages of the JSON output, one entry per line.
+fn count_messages(output: &str) -> anyhow::Result<(usize, usize)> {
+    let (mut errors, mut warnings) = (0, 0);
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: Value = serde_json::from_str(line).context("Invalid journalctl output")?;
+        let priority = entry
+            .get("PRIORITY")
+            .and_then(Value::as_str)
+            .and_then(|p| p.parse::<u8>().ok());
+        match priority {
+            Some(p) if p <= PRIORITY_ERR => errors += 1,
+            Some(PRIORITY_WARNING) => warnings += 1,
+            _ => {}
+        }
+    }
+    Ok((errors, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_errors_and_warnings() {
+        let output = r#"{"__CURSOR":"s=1","PRIORITY":"3"}
+{"__CURSOR":"s=2","PRIORITY":"4"}
+{"__CURSOR":"s=3","PRIORITY":"2"}
+
+{"__CURSOR":"s=4","PRIORITY":"4"}
+{"__CURSOR":"s=5"}
+"#;
+        assert_eq!((2, 2), count_messages(output).unwrap());
+        assert_eq!((0, 0), count_messages("").unwrap());
+        assert!(count_messages("-- No entries --").is_err());
+
+        let cfg = JournalConfig {
+            minutes: Some(60),
+            units: vec!["nginx.service".into()],
+            ..Default::default()
+        };
+        let command = journalctl_command(&cfg);
+        assert_eq!("journalctl", command[0]);
+        assert!(command.contains(&"--since=-60min".to_string()));
+        assert!(command.contains(&"--unit=nginx.service".to_string()));
+    }
+}
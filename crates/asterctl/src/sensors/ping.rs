@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Host reachability sensor source using the system `ping` command.
+//!
+//! Raw ICMP sockets require elevated privileges, therefore the setuid / capability enabled `ping`
+//! tool is used instead.
+
+use super::SensorSource;
+use super::schedule::PollSchedule;
+use anyhow::anyhow;
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+/// Ping sensor source with the name `ping`.
+///
+/// Every host is pinged once per interval, all hosts in parallel. Sensor keys:
+/// - `ping_{host}_ms`: round trip time in milliseconds, `N/A` if the host is unreachable
+/// - `ping_{host}_up`: 1 if the host replied, 0 otherwise
+///
+/// Characters other than alphanumerics, `.`, `-` and `_` in the host name are replaced with `_`.
+pub struct PingSensorSource {
+    hosts: Vec<String>,
+    schedule: PollSchedule,
+}
+
+impl PingSensorSource {
+    /// Create a new ping sensor source for the given hosts.
+    ///
+    /// # Arguments
+    ///
+    /// * `hosts`: host names or IP addresses to ping
+    /// * `interval`: optional ping interval in seconds, minimum 1s. Also used as reply timeout.
+    /// * `refresh`: default ping interval if `interval` is not set
+    pub fn new(hosts: Vec<String>, interval: Option<f32>, refresh: Duration) -> Self {
+        let schedule = PollSchedule::new("ping", interval, refresh, Duration::from_secs(1), None);
+        Self { hosts, schedule }
+    }
+}
+
+impl SensorSource for PingSensorSource {
+    fn name(&self) -> &str {
+        "ping"
+    }
+
+    fn interval(&self) -> Duration {
+        self.schedule.interval()
+    }
+
+    fn jitter(&self) -> Duration {
+        self.schedule.jitter()
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        let timeout = self.schedule.interval().as_secs().max(1);
+        let children: Vec<_> = self
+            .hosts
+            .iter()
+            .map(|host| (host, spawn_ping(host, timeout)))
+            .collect();
+        let mut error = None;
+        for (host, child) in children {
+            let rtt = child
+                .inspect_err(|e| {
+                    error.get_or_insert(anyhow!("Failed to run ping: {e}"));
+                })
+                .ok()
+                .and_then(|child| {
+                    let output = child.wait_with_output().ok()?;
+                    if !output.status.success() {
+                        return None;
+                    }
+                    parse_ping_time(&String::from_utf8_lossy(&output.stdout))
+                });
+            debug!("Ping {host}: {rtt:?}");
+            add_ping_sensors(values, host, rtt);
+        }
+
+        error.map_or(Ok(()), Err)
+    }
+}
+
+fn spawn_ping(host: &str, timeout: u64) -> std::io::Result<Child> {
+    Command::new("ping")
+        .args(["-n", "-c", "1", "-W", &timeout.to_string(), host])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .inspect_err(|e| warn!("Failed to run ping: {e}"))
+}
+
+fn add_ping_sensors(sensors: &mut HashMap<String, String>, host: &str, rtt: Option<f32>) {
+    let host: String = host
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    sensors.insert(
+        format!("ping_{host}_ms"),
+        rtt.map(|rtt| format!("{rtt:.1}"))
+            .unwrap_or_else(|| "N/A".to_string()),
+    );
+    sensors.insert(format!("ping_{host}_ms#unit"), "ms".to_string());
+    sensors.insert(
+        format!("ping_{host}_up"),
+        u8::from(rtt.is_some()).to_string(),
+    );
+}
+
+/// Parse the round trip time of the reply line, e.g. `64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms`.
+fn parse_ping_time(output: &str) -> Option<f32> {
+    output.lines().find_map(|line| {
+        let (_, time) = line
+            .split_once("time=")
+            .or_else(|| line.split_once("time<"))?;
+        time.split_whitespace().next()?.parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ping_reply_time() {
+        let output = "PING 1.1.1.1 (1.1.1.1) 56(84) bytes of data.
+64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms
+
+--- 1.1.1.1 ping statistics ---
+1 packets transmitted, 1 received, 0% packet loss, time 0ms
+rtt min/avg/max/mdev = 12.345/12.345/12.345/0.000 ms
+";
+        assert_eq!(Some(12.3), parse_ping_time(output));
+        assert_eq!(
+            Some(1.0),
+            parse_ping_time("Reply from 192.168.1.1: bytes=32 time<1 ms TTL=64")
+        );
+        assert_eq!(
+            None,
+            parse_ping_time("1 packets transmitted, 0 received, 100% packet loss, time 0ms")
+        );
+    }
+
+    #[test]
+    fn ping_sensor_keys() {
+        let mut sensors = HashMap::new();
+        add_ping_sensors(&mut sensors, "192.168.1.1", Some(0.4));
+        add_ping_sensors(&mut sensors, "fe80::1", None);
+
+        assert_eq!(Some(&"0.4".to_string()), sensors.get("ping_192.168.1.1_ms"));
+        assert_eq!(Some(&"1".to_string()), sensors.get("ping_192.168.1.1_up"));
+        assert_eq!(Some(&"N/A".to_string()), sensors.get("ping_fe80__1_ms"));
+        assert_eq!(Some(&"0".to_string()), sensors.get("ping_fe80__1_up"));
+    }
+}
@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Unix domain socket push sensor source.
+//!
+//! External programs connect to the socket and push sensor value updates, which are merged into
+//! the shared sensor values immediately. This allows event-driven sensor values instead of
+//! polling.
+
+use super::exec::flatten_json;
+use super::{SensorAliases, SensorFilter, SensorFilters, SensorStore, apply_sensor_values};
+use anyhow::{Context, bail};
+use log::{debug, info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Start listening for sensor value updates on a Unix domain socket.
+///
+/// Each line sent to the socket is an update:
+/// - `key=value` or `key: value`: set a sensor value. An empty value removes the sensor key.
+/// - `{"key": value, ...}`: JSON object with sensor values. Nested objects and arrays are
+///   flattened into sensor keys joined with `_`, `null` removes a sensor key.
+///
+/// Empty lines and comments starting with `#` are ignored.
+///
+/// # Arguments
+///
+/// * `values`: a shared, reader-writer lock protected sensor store
+/// * `path`: socket path. An existing socket file is replaced. Only the owner can connect to the
+///   new socket.
+/// * `sensor_filter`: sensor key filters, the filter of the source is applied.
+/// * `aliases`: sensor key aliases, applied before the sensor filter.
+///
+/// returns: Result<(), Error>
+pub fn start_push_socket(
+    values: Arc<RwLock<SensorStore>>,
+    path: impl Into<PathBuf>,
+    sensor_filter: &SensorFilters,
+    aliases: &SensorAliases,
+) -> anyhow::Result<()> {
+    let path = path.into();
+    let listener = bind_socket(&path)?;
+    let sensor_filter = sensor_filter.source("push");
+    let aliases = aliases.clone();
+
+    info!("Listening for sensor value updates on {}", path.display());
+
+    std::thread::Builder::new()
+        .name("push-socket".into())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        warn!("Failed to accept push socket connection: {e}");
+                        continue;
+                    }
+                };
+                let values = values.clone();
+                let sensor_filter = sensor_filter.clone();
+                let aliases = aliases.clone();
+                if let Err(e) = std::thread::Builder::new()
+                    .name("push-client".into())
+                    .spawn(move || handle_client(stream, &values, &sensor_filter, &aliases))
+                {
+                    warn!("Failed to start push socket client thread: {e}");
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+fn bind_socket(path: &Path) -> anyhow::Result<UnixListener> {
+    // remove a left over socket of a previous run
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            bail!("Push socket path {} is not a socket", path.display());
+        }
+        fs::remove_file(path)
+            .with_context(|| format!("Failed to remove old push socket {}", path.display()))?;
+    }
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)?;
+
+    // The default umask lets other local users connect and push sensor values. The socket is
+    // created in a private directory and restricted before it is moved into place, so it is never
+    // accessible by other users.
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid push socket path {}", path.display()))?;
+    let private_dir = parent.join(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .with_context(|| format!("Failed to create directory {}", private_dir.display()))?;
+    let private_path = private_dir.join("socket");
+    let listener = UnixListener::bind(&private_path)
+        .with_context(|| format!("Failed to bind push socket {}", path.display()))
+        .and_then(|listener| {
+            fs::set_permissions(&private_path, fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Failed to restrict push socket {}", path.display()))?;
+            fs::rename(&private_path, path)
+                .with_context(|| format!("Failed to move push socket to {}", path.display()))?;
+            Ok(listener)
+        });
+    if listener.is_err() {
+        let _ = fs::remove_file(&private_path);
+    }
+    let _ = fs::remove_dir(&private_dir);
+    listener
+}
+
+fn handle_client(
+    stream: UnixStream,
+    values: &RwLock<SensorStore>,
+    sensor_filter: &SensorFilter,
+    aliases: &SensorAliases,
+) {
+    debug!("Push socket client connected");
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to read from push socket client: {e}");
+                break;
+            }
+        };
+        match parse_update(&line) {
+            Ok(update) if update.is_empty() => {}
+            Ok(update) => apply_update(values, &update, sensor_filter, aliases),
+            Err(e) => warn!("Invalid push socket update: {e:#}"),
+        }
+    }
+    debug!("Push socket client disconnected");
+}
+
+/// Apply a sensor value update. Keys with an empty value are removed.
+fn apply_update(
+    values: &RwLock<SensorStore>,
+    update: &HashMap<String, String>,
+    sensor_filter: &SensorFilter,
+    aliases: &SensorAliases,
+) {
+    let (removed, changed): (HashMap<_, _>, HashMap<_, _>) = update
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .partition(|(_, value)| value.is_empty());
+
+    let mut val = values.write().expect("Poisoned sensor RwLock");
+    apply_sensor_values(&mut val, "push", &changed, sensor_filter, aliases);
+    for key in removed.keys() {
+        let key = aliases.resolve(key);
+        if !sensor_filter.is_filtered(&key) {
+            val.remove(&key);
+        }
+    }
+}
+
+/// Parse an update line into sensor values. An empty value marks a sensor key for removal.
+fn parse_update(line: &str) -> anyhow::Result<HashMap<String, String>> {
+    let line = line.trim();
+    let mut update = HashMap::new();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(update);
+    }
+
+    if line.starts_with('{') {
+        let document: Value = serde_json::from_str(line).context("Invalid JSON update")?;
+        mark_null_values("", &document, &mut update);
+        flatten_json("", &document, &mut update);
+        return Ok(update);
+    }
+
+    let Some(idx) = line.find(['=', ':']) else {
+        bail!("Missing separator in '{line}'");
+    };
+    let key = line[..idx].trim();
+    if key.is_empty() {
+        bail!("Missing sensor key in '{line}'");
+    }
+    update.insert(key.to_string(), line[idx + 1..].trim().to_string());
+    Ok(update)
+}
+
+/// Mark JSON `null` values for removal, they are skipped when flattening the document.
+fn mark_null_values(prefix: &str, value: &Value, update: &mut HashMap<String, String>) {
+    if let Value::Object(obj) = value {
+        for (name, value) in obj {
+            let key = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}_{name}")
+            };
+            if value.is_null() {
+                update.insert(key, String::new());
+            } else {
+                mark_null_values(&key, value, update);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::{Duration, Instant};
+
+    fn update(values: &[(&str, &str)]) -> HashMap<String, String> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_update_lines() {
+        assert_eq!(
+            update(&[("door", "open")]),
+            parse_update("door=open").unwrap()
+        );
+        assert_eq!(
+            update(&[("url", "http://x?a=1")]),
+            parse_update(" url: http://x?a=1 ").unwrap()
+        );
+        assert_eq!(update(&[("door", "")]), parse_update("door=").unwrap());
+        assert_eq!(
+            update(&[("ups_load", "23"), ("ups_online", "1"), ("door", "")]),
+            parse_update(r#"{"ups": {"load": 23, "online": true}, "door": null}"#).unwrap()
+        );
+        assert!(parse_update("# comment").unwrap().is_empty());
+        assert!(parse_update("no separator").is_err());
+        assert!(parse_update("=value").is_err());
+    }
+
+    #[test]
+    fn push_values_over_socket() {
+        let path = std::env::temp_dir().join(format!("asterctl-push-{}.sock", std::process::id()));
+        let values = Arc::new(RwLock::new(SensorStore::from_iter([("door", "closed")])));
+        let aliases = SensorAliases::new(&HashMap::from([("bell".into(), "doorbell".into())]));
+        start_push_socket(values.clone(), &path, &SensorFilters::default(), &aliases).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(0o600, mode & 0o777);
+        let private_dir = std::env::temp_dir().join(format!(
+            ".asterctl-push-{}.sock.{}",
+            std::process::id(),
+            std::process::id()
+        ));
+        assert!(!private_dir.exists());
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream
+            .write_all(b"door=\nbell: ringing\n{\"mail\": 2}\n")
+            .unwrap();
+        drop(stream);
+
+        let start = Instant::now();
+        while values.read().unwrap().get("mail").is_none() {
+            assert!(start.elapsed() < Duration::from_secs(5), "timeout");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let values = values.read().unwrap();
+        assert_eq!(None, values.get("door"));
+        assert_eq!("ringing", values["doorbell"]);
+        assert!(!values.contains_key("bell"));
+        assert_eq!("2", values["mail"]);
+        let _ = fs::remove_file(path);
+    }
+}
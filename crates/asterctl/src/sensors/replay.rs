@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Playback of recorded sensor values.
+//!
+//! A sensor recording is a JSON lines file with a timestamped sensor snapshot per line:
+//!
+//! ```json
+//! {"time":"2026-10-17T12:00:00.000+02:00","values":{"cpu_usage_percent":"12","temperature_cpu":"45.0"}}
+//! ```
+//!
+//! The snapshots are played back in the time intervals of the recording, optionally faster or
+//! slower, and start over after the last snapshot. A snapshot only needs to contain the changed
+//! values, the values of the previous snapshots are kept.
+
+use super::SensorSource;
+use super::http::value_to_string;
+use anyhow::{Context, bail};
+use chrono::{DateTime, FixedOffset};
+use log::info;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Source name of the replayed sensor values.
+pub const REPLAY_SOURCE: &str = "replay";
+
+/// Poll interval of the replay source, the resolution of the snapshot times.
+const REPLAY_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Deserialize)]
+struct Snapshot {
+    time: DateTime<FixedOffset>,
+    values: Map<String, Value>,
+}
+
+/// Sensor snapshots of a recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorRecording {
+    /// Time of each snapshot since the first snapshot, and its sensor values.
+    snapshots: Vec<(Duration, HashMap<String, String>)>,
+}
+
+impl SensorRecording {
+    /// Load a sensor recording from a JSON lines file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let recording = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sensor recording {}", path.display()))?;
+        Self::parse(&recording)
+            .with_context(|| format!("Invalid sensor recording {}", path.display()))
+    }
+
+    /// Parse a sensor recording with a JSON sensor snapshot per line. Empty lines are skipped.
+    ///
+    /// returns: Result<SensorRecording, Error>, an error if a snapshot is invalid or older than
+    /// the previous snapshot, or if there are no snapshots.
+    pub fn parse(recording: &str) -> anyhow::Result<Self> {
+        let mut snapshots = Vec::new();
+        let mut start = None;
+        let mut last = Duration::ZERO;
+        for (idx, line) in recording.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let snapshot: Snapshot = serde_json::from_str(line)
+                .with_context(|| format!("Invalid snapshot in line {}", idx + 1))?;
+            let start = *start.get_or_insert(snapshot.time);
+            let Ok(time) = (snapshot.time - start).to_std() else {
+                bail!(
+                    "Snapshot in line {} is older than the first snapshot",
+                    idx + 1
+                );
+            };
+            if time < last {
+                bail!(
+                    "Snapshot in line {} is older than the previous snapshot",
+                    idx + 1
+                );
+            }
+            last = time;
+            let values = snapshot
+                .values
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), value_to_string(value)?)))
+                .collect();
+            snapshots.push((time, values));
+        }
+        if snapshots.is_empty() {
+            bail!("No sensor snapshots");
+        }
+        Ok(Self { snapshots })
+    }
+
+    /// Number of snapshots.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Time of the last snapshot since the first snapshot.
+    pub fn duration(&self) -> Duration {
+        self.snapshots
+            .last()
+            .map_or(Duration::ZERO, |(time, _)| *time)
+    }
+
+    /// Sensor values at a playback time: the values of all snapshots up to this time.
+    ///
+    /// For tests rendering a panel at a deterministic point of a recording.
+    pub fn values_at(&self, time: Duration) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        for (_, snapshot) in self.snapshots.iter().take_while(|(at, _)| *at <= time) {
+            values.extend(snapshot.clone());
+        }
+        values
+    }
+
+    /// Length of a playback cycle: the last snapshot is shown for the average snapshot interval.
+    fn cycle(&self) -> Duration {
+        match self.snapshots.len() {
+            0 | 1 => Duration::ZERO,
+            len => self.duration() + self.duration() / (len as u32 - 1),
+        }
+    }
+}
+
+/// Sensor source playing back a sensor recording with the name `replay`.
+pub struct ReplaySensorSource {
+    recording: SensorRecording,
+    speed: f64,
+    start: Option<Instant>,
+    /// Playback time of the last poll.
+    time: Duration,
+    /// Index of the next snapshot.
+    next: usize,
+}
+
+impl ReplaySensorSource {
+    /// Create a new replay source.
+    ///
+    /// # Arguments
+    ///
+    /// * `recording`: sensor snapshots to play back.
+    /// * `speed`: playback speed factor, e.g. 10 for ten times faster. Values <= 0 play back in
+    ///   real time.
+    pub fn new(recording: SensorRecording, speed: f32) -> Self {
+        info!(
+            "Replaying {} sensor snapshots of {}s at {speed}x speed",
+            recording.len(),
+            recording.duration().as_secs()
+        );
+        Self {
+            recording,
+            speed: if speed > 0.0 { speed as f64 } else { 1.0 },
+            start: None,
+            time: Duration::ZERO,
+            next: 0,
+        }
+    }
+
+    /// Add the values of the snapshots up to the playback time `elapsed`.
+    fn poll_at(&mut self, elapsed: Duration, values: &mut HashMap<String, String>) {
+        let cycle = self.recording.cycle();
+        let time = if cycle.is_zero() {
+            elapsed
+        } else {
+            Duration::from_secs_f64(elapsed.as_secs_f64() % cycle.as_secs_f64())
+        };
+        if time < self.time {
+            info!("Restarting the sensor replay");
+            self.next = 0;
+        }
+        self.time = time;
+
+        while let Some((at, snapshot)) = self.recording.snapshots.get(self.next)
+            && *at <= time
+        {
+            values.extend(snapshot.clone());
+            self.next += 1;
+        }
+    }
+}
+
+impl SensorSource for ReplaySensorSource {
+    fn name(&self) -> &str {
+        REPLAY_SOURCE
+    }
+
+    fn interval(&self) -> Duration {
+        REPLAY_INTERVAL
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        self.poll_at(start.elapsed().mul_f64(self.speed), values);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECORDING: &str = r#"
+{"time":"2026-10-17T12:00:00+02:00","values":{"temperature_cpu":"40.0","temperature_cpu#unit":"°C","cpu_usage_percent":5}}
+{"time":"2026-10-17T12:00:01+02:00","values":{"temperature_cpu":"45.0"}}
+
+{"time":"2026-10-17T10:00:03Z","values":{"temperature_cpu":"50.0","fan_cpu":null}}
+"#;
+
+    #[test]
+    fn parse_recording() {
+        let recording = SensorRecording::parse(RECORDING).unwrap();
+
+        assert_eq!(3, recording.len());
+        assert_eq!(Duration::from_secs(3), recording.duration());
+        let values = recording.values_at(Duration::from_secs(2));
+        let value = |key: &str| values.get(key).map(String::as_str);
+        assert_eq!(Some("45.0"), value("temperature_cpu"));
+        assert_eq!(Some("°C"), value("temperature_cpu#unit"));
+        assert_eq!(Some("5"), value("cpu_usage_percent"));
+        let values = recording.values_at(Duration::from_secs(3));
+        assert!(!values.contains_key("fan_cpu"));
+
+        let error = SensorRecording::parse(
+            r#"{"time":"2026-10-17T12:00:01Z","values":{}}
+               {"time":"2026-10-17T12:00:00Z","values":{}}"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            "Snapshot in line 2 is older than the first snapshot",
+            error.to_string()
+        );
+        assert!(SensorRecording::parse("\n").is_err());
+    }
+
+    #[test]
+    fn replay_snapshots_in_cycles() {
+        let recording = SensorRecording::parse(RECORDING).unwrap();
+        let mut source = ReplaySensorSource::new(recording, 1.0);
+        let mut poll = |millis| {
+            let mut values = HashMap::new();
+            source.poll_at(Duration::from_millis(millis), &mut values);
+            values.get("temperature_cpu").cloned()
+        };
+
+        assert_eq!(Some("40.0".to_string()), poll(0));
+        assert_eq!(None, poll(500));
+        assert_eq!(Some("45.0".to_string()), poll(1500));
+        assert_eq!(Some("50.0".to_string()), poll(3500));
+        // the last snapshot is shown for the average snapshot interval of 1.5s
+        assert_eq!(None, poll(4400));
+        assert_eq!(Some("40.0".to_string()), poll(4600));
+    }
+}
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Poll scheduling of network sensor sources.
+//!
+//! Network sources are rate limited with a per-source minimum interval, so a misconfigured short
+//! interval doesn't hammer external APIs. A random jitter spreads the requests of multiple
+//! sources and multiple devices polling the same service.
+
+use log::warn;
+use std::hash::{BuildHasher, RandomState};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Poll interval of a sensor source with a lower limit and random jitter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct PollSchedule {
+    interval: Duration,
+    jitter: Duration,
+}
+
+impl PollSchedule {
+    /// Create a new poll schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: source name for logging.
+    /// * `interval`: configured poll interval in seconds, `default` is used if not set.
+    /// * `default`: default poll interval.
+    /// * `min_interval`: minimum poll interval. Shorter configured intervals are raised.
+    /// * `jitter`: configured maximum random delay in seconds added to each interval.
+    ///   Default: 10% of the interval, limited to the interval.
+    pub(super) fn new(
+        source: &str,
+        interval: Option<f32>,
+        default: Duration,
+        min_interval: Duration,
+        jitter: Option<f32>,
+    ) -> Self {
+        let mut interval = interval
+            .map(|secs| Duration::from_secs_f32(secs.max(0.0)))
+            .unwrap_or(default);
+        if interval < min_interval {
+            warn!(
+                "Poll interval of {source} is too short, using the minimum interval of {}s",
+                min_interval.as_secs_f32()
+            );
+            interval = min_interval;
+        }
+        let jitter = jitter
+            .map(|secs| Duration::from_secs_f32(secs.max(0.0)))
+            .unwrap_or(interval / 10)
+            .min(interval);
+
+        Self { interval, jitter }
+    }
+
+    /// Create a poll schedule with the given interval and maximum jitter, without limits.
+    pub(super) fn exact(interval: Duration, jitter: Duration) -> Self {
+        Self { interval, jitter }
+    }
+
+    pub(super) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub(super) fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Delay until the next poll, given the elapsed time since the start of the current poll.
+    pub(super) fn next_delay(&self, elapsed: Duration) -> Duration {
+        let jitter = self.jitter.mul_f64(random_fraction());
+        (self.interval + jitter).saturating_sub(elapsed)
+    }
+
+    /// Sleep until the next poll of a poll cycle started at `start`.
+    pub(super) fn wait(&self, start: Instant) {
+        sleep(self.next_delay(start.elapsed()));
+    }
+}
+
+/// Random number in the range `[0, 1)`.
+///
+/// The jitter doesn't need a good random number generator: the randomly seeded std hasher is
+/// sufficient.
+fn random_fraction() -> f64 {
+    let hash = RandomState::new().hash_one(Instant::now());
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poll_schedule_limits_and_jitter() {
+        let min = Duration::from_secs(5);
+        let default = Duration::from_secs(60);
+
+        let schedule = PollSchedule::new("test", None, default, min, None);
+        assert_eq!(default, schedule.interval());
+        assert_eq!(Duration::from_secs(6), schedule.jitter());
+
+        let schedule = PollSchedule::new("test", Some(1.0), default, min, Some(0.0));
+        assert_eq!(min, schedule.interval());
+        assert_eq!(Duration::ZERO, schedule.jitter());
+        assert_eq!(
+            Duration::from_secs(3),
+            schedule.next_delay(Duration::from_secs(2))
+        );
+        assert_eq!(Duration::ZERO, schedule.next_delay(Duration::from_secs(7)));
+
+        let schedule = PollSchedule::new("test", Some(10.0), default, min, Some(2.0));
+        for _ in 0..100 {
+            let delay = schedule.next_delay(Duration::ZERO);
+            assert!(delay >= Duration::from_secs(10) && delay <= Duration::from_secs(12));
+        }
+    }
+}
@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Pluggable sensor sources.
+//!
+//! A sensor source only reads its sensor values, the [SensorSources] registry polls each
+//! registered source in its own thread on the schedule of the source. The registry renames the
+//! sensor keys with their alias, applies the sensor filter, stores the values in the shared
+//! [SensorStore] and reports the health of the source.
+
+use super::schedule::PollSchedule;
+use super::{SensorAliases, SensorFilters, SensorStore, SourceHealth, apply_sensor_values};
+use anyhow::bail;
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// A polled sensor source.
+pub trait SensorSource: Send {
+    /// Unique source name, used for the sensor filter, the source health and as source of the
+    /// sensor values.
+    fn name(&self) -> &str;
+
+    /// Time between the start of two polls.
+    fn interval(&self) -> Duration;
+
+    /// Maximum random delay added to each poll interval. Default: no delay
+    fn jitter(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Read the initial sensor values when the source is registered, before the first poll.
+    ///
+    /// The values are available immediately after registration, e.g. for static sensors or
+    /// sensors which are only polled rarely. Default: no initial values
+    fn init(&mut self, _values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Read the current sensor values.
+    ///
+    /// The values added to `values` are stored even if an error is returned, e.g. for sources
+    /// which failed to read only some of their sensors.
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()>;
+
+    /// Number of consecutive failed polls after which all sensor values of the source are
+    /// removed until the next successful poll. Default: values are kept
+    fn max_failures(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Registry of the running sensor sources.
+///
+/// Sources can be registered at any time, each registered source is polled immediately.
+#[derive(Clone)]
+pub struct SensorSources {
+    values: Arc<RwLock<SensorStore>>,
+    sensor_filter: SensorFilters,
+    aliases: SensorAliases,
+    health: SourceHealth,
+    names: Arc<Mutex<Vec<String>>>,
+}
+
+impl SensorSources {
+    /// Create a new source registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `values`: a shared, reader-writer lock protected sensor store
+    /// * `sensor_filter`: sensor key filters, the filter of each source is applied.
+    /// * `aliases`: sensor key aliases, applied before the sensor filter.
+    /// * `health`: source health registry, each source is registered with its name.
+    pub fn new(
+        values: Arc<RwLock<SensorStore>>,
+        sensor_filter: &SensorFilters,
+        aliases: &SensorAliases,
+        health: &SourceHealth,
+    ) -> Self {
+        Self {
+            values,
+            sensor_filter: sensor_filter.clone(),
+            aliases: aliases.clone(),
+            health: health.clone(),
+            names: Default::default(),
+        }
+    }
+
+    /// Register a sensor source and start polling it in its own thread.
+    ///
+    /// returns: Result<(), Error>, an error if a source with the same name is already registered.
+    pub fn register(&self, source: impl SensorSource + 'static) -> anyhow::Result<()> {
+        self.register_boxed(Box::new(source))
+    }
+
+    /// Register a boxed sensor source, see [SensorSources::register].
+    pub fn register_boxed(&self, mut source: Box<dyn SensorSource>) -> anyhow::Result<()> {
+        let name = source.name().to_string();
+        {
+            let mut names = self.names.lock().expect("Poisoned source registry mutex");
+            if names.contains(&name) {
+                bail!("Sensor source {name} is already registered");
+            }
+            names.push(name.clone());
+        }
+
+        let sensor_filter = self.sensor_filter.source(&name);
+        self.health.register(&name, &self.values);
+
+        let mut sensors = HashMap::new();
+        if let Err(e) = source.init(&mut sensors) {
+            warn!("Initial update of sensor source {name} failed: {e:#}");
+        }
+        if !sensors.is_empty() {
+            let mut val = self.values.write().expect("Poisoned sensor RwLock");
+            apply_sensor_values(&mut val, &name, &sensors, &sensor_filter, &self.aliases);
+        }
+
+        info!(
+            "Starting sensor source {name} with interval={}ms",
+            source.interval().as_millis()
+        );
+
+        let values = self.values.clone();
+        let aliases = self.aliases.clone();
+        let health = self.health.clone();
+        std::thread::Builder::new()
+            .name(format!("source-{name}"))
+            .spawn(move || {
+                let mut failures = 0;
+                let mut reported_keys = HashSet::new();
+
+                loop {
+                    let upd_start_time = Instant::now();
+
+                    let mut sensors = HashMap::new();
+                    let result = source.poll(&mut sensors);
+                    reported_keys.extend(
+                        sensors
+                            .keys()
+                            .map(|key| aliases.resolve(key).into_owned()),
+                    );
+
+                    {
+                        let mut val = values.write().expect("Poisoned sensor RwLock");
+                        apply_sensor_values(&mut val, &name, &sensors, &sensor_filter, &aliases);
+                        match result {
+                            Ok(()) => {
+                                if source.max_failures().is_some_and(|max| failures >= max) {
+                                    info!("Sensor source {name} recovered");
+                                }
+                                failures = 0;
+                                health.success(&name, &mut val);
+                            }
+                            Err(e) => {
+                                failures += 1;
+                                warn!("Sensor source {name} failed ({failures}x): {e:#}");
+                                if source.max_failures() == Some(failures) {
+                                    error!(
+                                        "Sensor source {name} failed {failures} times, removing its sensor values"
+                                    );
+                                    for key in reported_keys.drain() {
+                                        val.remove(&key);
+                                    }
+                                }
+                                health.failure(&name, e, &mut val);
+                            }
+                        }
+                    }
+
+                    PollSchedule::exact(source.interval(), source.jitter()).wait(upd_start_time);
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Names of the registered sources in registration order.
+    pub fn names(&self) -> Vec<String> {
+        self.names
+            .lock()
+            .expect("Poisoned source registry mutex")
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::thread::sleep;
+
+    struct CountingSource {
+        polls: u32,
+    }
+
+    impl SensorSource for CountingSource {
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        fn interval(&self) -> Duration {
+            Duration::from_millis(10)
+        }
+
+        fn init(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+            values.insert("counter_version".into(), "1".into());
+            Ok(())
+        }
+
+        fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+            self.polls += 1;
+            if self.polls > 2 {
+                return Err(anyhow!("counter overflow"));
+            }
+            values.insert("counter".into(), self.polls.to_string());
+            Ok(())
+        }
+
+        fn max_failures(&self) -> Option<u32> {
+            Some(2)
+        }
+    }
+
+    #[test]
+    fn registered_source_is_polled() {
+        let values = Arc::new(RwLock::new(SensorStore::new()));
+        let sources = SensorSources::new(
+            values.clone(),
+            &SensorFilters::default(),
+            &SensorAliases::default(),
+            &SourceHealth::new(),
+        );
+        sources.register(CountingSource { polls: 0 }).unwrap();
+        assert_eq!(Some("1"), values.read().unwrap().value("counter_version"));
+        assert!(sources.register(CountingSource { polls: 0 }).is_err());
+        assert_eq!(vec!["counter".to_string()], sources.names());
+
+        // two successful polls, the second failure removes the polled values
+        sleep(Duration::from_millis(200));
+        let values = values.read().unwrap();
+        assert!(!values.contains_key("counter"));
+        assert_eq!(Some("error"), values.value("__source_counter_status"));
+        assert_eq!("counter", values.get("counter_version").unwrap().source);
+    }
+}
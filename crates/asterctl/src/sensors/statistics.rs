@@ -0,0 +1,264 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Session minimum, maximum and average values of sensors.
+//!
+//! The tracked values of a sensor `{key}` are stored as the sensors `{key}#min`, `{key}#max` and
+//! `{key}#avg`, which can be shown on a panel like any other sensor. The statistics are kept until
+//! they are reset with the control API.
+
+use super::SensorStore;
+use crate::cfg::StatisticsConfig;
+use anyhow::Context;
+use chrono::{DateTime, Local, TimeDelta};
+use log::info;
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Source name of the sensor statistics.
+pub const STATISTICS_SOURCE: &str = "statistics";
+
+/// Statistics of a tracked sensor.
+#[derive(Debug)]
+struct Tracked {
+    /// Update time of the last sample.
+    updated_at: DateTime<Local>,
+    min: f64,
+    max: f64,
+    /// Sum and number of all samples for the session average.
+    sum: f64,
+    count: u64,
+    /// Samples of the averaging window, oldest first.
+    samples: VecDeque<(DateTime<Local>, f64)>,
+    /// Maximum number of decimal places of the sensor values.
+    decimals: usize,
+}
+
+/// Shared handle of the tracked sensor statistics.
+#[derive(Debug, Clone, Default)]
+pub struct SensorStatistics {
+    tracked: Arc<Mutex<HashMap<String, Tracked>>>,
+}
+
+impl SensorStatistics {
+    /// Reset the statistics of a sensor, or of all sensors if `key` is `None`.
+    ///
+    /// The statistics start again with the current sensor value at the next update.
+    ///
+    /// returns: false if no statistics were tracked for the sensor.
+    pub fn reset(&self, key: Option<&str>) -> bool {
+        let mut tracked = self.tracked.lock().expect("Poisoned statistics Mutex");
+        match key {
+            Some(key) => tracked.remove(key).is_some(),
+            None => {
+                let reset = !tracked.is_empty();
+                tracked.clear();
+                reset
+            }
+        }
+    }
+}
+
+/// Start tracking the statistics of the configured sensors at the given interval.
+///
+/// # Arguments
+///
+/// * `values`: a shared, reader-writer lock protected sensor store
+/// * `cfg`: statistics configuration, nothing is tracked if `None`
+/// * `refresh`: update interval
+///
+/// returns: Result<SensorStatistics, Error>, an error if a sensor pattern is invalid.
+pub fn start_sensor_statistics(
+    values: Arc<RwLock<SensorStore>>,
+    cfg: Option<&StatisticsConfig>,
+    refresh: Duration,
+) -> anyhow::Result<SensorStatistics> {
+    let statistics = SensorStatistics::default();
+    let Some(cfg) = cfg.filter(|cfg| !cfg.sensors.is_empty()) else {
+        return Ok(statistics);
+    };
+    let patterns = cfg
+        .sensors
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern)
+                .with_context(|| format!("Invalid statistics sensor pattern {pattern}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let window = cfg
+        .window
+        .filter(|window| *window > 0.0)
+        .map(|window| TimeDelta::milliseconds((window * 1000.0) as i64));
+
+    info!(
+        "Starting sensor statistics of {} patterns with refresh={}ms",
+        patterns.len(),
+        refresh.as_millis()
+    );
+
+    let tracked = statistics.tracked.clone();
+    std::thread::Builder::new()
+        .name("statistics".into())
+        .spawn(move || {
+            loop {
+                let start = Instant::now();
+                {
+                    let mut values = values.write().expect("Poisoned sensor RwLock");
+                    let mut tracked = tracked.lock().expect("Poisoned statistics Mutex");
+                    update_statistics(&patterns, window, &mut tracked, &mut values);
+                }
+                let elapsed = start.elapsed();
+                if refresh > elapsed {
+                    std::thread::sleep(refresh - elapsed);
+                }
+            }
+        })?;
+
+    Ok(statistics)
+}
+
+fn update_statistics(
+    patterns: &[Regex],
+    window: Option<TimeDelta>,
+    tracked: &mut HashMap<String, Tracked>,
+    values: &mut SensorStore,
+) {
+    let samples: Vec<_> = values
+        .iter()
+        .filter(|(key, sensor)| {
+            sensor.source != STATISTICS_SOURCE
+                && patterns.iter().any(|pattern| pattern.is_match(key))
+        })
+        .filter(|(key, sensor)| {
+            tracked
+                .get(*key)
+                .is_none_or(|tracked| tracked.updated_at != sensor.updated_at)
+        })
+        .filter_map(|(key, sensor)| {
+            let value = sensor.value.trim();
+            let sample = value
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())?;
+            let decimals = value
+                .split_once('.')
+                .map_or(0, |(_, decimals)| decimals.len());
+            Some((
+                key.clone(),
+                sample,
+                decimals,
+                sensor.updated_at,
+                sensor.unit.clone(),
+            ))
+        })
+        .collect();
+
+    for (key, sample, decimals, updated_at, unit) in samples {
+        let stats = tracked.entry(key.clone()).or_insert_with(|| Tracked {
+            updated_at,
+            min: sample,
+            max: sample,
+            sum: 0.0,
+            count: 0,
+            samples: VecDeque::new(),
+            decimals,
+        });
+        stats.updated_at = updated_at;
+        stats.min = stats.min.min(sample);
+        stats.max = stats.max.max(sample);
+        stats.sum += sample;
+        stats.count += 1;
+        stats.decimals = stats.decimals.max(decimals);
+
+        let average = match window {
+            Some(window) => {
+                stats.samples.push_back((updated_at, sample));
+                while stats
+                    .samples
+                    .front()
+                    .is_some_and(|(time, _)| *time < updated_at - window)
+                {
+                    stats.samples.pop_front();
+                }
+                stats.samples.iter().map(|(_, value)| value).sum::<f64>()
+                    / stats.samples.len() as f64
+            }
+            None => stats.sum / stats.count as f64,
+        };
+
+        let decimals = stats.decimals;
+        let avg_decimals = decimals.max(1);
+        for (suffix, value) in [
+            ("min", format!("{:.decimals$}", stats.min)),
+            ("max", format!("{:.decimals$}", stats.max)),
+            ("avg", format!("{average:.avg_decimals$}")),
+        ] {
+            let stat_key = format!("{key}#{suffix}");
+            values.set(&stat_key, value, STATISTICS_SOURCE);
+            if let Some(unit) = &unit {
+                values.set_unit(&stat_key, unit);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensors::SensorValue;
+
+    fn set(values: &mut SensorStore, key: &str, value: &str, updated_at: DateTime<Local>) {
+        values.insert(
+            key,
+            SensorValue {
+                value: value.to_string(),
+                unit: Some("°C".to_string()),
+                description: None,
+                source: "sysinfo".to_string(),
+                updated_at,
+            },
+        );
+    }
+
+    #[test]
+    fn track_min_max_avg() {
+        let start = Local::now();
+        let at = |secs: i64| start + TimeDelta::seconds(secs);
+        let patterns = [Regex::new("^temperature_").unwrap()];
+        let window = Some(TimeDelta::seconds(2));
+        let statistics = SensorStatistics::default();
+        let mut values = SensorStore::new();
+        let update = |values: &mut SensorStore| {
+            let mut tracked = statistics.tracked.lock().unwrap();
+            update_statistics(&patterns, window, &mut tracked, values);
+        };
+
+        for (secs, value) in [(0, "40"), (1, "50"), (2, "60"), (4, "45")] {
+            set(&mut values, "temperature_cpu", value, at(secs));
+            update(&mut values);
+        }
+        // an unchanged update time doesn't add a sample
+        update(&mut values);
+
+        assert_eq!(Some("40"), values.value("temperature_cpu#min"));
+        assert_eq!(Some("60"), values.value("temperature_cpu#max"));
+        // samples older than the window are dropped from the average
+        assert_eq!(Some("52.5"), values.value("temperature_cpu#avg"));
+        assert_eq!(Some("°C"), values.unit("temperature_cpu#max"));
+        assert_eq!(
+            "statistics",
+            values.get("temperature_cpu#min").unwrap().source
+        );
+        assert!(!values.contains_key("temperature_cpu#min#min"));
+
+        // a reset starts again with the current value
+        assert!(statistics.reset(Some("temperature_cpu")));
+        assert!(!statistics.reset(Some("temperature_gpu")));
+        update(&mut values);
+        assert_eq!(Some("45"), values.value("temperature_cpu#min"));
+        assert_eq!(Some("45"), values.value("temperature_cpu#max"));
+        assert_eq!(Some("45.0"), values.value("temperature_cpu#avg"));
+    }
+}
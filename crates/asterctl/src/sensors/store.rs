@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Shared sensor value store with sensor metadata.
+//!
+//! Sensor sources deliver plain key value maps. Metadata keys of a sensor, `{key}#unit` and
+//! `{key}#description`, are folded into the [SensorValue] of the sensor instead of being stored as
+//! separate sensors.
+
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::ops::Index;
+
+/// Key suffix of the sensor unit in the values of a sensor source.
+pub const UNIT_SUFFIX: &str = "#unit";
+/// Key suffix of the sensor description in the values of a sensor source.
+pub const DESCRIPTION_SUFFIX: &str = "#description";
+
+/// Current value of a sensor with its metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorValue {
+    /// Sensor value as reported by the source.
+    pub value: String,
+    /// Unit of the value, e.g. `°C`.
+    pub unit: Option<String>,
+    /// Human-readable description of the sensor.
+    pub description: Option<String>,
+    /// Name of the sensor source, e.g. `sysinfo` or `weather`.
+    pub source: String,
+    /// Time of the last update by the source, also if the value didn't change.
+    pub updated_at: DateTime<Local>,
+}
+
+/// Current sensor values by sensor key.
+#[derive(Debug, Clone, Default)]
+pub struct SensorStore {
+    sensors: HashMap<String, SensorValue>,
+}
+
+impl SensorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sensor value with metadata.
+    pub fn get(&self, key: &str) -> Option<&SensorValue> {
+        self.sensors.get(key)
+    }
+
+    /// Sensor value.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.sensors.get(key).map(|sensor| sensor.value.as_str())
+    }
+
+    /// Unit of a sensor, `None` if the sensor is unknown or the source didn't provide a unit.
+    pub fn unit(&self, key: &str) -> Option<&str> {
+        self.sensors.get(key)?.unit.as_deref()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.sensors.contains_key(key)
+    }
+
+    pub fn keys(&self) -> hash_map::Keys<'_, String, SensorValue> {
+        self.sensors.keys()
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, String, SensorValue> {
+        self.sensors.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sensors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sensors.is_empty()
+    }
+
+    /// Set the value of a sensor. The metadata of an existing sensor is kept.
+    pub fn set(&mut self, key: &str, value: impl Into<String>, source: &str) {
+        self.set_at(key, value.into(), source, Local::now());
+    }
+
+    /// Set the unit of an existing sensor.
+    pub fn set_unit(&mut self, key: &str, unit: impl Into<String>) {
+        if let Some(sensor) = self.sensors.get_mut(key) {
+            sensor.unit = Some(unit.into());
+        }
+    }
+
+    /// Set the description of an existing sensor.
+    pub fn set_description(&mut self, key: &str, description: impl Into<String>) {
+        if let Some(sensor) = self.sensors.get_mut(key) {
+            sensor.description = Some(description.into());
+        }
+    }
+
+    /// Insert a sensor value with its metadata, replacing an existing sensor.
+    pub fn insert(&mut self, key: impl Into<String>, sensor: SensorValue) -> Option<SensorValue> {
+        self.sensors.insert(key.into(), sensor)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<SensorValue> {
+        self.sensors.remove(key)
+    }
+
+    /// Store the values of a sensor source.
+    ///
+    /// `{key}#unit` and `{key}#description` entries set the metadata of sensor `{key}`. Metadata
+    /// of a sensor without a value is ignored.
+    pub fn update<'a>(
+        &mut self,
+        source: &str,
+        values: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) {
+        let now = Local::now();
+        let mut metadata = Vec::new();
+        for (key, value) in values {
+            if key.ends_with(UNIT_SUFFIX) || key.ends_with(DESCRIPTION_SUFFIX) {
+                metadata.push((key, value));
+            } else {
+                self.set_at(key, value.clone(), source, now);
+            }
+        }
+
+        for (key, value) in metadata {
+            if let Some(key) = key.strip_suffix(UNIT_SUFFIX) {
+                self.set_unit(key, value.clone());
+            } else if let Some(key) = key.strip_suffix(DESCRIPTION_SUFFIX) {
+                self.set_description(key, value.clone());
+            }
+        }
+    }
+
+    fn set_at(&mut self, key: &str, value: String, source: &str, now: DateTime<Local>) {
+        match self.sensors.get_mut(key) {
+            Some(sensor) => {
+                sensor.value = value;
+                if sensor.source != source {
+                    sensor.source = source.to_string();
+                }
+                sensor.updated_at = now;
+            }
+            None => {
+                self.sensors.insert(
+                    key.to_string(),
+                    SensorValue {
+                        value,
+                        unit: None,
+                        description: None,
+                        source: source.to_string(),
+                        updated_at: now,
+                    },
+                );
+            }
+        }
+    }
+}
+
+impl Index<&str> for SensorStore {
+    type Output = String;
+
+    /// Sensor value, panics if the sensor is unknown.
+    fn index(&self, key: &str) -> &String {
+        &self.sensors[key].value
+    }
+}
+
+/// Sensor values without a source, e.g. static test values. `#unit` and `#description` keys are
+/// stored as metadata.
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for SensorStore {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let values: HashMap<String, String> = iter
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        let mut store = SensorStore::new();
+        store.update("", &values);
+        store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_with_metadata() {
+        let mut store = SensorStore::new();
+        let values = HashMap::from([
+            ("ups_load#unit".to_string(), "%".to_string()),
+            ("ups_load".to_string(), "23".to_string()),
+            ("ups_load#description".to_string(), "UPS load".to_string()),
+            ("ups_status".to_string(), "on line".to_string()),
+            ("unknown#unit".to_string(), "W".to_string()),
+        ]);
+        store.update("exec-ups", &values);
+
+        assert_eq!(2, store.len());
+        assert_eq!(Some("23"), store.value("ups_load"));
+        assert_eq!(Some("%"), store.unit("ups_load"));
+        let sensor = store.get("ups_load").unwrap();
+        assert_eq!(Some("UPS load"), sensor.description.as_deref());
+        assert_eq!("exec-ups", sensor.source);
+        assert_eq!(None, store.unit("ups_status"));
+        assert!(!store.contains_key("unknown"));
+        assert!(!store.contains_key("ups_load#unit"));
+
+        // a new value keeps the metadata
+        let updated_at = sensor.updated_at;
+        store.set("ups_load", "25", "push");
+        let sensor = store.get("ups_load").unwrap();
+        assert_eq!("25", sensor.value);
+        assert_eq!(Some("%"), sensor.unit.as_deref());
+        assert_eq!("push", sensor.source);
+        assert!(sensor.updated_at >= updated_at);
+    }
+}
@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Daily summary of sensor values.
+//!
+//! The values of the summarized sensors are aggregated per day: average, minimum, maximum and the
+//! increase of counters. The aggregates of the previous day are provided as the sensors
+//! `{key}#yesterday_{stat}` for the summary page. The aggregates are saved in a state file, so the
+//! summary survives a restart of the sensor panel.
+
+use super::SensorStore;
+use crate::cfg::{DailySummaryConfig, SummaryStat};
+use chrono::{DateTime, Local, NaiveDate};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Source name of the daily summary sensors.
+pub const SUMMARY_SOURCE: &str = "summary";
+/// File name of the daily aggregates in the state directory.
+pub const SUMMARY_STATE_FILE: &str = "summary.json";
+
+/// Interval to save the daily aggregates.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Aggregated values of a sensor on one day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Aggregate {
+    sum: f64,
+    count: u64,
+    min: f64,
+    max: f64,
+    /// Increase of a counter. A decreasing value is a counter reset, e.g. after a reboot.
+    increase: f64,
+    /// Last value for the increase, kept over the day boundary.
+    last: Option<f64>,
+    unit: Option<String>,
+    /// Maximum number of decimal places of the sensor values.
+    decimals: usize,
+}
+
+impl Aggregate {
+    fn add(&mut self, value: f64, decimals: usize, unit: Option<&str>) {
+        if self.count == 0 {
+            (self.min, self.max) = (value, value);
+        }
+        self.sum += value;
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if let Some(last) = self.last {
+            self.increase += if value >= last { value - last } else { value };
+        }
+        self.last = Some(value);
+        self.decimals = self.decimals.max(decimals);
+        if let Some(unit) = unit {
+            self.unit = Some(unit.to_string());
+        }
+    }
+
+    /// Formatted aggregate, `None` without values.
+    fn value(&self, stat: SummaryStat) -> Option<String> {
+        let decimals = self.decimals;
+        let value = match stat {
+            _ if self.count == 0 => return None,
+            SummaryStat::Avg => {
+                let decimals = decimals.max(1);
+                return Some(format!("{:.decimals$}", self.sum / self.count as f64));
+            }
+            SummaryStat::Min => self.min,
+            SummaryStat::Max => self.max,
+            SummaryStat::Increase => self.increase,
+        };
+        Some(format!("{value:.decimals$}"))
+    }
+}
+
+/// Aggregates of the current and the previous day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryState {
+    day: NaiveDate,
+    today: HashMap<String, Aggregate>,
+    yesterday: HashMap<String, Aggregate>,
+}
+
+impl SummaryState {
+    fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            today: HashMap::new(),
+            yesterday: HashMap::new(),
+        }
+    }
+
+    /// Start a new day, today's aggregates become yesterday's if today was the previous day.
+    ///
+    /// returns: true if a new day was started.
+    fn roll_over(&mut self, day: NaiveDate) -> bool {
+        if day == self.day {
+            return false;
+        }
+        let today = std::mem::take(&mut self.today);
+        // the increase of counters continues from the last value
+        self.today = today
+            .iter()
+            .map(|(key, aggregate)| {
+                let aggregate = Aggregate {
+                    last: aggregate.last,
+                    ..Default::default()
+                };
+                (key.clone(), aggregate)
+            })
+            .collect();
+        self.yesterday = if self.day.succ_opt() == Some(day) {
+            today
+        } else {
+            HashMap::new()
+        };
+        self.day = day;
+        true
+    }
+}
+
+struct DailySummary {
+    /// Summarized sensors with the aggregate to show.
+    items: Vec<(String, SummaryStat)>,
+    state: SummaryState,
+    /// Update time of the last sample of each sensor.
+    updated_at: HashMap<String, DateTime<Local>>,
+    published: bool,
+}
+
+impl DailySummary {
+    /// Add the updated sensor values to today's aggregates and publish yesterday's summary on a
+    /// new day.
+    fn update(&mut self, values: &mut SensorStore, day: NaiveDate) {
+        if self.state.roll_over(day) {
+            self.published = false;
+        }
+
+        for (key, _) in &self.items {
+            let Some(sensor) = values.get(key) else {
+                continue;
+            };
+            if self.updated_at.get(key) == Some(&sensor.updated_at) {
+                continue;
+            }
+            self.updated_at.insert(key.clone(), sensor.updated_at);
+            let value = sensor.value.trim();
+            let Some(sample) = value.parse::<f64>().ok().filter(|value| value.is_finite()) else {
+                continue;
+            };
+            let decimals = value
+                .split_once('.')
+                .map_or(0, |(_, decimals)| decimals.len());
+            self.state.today.entry(key.clone()).or_default().add(
+                sample,
+                decimals,
+                sensor.unit.as_deref(),
+            );
+        }
+
+        if !self.published {
+            self.publish(values);
+            self.published = true;
+        }
+    }
+
+    fn publish(&self, values: &mut SensorStore) {
+        for (key, stat) in &self.items {
+            let summary_key = format!("{key}#yesterday_{}", stat.name());
+            let aggregate = self.state.yesterday.get(key);
+            match aggregate.and_then(|aggregate| aggregate.value(*stat)) {
+                Some(value) => {
+                    values.set(&summary_key, value, SUMMARY_SOURCE);
+                    if let Some(unit) = aggregate.and_then(|aggregate| aggregate.unit.as_ref()) {
+                        values.set_unit(&summary_key, unit);
+                    }
+                }
+                None => {
+                    values.remove(&summary_key);
+                }
+            }
+        }
+    }
+}
+
+/// Start aggregating the values of the summarized sensors at the given interval.
+///
+/// # Arguments
+///
+/// * `values`: a shared, reader-writer lock protected sensor store
+/// * `cfg`: daily summary configuration, nothing is aggregated if `None`
+/// * `state_file`: file to save the daily aggregates, the summary starts empty if `None`
+/// * `refresh`: update interval
+///
+/// returns: Result<(), Error>
+pub fn start_daily_summary(
+    values: Arc<RwLock<SensorStore>>,
+    cfg: Option<&DailySummaryConfig>,
+    state_file: Option<PathBuf>,
+    refresh: Duration,
+) -> anyhow::Result<()> {
+    let Some(cfg) = cfg.filter(|cfg| !cfg.items.is_empty()) else {
+        return Ok(());
+    };
+    let today = Local::now().date_naive();
+    let mut summary = DailySummary {
+        items: cfg
+            .items
+            .iter()
+            .map(|item| (item.sensor.clone(), item.stat))
+            .collect(),
+        state: state_file
+            .as_deref()
+            .and_then(load_state)
+            .unwrap_or_else(|| SummaryState::new(today)),
+        updated_at: HashMap::new(),
+        published: false,
+    };
+
+    info!(
+        "Starting daily summary of {} items with refresh={}ms",
+        summary.items.len(),
+        refresh.as_millis()
+    );
+
+    std::thread::Builder::new()
+        .name("summary".into())
+        .spawn(move || {
+            let mut last_save = Instant::now();
+            loop {
+                let start = Instant::now();
+                let day = summary.state.day;
+                {
+                    let mut values = values.write().expect("Poisoned sensor RwLock");
+                    summary.update(&mut values, Local::now().date_naive());
+                }
+                if let Some(state_file) = &state_file
+                    && (last_save.elapsed() >= SAVE_INTERVAL || summary.state.day != day)
+                {
+                    save_state(state_file, &summary.state);
+                    last_save = Instant::now();
+                }
+                let elapsed = start.elapsed();
+                if refresh > elapsed {
+                    std::thread::sleep(refresh - elapsed);
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+fn load_state(path: &Path) -> Option<SummaryState> {
+    let state = fs::read_to_string(path)
+        .map_err(|e| debug!("No daily summary in {path:?}: {e}"))
+        .ok()?;
+    serde_json::from_str(&state)
+        .map_err(|e| warn!("Invalid daily summary in {path:?}: {e}"))
+        .ok()
+}
+
+/// Save the daily aggregates. Errors are only logged, the aggregates are kept in memory.
+fn save_state(path: &Path, state: &SummaryState) {
+    let tmp = path.with_extension("tmp");
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&tmp, serde_json::to_vec(state).unwrap_or_default()))
+        .and_then(|_| fs::rename(&tmp, path));
+    if let Err(e) = result {
+        warn!("Failed to save daily summary to {path:?}: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_previous_day() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let mut summary = DailySummary {
+            items: vec![
+                ("temperature_cpu".to_string(), SummaryStat::Avg),
+                ("temperature_cpu".to_string(), SummaryStat::Max),
+                (
+                    "network_eth0_total_received_bytes".to_string(),
+                    SummaryStat::Increase,
+                ),
+            ],
+            state: SummaryState::new(day),
+            updated_at: HashMap::new(),
+            published: false,
+        };
+        let mut values = SensorStore::new();
+        let mut update = |values: &mut SensorStore, temp: &str, received: &str, day| {
+            values.set("temperature_cpu", temp, "sysinfo");
+            values.set_unit("temperature_cpu", "°C");
+            values.set("network_eth0_total_received_bytes", received, "sysinfo");
+            summary.update(values, day);
+        };
+
+        update(&mut values, "40", "1000", day);
+        update(&mut values, "60", "1500", day);
+        // a counter reset after a reboot
+        update(&mut values, "50", "200", day);
+        assert!(!values.contains_key("temperature_cpu#yesterday_max"));
+
+        let next_day = day.succ_opt().unwrap();
+        update(&mut values, "45", "300", next_day);
+        assert_eq!(Some("50.0"), values.value("temperature_cpu#yesterday_avg"));
+        assert_eq!(Some("60"), values.value("temperature_cpu#yesterday_max"));
+        assert_eq!(Some("°C"), values.unit("temperature_cpu#yesterday_max"));
+        assert_eq!(
+            Some("700"),
+            values.value("network_eth0_total_received_bytes#yesterday_increase")
+        );
+
+        // a gap of more than a day has no summary
+        update(&mut values, "45", "300", next_day + chrono::Days::new(2));
+        assert!(!values.contains_key("temperature_cpu#yesterday_avg"));
+    }
+}
@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! System sensor source using aster-sysinfo.
+//!
+//! Sensor values are read directly from the system, eliminating the need for external scripts
+//! and text files.
+
+use super::SensorSource;
+use crate::cfg::SensorIntervals;
+use anyhow::anyhow;
+use aster_sysinfo::{
+    SysinfoSource, update_audio_sensors, update_battery_sensors, update_bluetooth_sensors,
+    update_hwmon_sensors, update_maintenance_sensors, update_storage_sensors,
+    update_thermal_zone_sensors, update_wireless_sensors,
+};
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Default refresh interval of the individual disk and maintenance sensors.
+const STORAGE_REFRESH: Duration = Duration::from_secs(300);
+
+/// Minimum poll interval of a sensor group.
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// System sensor group with its own poll interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SensorGroup {
+    System,
+    Gpu,
+    Hwmon,
+    Thermal,
+    Wireless,
+    Bluetooth,
+    Battery,
+    Audio,
+    Storage,
+}
+
+/// Poll interval and last update of a sensor group.
+#[derive(Debug)]
+struct GroupSchedule {
+    group: SensorGroup,
+    interval: Duration,
+    last_update: Option<Instant>,
+}
+
+impl GroupSchedule {
+    /// Check if the group is due in a poll at `now`. Polls up to `tolerance` before the end of
+    /// the group interval are accepted, so a group isn't delayed by a full source interval.
+    fn is_due(&self, now: Instant, tolerance: Duration) -> bool {
+        self.last_update
+            .is_none_or(|last| now.saturating_duration_since(last) + tolerance >= self.interval)
+    }
+}
+
+/// System sensor source with the name `sysinfo`.
+pub struct SystemSensorSource {
+    sysinfo_source: SysinfoSource,
+    refresh: Duration,
+    hwmon_labels: HashMap<String, String>,
+    groups: Vec<GroupSchedule>,
+}
+
+impl SystemSensorSource {
+    /// Create a new system sensor source.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh`: default sensor refresh interval
+    /// * `intervals`: poll intervals of the sensor groups, overriding `refresh`.
+    /// * `hwmon_labels`: rename map for chip-qualified hwmon sensor keys.
+    /// * `cpu_temperature_sources`: preference order of the CPU temperature chips, the default
+    ///   order of aster-sysinfo is used if not set.
+    pub fn new(
+        refresh: Duration,
+        intervals: &SensorIntervals,
+        hwmon_labels: HashMap<String, String>,
+        cpu_temperature_sources: Option<Vec<String>>,
+    ) -> Self {
+        let mut sysinfo_source = SysinfoSource::new();
+        if let Some(sources) = cpu_temperature_sources {
+            sysinfo_source.set_cpu_temperature_sources(sources);
+        }
+
+        let interval = |secs: Option<f32>, default: Duration| {
+            secs.map(|secs| Duration::from_secs_f32(secs.max(0.0)))
+                .unwrap_or(default)
+                .max(MIN_INTERVAL)
+        };
+        let system = interval(intervals.system, refresh);
+        let groups: Vec<_> = [
+            (SensorGroup::System, system),
+            (SensorGroup::Gpu, interval(intervals.gpu, system)),
+            (SensorGroup::Hwmon, interval(intervals.hwmon, system)),
+            (SensorGroup::Thermal, interval(intervals.thermal, system)),
+            (SensorGroup::Wireless, interval(intervals.wireless, system)),
+            (
+                SensorGroup::Bluetooth,
+                interval(intervals.bluetooth, system),
+            ),
+            (SensorGroup::Battery, interval(intervals.battery, system)),
+            (SensorGroup::Audio, interval(intervals.audio, system)),
+            (
+                SensorGroup::Storage,
+                interval(intervals.storage, STORAGE_REFRESH),
+            ),
+        ]
+        .into_iter()
+        .map(|(group, interval)| GroupSchedule {
+            group,
+            interval,
+            last_update: None,
+        })
+        .collect();
+        let refresh = groups
+            .iter()
+            .map(|schedule| schedule.interval)
+            .min()
+            .unwrap_or(system);
+
+        Self {
+            sysinfo_source,
+            refresh,
+            hwmon_labels,
+            groups,
+        }
+    }
+
+    /// Read the sensor groups which are due, or all groups with `force`.
+    fn update_sensors(
+        &mut self,
+        sensors: &mut HashMap<String, String>,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let mut error = None;
+        let mut check = |result: Result<(), Box<dyn std::error::Error>>, name: &str| {
+            if let Err(e) = result {
+                let e = anyhow!("{name} sensor update failed: {e}");
+                if error.is_none() {
+                    error = Some(e);
+                } else {
+                    warn!("{e}");
+                }
+            }
+        };
+
+        let now = Instant::now();
+        let tolerance = self.refresh / 2;
+        for schedule in &mut self.groups {
+            if !force && !schedule.is_due(now, tolerance) {
+                continue;
+            }
+            schedule.last_update = Some(now);
+
+            match schedule.group {
+                SensorGroup::System => {
+                    self.sysinfo_source.refresh();
+                    check(self.sysinfo_source.update_sensors(sensors), "System");
+                    check(
+                        self.sysinfo_source.update_workload_sensors(sensors),
+                        "Workload",
+                    );
+                }
+                SensorGroup::Gpu => check(
+                    self.sysinfo_source.update_gpu_engine_sensors(sensors),
+                    "GPU engine",
+                ),
+                SensorGroup::Hwmon => {
+                    check(update_hwmon_sensors(sensors, &self.hwmon_labels), "hwmon")
+                }
+                SensorGroup::Thermal => check(update_thermal_zone_sensors(sensors), "Thermal zone"),
+                SensorGroup::Wireless => check(update_wireless_sensors(sensors), "Wireless"),
+                SensorGroup::Bluetooth => check(update_bluetooth_sensors(sensors), "Bluetooth"),
+                SensorGroup::Battery => check(update_battery_sensors(sensors), "Battery"),
+                SensorGroup::Audio => check(update_audio_sensors(sensors), "Audio"),
+                SensorGroup::Storage => {
+                    debug!("Refreshing individual disks and maintenance sensors");
+                    check(update_storage_sensors(sensors, false), "Storage");
+                    check(update_maintenance_sensors(sensors), "Maintenance");
+                }
+            }
+        }
+
+        error.map_or(Ok(()), Err)
+    }
+}
+
+impl SensorSource for SystemSensorSource {
+    fn name(&self) -> &str {
+        "sysinfo"
+    }
+
+    fn interval(&self) -> Duration {
+        self.refresh
+    }
+
+    fn init(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        self.sysinfo_source.update_static_sensors(values);
+        values.insert("asterctl_version".into(), env!("CARGO_PKG_VERSION").into());
+        self.update_sensors(values, true)
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        self.update_sensors(values, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensor_groups_are_polled_at_their_interval() {
+        let intervals = SensorIntervals {
+            system: Some(2.0),
+            hwmon: Some(0.0),
+            storage: Some(600.0),
+            ..Default::default()
+        };
+        let source =
+            SystemSensorSource::new(Duration::from_secs(1), &intervals, HashMap::new(), None);
+        assert_eq!(MIN_INTERVAL, source.interval());
+        let interval = |group| {
+            source
+                .groups
+                .iter()
+                .find(|schedule| schedule.group == group)
+                .unwrap()
+                .interval
+        };
+        assert_eq!(Duration::from_secs(2), interval(SensorGroup::System));
+        assert_eq!(Duration::from_secs(2), interval(SensorGroup::Battery));
+        assert_eq!(Duration::from_secs(600), interval(SensorGroup::Storage));
+
+        let now = Instant::now();
+        let schedule = GroupSchedule {
+            group: SensorGroup::System,
+            interval: Duration::from_secs(5),
+            last_update: Some(now),
+        };
+        let tolerance = Duration::from_millis(500);
+        assert!(!schedule.is_due(now + Duration::from_secs(4), tolerance));
+        assert!(schedule.is_due(now + Duration::from_millis(4600), tolerance));
+    }
+}
@@ -0,0 +1,237 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Open-Meteo weather sensor source.
+//!
+//! Polls the current weather and a daily forecast for a configured location from the free
+//! [Open-Meteo](https://open-meteo.com/) forecast API. No API key is required.
+
+use super::SensorSource;
+use super::http::{http_agent, value_to_string};
+use super::schedule::PollSchedule;
+use crate::cfg::WeatherConfig;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// Current weather fields: Open-Meteo variable and sensor key suffix.
+const CURRENT_FIELDS: [(&str, &str); 5] = [
+    ("temperature_2m", "temperature"),
+    ("relative_humidity_2m", "humidity"),
+    ("wind_speed_10m", "wind_speed"),
+    ("weather_code", "code"),
+    ("is_day", "is_day"),
+];
+
+/// Daily forecast fields: Open-Meteo variable and sensor key suffix.
+const DAILY_FIELDS: [(&str, &str); 4] = [
+    ("weather_code", "code"),
+    ("temperature_2m_max", "temperature_max"),
+    ("temperature_2m_min", "temperature_min"),
+    ("precipitation_probability_max", "precipitation_probability"),
+];
+
+/// Open-Meteo weather sensor source with the name `weather`.
+///
+/// Sensor keys:
+/// - `weather_temperature`, `weather_humidity`, `weather_wind_speed`, `weather_code`,
+///   `weather_condition`, `weather_is_day`: current weather.
+/// - `weather_day{n}_code`, `weather_day{n}_condition`, `weather_day{n}_temperature_max`,
+///   `weather_day{n}_temperature_min`, `weather_day{n}_precipitation_probability`: daily
+///   forecast, `n` = 0 is today.
+pub struct WeatherSensorSource {
+    cfg: WeatherConfig,
+    url: String,
+    forecast_days: u8,
+    schedule: PollSchedule,
+    agent: ureq::Agent,
+}
+
+impl WeatherSensorSource {
+    /// Create a new weather sensor source for the location of the weather configuration.
+    pub fn new(cfg: WeatherConfig) -> Self {
+        // Open-Meteo updates the forecast every 15 minutes
+        let schedule = PollSchedule::new(
+            "weather",
+            cfg.interval,
+            Duration::from_secs(900),
+            Duration::from_secs(60),
+            cfg.jitter,
+        );
+        let forecast_days = cfg.forecast_days.unwrap_or(3).clamp(1, 16);
+        let url = cfg
+            .url
+            .clone()
+            .unwrap_or_else(|| OPEN_METEO_URL.to_string());
+        let agent = http_agent(Duration::from_secs_f32(
+            cfg.timeout.unwrap_or(15.0).max(1.0),
+        ));
+
+        Self {
+            cfg,
+            url,
+            forecast_days,
+            schedule,
+            agent,
+        }
+    }
+}
+
+impl SensorSource for WeatherSensorSource {
+    fn name(&self) -> &str {
+        "weather"
+    }
+
+    fn interval(&self) -> Duration {
+        self.schedule.interval()
+    }
+
+    fn jitter(&self) -> Duration {
+        self.schedule.jitter()
+    }
+
+    fn poll(&mut self, values: &mut HashMap<String, String>) -> anyhow::Result<()> {
+        let current = CURRENT_FIELDS.map(|(field, _)| field).join(",");
+        let daily = DAILY_FIELDS.map(|(field, _)| field).join(",");
+        let mut request = self
+            .agent
+            .get(&self.url)
+            .query("latitude", self.cfg.latitude.to_string())
+            .query("longitude", self.cfg.longitude.to_string())
+            .query("current", current)
+            .query("daily", daily)
+            .query("forecast_days", self.forecast_days.to_string())
+            .query("timezone", "auto");
+        if let Some(unit) = &self.cfg.temperature_unit {
+            request = request.query("temperature_unit", unit);
+        }
+
+        let document = request.call()?.body_mut().read_json::<Value>()?;
+        values.extend(map_forecast(&document));
+        Ok(())
+    }
+}
+
+/// Map an Open-Meteo forecast response to sensor values.
+fn map_forecast(document: &Value) -> HashMap<String, String> {
+    let mut sensors = HashMap::new();
+
+    let current = &document["current"];
+    let current_units = &document["current_units"];
+    for (field, suffix) in CURRENT_FIELDS {
+        let key = format!("weather_{suffix}");
+        insert_value(&mut sensors, &key, &current[field], &current_units[field]);
+    }
+    if let Some(code) = current["weather_code"].as_u64() {
+        sensors.insert(
+            "weather_condition".to_string(),
+            weather_condition(code).to_string(),
+        );
+    }
+
+    let daily = &document["daily"];
+    let daily_units = &document["daily_units"];
+    for (field, suffix) in DAILY_FIELDS {
+        let Some(days) = daily[field].as_array() else {
+            continue;
+        };
+        for (day, value) in days.iter().enumerate() {
+            let key = format!("weather_day{day}_{suffix}");
+            insert_value(&mut sensors, &key, value, &daily_units[field]);
+        }
+    }
+    if let Some(codes) = daily["weather_code"].as_array() {
+        for (day, code) in codes.iter().enumerate() {
+            if let Some(code) = code.as_u64() {
+                sensors.insert(
+                    format!("weather_day{day}_condition"),
+                    weather_condition(code).to_string(),
+                );
+            }
+        }
+    }
+
+    sensors
+}
+
+fn insert_value(sensors: &mut HashMap<String, String>, key: &str, value: &Value, unit: &Value) {
+    let Some(value) = value_to_string(value) else {
+        return;
+    };
+    sensors.insert(key.to_string(), value);
+    // weather codes are reported with the unit "wmo code"
+    if let Some(unit) = unit.as_str()
+        && !unit.is_empty()
+        && !unit.starts_with("wmo")
+    {
+        sensors.insert(format!("{key}#unit"), unit.to_string());
+    }
+}
+
+/// Map a WMO weather interpretation code to a weather condition name.
+///
+/// The names are intended for icon file names, e.g. `weather/partly_cloudy.png`.
+pub fn weather_condition(code: u64) -> &'static str {
+    match code {
+        0 => "clear",
+        1 => "mostly_clear",
+        2 => "partly_cloudy",
+        3 => "overcast",
+        45 | 48 => "fog",
+        51..=57 => "drizzle",
+        61..=67 => "rain",
+        71..=77 => "snow",
+        80..=82 => "showers",
+        85 | 86 => "snow_showers",
+        95..=99 => "thunderstorm",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn map_open_meteo_forecast() {
+        let document = json!({
+            "current_units": { "temperature_2m": "°C", "relative_humidity_2m": "%", "weather_code": "wmo code" },
+            "current": { "time": "2026-10-16T12:00", "temperature_2m": 14.2, "relative_humidity_2m": 71, "weather_code": 61 },
+            "daily_units": { "weather_code": "wmo code", "temperature_2m_max": "°C" },
+            "daily": {
+                "time": ["2026-10-16", "2026-10-17"],
+                "weather_code": [61, 2],
+                "temperature_2m_max": [15.1, 18.4],
+                "precipitation_probability_max": [80, null]
+            }
+        });
+
+        let sensors = map_forecast(&document);
+
+        let expected = HashMap::from([
+            ("weather_temperature", "14.2"),
+            ("weather_temperature#unit", "°C"),
+            ("weather_humidity", "71"),
+            ("weather_humidity#unit", "%"),
+            ("weather_code", "61"),
+            ("weather_condition", "rain"),
+            ("weather_day0_code", "61"),
+            ("weather_day0_condition", "rain"),
+            ("weather_day1_code", "2"),
+            ("weather_day1_condition", "partly_cloudy"),
+            ("weather_day0_temperature_max", "15.1"),
+            ("weather_day0_temperature_max#unit", "°C"),
+            ("weather_day1_temperature_max", "18.4"),
+            ("weather_day1_temperature_max#unit", "°C"),
+            ("weather_day0_precipitation_probability", "80"),
+        ]);
+        let expected: HashMap<String, String> = expected
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(expected, sensors);
+    }
+}
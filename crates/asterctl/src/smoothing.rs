@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Smoothing of jittery sensor values and hysteresis of the color rules.
+//!
+//! Values like the CPU load or fan speeds change with every sensor update. A smoothed value and a
+//! color which doesn't toggle around a rule level keep the panel calm. The state is kept per
+//! sensor element and sensor key.
+
+use crate::cfg::{Sensor, Smoothing, SmoothingMethod};
+use crate::sensors::SensorValue;
+use chrono::{DateTime, Local, TimeDelta};
+use std::collections::{HashMap, VecDeque};
+
+/// Default smoothing time window in seconds.
+const DEFAULT_WINDOW: f32 = 5.0;
+
+/// Smoothing and hysteresis state of a sensor element.
+#[derive(Debug, Default)]
+struct ElementState {
+    /// Update time of the last sample.
+    updated_at: Option<DateTime<Local>>,
+    /// Samples of the moving average, oldest first.
+    samples: VecDeque<(DateTime<Local>, f64)>,
+    /// Exponential moving average.
+    ema: Option<f64>,
+    /// Shown value and the time it was shown first.
+    shown: Option<(String, DateTime<Local>)>,
+    /// Value the current color was resolved with.
+    color_value: Option<f32>,
+}
+
+/// Smoothing and color hysteresis state of all sensor elements.
+#[derive(Debug, Default)]
+pub struct ValueSmoother {
+    states: HashMap<String, ElementState>,
+}
+
+impl ValueSmoother {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Smoothed sensor value of an element, formatted with the decimal places of the sensor value.
+    ///
+    /// A new sample is only taken if the source updated the value. Non-numeric values are
+    /// returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `element`: unique key of the sensor element and sensor key.
+    /// * `cfg`: smoothing configuration of the element.
+    /// * `value`: current sensor value.
+    /// * `now`: current time for the hold time.
+    pub fn smooth(
+        &mut self,
+        element: &str,
+        cfg: &Smoothing,
+        value: &SensorValue,
+        now: DateTime<Local>,
+    ) -> String {
+        let Ok(sample) = value.value.trim().parse::<f64>() else {
+            return value.value.clone();
+        };
+        let state = self.states.entry(element.to_string()).or_default();
+        let window = TimeDelta::milliseconds(
+            (cfg.window.unwrap_or(DEFAULT_WINDOW).max(0.0) * 1000.0) as i64,
+        );
+
+        if state.updated_at != Some(value.updated_at) {
+            let elapsed = state
+                .updated_at
+                .map(|last| (value.updated_at - last).as_seconds_f64().max(0.0));
+            state.updated_at = Some(value.updated_at);
+            state.ema = Some(match (state.ema, elapsed) {
+                (Some(ema), Some(elapsed)) if window > TimeDelta::zero() => {
+                    let alpha = 1.0 - (-elapsed / window.as_seconds_f64()).exp();
+                    ema + alpha * (sample - ema)
+                }
+                _ => sample,
+            });
+            state.samples.push_back((value.updated_at, sample));
+            while state
+                .samples
+                .front()
+                .is_some_and(|(time, _)| *time < value.updated_at - window)
+            {
+                state.samples.pop_front();
+            }
+        }
+
+        let smoothed = match cfg.method {
+            SmoothingMethod::Ema => state.ema.unwrap_or(sample),
+            SmoothingMethod::Average => {
+                state.samples.iter().map(|(_, value)| value).sum::<f64>()
+                    / state.samples.len().max(1) as f64
+            }
+        };
+        let decimals = value
+            .value
+            .trim()
+            .split_once('.')
+            .map_or(0, |(_, decimals)| decimals.len());
+        let text = format!("{smoothed:.decimals$}");
+
+        let hold = TimeDelta::milliseconds((cfg.hold.unwrap_or(0.0).max(0.0) * 1000.0) as i64);
+        match &state.shown {
+            Some((shown, since)) if now - *since < hold => shown.clone(),
+            _ => {
+                state.shown = Some((text.clone(), now));
+                text
+            }
+        }
+    }
+
+    /// Value to resolve the color of an element with, applying the hysteresis of the color rules.
+    ///
+    /// The color only changes if the value is more than `hysteresis` beyond the level of the
+    /// color rule, otherwise the previous color value is kept.
+    pub fn color_value(
+        &mut self,
+        element: &str,
+        sensor: &Sensor,
+        hysteresis: f32,
+        value: f32,
+    ) -> f32 {
+        let state = self.states.entry(element.to_string()).or_default();
+        let color = |value: f32| sensor.resolve_color(&value.to_string());
+
+        let color_value = match state.color_value {
+            Some(previous) if color(value) != color(previous) => {
+                // the shifted value still has the new color if it's beyond the hysteresis
+                let shifted = if value > previous {
+                    value - hysteresis
+                } else {
+                    value + hysteresis
+                };
+                if color(shifted) == color(value) {
+                    value
+                } else {
+                    previous
+                }
+            }
+            _ => value,
+        };
+        state.color_value = Some(color_value);
+        color_value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(value: &str, updated_at: DateTime<Local>) -> SensorValue {
+        SensorValue {
+            value: value.to_string(),
+            unit: None,
+            description: None,
+            source: "sysinfo".to_string(),
+            updated_at,
+        }
+    }
+
+    #[test]
+    fn smooth_values() {
+        let start = Local::now();
+        let at = |secs: i64| start + TimeDelta::seconds(secs);
+        let mut smoother = ValueSmoother::new();
+        let average = Smoothing {
+            method: SmoothingMethod::Average,
+            window: Some(2.0),
+            hold: None,
+        };
+        let ema = Smoothing {
+            window: Some(1.0),
+            hold: Some(10.0),
+            ..Default::default()
+        };
+
+        let mut smooth = |cfg: &Smoothing, value: &str, secs: i64| {
+            smoother.smooth("cpu@0,0", cfg, &sample(value, at(secs)), at(secs))
+        };
+        assert_eq!("10.0", smooth(&average, "10.0", 0));
+        assert_eq!("15.0", smooth(&average, "20.0", 1));
+        assert_eq!("20.0", smooth(&average, "30.0", 2));
+        // samples older than the window are dropped
+        assert_eq!("45.0", smooth(&average, "60.0", 4));
+        // an unchanged update time doesn't add a sample
+        assert_eq!("45.0", smooth(&average, "60.0", 4));
+        assert_eq!("up", smooth(&average, "up", 5));
+
+        // the value is held for 10 seconds
+        let mut smooth = |value: &str, secs: i64| {
+            smoother.smooth("fan@0,0", &ema, &sample(value, at(secs)), at(secs))
+        };
+        assert_eq!("1000", smooth("1000", 0));
+        assert_eq!("1000", smooth("2000", 1));
+        assert_eq!("2000", smooth("2000", 10));
+    }
+
+    #[test]
+    fn color_hysteresis() {
+        let sensor: Sensor = serde_json::from_str(
+            r##"{ "mode": 1, "label": "cpu", "x": 0, "y": 0, "fontColor": "#00ff00",
+              "warnAbove": 50, "warnColor": "#ffff00" }"##,
+        )
+        .unwrap();
+        let mut smoother = ValueSmoother::new();
+        let mut color_value = |value| smoother.color_value("cpu@0,0", &sensor, 2.0, value);
+
+        assert_eq!(45.0, color_value(45.0));
+        assert_eq!(45.0, color_value(51.0));
+        assert_eq!(53.0, color_value(53.0));
+        assert_eq!(53.0, color_value(49.0));
+        assert_eq!(47.0, color_value(47.0));
+    }
+}
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Ticker region shown on top of all pages.
+//!
+//! The ticker cycles through its configured items independently of the page rotation.
+
+use crate::cfg::TickerConfig;
+use crate::sensors::SensorStore;
+use std::time::{Duration, Instant};
+
+/// Ticker item rotation state.
+pub struct Ticker {
+    items: Vec<String>,
+    item_time: Duration,
+    start: Instant,
+}
+
+impl Ticker {
+    /// Create a new ticker from the given configuration.
+    ///
+    /// Returns `None` if no ticker items are configured.
+    pub fn new(cfg: &TickerConfig) -> Option<Self> {
+        if cfg.items.is_empty() {
+            return None;
+        }
+        Some(Self {
+            items: cfg.items.clone(),
+            item_time: Duration::from_secs_f32(cfg.item_time.unwrap_or(5.0).max(0.1)),
+            start: Instant::now(),
+        })
+    }
+
+    /// Get the text of the currently active ticker item with expanded sensor placeholders.
+    pub fn current_text(&self, values: &SensorStore) -> String {
+        self.text_at(self.start.elapsed(), values)
+    }
+
+    fn text_at(&self, elapsed: Duration, values: &SensorStore) -> String {
+        let idx =
+            (elapsed.as_secs_f32() / self.item_time.as_secs_f32()) as usize % self.items.len();
+        expand_placeholders(&self.items[idx], values)
+    }
+}
+
+/// Replace `{sensor_key}` placeholders with the sensor value and its unit, if available.
+///
+/// Unknown sensor keys are replaced with `N/A`. Unclosed braces are kept as is.
+pub fn expand_placeholders(template: &str, values: &SensorStore) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let key = &rest[start + 1..start + len];
+        match values.get(key) {
+            Some(sensor) => {
+                result.push_str(&sensor.value);
+                if let Some(unit) = &sensor.unit {
+                    result.push_str(unit);
+                }
+            }
+            None => result.push_str("N/A"),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Sensor keys of the `{sensor_key}` placeholders in a template.
+pub fn placeholder_keys(template: &str) -> Vec<&str> {
+    let mut keys = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        keys.push(&rest[start + 1..start + len]);
+        rest = &rest[start + len + 1..];
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> SensorStore {
+        SensorStore::from_iter([
+            ("temperature_cpu".to_string(), "42".to_string()),
+            ("temperature_cpu#unit".to_string(), "°C".to_string()),
+            ("cpu_usage".to_string(), "7".to_string()),
+        ])
+    }
+
+    #[test]
+    fn expand_placeholders_with_units() {
+        assert_eq!(
+            "CPU 42°C 7% {open",
+            expand_placeholders("CPU {temperature_cpu} {cpu_usage}% {open", &values())
+        );
+        assert_eq!(
+            "GPU N/A",
+            expand_placeholders("GPU {temperature_gpu}", &values())
+        );
+        assert_eq!("Hello", expand_placeholders("Hello", &values()));
+    }
+
+    #[test]
+    fn ticker_rotates_items_by_time() {
+        let cfg = TickerConfig {
+            items: vec!["one".into(), "two {cpu_usage}".into()],
+            item_time: Some(2.0),
+            height: None,
+            font_family: None,
+            font_size: None,
+            font_color: None,
+            background: None,
+            text_align: None,
+        };
+        let ticker = Ticker::new(&cfg).unwrap();
+
+        assert_eq!("one", ticker.text_at(Duration::from_secs(1), &values()));
+        assert_eq!("two 7", ticker.text_at(Duration::from_secs(3), &values()));
+        assert_eq!("one", ticker.text_at(Duration::from_secs(5), &values()));
+    }
+}
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Countdown timer page, e.g. for the pomodoro technique.
+//!
+//! A timer is started with a control command and replaces the page rotation until it is over or
+//! cancelled. The finished timer stays visible for [FINISHED_TIME] before the page rotation
+//! resumes.
+
+use chrono::{DateTime, Local};
+use std::time::{Duration, Instant};
+
+/// Display time of a finished timer.
+pub const FINISHED_TIME: Duration = Duration::from_secs(10);
+
+/// A running countdown timer.
+#[derive(Debug, Clone)]
+pub struct Timer {
+    label: Option<String>,
+    duration: Duration,
+    start: Instant,
+    end_time: DateTime<Local>,
+}
+
+impl Timer {
+    /// Start a new timer with the given duration and optional label.
+    pub fn new(duration: Duration, label: Option<String>) -> Self {
+        Self {
+            label,
+            duration,
+            start: Instant::now(),
+            end_time: Local::now() + duration,
+        }
+    }
+
+    /// Optional label shown below the remaining time.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Wall clock time when the timer is over.
+    pub fn end_time(&self) -> DateTime<Local> {
+        self.end_time
+    }
+
+    /// Remaining time, zero if the timer is over.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.duration
+            .saturating_sub(now.saturating_duration_since(self.start))
+    }
+
+    /// Remaining part of the timer duration, from 1.0 at the start to 0.0 when the timer is over.
+    pub fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 0.0;
+        }
+        self.remaining(now).as_secs_f32() / self.duration.as_secs_f32()
+    }
+
+    /// Check if the timer is over and the finished timer has been shown for [FINISHED_TIME].
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration + FINISHED_TIME
+    }
+}
+
+/// Format the remaining time as `mm:ss`, or `h:mm:ss` from one hour on.
+///
+/// Started seconds are rounded up, so a 25 minute timer starts with `25:00` and shows `00:00`
+/// only when it is over.
+pub fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Parse a timer duration like `25m`, `1h30m`, `90s` or `2.5m`. Numbers without unit are seconds.
+pub fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid duration '{duration}', expected e.g. 25m, 1h30m or 90s");
+    let mut total = 0.0;
+    let mut rest = duration.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    while !rest.is_empty() {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number = rest[..end].parse::<f64>().map_err(|_| invalid())?;
+        rest = rest[end..].trim_start();
+        let unit = rest.chars().next();
+        let factor = match unit {
+            Some('h') => 3600.0,
+            Some('m') => 60.0,
+            Some('s') | None => 1.0,
+            Some(_) => return Err(invalid()),
+        };
+        if unit.is_some() {
+            rest = rest[1..].trim_start();
+        }
+        total += number * factor;
+    }
+    if total <= 0.0 || total > u32::MAX as f64 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs_f64(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timer_durations() {
+        assert_eq!(Ok(Duration::from_secs(1500)), parse_duration("25m"));
+        assert_eq!(Ok(Duration::from_secs(5400)), parse_duration("1h30m"));
+        assert_eq!(Ok(Duration::from_secs(5400)), parse_duration("1h 30m"));
+        assert_eq!(Ok(Duration::from_secs(90)), parse_duration("90s"));
+        assert_eq!(Ok(Duration::from_secs(90)), parse_duration("90"));
+        assert_eq!(Ok(Duration::from_secs(150)), parse_duration("2.5m"));
+
+        for invalid in ["", "0", "m", "25x", "1.2.3m", "-5m"] {
+            assert!(parse_duration(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn timer_progress() {
+        assert_eq!("25:00", format_remaining(Duration::from_secs(1500)));
+        assert_eq!("00:05", format_remaining(Duration::from_millis(4001)));
+        assert_eq!("00:00", format_remaining(Duration::ZERO));
+        assert_eq!("1:30:00", format_remaining(Duration::from_secs(5400)));
+
+        let timer = Timer::new(Duration::from_secs(100), Some("Focus".into()));
+        let start = timer.start;
+        assert_eq!(Some("Focus"), timer.label());
+        assert_eq!(1.0, timer.progress(start));
+        assert_eq!(0.25, timer.progress(start + Duration::from_secs(75)));
+        assert_eq!(
+            Duration::ZERO,
+            timer.remaining(start + Duration::from_secs(101))
+        );
+        assert!(!timer.is_expired(start + Duration::from_secs(101)));
+        assert!(timer.is_expired(start + Duration::from_secs(110)));
+    }
+}
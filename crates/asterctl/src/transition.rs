@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2026 Gabriel Max
+
+//! Page transition animations.
+//!
+//! A transition blends the previous and the new display image of a page change in a short burst
+//! of intermediate frames. The frame rate is capped, so that slow serial links aren't overwhelmed.
+
+use crate::cfg::{PageTransition, TransitionEffect};
+use image::{Rgba, RgbaImage};
+use std::time::Duration;
+
+/// Default transition duration in seconds.
+const DEFAULT_DURATION: f32 = 0.5;
+/// Default maximum frame rate of a transition.
+const DEFAULT_FPS: f32 = 10.0;
+
+/// Intermediate frames from a previous to a new display image.
+///
+/// Only the changed region of the display is animated, e.g. a single slot of a split-screen
+/// configuration above the ticker.
+pub struct Transition {
+    effect: TransitionEffect,
+    from: RgbaImage,
+    to: RgbaImage,
+    x: u32,
+    width: u32,
+    height: u32,
+    frames: u32,
+    interval: Duration,
+}
+
+impl Transition {
+    /// Create a transition of the display region starting at `x` with the given width and height.
+    ///
+    /// Returns `None` if the configured duration and frame rate don't result in an intermediate
+    /// frame, or if the image sizes don't match.
+    pub fn new(
+        cfg: &PageTransition,
+        from: RgbaImage,
+        to: RgbaImage,
+        x: u32,
+        width: u32,
+        height: u32,
+    ) -> Option<Self> {
+        if from.dimensions() != to.dimensions() {
+            return None;
+        }
+        let width = width.min(to.width().saturating_sub(x));
+        let height = height.min(to.height());
+        let duration = cfg.duration.unwrap_or(DEFAULT_DURATION).max(0.0);
+        let fps = cfg.fps.unwrap_or(DEFAULT_FPS).max(0.0);
+        let total_frames = (duration * fps).round() as u32;
+        if total_frames < 2 || width == 0 || height == 0 {
+            return None;
+        }
+
+        Some(Self {
+            effect: cfg.effect,
+            from,
+            to,
+            x,
+            width,
+            height,
+            frames: total_frames - 1,
+            interval: Duration::from_secs_f32(duration / total_frames as f32),
+        })
+    }
+
+    /// Number of intermediate frames, without the final image.
+    pub fn frame_count(&self) -> u32 {
+        self.frames
+    }
+
+    /// Display time of each frame.
+    pub fn frame_interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Render the intermediate frames in order.
+    pub fn frames(&self) -> impl Iterator<Item = RgbaImage> + '_ {
+        (1..=self.frames).map(|i| self.frame(i as f32 / (self.frames + 1) as f32))
+    }
+
+    /// Render the frame at the given progress from 0 (previous image) to 1 (new image).
+    pub fn frame(&self, progress: f32) -> RgbaImage {
+        let progress = progress.clamp(0.0, 1.0);
+        let (x0, w) = (self.x, self.width);
+        let shift = ((w as f32 * progress).round() as u32).min(w);
+        let mut image = self.to.clone();
+
+        for y in 0..self.height {
+            for dx in 0..w {
+                let pixel = match self.effect {
+                    // the new page pushes the previous page out to the left
+                    TransitionEffect::SlideLeft if dx < w - shift => {
+                        *self.from.get_pixel(x0 + dx + shift, y)
+                    }
+                    TransitionEffect::SlideLeft => *self.to.get_pixel(x0 + dx + shift - w, y),
+                    TransitionEffect::SlideRight if dx >= shift => {
+                        *self.from.get_pixel(x0 + dx - shift, y)
+                    }
+                    TransitionEffect::SlideRight => *self.to.get_pixel(x0 + dx + w - shift, y),
+                    TransitionEffect::Wipe if dx < shift => continue,
+                    TransitionEffect::Wipe => *self.from.get_pixel(x0 + dx, y),
+                    TransitionEffect::Fade => blend(
+                        self.from.get_pixel(x0 + dx, y),
+                        self.to.get_pixel(x0 + dx, y),
+                        progress,
+                    ),
+                };
+                image.put_pixel(x0 + dx, y, pixel);
+            }
+        }
+
+        image
+    }
+}
+
+fn blend(from: &Rgba<u8>, to: &Rgba<u8>, progress: f32) -> Rgba<u8> {
+    let mut pixel = *from;
+    for (channel, target) in pixel.0.iter_mut().zip(to.0) {
+        *channel = (*channel as f32 + (target as f32 - *channel as f32) * progress).round() as u8;
+    }
+    pixel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    fn transition(effect: TransitionEffect) -> Transition {
+        let cfg = PageTransition {
+            effect,
+            duration: Some(0.5),
+            fps: Some(8.0),
+        };
+        let from = RgbaImage::from_pixel(100, 20, RED);
+        let to = RgbaImage::from_pixel(100, 20, BLUE);
+        Transition::new(&cfg, from, to, 0, 100, 10).unwrap()
+    }
+
+    #[test]
+    fn frame_rate_is_capped() {
+        let transition = transition(TransitionEffect::Fade);
+        assert_eq!(3, transition.frame_count());
+        assert_eq!(Duration::from_millis(125), transition.frame_interval());
+        assert_eq!(3, transition.frames().count());
+
+        let cfg = PageTransition {
+            effect: TransitionEffect::Fade,
+            duration: Some(0.1),
+            fps: Some(5.0),
+        };
+        let image = RgbaImage::new(10, 10);
+        assert!(Transition::new(&cfg, image.clone(), image, 0, 10, 10).is_none());
+    }
+
+    #[test]
+    fn slide_and_wipe_frames() {
+        let frame = transition(TransitionEffect::SlideLeft).frame(0.25);
+        assert_eq!(&RED, frame.get_pixel(74, 0));
+        assert_eq!(&BLUE, frame.get_pixel(75, 0));
+        // outside of the transition region
+        assert_eq!(&BLUE, frame.get_pixel(0, 15));
+
+        let frame = transition(TransitionEffect::SlideRight).frame(0.25);
+        assert_eq!(&BLUE, frame.get_pixel(24, 0));
+        assert_eq!(&RED, frame.get_pixel(25, 0));
+
+        let frame = transition(TransitionEffect::Wipe).frame(0.5);
+        assert_eq!(&BLUE, frame.get_pixel(49, 5));
+        assert_eq!(&RED, frame.get_pixel(50, 5));
+    }
+
+    #[test]
+    fn fade_frame_blends_images() {
+        let frame = transition(TransitionEffect::Fade).frame(0.5);
+        assert_eq!(&Rgba([128, 0, 128, 255]), frame.get_pixel(50, 5));
+        assert_eq!(&RED, &transition(TransitionEffect::Fade).frame(0.0)[(0, 0)]);
+    }
+}